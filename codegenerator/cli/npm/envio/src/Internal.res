@@ -55,6 +55,28 @@ type handlerContext
 type handlerArgs = genericHandlerArgs<event, handlerContext, loaderReturn>
 type handler = genericHandler<handlerArgs>
 
+type callInputs
+
+@genType
+type genericCall<'inputs, 'block, 'transaction> = {
+  inputs: 'inputs,
+  chainId: int,
+  srcAddress: Address.t,
+  transaction: 'transaction,
+  block: 'block,
+}
+
+type call = genericCall<callInputs, eventBlock, eventTransaction>
+
+@genType
+type genericCallHandlerArgs<'call, 'context> = {
+  call: 'call,
+  context: 'context,
+}
+
+type callHandlerArgs = genericCallHandlerArgs<call, handlerContext>
+type callHandler = genericHandler<callHandlerArgs>
+
 @genType
 type genericHandlerWithLoader<'loader, 'handler, 'eventFilters> = {
   loader: 'loader,
@@ -52,6 +52,11 @@ module Dict = {
 
   let merge: (dict<'a>, dict<'a>) => dict<'a> = %raw(`(dictA, dictB) => ({...dictA, ...dictB})`)
 
+  /**
+    Checks whether the key is set on the dict, even when its value is undefined.
+   */
+  let has: (dict<'a>, string) => bool = %raw(`(dict, key) => key in dict`)
+
   let map = (dict, fn) => {
     let newDict = Js.Dict.empty()
     let keys = dict->Js.Dict.keys
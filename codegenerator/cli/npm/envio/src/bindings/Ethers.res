@@ -206,3 +206,60 @@ module JsonRpcProvider = {
   @send
   external getBlock: (t, int) => promise<Js.nullable<block>> = "getBlock"
 }
+
+module WebSocketProvider = {
+  // WebSocketProvider extends JsonRpcApiProvider,
+  // so it's safe to use it with the JsonRpcProvider bindings
+  @module("ethers") @scope("ethers") @new
+  external makeWithOptions: (
+    ~wsUrl: string,
+    ~network: Network.t,
+    ~options: JsonRpcProvider.rpcOptions,
+  ) => JsonRpcProvider.t = "WebSocketProvider"
+
+  let make = (~wsUrl: string, ~chainId: int): JsonRpcProvider.t => {
+    let network = Network.fromChainId(~chainId)
+    makeWithOptions(~wsUrl, ~network, ~options={staticNetwork: network})
+  }
+
+  // Same as JsonRpcProvider.make, but for WebSocket urls
+  let makeWithFallbacks = (~wsUrls: array<string>, ~chainId: int, ~fallbackStallTimeout) => {
+    let network = Network.fromChainId(~chainId)
+    switch wsUrls {
+    | [wsUrl] => makeWithOptions(~wsUrl, ~network, ~options={staticNetwork: network})
+    | wsUrls =>
+      JsonRpcProvider.makeFallbackProvider(
+        ~providers=wsUrls->Js.Array2.mapi((wsUrl, index) =>
+          makeWithOptions(
+            ~wsUrl,
+            ~network,
+            ~options={staticNetwork: network, priority: index, stallTimeout: fallbackStallTimeout},
+          )
+        ),
+        ~network,
+        ~options={
+          quorum: 1,
+        },
+      )
+    }
+  }
+
+  // Uses the eth_subscribe("newHeads") subscription under the hood
+  @send
+  external onBlock: (JsonRpcProvider.t, @as("block") _, int => unit) => unit = "on"
+
+  @send
+  external destroy: JsonRpcProvider.t => unit = "destroy"
+
+  // The underlying connection, which the provider doesn't reconnect when it's closed
+  type websocket
+
+  @get
+  external websocket: JsonRpcProvider.t => websocket = "websocket"
+
+  @send
+  external onClose: (websocket, @as("close") _, unit => unit) => unit = "addEventListener"
+
+  @send
+  external onError: (websocket, @as("error") _, unit => unit) => unit = "addEventListener"
+}
@@ -39,6 +39,7 @@ type tlsConnectOptions = {
   highWaterMark?: int /* Default: 16 * 1024 */,
   secureContext?: secureContext,
   onread?: onread,
+  ca?: string, // PEM encoded CA certificates to trust instead of the default ones
   /* Additional properties from tls.createSecureContext() and socket.connect() */
   // [key: string]: Js.Json.t,
 }
@@ -89,6 +90,10 @@ type poolConfig = {
 @module
 external makeSql: (~config: poolConfig) => sql = "postgres"
 
+// The config options take precedence over the ones set in the connection url
+@module
+external makeSqlWithUrl: (string, ~config: poolConfig) => sql = "postgres"
+
 @send external beginSql: (sql, sql => array<promise<unit>>) => promise<unit> = "begin"
 
 // TODO: can explore this approach (https://forum.rescript-lang.org/t/rfc-support-for-tagged-template-literals/3744)
@@ -11,6 +11,20 @@ type decodedEvent<'a> = {
 
 @module("viem") external decodeEventLogOrThrow: eventLog => decodedEvent<'a> = "decodeEventLog"
 
+type functionData = {
+  abi: EvmTypes.Abi.t,
+  data: string,
+}
+
+type decodedFunctionData = {
+  functionName: string,
+  // Undefined for functions without inputs
+  args?: array<unknown>,
+}
+
+@module("viem")
+external decodeFunctionDataOrThrow: functionData => decodedFunctionData = "decodeFunctionData"
+
 type hex = EvmTypes.Hex.t
 @module("viem") external toHex: 'a => hex = "toHex"
 @module("viem") external keccak256: hex => hex = "keccak256"
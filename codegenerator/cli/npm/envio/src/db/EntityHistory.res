@@ -260,6 +260,15 @@ let fromTable = (table: table, ~schema: S.t<'entity>): t<'entity> => {
   let dataFieldNamesDoubleQuoted = dataFieldNames->Belt.Array.map(fieldName => `"${fieldName}"`)
   let dataFieldNamesCommaSeparated = dataFieldNamesDoubleQuoted->Js.Array2.joinWith(", ")
 
+  //Values of jsonb fields need to be explicitly serialized as json
+  //since arrays would otherwise be serialized as postgres arrays
+  let jsonFieldNamesDoubleQuoted = dataFields->Belt.Array.keepMap(field =>
+    switch field {
+    | Field({fieldType: JsonB} as field) => Some(`"${field->getDbFieldName}"`)
+    | _ => None
+    }
+  )
+
   let allFieldNamesDoubleQuoted =
     Belt.Array.concatMany([
       currentChangeFieldNames,
@@ -326,7 +335,12 @@ let fromTable = (table: table, ~schema: S.t<'entity>): t<'entity> => {
 
   let insertFnString = `(sql, rowArgs, shouldCopyCurrentEntity) =>
       sql\`select ${insertFnName}(ROW(${allFieldNamesDoubleQuoted
-    ->Belt.Array.map(fieldNameDoubleQuoted => `\${rowArgs[${fieldNameDoubleQuoted}]\}`)
+    ->Belt.Array.map(fieldNameDoubleQuoted => {
+        let value = `rowArgs[${fieldNameDoubleQuoted}]`
+        jsonFieldNamesDoubleQuoted->Js.Array2.includes(fieldNameDoubleQuoted)
+          ? `\${${value} === null ? null : sql.json(${value})\}`
+          : `\${${value}\}`
+      })
     ->Js.Array2.joinWith(", ")}, NULL),  --NULL argument for SERIAL field
     \${shouldCopyCurrentEntity});\``
 
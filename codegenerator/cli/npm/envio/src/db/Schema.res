@@ -16,3 +16,18 @@ let getDerivedFromFieldName = (schema: t, derivedFromField: Table.derivedFromFie
     }
   | None => Error(UndefinedEntity(derivedFromField)) //Unexpected, schema should be parsed on codegen
   }
+
+/*
+Gets the join table of the field the derivedFrom field references,
+when it's a many-to-many relationship field
+*/
+let getDerivedFromJoinTableName = (schema: t, derivedFromField: Table.derivedFromField) =>
+  switch schema->Utils.Dict.dangerouslyGetNonOption(derivedFromField.derivedFromEntity) {
+  | Some(entity) =>
+    switch entity->Table.getFieldByName(derivedFromField.derivedFromField) {
+    | Some(Field(field)) if field->Table.isManyToManyField =>
+      Some(entity->Table.JoinTable.getName(field))
+    | _ => None
+    }
+  | None => None
+  }
@@ -71,6 +71,10 @@ let getUserDefinedFieldName = fieldOrDerived =>
 
 let isLinkedEntityField = field => field.linkedEntity->Option.isSome
 
+//Arrays of linked entities are many-to-many relationships
+//backed by a join table
+let isManyToManyField = field => field.isArray && field->isLinkedEntityField
+
 let getDbFieldName = field =>
   field->isLinkedEntityField ? field.fieldName ++ "_id" : field.fieldName
 
@@ -84,18 +88,123 @@ let getFieldType = (field: field) => {
   (field.fieldType :> string) ++ (field.isArray ? "[]" : "")
 }
 
+type indexMethod =
+  | @as("btree") BTree
+  | @as("hash") Hash
+  | @as("gin") Gin
+  | @as("gist") Gist
+  | @as("brin") Brin
+
+type indexOrder = | @as("ASC") Asc | @as("DESC") Desc
+
+// An index with a non default method, field orders or a partial index predicate
+type customIndex = {
+  fieldNames: array<string>,
+  method: indexMethod,
+  orders: array<indexOrder>,
+  predicate: option<string>,
+}
+
+let mkIndex = (fieldNames, ~method=BTree, ~orders=[], ~predicate=?) => {
+  fieldNames,
+  method,
+  orders,
+  predicate,
+}
+
+/*
+A full text search over text fields, stored in a generated tsvector column
+with a gin index and queried through a function with the name of the search
+*/
+type fulltextSearch = {
+  name: string,
+  // The Postgres text search configuration
+  language: string,
+  fieldNames: array<string>,
+}
+
+let mkFulltextSearch = (name, ~language, ~fieldNames) => {
+  name,
+  language,
+  fieldNames,
+}
+
 type table = {
   tableName: string,
   schemaName: string,
   fields: array<fieldOrDerived>,
   compositeIndices: array<array<string>>,
+  customIndices: array<customIndex>,
+  fulltextSearches: array<fulltextSearch>,
+  uniqueConstraints: array<array<string>>,
+  // Rows of an immutable table are only ever inserted and have no history,
+  // a rollback deletes them by the event tracked in their ImmutableRow columns
+  isImmutable: bool,
+  // Rows of a timeseries table are only inserted by handlers,
+  // but keep their history so they can be rolled back
+  isTimeseries: bool,
 }
 
-let mkTable = (tableName, ~schemaName, ~compositeIndices=[], ~fields) => {
+/*
+The columns tracking the event that created a row of an immutable table,
+so its rows can be deleted on a rollback without keeping any history
+*/
+module ImmutableRow = {
+  let chainIdFieldName = "envio_chain_id"
+  let blockNumberFieldName = "envio_block_number"
+  let logIndexFieldName = "envio_log_index"
+
+  let fields = [
+    mkField(chainIdFieldName, Integer),
+    mkField(blockNumberFieldName, Integer),
+    mkField(logIndexFieldName, Integer),
+  ]
+
+  let compositeIndex = [chainIdFieldName, blockNumberFieldName]
+}
+
+let mkTable = (
+  tableName,
+  ~schemaName,
+  ~compositeIndices=[],
+  ~customIndices=[],
+  ~fulltextSearches=[],
+  ~uniqueConstraints=[],
+  ~isImmutable=false,
+  ~isTimeseries=false,
+  ~fields,
+) => {
   tableName,
   schemaName,
-  fields,
-  compositeIndices,
+  fields: isImmutable ? fields->Array.concat(ImmutableRow.fields) : fields,
+  compositeIndices: isImmutable
+    ? compositeIndices->Array.concat([ImmutableRow.compositeIndex])
+    : compositeIndices,
+  customIndices,
+  fulltextSearches,
+  uniqueConstraints,
+  isImmutable,
+  isTimeseries,
+}
+
+/*
+A view over the tables of several entities (used for interfaces and unions)
+With an additional column holding the entity name of each row
+*/
+type view = {
+  viewName: string,
+  schemaName: string,
+  fieldNames: array<string>,
+  tableNames: array<string>,
+}
+
+let viewEntityTypeFieldName = "entity_type"
+
+let mkView = (viewName, ~schemaName, ~fieldNames, ~tableNames) => {
+  viewName,
+  schemaName,
+  fieldNames,
+  tableNames,
 }
 
 let getPrimaryKeyFieldNames = table =>
@@ -129,13 +238,17 @@ let getNonDefaultFields = table =>
 let getLinkedEntityFields = table =>
   table.fields->Array.keepMap(field =>
     switch field {
-    | Field({linkedEntity: Some(linkedEntityName)} as field) => Some((field, linkedEntityName))
+    | Field({linkedEntity: Some(linkedEntityName), isArray: false} as field) =>
+      Some((field, linkedEntityName))
+    | Field({linkedEntity: Some(_), isArray: true})
     | Field({linkedEntity: None})
     | DerivedFrom(_) =>
       None
     }
   )
 
+let getManyToManyFields = table => table->getFields->Array.keep(isManyToManyField)
+
 let getDerivedFromFields = table =>
   table.fields->Array.keepMap(field =>
     switch field {
@@ -165,15 +278,297 @@ exception NonExistingTableField(string)
 Gets all composite indicies (whether they are single indices or not)
 And maps the fields defined to their actual db name (some have _id suffix)
 */
+let toDbFieldNamesUnsafe = (table, userDefinedFieldNames) =>
+  userDefinedFieldNames->Array.map(userDefinedFieldName =>
+    switch table->getFieldByName(userDefinedFieldName) {
+    | Some(field) => field->getFieldName
+    | None => raise(NonExistingTableField(userDefinedFieldName)) //Unexpected should be validated in schema parser
+    }
+  )
+
 let getUnfilteredCompositeIndicesUnsafe = (table): array<array<string>> => {
-  table.compositeIndices->Array.map(compositeIndex =>
-    compositeIndex->Array.map(userDefinedFieldName =>
-      switch table->getFieldByName(userDefinedFieldName) {
-      | Some(field) => field->getFieldName
-      | None => raise(NonExistingTableField(userDefinedFieldName)) //Unexpected should be validated in schema parser
-      }
+  table.compositeIndices->Array.map(toDbFieldNamesUnsafe(table, _))
+}
+
+/*
+Gets all unique constraints
+And maps the fields defined to their actual db name (some have _id suffix)
+*/
+let getUniqueConstraints = (table): array<array<string>> => {
+  table.uniqueConstraints->Array.map(toDbFieldNamesUnsafe(table, _))
+}
+
+/*
+The join table of a many-to-many relationship field,
+holding a row for every entity referenced by the field of each entity
+*/
+module JoinTable = {
+  let sourceFieldName = "source_id"
+  let targetFieldName = "target_id"
+
+  let getName = (table: table, field: field) => `${table.tableName}_${field.fieldName}`
+
+  let getNameByDbFieldName = (table, dbFieldName) =>
+    switch table->getFieldByDbName(dbFieldName) {
+    | Some(Field(field)) if field->isManyToManyField => table->getName(field)
+    | _ => raise(NonExistingTableField(dbFieldName))
+    }
+
+  let make = (table, field) =>
+    mkTable(
+      table->getName(field),
+      ~schemaName=table.schemaName,
+      ~fields=[
+        mkField(sourceFieldName, Text, ~isPrimaryKey=true),
+        mkField(targetFieldName, Text, ~isPrimaryKey=true, ~isIndex=true),
+      ],
     )
-  )
+
+  /*
+  Creates a trigger on the entity table which keeps the join table
+  in sync with the field on every insert, update and delete of an entity
+  */
+  let makeSyncTriggerQuery = (table, field) => {
+    let joinTablePath = `"${table.schemaName}"."${table->getName(field)}"`
+    let functionName = `"${table.schemaName}"."sync_${table->getName(field)}"`
+    let triggerName = `"sync_${table->getName(field)}"`
+    let dbFieldName = field->getDbFieldName
+    `CREATE OR REPLACE FUNCTION ${functionName}()
+      RETURNS TRIGGER AS $$
+      BEGIN
+        IF TG_OP = 'UPDATE' AND OLD."${dbFieldName}" IS NOT DISTINCT FROM NEW."${dbFieldName}" THEN
+          RETURN NULL;
+        END IF;
+        IF TG_OP <> 'INSERT' THEN
+          DELETE FROM ${joinTablePath} WHERE "${sourceFieldName}" = OLD."id";
+        END IF;
+        IF TG_OP <> 'DELETE' THEN
+          INSERT INTO ${joinTablePath} ("${sourceFieldName}", "${targetFieldName}")
+          SELECT NEW."id", unnest(NEW."${dbFieldName}")
+          ON CONFLICT DO NOTHING;
+        END IF;
+        RETURN NULL;
+      END;
+      $$ LANGUAGE plpgsql;
+    DROP TRIGGER IF EXISTS ${triggerName} ON "${table.schemaName}"."${table.tableName}";
+    CREATE TRIGGER ${triggerName}
+      AFTER INSERT OR UPDATE OR DELETE ON "${table.schemaName}"."${table.tableName}"
+      FOR EACH ROW EXECUTE FUNCTION ${functionName}();`
+  }
+}
+
+module FulltextSearch = {
+  /*
+  Adds the generated tsvector column and its gin index to the table
+  and creates the search function returning the matching rows by rank.
+  The generation expression is kept as the comment of the column, so a column
+  generated from other fields or another language is dropped and added again
+  */
+  let makeCreateQuery = (table, fulltextSearch: fulltextSearch) => {
+    let {name, language} = fulltextSearch
+    let tablePath = `"${table.schemaName}"."${table.tableName}"`
+    let document =
+      table
+      ->toDbFieldNamesUnsafe(fulltextSearch.fieldNames)
+      ->Array.map(fieldName => `coalesce("${fieldName}", '')`)
+      ->Js.Array2.joinWith(` || ' ' || `)
+    let expression = `to_tsvector('${language}'::regconfig, ${document})`
+    let expressionLiteral = `'${expression->Js.String2.replaceByRe(%re("/'/g"), "''")}'`
+    //Refer to the argument by position, since its name could clash with a column
+    let query = `websearch_to_tsquery('${language}', $1)`
+    `DO $envio$
+      BEGIN
+        IF EXISTS (
+          SELECT 1 FROM pg_attribute
+          WHERE attrelid = '${tablePath}'::regclass AND attname = '${name}' AND NOT attisdropped
+            AND col_description(attrelid, attnum) IS DISTINCT FROM ${expressionLiteral}
+        ) THEN
+          ALTER TABLE ${tablePath} DROP COLUMN "${name}";
+        END IF;
+      END $envio$;
+    ALTER TABLE ${tablePath} ADD COLUMN IF NOT EXISTS "${name}" tsvector
+      GENERATED ALWAYS AS (${expression}) STORED;
+    COMMENT ON COLUMN ${tablePath}."${name}" IS ${expressionLiteral};
+    CREATE INDEX IF NOT EXISTS "${table.tableName}_${name}_gin" ON ${tablePath} USING gin("${name}");
+    CREATE OR REPLACE FUNCTION "${table.schemaName}"."${name}"(search text)
+      RETURNS SETOF ${tablePath} AS $$
+        SELECT * FROM ${tablePath}
+        WHERE "${name}" @@ ${query}
+        ORDER BY ts_rank("${name}", ${query}) DESC;
+      $$ LANGUAGE sql STABLE;`
+  }
+}
+
+type aggregationInterval = | @as("hour") Hour | @as("day") Day
+
+type aggregateFn =
+  | @as("sum") Sum
+  | @as("count") Count
+  | @as("min") Min
+  | @as("max") Max
+  | @as("first") First
+  | @as("last") Last
+
+type aggregate = {
+  fieldName: string,
+  fn: aggregateFn,
+  // The field of the source rows to aggregate
+  arg: option<string>,
+}
+
+let mkAggregate = (fieldName, fn, ~arg=?) => {
+  fieldName,
+  fn,
+  arg,
+}
+
+/*
+Rolls up the rows of a timeseries table into a table per interval,
+with a row per bucket and combination of the dimension fields
+*/
+type aggregation = {
+  name: string,
+  schemaName: string,
+  source: table,
+  intervals: array<aggregationInterval>,
+  dimensionFieldNames: array<string>,
+  aggregates: array<aggregate>,
+  fields: array<fieldOrDerived>,
+}
+
+let mkAggregation = (
+  name,
+  ~schemaName,
+  ~source,
+  ~intervals,
+  ~dimensionFieldNames,
+  ~aggregates,
+  ~fields,
+) => {
+  name,
+  schemaName,
+  source,
+  intervals,
+  dimensionFieldNames,
+  aggregates,
+  fields,
+}
+
+module Aggregation = {
+  let timestampFieldName = "timestamp"
+
+  let getTableName = (aggregation, interval: aggregationInterval) =>
+    `${aggregation.name}_${(interval :> string)}`
+
+  let makeTable = (aggregation, interval) =>
+    mkTable(
+      aggregation->getTableName(interval),
+      ~schemaName=aggregation.schemaName,
+      ~fields=aggregation.fields,
+      ~customIndices=[
+        mkIndex(aggregation.dimensionFieldNames->Array.concat([timestampFieldName])),
+      ],
+    )
+
+  let getTables = aggregation => aggregation.intervals->Array.map(makeTable(aggregation, _))
+
+  /*
+  Creates statement level triggers on the source table which recompute every bucket
+  touched by inserted, updated or deleted rows from the source rows of the bucket.
+  Since rollbacks delete the source rows, the aggregates are rolled back with them
+  */
+  let makeSyncTriggerQuery = (aggregation, interval) => {
+    let {source, schemaName} = aggregation
+    let table = aggregation->makeTable(interval)
+    let tablePath = `"${schemaName}"."${table.tableName}"`
+    let sourcePath = `"${source.schemaName}"."${source.tableName}"`
+    let functionName = `"${schemaName}"."aggregate_${table.tableName}"`
+    let unit = (interval :> string)
+    let toBucket = alias =>
+      `date_trunc('${unit}', ${alias}."${timestampFieldName}" AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'`
+    let dimensions = source->toDbFieldNamesUnsafe(aggregation.dimensionFieldNames)
+    let selectDimensions = alias =>
+      dimensions->Array.map(dimension => `, ${alias}."${dimension}"`)->Js.Array2.joinWith("")
+    let isSameBucket = (a, b) =>
+      [`${a}."${timestampFieldName}" = ${b}."${timestampFieldName}"`]
+      ->Array.concat(
+        dimensions->Array.map(dimension =>
+          `${a}."${dimension}" IS NOT DISTINCT FROM ${b}."${dimension}"`
+        ),
+      )
+      ->Js.Array2.joinWith(" AND ")
+    let aggregateFieldNames =
+      table->toDbFieldNamesUnsafe(aggregation.aggregates->Array.map(({fieldName}) => fieldName))
+    let aggregateExpressions = aggregation.aggregates->Array.map(({fn, arg}) => {
+      let arg = switch arg {
+      | Some(arg) => `s."${source->toDbFieldNamesUnsafe([arg])->Array.getExn(0)}"`
+      | None => "*"
+      }
+      switch fn {
+      | Sum | Count | Min | Max => `${(fn :> string)}(${arg})`
+      | First => `(array_agg(${arg} ORDER BY s."${timestampFieldName}", s."id"))[1]`
+      | Last => `(array_agg(${arg} ORDER BY s."${timestampFieldName}" DESC, s."id" DESC))[1]`
+      }
+    })
+    let recomputeBuckets = changedRows =>
+      `WITH buckets AS (
+          SELECT DISTINCT ${toBucket("r")} AS "${timestampFieldName}"${selectDimensions("r")}
+          FROM ${changedRows} r
+        ), aggregates AS (
+          SELECT b."${timestampFieldName}"${selectDimensions("b")}${aggregateExpressions
+        ->Array.mapWithIndex((i, expression) =>
+          `, ${expression} AS "${aggregateFieldNames->Array.getExn(i)}"`
+        )
+        ->Js.Array2.joinWith("")}
+          FROM buckets b JOIN ${sourcePath} s
+          ON s."${timestampFieldName}" >= b."${timestampFieldName}"
+          AND s."${timestampFieldName}" < b."${timestampFieldName}" + interval '1 ${unit}'${dimensions
+        ->Array.map(dimension => ` AND s."${dimension}" IS NOT DISTINCT FROM b."${dimension}"`)
+        ->Js.Array2.joinWith("")}
+          GROUP BY b."${timestampFieldName}"${selectDimensions("b")}
+        ), emptied AS (
+          DELETE FROM ${tablePath} a USING buckets b
+          WHERE ${isSameBucket("a", "b")}
+          AND NOT EXISTS (SELECT 1 FROM aggregates g WHERE ${isSameBucket("g", "b")})
+        )
+        INSERT INTO ${tablePath} ("id", "${timestampFieldName}"${dimensions
+        ->Array.map(dimension => `, "${dimension}"`)
+        ->Js.Array2.joinWith("")}${aggregateFieldNames
+        ->Array.map(fieldName => `, "${fieldName}"`)
+        ->Js.Array2.joinWith("")})
+        SELECT concat_ws('-', extract(epoch FROM g."${timestampFieldName}")::bigint${selectDimensions(
+          "g",
+        )}), g."${timestampFieldName}"${selectDimensions("g")}${aggregateFieldNames
+        ->Array.map(fieldName => `, g."${fieldName}"`)
+        ->Js.Array2.joinWith("")}
+        FROM aggregates g
+        ON CONFLICT ("id") DO UPDATE SET ${aggregateFieldNames
+        ->Array.map(fieldName => `"${fieldName}" = EXCLUDED."${fieldName}"`)
+        ->Js.Array2.joinWith(", ")};`
+    let makeTrigger = (~event, ~transitionTables) => {
+      let triggerName = `"aggregate_${table.tableName}_${event->Js.String2.toLowerCase}"`
+      `DROP TRIGGER IF EXISTS ${triggerName} ON ${sourcePath};
+    CREATE TRIGGER ${triggerName}
+      AFTER ${event} ON ${sourcePath}
+      REFERENCING ${transitionTables}
+      FOR EACH STATEMENT EXECUTE FUNCTION ${functionName}();`
+    }
+    `CREATE OR REPLACE FUNCTION ${functionName}()
+      RETURNS TRIGGER AS $$
+      BEGIN
+        IF TG_OP <> 'DELETE' THEN
+          ${recomputeBuckets("new_rows")}
+        END IF;
+        IF TG_OP <> 'INSERT' THEN
+          ${recomputeBuckets("old_rows")}
+        END IF;
+        RETURN NULL;
+      END;
+      $$ LANGUAGE plpgsql;
+    ${makeTrigger(~event="INSERT", ~transitionTables="NEW TABLE AS new_rows")}
+    ${makeTrigger(~event="UPDATE", ~transitionTables="OLD TABLE AS old_rows NEW TABLE AS new_rows")}
+    ${makeTrigger(~event="DELETE", ~transitionTables="OLD TABLE AS old_rows")}`
+  }
 }
 
 type sqlParams<'entity> = {
@@ -294,6 +689,17 @@ let getCompositeIndices = (table): array<array<string>> => {
   ->Array.keep(ind => ind->Array.length > 1)
 }
 
+/*
+Gets all custom indices
+And maps the fields defined to their actual db name (some have _id suffix)
+*/
+let getCustomIndices = (table): array<customIndex> => {
+  table.customIndices->Array.map(index => {
+    ...index,
+    fieldNames: table->toDbFieldNamesUnsafe(index.fieldNames),
+  })
+}
+
 module PostgresInterop = {
   type pgFn<'payload, 'return> = (Postgres.sql, 'payload) => promise<'return>
   type batchSetFn<'a> = (Postgres.sql, array<'a>) => promise<unit>
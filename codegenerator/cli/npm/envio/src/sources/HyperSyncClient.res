@@ -399,14 +399,37 @@ module ResponseTypes = {
     /** Rollback guard, supposed to be used to detect rollbacks */
     rollbackGuard: option<rollbackGuard>,
   }
+
+  type queryResponseData = {
+    blocks?: array<block>,
+    transactions?: array<transaction>,
+    logs?: array<log>,
+  }
+
+  type queryResponse = {
+    /** Current height of the source hypersync instance */
+    archiveHeight: option<int>,
+    /**
+     * Next block to query for, the responses are paginated so,
+     *  the caller should continue the query from this block if they
+     *  didn't get responses up to the to_block they specified in the Query.
+     */
+    nextBlock: int,
+    /** Total time it took the hypersync instance to execute the query. */
+    totalExecutionTime: int,
+    /** Response data */
+    data: queryResponseData,
+    /** Rollback guard, supposed to be used to detect rollbacks */
+    rollbackGuard: option<rollbackGuard>,
+  }
 }
 
 type query = QueryTypes.query
 type eventResponse = ResponseTypes.eventResponse
+type queryResponse = ResponseTypes.queryResponse
 
 //Todo, add bindings for these types
 type streamConfig
-type queryResponse
 type queryResponseStream
 type eventStream
 type t = {
@@ -16,7 +16,9 @@ pub mod evm {
             chain_helpers,
             contract_import::converters::{NetworkKind, SelectedContract},
             human_config::{
-                evm::{ContractConfig, EventConfig, HumanConfig, Network, RpcConfig},
+                evm::{
+                    ContractConfig, EventConfig, HumanConfig, HypersyncConfig, Network, RpcConfig,
+                },
                 GlobalContract, NetworkContract,
             },
             system_config::EvmAbi,
@@ -58,6 +60,9 @@ pub mod evm {
                         event: EvmAbi::event_signature_from_abi_event(&event),
                         name: None,
                         field_selection: None,
+                        start_block: None,
+                        end_block: None,
+                        filter: None,
                     })
                     .collect();
 
@@ -72,6 +77,8 @@ pub mod evm {
                             abi_file_path: None,
                             handler,
                             events,
+                            functions: None,
+                            factory: None,
                         },
                     };
 
@@ -92,6 +99,8 @@ pub mod evm {
                         abi_file_path: None,
                         handler,
                         events,
+                        functions: None,
+                        factory: None,
                     })
                 };
 
@@ -107,9 +116,12 @@ pub mod evm {
                         .entry(selected_network.network.get_network_id())
                         .or_insert({
                             let rpc_config = match &selected_network.network {
-                                NetworkKind::Supported(_) => None,
+                                NetworkKind::Supported(_) | NetworkKind::Registered(_) => None,
                                 NetworkKind::Unsupported { rpc_url, .. } => Some(RpcConfig {
                                     url: rpc_url.clone().into(),
+                                    role: None,
+                                    realtime_threshold_blocks: None,
+                                    fallback_threshold_millis: None,
                                     sync_config: None,
                                 }),
                             };
@@ -118,6 +130,7 @@ pub mod evm {
                                 NetworkKind::Supported(network) => {
                                     chain_helpers::Network::from(network).get_finite_end_block()
                                 }
+                                NetworkKind::Registered(_) => None,
                                 NetworkKind::Unsupported { network_id, .. } => {
                                     chain_helpers::Network::from_network_id(network_id)
                                         .ok()
@@ -125,20 +138,39 @@ pub mod evm {
                                 }
                             };
 
+                            //Write the registry values to the config, so the project
+                            //doesn't depend on the chains.yaml of the user
+                            let (hypersync_config, confirmed_block_threshold) =
+                                match &selected_network.network {
+                                    NetworkKind::Registered(chain) => (
+                                        chain
+                                            .hypersync_url
+                                            .clone()
+                                            .map(|url| HypersyncConfig { url }),
+                                        chain.confirmed_block_threshold,
+                                    ),
+                                    NetworkKind::Supported(_) | NetworkKind::Unsupported { .. } => {
+                                        (None, None)
+                                    }
+                                };
+
                             Network {
                                 id: selected_network.network.get_network_id(),
-                                hypersync_config: None,
+                                hypersync_config,
                                 rpc_config,
                                 start_block: selected_network.network.get_start_block(),
                                 end_block,
-                                confirmed_block_threshold: None,
+                                confirmed_block_threshold,
                                 contracts: Vec::new(),
+                                block_handlers: None,
                             }
                         });
 
                     let contract = NetworkContract {
                         name: selected_contract.name.clone(),
                         address,
+                        start_block: None,
+                        end_block: None,
                         config: config.clone(),
                     };
 
@@ -168,13 +200,14 @@ pub mod evm {
                 save_full_history: None,
                 field_selection: None,
                 raw_events: None,
+                env_interpolation: None,
             })
         }
 
         fn uses_hypersync(&self) -> bool {
-            self.selected_contracts.iter().any(|c| c.networks
-                        .iter()
-                        .any(|n| n.uses_hypersync()))
+            self.selected_contracts
+                .iter()
+                .any(|c| c.networks.iter().any(|n| n.uses_hypersync()))
         }
     }
 
@@ -278,6 +311,8 @@ pub mod fuel {
                                     .map(|a| a.to_string())
                                     .collect::<Vec<String>>()
                                     .into(),
+                                start_block: None,
+                                end_block: None,
                                 config: Some(ContractConfig {
                                     abi_file_path: selected_contract.get_vendored_abi_file_path(),
                                     handler: init_config.language.get_event_handler_directory(),
@@ -296,6 +331,7 @@ pub mod fuel {
                 schema: None,
                 contracts: None,
                 raw_events: None,
+                env_interpolation: None,
                 networks: network_configs,
             }
         }
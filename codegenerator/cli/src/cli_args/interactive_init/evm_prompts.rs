@@ -12,7 +12,8 @@ use crate::{
     clap_definitions::evm::NetworkOrChainId,
     cli_args::interactive_init::validation::filter_duplicate_events,
     config_parsing::{
-        chain_helpers::{HypersyncNetwork, Network, NetworkWithExplorer},
+        chain_helpers::{ChainWithExplorer, HypersyncNetwork, Network},
+        chain_registry::{ChainDefinition, ChainRegistry},
         contract_import::{
             contract_import,
             converters::{self, ContractImportNetworkSelection, SelectedContract},
@@ -82,16 +83,16 @@ impl ContractImportArgs {
 
     async fn get_selected_contract(
         &self,
-        network: &NetworkWithExplorer,
+        chain: &ChainWithExplorer,
         contract_address: Address,
     ) -> anyhow::Result<SelectedContract> {
-        let result = match contract_import(network, &contract_address, 0).await {
+        let result = match contract_import(chain, &contract_address).await {
             Ok(ContractImportResult::Contract(contract_data)) => Ok(contract_data),
             Ok(ContractImportResult::NotVerified) => {
                 Err("Failed to find the verified contract on a block explorer.".to_string())
             }
             Ok(ContractImportResult::UnsupportedChain) => Err(format!(
-                "The \"{network}\" chain doesn't support contract import yet. Let us know if you \
+                "The \"{chain}\" chain doesn't support contract import yet. Let us know if you \
                  want it by opening an issue on Github."
             )),
             Err(e) => Err(format!(
@@ -108,7 +109,7 @@ impl ContractImportArgs {
                     ..self.clone()
                 })
                 .get_contract_import_selection_from_local_import_args(&LocalImportArgs {
-                    blockchain: Some(NetworkOrChainId::ChainId(*network as u64)),
+                    blockchain: Some(NetworkOrChainId::ChainId(chain.get_id())),
                     ..LocalImportArgs::default()
                 })
                 .await;
@@ -120,7 +121,7 @@ impl ContractImportArgs {
             abi_events = prompt_abi_events_selection(abi_events)?;
         }
 
-        let network_kind = get_converter_network_u64(*network as u64, &None, &None)?;
+        let network_kind = get_converter_network_u64(chain.get_id(), &None, &None)?;
 
         let network_selection = ContractImportNetworkSelection::new(network_kind, contract_address);
 
@@ -141,16 +142,16 @@ impl ContractImportArgs {
         &self,
         explorer_import_args: &ExplorerImportArgs,
     ) -> Result<SelectedContract> {
-        let network_with_explorer: NetworkWithExplorer = explorer_import_args
-            .get_network_with_explorer()
-            .context("Failed getting NetworkWithExplorer")?;
+        let chain_with_explorer = explorer_import_args
+            .get_chain_with_explorer()
+            .context("Failed getting ChainWithExplorer")?;
 
         let chosen_contract_address = self
             .get_contract_address()
             .context("Failed getting contract address")?;
 
         let selected_contract = self
-            .get_selected_contract(&network_with_explorer, chosen_contract_address)
+            .get_selected_contract(&chain_with_explorer, chosen_contract_address)
             .await
             .context("Failed getting SelectedContract from explorer")?;
 
@@ -189,6 +190,7 @@ impl ContractImportArgs {
 enum NetworkSelection {
     EnterNetworkId,
     Network(HypersyncNetwork),
+    RegisteredChain(ChainDefinition),
 }
 
 impl fmt::Display for NetworkSelection {
@@ -196,6 +198,7 @@ impl fmt::Display for NetworkSelection {
         match self {
             Self::EnterNetworkId => write!(f, "<Enter Network Id>"),
             Self::Network(network) => write!(f, "{}", network.get_pretty_name()),
+            Self::RegisteredChain(chain) => write!(f, "{}", chain.get_pretty_name()),
         }
     }
 }
@@ -210,6 +213,23 @@ fn prompt_for_network_id(
     opt_start_block: &Option<u64>,
     already_selected_ids: Vec<u64>,
 ) -> Result<converters::NetworkKind> {
+    //The project doesn't exist yet, so only the user level registry is used
+    let chain_registry =
+        ChainRegistry::load_user(ChainRegistry::get_user_chains_path().as_deref())?;
+
+    //Chains from the registry with a HyperSync url, they take precedence
+    //over our supported networks with the same id
+    let registered_chains = chain_registry
+        .iter()
+        .filter(|chain| {
+            chain.hypersync_url.is_some()
+                && chain.is_public()
+                && !already_selected_ids.contains(&chain.id)
+        })
+        .cloned()
+        .map(NetworkSelection::RegisteredChain)
+        .collect::<Vec<_>>();
+
     //Select one of our supported networks
     let networks = HypersyncNetwork::iter()
         //Don't allow selection of networks that have been previously
@@ -217,12 +237,20 @@ fn prompt_for_network_id(
         .filter(|n| {
             let network_id = *n as u64;
             !already_selected_ids.contains(&network_id)
+                && chain_registry
+                    .get(network_id)
+                    .is_none_or(|chain| chain.hypersync_url.is_none())
         })
         .map(NetworkSelection::Network)
         .collect::<Vec<_>>();
 
     //User's options to either enter an id or select a supported network
-    let options = [vec![NetworkSelection::EnterNetworkId], networks].concat();
+    let options = [
+        vec![NetworkSelection::EnterNetworkId],
+        registered_chains,
+        networks,
+    ]
+    .concat();
 
     //Action prompt
     let choose_from_networks = Select::new("Choose network:", options)
@@ -248,6 +276,7 @@ fn prompt_for_network_id(
         //parse it back to a supported network since it was serialized as a
         //string
         NetworkSelection::Network(network) => converters::NetworkKind::Supported(network),
+        NetworkSelection::RegisteredChain(chain) => converters::NetworkKind::Registered(chain),
     };
 
     Ok(selected)
@@ -261,6 +290,15 @@ fn get_converter_network_u64(
     rpc_url: &Option<String>,
     start_block: &Option<u64>,
 ) -> Result<converters::NetworkKind> {
+    let chain_registry =
+        ChainRegistry::load_user(ChainRegistry::get_user_chains_path().as_deref())?;
+    if let Some(chain) = chain_registry
+        .get(network_id)
+        .filter(|chain| chain.hypersync_url.is_some())
+    {
+        return Ok(converters::NetworkKind::Registered(chain.clone()));
+    }
+
     let maybe_supported_network =
         Network::from_network_id(network_id).and_then(|n| Ok(HypersyncNetwork::try_from(n)?));
 
@@ -311,13 +349,18 @@ fn prompt_for_rpc_url() -> Result<String> {
 }
 
 impl ExplorerImportArgs {
-    ///Either take the NetworkWithExplorer value from the cli args or prompt
-    ///for a user to select one.
-    fn get_network_with_explorer(&self) -> Result<NetworkWithExplorer> {
-        let chosen_network = match &self.blockchain {
-            Some(chain) => *chain,
+    ///Either take the chain from the cli args or prompt for a user to select one.
+    ///Chains of the chains registry with a block explorer are offered too and
+    ///override our networks with the same id.
+    fn get_chain_with_explorer(&self) -> Result<ChainWithExplorer> {
+        let chain_registry =
+            ChainRegistry::load_user(ChainRegistry::get_user_chains_path().as_deref())?;
+
+        let chosen_chain = match &self.blockchain {
+            Some(network) => ChainWithExplorer::from_id(*network as u64, &chain_registry)
+                .context("Failed getting the chain with explorer")?,
             None => {
-                let options = NetworkWithExplorer::iter().collect();
+                let options = ChainWithExplorer::get_all(&chain_registry);
 
                 Select::new(
                     "Which blockchain would you like to import a contract from?",
@@ -327,7 +370,7 @@ impl ExplorerImportArgs {
             }
         };
 
-        Ok(chosen_network)
+        Ok(chosen_chain)
     }
 }
 
@@ -350,6 +393,8 @@ impl LocalImportArgs {
             project_root: current_dir.clone(),
             config: current_dir.clone(),
             generated: current_dir,
+            profile: None,
+            user_chains_path: None,
         };
 
         // Convert the absolute path to a relative path string for EvmAbi::from_file
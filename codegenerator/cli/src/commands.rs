@@ -1,24 +1,57 @@
 use anyhow::Context;
 use std::path::Path;
 
+fn make_command(
+    cmd: &str,
+    args: &[&str],
+    current_dir: &Path,
+    envs: &[(&str, String)],
+) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(cmd);
+    command
+        .args(args)
+        .current_dir(current_dir)
+        .envs(envs.iter().map(|(key, value)| (key, value)))
+        .stdin(std::process::Stdio::null()) //passes null on any stdinprompt
+        //needed so that dropped threads calling this will also drop the child process
+        .kill_on_drop(true);
+    command
+}
+
+fn spawn(
+    mut command: tokio::process::Command,
+    cmd: &str,
+    args: &[&str],
+    current_dir: &Path,
+) -> anyhow::Result<tokio::process::Child> {
+    command.spawn().context(format!(
+        "Failed to spawn command {} {} at {} as child process",
+        cmd,
+        args.join(" "),
+        current_dir.to_str().unwrap_or("bad_path")
+    ))
+}
+
+fn spawn_command(
+    cmd: &str,
+    args: &[&str],
+    current_dir: &Path,
+    envs: &[(&str, String)],
+) -> anyhow::Result<tokio::process::Child> {
+    spawn(
+        make_command(cmd, args, current_dir, envs),
+        cmd,
+        args,
+        current_dir,
+    )
+}
+
 async fn execute_command(
     cmd: &str,
     args: Vec<&str>,
     current_dir: &Path,
 ) -> anyhow::Result<std::process::ExitStatus> {
-    tokio::process::Command::new(cmd)
-        .args(&args)
-        .current_dir(current_dir)
-        .stdin(std::process::Stdio::null()) //passes null on any stdinprompt
-        .kill_on_drop(true) //needed so that dropped threads calling this will also drop
-        //the child process
-        .spawn()
-        .context(format!(
-            "Failed to spawn command {} {} at {} as child process",
-            cmd,
-            args.join(" "),
-            current_dir.to_str().unwrap_or("bad_path")
-        ))?
+    spawn_command(cmd, &args, current_dir, &[])?
         .wait()
         .await
         .context(format!(
@@ -162,28 +195,45 @@ pub mod codegen {
 }
 
 pub mod start {
-    use super::execute_command;
+    use super::{make_command, spawn};
     use crate::project_paths::ParsedProjectPaths;
-    use anyhow::anyhow;
+    use anyhow::{anyhow, Context};
+    use std::{process::ExitStatus, time::Duration};
+    use tokio::process::Child;
+
+    const INDEXER_CMD: &str = "npm";
+    const INDEXER_ARGS: [&str; 2] = ["run", "start"];
+    ///How long the indexer gets to exit gracefully, before it's killed
+    const STOP_INDEXER_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn open_hasura_console() {
+        println!("Opening Hasura console at http://localhost:8080 ...");
+        if open::that_detached("http://localhost:8080").is_err() {
+            println!(
+                "Unable to open http://localhost:8080 in your browser automatically for you. You \
+                 can open that link yourself to view hasura"
+            );
+        }
+    }
 
+    ///Runs the indexer until it exits. The env vars are only set for the indexer process
     pub async fn start_indexer(
         project_paths: &ParsedProjectPaths,
         should_open_hasura: bool,
+        envs: &[(&str, String)],
     ) -> anyhow::Result<()> {
         if should_open_hasura {
-            println!("Opening Hasura console at http://localhost:8080 ...");
-            if open::that_detached("http://localhost:8080").is_err() {
-                println!(
-                    "Unable to open http://localhost:8080 in your browser automatically for you. \
-                     You can open that link yourself to view hasura"
-                );
-            }
+            open_hasura_console();
         }
-        let cmd = "npm";
-        let args = vec!["run", "start"];
-        let current_dir = &project_paths.project_root;
 
-        let exit = execute_command(cmd, args, current_dir).await?;
+        let mut indexer = spawn_indexer(project_paths, envs)?;
+        let exit = tokio::select! {
+            exit = indexer.wait() => exit.context("Failed to exit the indexer child process")?,
+            _ = tokio::signal::ctrl_c() => {
+                println!("Stopping indexer");
+                return indexer.stop().await.context("Failed stopping the indexer");
+            }
+        };
 
         if !exit.success() {
             return Err(anyhow!(
@@ -197,6 +247,90 @@ pub mod start {
         );
         Ok(())
     }
+
+    ///The running indexer. It's started in its own process group, so signals reach
+    ///npm along with the node process running the indexer
+    pub struct Indexer {
+        child: Child,
+    }
+
+    impl Indexer {
+        pub async fn wait(&mut self) -> std::io::Result<ExitStatus> {
+            self.child.wait().await
+        }
+
+        ///Asks the indexer to terminate, giving it the chance to exit gracefully,
+        ///and kills it if it doesn't exit in time
+        pub async fn stop(&mut self) -> anyhow::Result<()> {
+            #[cfg(unix)]
+            if let Some(process_group) = self.process_group() {
+                unsafe {
+                    libc::kill(process_group, libc::SIGTERM);
+                }
+                let _ = tokio::time::timeout(STOP_INDEXER_TIMEOUT, async {
+                    let _ = self.child.wait().await;
+                    //npm might exit before the processes it started
+                    while unsafe { libc::kill(process_group, 0) } == 0 {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                })
+                .await;
+                //Kills whatever didn't exit in time
+                unsafe {
+                    libc::kill(process_group, libc::SIGKILL);
+                }
+            }
+
+            match self.child.try_wait()? {
+                Some(_) => Ok(()),
+                None => self
+                    .child
+                    .kill()
+                    .await
+                    .context("Failed killing the indexer"),
+            }
+        }
+
+        ///The negated pid of the indexer, which signals every process of its group
+        #[cfg(unix)]
+        fn process_group(&self) -> Option<libc::pid_t> {
+            self.child.id().map(|pid| -(pid as libc::pid_t))
+        }
+    }
+
+    impl Drop for Indexer {
+        ///Makes sure no process of the indexer outlives the cli
+        fn drop(&mut self) {
+            #[cfg(unix)]
+            if let Some(process_group) = self.process_group() {
+                unsafe {
+                    libc::kill(process_group, libc::SIGKILL);
+                }
+            }
+        }
+    }
+
+    ///Starts the indexer without waiting for it to exit, so it can be restarted
+    pub fn spawn_indexer(
+        project_paths: &ParsedProjectPaths,
+        envs: &[(&str, String)],
+    ) -> anyhow::Result<Indexer> {
+        let mut command = make_command(
+            INDEXER_CMD,
+            &INDEXER_ARGS,
+            &project_paths.project_root,
+            envs,
+        );
+        #[cfg(unix)]
+        command.process_group(0);
+        let child = spawn(
+            command,
+            INDEXER_CMD,
+            &INDEXER_ARGS,
+            &project_paths.project_root,
+        )?;
+        Ok(Indexer { child })
+    }
 }
 pub mod docker {
     use super::execute_command;
@@ -228,7 +362,10 @@ pub mod db_migrate {
     use std::process::ExitStatus;
 
     use super::execute_command;
-    use crate::{persisted_state::PersistedState, project_paths::ParsedProjectPaths};
+    use crate::{
+        persisted_state::{MigrationPlan, PersistedState},
+        project_paths::ParsedProjectPaths,
+    };
 
     pub async fn run_up_migrations(
         project_paths: &ParsedProjectPaths,
@@ -243,12 +380,27 @@ pub mod db_migrate {
         }
 
         persisted_state
-            .upsert_to_db()
+            .upsert_to_db(project_paths)
             .await
             .context("Failed to upsert persisted state table")?;
         Ok(())
     }
 
+    ///Applies the additive schema changes of the plan to the existing tables and
+    ///then runs the up migrations, creating the new tables, views and functions
+    pub async fn run_incremental_migrations(
+        project_paths: &ParsedProjectPaths,
+        persisted_state: &PersistedState,
+        migration_plan: &MigrationPlan,
+    ) -> anyhow::Result<()> {
+        migration_plan
+            .execute(project_paths)
+            .await
+            .context("Failed applying the schema migrations")?;
+
+        run_up_migrations(project_paths, persisted_state).await
+    }
+
     pub async fn run_drop_schema(project_paths: &ParsedProjectPaths) -> anyhow::Result<ExitStatus> {
         let args = vec!["db-down"];
         let current_dir = &project_paths.generated;
@@ -268,7 +420,7 @@ pub mod db_migrate {
         }
 
         persisted_state
-            .upsert_to_db()
+            .upsert_to_db(project_paths)
             .await
             .context("Failed to upsert persisted state table")?;
         Ok(())
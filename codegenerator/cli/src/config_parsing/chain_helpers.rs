@@ -6,6 +6,7 @@ use std::fmt;
 use strum::IntoEnumIterator;
 use subenum::subenum;
 
+use super::chain_registry::{ChainDefinition, ChainRegistry};
 use crate::constants::DEFAULT_CONFIRMED_BLOCK_THRESHOLD;
 
 #[derive(strum::Display)]
@@ -489,7 +490,7 @@ impl Network {
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq, strum::Display)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, strum::Display)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum ChainTier {
     Gold,
@@ -581,16 +582,78 @@ impl fmt::Display for NetworkWithExplorer {
     }
 }
 
-pub fn get_confirmed_block_threshold_from_id(id: u64) -> i32 {
-    Network::from_network_id(id).map_or(DEFAULT_CONFIRMED_BLOCK_THRESHOLD, |n| {
-        n.get_confirmed_block_threshold()
-    })
+///A chain to import contracts from with a block explorer. Either one of our networks
+///with an explorer or a chain from the chains registry declaring a block explorer
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainWithExplorer {
+    Network(NetworkWithExplorer),
+    Registered(ChainDefinition),
+}
+
+impl ChainWithExplorer {
+    ///The registered chains with a block explorer take precedence over our networks
+    ///with the same id
+    pub fn from_id(id: u64, chain_registry: &ChainRegistry) -> Option<Self> {
+        match chain_registry
+            .get(id)
+            .filter(|chain| chain.block_explorer.is_some())
+        {
+            Some(chain) => Some(Self::Registered(chain.clone())),
+            None => NetworkWithExplorer::from_repr(id).map(Self::Network),
+        }
+    }
+
+    ///All the chains with a block explorer, starting with the registered ones
+    pub fn get_all(chain_registry: &ChainRegistry) -> Vec<Self> {
+        let registered_chains = chain_registry
+            .iter()
+            .filter(|chain| chain.block_explorer.is_some())
+            .cloned()
+            .map(Self::Registered);
+        let networks = NetworkWithExplorer::iter()
+            .filter(|network| {
+                chain_registry
+                    .get(*network as u64)
+                    .is_none_or(|chain| chain.block_explorer.is_none())
+            })
+            .map(Self::Network);
+        registered_chains.chain(networks).collect()
+    }
+
+    pub fn get_id(&self) -> u64 {
+        match self {
+            Self::Network(network) => *network as u64,
+            Self::Registered(chain) => chain.id,
+        }
+    }
+}
+
+impl fmt::Display for ChainWithExplorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(network) => write!(f, "{network}"),
+            Self::Registered(chain) => write!(f, "{chain}"),
+        }
+    }
+}
+
+pub fn get_confirmed_block_threshold_from_id(id: u64, chain_registry: &ChainRegistry) -> i32 {
+    match chain_registry
+        .get(id)
+        .and_then(|chain| chain.confirmed_block_threshold)
+    {
+        Some(threshold) => threshold,
+        None => Network::from_network_id(id).map_or(DEFAULT_CONFIRMED_BLOCK_THRESHOLD, |n| {
+            n.get_confirmed_block_threshold()
+        }),
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{GraphNetwork, HypersyncNetwork};
+    use super::{ChainWithExplorer, GraphNetwork, HypersyncNetwork, NetworkWithExplorer};
     use crate::config_parsing::chain_helpers::Network;
+    use crate::config_parsing::chain_registry::ChainRegistry;
     use itertools::Itertools;
     use pretty_assertions::assert_eq;
     use serde::Deserialize;
@@ -609,6 +672,52 @@ mod test {
         );
     }
 
+    #[test]
+    fn registered_chains_with_explorer_override_networks() {
+        let chain_registry = ChainRegistry::from_yaml_str(
+            r#"
+chains:
+  - id: 1337
+    name: local-devnet
+    block_explorer: http://localhost:4000/api
+  - id: 1
+    name: my-mainnet
+    block_explorer: https://my-explorer.xyz/api
+  - id: 10
+    name: my-optimism
+"#,
+        )
+        .unwrap();
+
+        let local_devnet = chain_registry.get(1337).unwrap().clone();
+        let my_mainnet = chain_registry.get(1).unwrap().clone();
+        assert_eq!(
+            ChainWithExplorer::from_id(1337, &chain_registry),
+            Some(ChainWithExplorer::Registered(local_devnet.clone()))
+        );
+        assert_eq!(
+            ChainWithExplorer::from_id(1, &chain_registry),
+            Some(ChainWithExplorer::Registered(my_mainnet.clone()))
+        );
+        assert_eq!(
+            ChainWithExplorer::from_id(10, &chain_registry),
+            Some(ChainWithExplorer::Network(NetworkWithExplorer::Optimism))
+        );
+
+        let chains = ChainWithExplorer::get_all(&chain_registry);
+        assert_eq!(
+            chains[..2],
+            [
+                ChainWithExplorer::Registered(local_devnet),
+                ChainWithExplorer::Registered(my_mainnet)
+            ]
+        );
+        assert!(!chains.contains(&ChainWithExplorer::Network(
+            NetworkWithExplorer::EthereumMainnet
+        )));
+        assert_eq!(chains.len(), NetworkWithExplorer::iter().count() + 1);
+    }
+
     #[test]
     fn network_deserialize() {
         let names = r#"["ethereum-mainnet", "polygon"]"#;
@@ -682,9 +791,7 @@ mod test {
         let defined_networks = GraphNetwork::iter().collect::<Vec<_>>();
 
         for n in defined_networks {
-            let included_in_supported_networks = supported_graph_networks
-                .iter()
-                .any(|sn| &n == sn);
+            let included_in_supported_networks = supported_graph_networks.iter().any(|sn| &n == sn);
             assert!(
                 included_in_supported_networks,
                 "expected {:?} to be included",
@@ -0,0 +1,261 @@
+use super::chain_helpers::ChainTier;
+use crate::project_paths::ParsedProjectPaths;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+pub const CHAINS_FILE_NAME: &str = "chains.yaml";
+
+/// A chain declared in a chains.yaml file. It extends (or overrides) the
+/// chains known by the hardcoded chain_helpers::Network enum.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainDefinition {
+    pub id: u64,
+    pub name: String,
+    pub hypersync_url: Option<String>,
+    pub confirmed_block_threshold: Option<i32>,
+    /// The url of an Etherscan compatible block explorer api (eg. Blockscout),
+    /// used to import verified contracts with envio init
+    pub block_explorer: Option<String>,
+    pub tier: Option<ChainTier>,
+}
+
+impl ChainDefinition {
+    pub fn get_pretty_name(&self) -> String {
+        match &self.tier {
+            Some(tier) => format!("{} {}", self.name, tier.get_icon()),
+            None => self.name.clone(),
+        }
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.tier.as_ref().is_none_or(|tier| tier.is_public())
+    }
+}
+
+impl fmt::Display for ChainDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_pretty_name())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainsFile {
+    chains: Vec<ChainDefinition>,
+}
+
+/// Chains declared by the user in a chains.yaml file at the project root
+/// or at ~/.envio/chains.yaml. Project level definitions take precedence
+/// over user level ones with the same id.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChainRegistry {
+    chains: Vec<ChainDefinition>,
+}
+
+impl ChainRegistry {
+    pub fn load(project_paths: &ParsedProjectPaths) -> Result<Self> {
+        let user_registry = Self::load_user(project_paths.user_chains_path.as_deref())?;
+        let project_registry = Self::from_file(&Self::get_project_chains_path(project_paths))?;
+        Ok(user_registry.merge(project_registry))
+    }
+
+    ///The path of the project level registry at the project root
+    pub fn get_project_chains_path(project_paths: &ParsedProjectPaths) -> PathBuf {
+        project_paths.project_root.join(CHAINS_FILE_NAME)
+    }
+
+    pub fn load_user(user_chains_path: Option<&Path>) -> Result<Self> {
+        match user_chains_path {
+            Some(path) => Self::from_file(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    ///The path of the user level registry at ~/.envio/chains.yaml
+    pub fn get_user_chains_path() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME")?;
+        Some(PathBuf::from(home).join(".envio").join(CHAINS_FILE_NAME))
+    }
+
+    fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let chains_string = std::fs::read_to_string(path).context(format!(
+            "Failed to read chains registry at {}",
+            path.display()
+        ))?;
+        Self::from_yaml_str(&chains_string).context(format!(
+            "Failed to parse chains registry at {}",
+            path.display()
+        ))
+    }
+
+    pub fn from_yaml_str(chains_string: &str) -> Result<Self> {
+        let chains_file: ChainsFile = serde_yaml::from_str(chains_string).context(
+            "EE119: Failed to deserialize chains registry. Every chain requires an id and a name",
+        )?;
+
+        let mut registry = Self::default();
+        for chain in chains_file.chains {
+            if registry.get(chain.id).is_some() {
+                return Err(anyhow!(
+                    "EE119: The chain id {} is defined more than once in the chains registry",
+                    chain.id
+                ));
+            }
+            for (url_name, url) in [
+                ("HyperSync", &chain.hypersync_url),
+                ("block explorer", &chain.block_explorer),
+            ] {
+                if let Some(url) = url {
+                    if !url.starts_with("http://") && !url.starts_with("https://") {
+                        return Err(anyhow!(
+                            "EE119: The {url_name} url \"{}\" of the chain {} is incorrect \
+                             format. The {url_name} url needs to start with either http:// or \
+                             https://",
+                            url,
+                            chain.id
+                        ));
+                    }
+                }
+            }
+            registry.chains.push(chain);
+        }
+        Ok(registry)
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for chain in other.chains {
+            match self.chains.iter_mut().find(|c| c.id == chain.id) {
+                Some(existing) => *existing = chain,
+                None => self.chains.push(chain),
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ChainDefinition> {
+        self.chains.iter().find(|c| c.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ChainDefinition> {
+        self.chains.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChainDefinition, ChainRegistry, CHAINS_FILE_NAME};
+    use crate::{config_parsing::chain_helpers::ChainTier, project_paths::ParsedProjectPaths};
+    use pretty_assertions::assert_eq;
+
+    const CHAINS: &str = r#"
+chains:
+  - id: 1337
+    name: local-devnet
+    hypersync_url: http://localhost:1337
+    confirmed_block_threshold: 0
+    block_explorer: http://localhost:4000/api
+    tier: STONE
+  - id: 1
+    name: ethereum-mainnet
+    hypersync_url: https://eth.my-hypersync.xyz
+"#;
+
+    #[test]
+    fn parses_chains_registry() {
+        let registry = ChainRegistry::from_yaml_str(CHAINS).unwrap();
+
+        assert_eq!(
+            registry.get(1337),
+            Some(&ChainDefinition {
+                id: 1337,
+                name: "local-devnet".to_string(),
+                hypersync_url: Some("http://localhost:1337".to_string()),
+                confirmed_block_threshold: Some(0),
+                block_explorer: Some("http://localhost:4000/api".to_string()),
+                tier: Some(ChainTier::Stone),
+            })
+        );
+        assert_eq!(
+            registry.get(1337).unwrap().get_pretty_name(),
+            "local-devnet 🪨"
+        );
+        assert_eq!(registry.get(10), None);
+    }
+
+    #[test]
+    fn project_registry_overrides_user_registry() {
+        let user_registry = ChainRegistry::from_yaml_str(CHAINS).unwrap();
+        let project_registry = ChainRegistry::from_yaml_str(
+            r#"
+chains:
+  - id: 1337
+    name: project-devnet
+"#,
+        )
+        .unwrap();
+
+        let registry = user_registry.merge(project_registry);
+
+        assert_eq!(registry.get(1337).unwrap().name, "project-devnet");
+        assert_eq!(registry.get(1337).unwrap().hypersync_url, None);
+        assert_eq!(registry.iter().count(), 2);
+    }
+
+    #[test]
+    fn loads_registries_from_project_paths() {
+        let dir = tempdir::TempDir::new("chain_registry_test").unwrap();
+        let project_root = dir.path().join("project");
+        let user_chains_path = dir.path().join(CHAINS_FILE_NAME);
+        std::fs::create_dir(&project_root).unwrap();
+        std::fs::write(&user_chains_path, CHAINS).unwrap();
+        std::fs::write(
+            project_root.join(CHAINS_FILE_NAME),
+            r#"
+chains:
+  - id: 1337
+    name: project-devnet
+"#,
+        )
+        .unwrap();
+
+        let project_paths =
+            ParsedProjectPaths::default_with_root(project_root.to_str().unwrap()).unwrap();
+
+        let registry = ChainRegistry::load(&project_paths).unwrap();
+        assert_eq!(registry.get(1337).unwrap().name, "project-devnet");
+        assert_eq!(registry.iter().count(), 1);
+
+        let registry =
+            ChainRegistry::load(&project_paths.with_user_chains_path(Some(user_chains_path)))
+                .unwrap();
+        assert_eq!(registry.get(1337).unwrap().name, "project-devnet");
+        assert_eq!(registry.iter().count(), 2);
+    }
+
+    #[test]
+    fn fails_with_duplicate_chain_ids() {
+        let error = ChainRegistry::from_yaml_str(
+            r#"
+chains:
+  - id: 1337
+    name: a
+  - id: 1337
+    name: b
+"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "EE119: The chain id 1337 is defined more than once in the chains registry"
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::env;
 
 use crate::{
     cli_args::interactive_init::validation::filter_duplicate_events,
-    config_parsing::chain_helpers::NetworkWithExplorer, evm::address::Address,
+    config_parsing::chain_helpers::ChainWithExplorer, evm::address::Address,
 };
 use anyhow::{anyhow, Context};
 use async_recursion::async_recursion;
@@ -37,30 +37,119 @@ enum ContractImportResponse {
     },
 }
 
+///Response of the getsourcecode action of Etherscan compatible explorer apis
+#[derive(Deserialize, Debug)]
+struct ExplorerSourceCodeResponse {
+    result: ExplorerSourceCodeResult,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ExplorerSourceCodeResult {
+    Contracts(Vec<ExplorerContract>),
+    Error(String),
+}
+
+#[derive(Deserialize, Debug)]
+struct ExplorerContract {
+    #[serde(rename = "ABI")]
+    abi: String,
+    #[serde(rename = "ContractName")]
+    name: Option<String>,
+}
+
+const EXPLORER_NOT_VERIFIED_ABI: &str = "Contract source code not verified";
+
+fn parse_contract_data(name: Option<String>, abi: &str) -> anyhow::Result<ContractData> {
+    let mut abi: ethers::abi::Contract =
+        serde_json::from_str(abi).context("Failed parsing contract ABI")?;
+
+    abi.events = filter_duplicate_events(abi.events);
+
+    Ok(ContractData { name, abi })
+}
+
 #[async_recursion]
-pub async fn contract_import(
-    network: &NetworkWithExplorer,
-    address: &Address,
-    retry: u64,
-) -> anyhow::Result<ContractImportResult> {
-    let api_url = env::var("ENVIO_API_URL").unwrap_or("https://envio.dev/api".to_string());
-    let response: reqwest::Response = match reqwest::get(format!(
-        "{api_url}/hyperindex/contract-import?chain={}&address={}",
-        *network as u64,
-        address.to_checksum_hex_string()
-    ))
-    .await
-    {
-        Ok(response) => response,
+async fn fetch_with_retries(url: &str, retry: u64) -> anyhow::Result<reqwest::Response> {
+    match reqwest::get(url).await {
+        Ok(response) => Ok(response),
         Err(err) => {
             // Just a few retries in case of a bad internet connection
             if retry > 2 {
                 return Err(anyhow!("Failed to fetch contract import. {}", err));
             }
             tokio::time::sleep(Duration::from_secs(2)).await;
-            return contract_import(network, address, retry + 1).await;
+            fetch_with_retries(url, retry + 1).await
         }
-    };
+    }
+}
+
+pub async fn contract_import(
+    chain: &ChainWithExplorer,
+    address: &Address,
+) -> anyhow::Result<ContractImportResult> {
+    match chain {
+        ChainWithExplorer::Registered(chain_definition) => {
+            let block_explorer = chain_definition.block_explorer.as_ref().ok_or(anyhow!(
+                "The chain {chain_definition} doesn't have a block explorer"
+            ))?;
+            explorer_contract_import(block_explorer, address).await
+        }
+        ChainWithExplorer::Network(_) => envio_contract_import(chain.get_id(), address).await,
+    }
+}
+
+///Gets the verified contract from an Etherscan compatible block explorer api
+async fn explorer_contract_import(
+    block_explorer: &str,
+    address: &Address,
+) -> anyhow::Result<ContractImportResult> {
+    let url = reqwest::Url::parse_with_params(
+        block_explorer,
+        &[
+            ("module", "contract"),
+            ("action", "getsourcecode"),
+            ("address", &address.to_checksum_hex_string()),
+        ],
+    )
+    .context(format!("Invalid block explorer url {block_explorer}"))?;
+
+    let response: ExplorerSourceCodeResponse = fetch_with_retries(url.as_str(), 0)
+        .await?
+        .json()
+        .await
+        .context("Failed to parse the block explorer response")?;
+
+    match response.result {
+        ExplorerSourceCodeResult::Contracts(contracts) => match contracts.into_iter().next() {
+            Some(contract) if contract.abi != EXPLORER_NOT_VERIFIED_ABI => {
+                let name = contract.name.filter(|name| !name.is_empty());
+                Ok(ContractImportResult::Contract(parse_contract_data(
+                    name,
+                    &contract.abi,
+                )?))
+            }
+            _ => Ok(ContractImportResult::NotVerified),
+        },
+        ExplorerSourceCodeResult::Error(message) => Err(anyhow!(
+            "Failed to fetch the contract from the block explorer. {message}"
+        )),
+    }
+}
+
+async fn envio_contract_import(
+    chain_id: u64,
+    address: &Address,
+) -> anyhow::Result<ContractImportResult> {
+    let api_url = env::var("ENVIO_API_URL").unwrap_or("https://envio.dev/api".to_string());
+    let response = fetch_with_retries(
+        &format!(
+            "{api_url}/hyperindex/contract-import?chain={chain_id}&address={}",
+            address.to_checksum_hex_string()
+        ),
+        0,
+    )
+    .await?;
 
     let contract_import_response: ContractImportResponse = response
         .json()
@@ -68,14 +157,9 @@ pub async fn contract_import(
         .context("Failed to parse Contract Import response")?;
 
     match contract_import_response {
-        ContractImportResponse::Contract { name, abi } => {
-            let mut abi: ethers::abi::Contract =
-                serde_json::from_str(&abi).context("Failed parsing contract ABI")?;
-
-            abi.events = filter_duplicate_events(abi.events);
-
-            Ok(ContractImportResult::Contract(ContractData { name, abi }))
-        }
+        ContractImportResponse::Contract { name, abi } => Ok(ContractImportResult::Contract(
+            parse_contract_data(name, &abi)?,
+        )),
         ContractImportResponse::Error { tag } => {
             if tag == Some("NotVerified".to_string()) {
                 Ok(ContractImportResult::NotVerified)
@@ -87,3 +171,78 @@ pub async fn contract_import(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{contract_import, ContractImportResult};
+    use crate::{
+        config_parsing::{chain_helpers::ChainWithExplorer, chain_registry::ChainRegistry},
+        evm::address::Address,
+    };
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    ///Serves a single request with the given json body and returns the requested path
+    fn serve_json_once(listener: TcpListener, body: String) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+                 {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request.lines().next().unwrap().to_string()
+        })
+    }
+
+    #[tokio::test]
+    async fn imports_contract_from_registered_chain_explorer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let abi = r#"[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true}]}]"#;
+        let server = serve_json_once(
+            listener,
+            serde_json::json!({
+                "status": "1",
+                "message": "OK",
+                "result": [{ "ABI": abi, "ContractName": "MyToken" }]
+            })
+            .to_string(),
+        );
+
+        let chain_registry = ChainRegistry::from_yaml_str(&format!(
+            r#"
+chains:
+  - id: 1337
+    name: local-devnet
+    block_explorer: http://127.0.0.1:{port}/api
+"#
+        ))
+        .unwrap();
+        let chain = ChainWithExplorer::from_id(1337, &chain_registry).unwrap();
+        let address = Address::new("0x2E645469f354BB4F5c8a05B3b30A929361cf77eC").unwrap();
+
+        let result = contract_import(&chain, &address).await.unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            "GET /api?module=contract&action=getsourcecode&\
+             address=0x2E645469f354BB4F5c8a05B3b30A929361cf77eC HTTP/1.1"
+        );
+        match result {
+            ContractImportResult::Contract(contract_data) => {
+                assert_eq!(contract_data.name, Some("MyToken".to_string()));
+                assert!(contract_data.abi.event("Transfer").is_ok());
+            }
+            _ => panic!("Expected the contract to be imported from the block explorer"),
+        }
+    }
+}
@@ -7,7 +7,9 @@ use super::{
 };
 use crate::{
     constants::project_paths::DEFAULT_SCHEMA_PATH,
-    hbs_templating::codegen_templates::DerivedFieldTemplate,
+    hbs_templating::{
+        codegen_templates::DerivedFieldTemplate, hbs_dir_generator::to_string_literal,
+    },
     project_paths::{path_utils, ParsedProjectPaths},
     rescript_types::RescriptTypeIdent,
     utils::{text::Capitalize, unique_hashmap},
@@ -15,8 +17,8 @@ use crate::{
 use anyhow::{anyhow, Context};
 use ethers::abi::ethabi::ParamType as EthAbiParamType;
 use graphql_parser::schema::{
-    Definition, Directive, Document, EnumType, Field as ObjField, ObjectType, Type as ObjType,
-    TypeDefinition, Value,
+    Definition, Directive, Document, EnumType, Field as ObjField, InterfaceType, ObjectType,
+    Type as ObjType, TypeDefinition, UnionType, Value,
 };
 use itertools::Itertools;
 use serde::{Serialize, Serializer};
@@ -31,11 +33,36 @@ use subenum::subenum;
 pub struct Schema {
     pub entities: HashMap<String, Entity>,
     pub enums: HashMap<String, GraphQLEnum>,
+    pub interfaces: HashMap<String, GraphQLInterface>,
+    pub unions: HashMap<String, GraphQLUnion>,
+    pub aggregations: HashMap<String, Aggregation>,
 }
 
 enum TypeDef<'a> {
     Entity(&'a Entity),
     Enum,
+    Interface(&'a GraphQLInterface),
+    Union(&'a GraphQLUnion),
+}
+
+impl TypeDef<'_> {
+    ///Entities, interfaces and unions are all referenced by id
+    fn is_entity_reference(&self) -> bool {
+        match self {
+            Self::Entity(_) | Self::Interface(_) | Self::Union(_) => true,
+            Self::Enum => false,
+        }
+    }
+
+    ///The name of the table or view a reference to this type def links to
+    fn get_linked_name(&self) -> Option<String> {
+        match self {
+            Self::Entity(entity) => Some(entity.name.clone()),
+            Self::Interface(interface) => Some(interface.name.clone()),
+            Self::Union(union) => Some(union.name.clone()),
+            Self::Enum => None,
+        }
+    }
 }
 
 impl Schema {
@@ -43,10 +70,19 @@ impl Schema {
         Schema {
             entities: HashMap::new(),
             enums: HashMap::new(),
+            interfaces: HashMap::new(),
+            unions: HashMap::new(),
+            aggregations: HashMap::new(),
         }
     }
 
-    pub fn new(entities: Vec<Entity>, enums: Vec<GraphQLEnum>) -> anyhow::Result<Self> {
+    pub fn new(
+        entities: Vec<Entity>,
+        enums: Vec<GraphQLEnum>,
+        interfaces: Vec<GraphQLInterface>,
+        unions: Vec<GraphQLUnion>,
+        aggregations: Vec<Aggregation>,
+    ) -> anyhow::Result<Self> {
         let entities = unique_hashmap::from_vec_no_duplicates(
             entities.into_iter().map(|e| (e.name.clone(), e)).collect(),
         )
@@ -55,12 +91,37 @@ impl Schema {
             enums.into_iter().map(|e| (e.name.clone(), e)).collect(),
         )
         .context("Found enums with duplicate names")?;
-
-        Self { entities, enums }.validate()
+        let interfaces = unique_hashmap::from_vec_no_duplicates(
+            interfaces
+                .into_iter()
+                .map(|i| (i.name.clone(), i))
+                .collect(),
+        )
+        .context("Found interfaces with duplicate names")?;
+        let unions = unique_hashmap::from_vec_no_duplicates(
+            unions.into_iter().map(|u| (u.name.clone(), u)).collect(),
+        )
+        .context("Found unions with duplicate names")?;
+        let aggregations = unique_hashmap::from_vec_no_duplicates(
+            aggregations
+                .into_iter()
+                .map(|a| (a.entity.name.clone(), a))
+                .collect(),
+        )
+        .context("Found aggregations with duplicate names")?;
+
+        Self {
+            entities,
+            enums,
+            interfaces,
+            unions,
+            aggregations,
+        }
+        .validate()
     }
 
-    fn from_document(document: Document<String>) -> anyhow::Result<Self> {
-        let entities = document
+    pub(crate) fn from_document(document: Document<String>) -> anyhow::Result<Self> {
+        let (schema_objects, entity_objects): (Vec<_>, Vec<_>) = document
             .definitions
             .iter()
             .filter_map(|d| match d {
@@ -71,10 +132,77 @@ impl Schema {
                 TypeDefinition::Object(obj) => Some(obj),
                 _ => None,
             })
+            .partition(|obj| obj.name == FulltextSearch::SUBGRAPH_SCHEMA_TYPE_NAME);
+
+        let (aggregation_objects, entity_objects): (Vec<_>, Vec<_>) =
+            entity_objects.into_iter().partition(|obj| {
+                obj.directives
+                    .iter()
+                    .any(|directive| directive.name == Aggregation::DIRECTIVE_NAME)
+            });
+
+        let aggregations = aggregation_objects
+            .into_iter()
+            .map(|obj| Aggregation::from_object(obj))
+            .collect::<anyhow::Result<Vec<Aggregation>>>()
+            .context("Failed constructing aggregations in schema from document")?;
+
+        let mut entities = entity_objects
+            .into_iter()
             .map(|obj| Entity::from_object(obj))
             .collect::<anyhow::Result<Vec<Entity>>>()
             .context("Failed constructing entities in schema from document")?;
 
+        //Full text searches defined the subgraph way on the _Schema_ type
+        for directive in schema_objects
+            .iter()
+            .flat_map(|obj| obj.directives.iter())
+            .filter(|directive| directive.name == "fulltext")
+        {
+            let (entity_name, fulltext_search) =
+                FulltextSearch::from_subgraph_directive(directive)?;
+            let entity = entities
+                .iter_mut()
+                .find(|entity| entity.name == entity_name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "EE223: The entity '{entity_name}' included in the full text search '{}' \
+                         does not exist",
+                        fulltext_search.name
+                    )
+                })?;
+            entity.add_fulltext_search(fulltext_search)?;
+        }
+
+        let interfaces = document
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::TypeDefinition(type_def) => Some(type_def),
+                _ => None,
+            })
+            .filter_map(|type_def| match type_def {
+                TypeDefinition::Interface(interface) => Some(interface),
+                _ => None,
+            })
+            .map(|interface| GraphQLInterface::from_interface(interface))
+            .collect::<anyhow::Result<Vec<GraphQLInterface>>>()
+            .context("Failed constructing interfaces in schema from document")?;
+
+        let unions = document
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::TypeDefinition(type_def) => Some(type_def),
+                _ => None,
+            })
+            .filter_map(|type_def| match type_def {
+                TypeDefinition::Union(union) => Some(union),
+                _ => None,
+            })
+            .map(|union| GraphQLUnion::from_union(union))
+            .collect::<Vec<GraphQLUnion>>();
+
         let enums = document
             .definitions
             .iter()
@@ -90,7 +218,7 @@ impl Schema {
             .collect::<anyhow::Result<Vec<GraphQLEnum>>>()
             .context("Failed constructing enums in schema from document")?;
 
-        Self::new(entities, enums)
+        Self::new(entities, enums, interfaces, unions, aggregations)
     }
 
     pub fn parse_from_file(
@@ -124,8 +252,14 @@ impl Schema {
         self.check_enum_type_defs()?
             .check_schema_for_reserved_words()?
             .check_duplicate_naming_between_enums_and_entities()?
+            .check_duplicate_naming_of_interfaces_and_unions()?
+            .check_fulltext_search_names()?
+            .validate_aggregations()?
             .check_related_type_defs_exist()?
-            .validate_entity_field_types()
+            .validate_entity_field_types()?
+            .resolve_index_predicates()?
+            .validate_interface_implementations()?
+            .validate_union_types()
     }
 
     fn get_all_enum_type_names(&self) -> Vec<String> {
@@ -137,6 +271,13 @@ impl Schema {
     fn get_all_entity_type_names(&self) -> Vec<String> {
         self.entities.keys().cloned().collect()
     }
+    fn get_all_interface_and_union_names(&self) -> Vec<String> {
+        self.interfaces
+            .keys()
+            .chain(self.unions.keys())
+            .cloned()
+            .collect()
+    }
     fn get_all_entity_field_names(&self) -> Vec<String> {
         self.entities
             .values()
@@ -161,6 +302,7 @@ impl Schema {
             self.get_all_enum_values(),
             self.get_all_entity_type_names(),
             self.get_all_entity_field_names(),
+            self.get_all_interface_and_union_names(),
         ]
         .concat();
 
@@ -190,15 +332,109 @@ impl Schema {
         }
     }
 
+    fn check_duplicate_naming_of_interfaces_and_unions(self) -> anyhow::Result<Self> {
+        let mut type_names: HashSet<String> = [
+            self.get_all_enum_type_names(),
+            self.get_all_entity_type_names(),
+        ]
+        .concat()
+        .into_iter()
+        .collect();
+
+        let duplicate_names = self
+            .interfaces
+            .keys()
+            .chain(self.unions.keys())
+            .sorted()
+            .filter(|name| !type_names.insert(name.to_string()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !duplicate_names.is_empty() {
+            Err(anyhow!(
+                "EE214: Schema contains the following interfaces or unions with the same name as \
+                 another type definition, all type definitions must be unique in the schema: {}",
+                duplicate_names.join(", ")
+            ))
+        } else {
+            Ok(self)
+        }
+    }
+
+    ///Full text searches are exposed as functions next to the entity tables,
+    ///so their names can't clash with each other or with other type definitions
+    fn check_fulltext_search_names(self) -> anyhow::Result<Self> {
+        let mut names: HashSet<String> = [
+            self.get_all_enum_type_names(),
+            self.get_all_entity_type_names(),
+            self.get_all_interface_and_union_names(),
+        ]
+        .concat()
+        .into_iter()
+        .collect();
+
+        let duplicate_names = self
+            .entities
+            .values()
+            .flat_map(|entity| entity.fulltext_searches.iter())
+            .map(|fulltext_search| &fulltext_search.name)
+            .sorted()
+            .filter(|name| !names.insert(name.to_string()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !duplicate_names.is_empty() {
+            Err(anyhow!(
+                "EE223: Schema contains the following full text searches with the same name as \
+                 another full text search or type definition: {}",
+                duplicate_names.join(", ")
+            ))
+        } else {
+            Ok(self)
+        }
+    }
+
+    fn validate_aggregations(self) -> anyhow::Result<Self> {
+        let type_names: HashSet<String> = [
+            self.get_all_enum_type_names(),
+            self.get_all_entity_type_names(),
+            self.get_all_interface_and_union_names(),
+        ]
+        .concat()
+        .into_iter()
+        .collect();
+
+        for aggregation in self.aggregations.values().sorted_by_key(|a| &a.entity.name) {
+            if type_names.contains(&aggregation.entity.name) {
+                return Err(anyhow!(
+                    "EE224: The aggregation {} has the same name as another type definition, all \
+                     type definitions must be unique in the schema",
+                    aggregation.entity.name
+                ));
+            }
+            aggregation
+                .validate_source(&self)
+                .context(format!("Invalid aggregation {}", aggregation.entity.name))?;
+        }
+        Ok(self)
+    }
+
     fn try_get_type_def(&self, name: &String) -> anyhow::Result<TypeDef> {
-        match (self.entities.get(name), self.enums.get(name)) {
-            (None, None) => Err(anyhow!("No type definition '{}' exists in schema", name)),
-            (Some(_), Some(_)) => Err(anyhow!(
-                "Both an enum and an entity type definition '{}' exist in schema",
+        match (
+            self.entities.get(name),
+            self.enums.get(name),
+            self.interfaces.get(name),
+            self.unions.get(name),
+        ) {
+            (None, None, None, None) => {
+                Err(anyhow!("No type definition '{}' exists in schema", name))
+            }
+            (Some(entity), None, None, None) => Ok(TypeDef::Entity(entity)),
+            (None, Some(_), None, None) => Ok(TypeDef::Enum),
+            (None, None, Some(interface), None) => Ok(TypeDef::Interface(interface)),
+            (None, None, None, Some(union)) => Ok(TypeDef::Union(union)),
+            _ => Err(anyhow!(
+                "Multiple type definitions with the name '{}' exist in schema",
                 name
             )),
-            (Some(entity), None) => Ok(TypeDef::Entity(entity)),
-            (None, Some(_)) => Ok(TypeDef::Enum),
         }
     }
 
@@ -216,10 +452,13 @@ impl Schema {
                         let type_def = self.try_get_type_def(name)?;
 
                         match type_def {
-                            TypeDef::Enum => Err(anyhow!(
-                                "Cannot derive field {derived_from_field} from enum {name}. \
-                                 derivedFrom is intended to be used with Entity type definitions"
-                            ))?,
+                            TypeDef::Enum | TypeDef::Interface(_) | TypeDef::Union(_) => {
+                                Err(anyhow!(
+                                    "Cannot derive field {derived_from_field} from {name}. \
+                                     derivedFrom is intended to be used with Entity type \
+                                     definitions"
+                                ))?
+                            }
                             TypeDef::Entity(derived_entity) => {
                                 match derived_entity.fields.get(derived_from_field) {
                                     None => Err(anyhow!(
@@ -255,8 +494,226 @@ impl Schema {
         for e in self.entities.values() {
             e.validate_field_types(&self)?;
         }
+        for i in self.interfaces.values() {
+            for field in i.get_fields() {
+                field.validate_field_type(&self)?;
+            }
+        }
+        Ok(self)
+    }
+
+    ///Quotes the columns referenced in the where predicates of partial indexes,
+    ///so they match the camelCase column names and the _id columns of relations
+    fn resolve_index_predicates(mut self) -> anyhow::Result<Self> {
+        let mut resolved_predicates = vec![];
+        for entity in self.entities.values() {
+            let column_names = entity
+                .get_fields()
+                .iter()
+                .filter_map(|field| match &field.field_type {
+                    FieldType::DerivedFromField { .. } => None,
+                    FieldType::RegularField { field_type, .. } => {
+                        Some(field_type.get_linked_entity(&self).map(|linked_entity| {
+                            match linked_entity {
+                                Some(_) => (field.name.clone(), format!("{}_id", field.name)),
+                                None => (field.name.clone(), field.name.clone()),
+                            }
+                        }))
+                    }
+                })
+                .chain([Ok((
+                    "db_write_timestamp".to_string(),
+                    "db_write_timestamp".to_string(),
+                ))])
+                .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+            for (index_position, index) in entity.multi_field_indexes.iter().enumerate() {
+                if let Some(predicate) = &index.where_predicate {
+                    let predicate = quote_where_predicate_columns(predicate, &column_names)
+                        .context(format!(
+                            "Failed parsing the where predicate of the index on fields {} in \
+                             entity '{}'",
+                            index.field_names.join(", "),
+                            entity.name
+                        ))?;
+                    resolved_predicates.push((entity.name.clone(), index_position, predicate));
+                }
+            }
+        }
+
+        for (entity_name, index_position, predicate) in resolved_predicates {
+            if let Some(entity) = self.entities.get_mut(&entity_name) {
+                entity.multi_field_indexes[index_position].where_predicate = Some(predicate);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Validate that every entity implementing an interface defines all the
+    /// fields of the interface with the same types, so the interface can be
+    /// queried as a union of the entity tables.
+    fn validate_interface_implementations(self) -> anyhow::Result<Self> {
+        for entity in self.entities.values() {
+            for interface_name in &entity.implements {
+                let interface = self.interfaces.get(interface_name).ok_or_else(|| {
+                    anyhow!(
+                        "EE218: Entity '{}' implements '{}', which is not an interface defined in \
+                         the schema",
+                        entity.name,
+                        interface_name
+                    )
+                })?;
+
+                for interface_field in interface.get_fields() {
+                    match entity.fields.get(&interface_field.name) {
+                        None => Err(anyhow!(
+                            "EE218: Entity '{}' implements the interface '{}' but doesn't define \
+                             the field '{}'",
+                            entity.name,
+                            interface.name,
+                            interface_field.name
+                        ))?,
+                        Some(field) if !field.has_same_type(interface_field) => Err(anyhow!(
+                            "EE218: The field '{}' on entity '{}' must have the type '{}' defined \
+                             by the interface '{}'",
+                            field.name,
+                            entity.name,
+                            interface_field.field_type,
+                            interface.name
+                        ))?,
+                        Some(_) => (),
+                    }
+                }
+            }
+        }
+
+        for interface in self.interfaces.values() {
+            if self.get_interface_implementations(interface).is_empty() {
+                return Err(anyhow!(
+                    "EE218: The interface '{}' is not implemented by any entity",
+                    interface.name
+                ));
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn validate_union_types(self) -> anyhow::Result<Self> {
+        for union in self.unions.values() {
+            for type_name in &union.types {
+                if !self.entities.contains_key(type_name) {
+                    return Err(anyhow!(
+                        "EE218: The union '{}' contains '{}', union members must be entities \
+                         defined in the schema",
+                        union.name,
+                        type_name
+                    ));
+                }
+            }
+        }
         Ok(self)
     }
+
+    /// Returns the entities implementing the given interface sorted by name.
+    pub fn get_interface_implementations(&self, interface: &GraphQLInterface) -> Vec<&Entity> {
+        self.entities
+            .values()
+            .filter(|entity| entity.implements.contains(&interface.name))
+            .sorted_by_key(|entity| &entity.name)
+            .collect()
+    }
+}
+
+/// An interface is stored as a view over the tables of the implementing
+/// entities, with an additional column holding the entity name of each row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQLInterface {
+    pub name: String,
+    pub fields: HashMap<String, Field>,
+}
+
+impl GraphQLInterface {
+    pub const ENTITY_TYPE_FIELD_NAME: &'static str = "entity_type";
+
+    fn new(name: &str, fields: Vec<Field>) -> anyhow::Result<Self> {
+        let fields = unique_hashmap::from_vec_no_duplicates(
+            fields.into_iter().map(|f| (f.name.clone(), f)).collect(),
+        )
+        .context(format!(
+            "Found fields with duplicate names on interface {name}"
+        ))?;
+
+        if !fields.contains_key("id") {
+            return Err(anyhow!(
+                "EE218: No 'id' field found on interface {}. Please add an 'id' field to your \
+                 interface.",
+                name
+            ));
+        }
+
+        if fields.contains_key(Self::ENTITY_TYPE_FIELD_NAME) {
+            return Err(anyhow!(
+                "EE218: The field name '{}' is reserved on interface {}",
+                Self::ENTITY_TYPE_FIELD_NAME,
+                name
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            fields,
+        })
+    }
+
+    fn from_interface(interface: &InterfaceType<String>) -> anyhow::Result<Self> {
+        let name = &interface.name;
+        let fields = interface
+            .fields
+            .iter()
+            .map(Field::from_obj_field)
+            .collect::<anyhow::Result<Vec<Field>>>()
+            .context(format!("Failed parsing fields on interface {name}"))?;
+
+        Self::new(name, fields)
+    }
+
+    /// Returns the fields of this [`GraphQLInterface`] sorted by field name.
+    pub fn get_fields(&self) -> Vec<&Field> {
+        self.fields.values().sorted_by_key(|v| &v.name).collect()
+    }
+
+    /// Returns the db column names of the fields stored on the implementing
+    /// entities (derived fields are not part of the view).
+    pub fn get_db_field_names(&self, schema: &Schema) -> anyhow::Result<Vec<String>> {
+        self.get_fields()
+            .into_iter()
+            .filter(|field| !field.field_type.is_derived_from())
+            .map(|field| {
+                Ok(if field.field_type.is_entity_field(schema)? {
+                    format!("{}_id", field.name)
+                } else {
+                    field.name.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// A union is stored as a view over the ids of the member entities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQLUnion {
+    pub name: String,
+    pub types: Vec<String>,
+}
+
+impl GraphQLUnion {
+    fn from_union(union: &UnionType<String>) -> Self {
+        Self {
+            name: union.name.clone(),
+            types: union.types.iter().sorted().cloned().collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -330,6 +787,12 @@ pub struct Entity {
     pub name: String,
     pub fields: HashMap<String, Field>,
     pub multi_field_indexes: Vec<MultiFieldIndex>,
+    pub unique_constraints: Vec<UniqueConstraint>,
+    pub fulltext_searches: Vec<FulltextSearch>,
+    pub implements: Vec<String>,
+    pub is_immutable: bool,
+    ///Timeseries rows are insert only and can be rolled up by aggregations
+    pub is_timeseries: bool,
 }
 
 impl Entity {
@@ -337,6 +800,10 @@ impl Entity {
         name: &str,
         fields: Vec<Field>,
         multi_field_indexes: Vec<MultiFieldIndex>,
+        unique_constraints: Vec<UniqueConstraint>,
+        fulltext_searches: Vec<FulltextSearch>,
+        implements: Vec<String>,
+        is_immutable: bool,
     ) -> anyhow::Result<Self> {
         let fields = unique_hashmap::from_vec_no_duplicates(
             fields.into_iter().map(|f| (f.name.clone(), f)).collect(),
@@ -355,6 +822,7 @@ impl Entity {
                         &["db_write_timestamp".to_string()],
                     )?
                     .validate_no_index_on_derived_field(&fields)?
+                    .validate_options(&fields)?
                     .validate_no_index_on_id_field()
             })
             .collect::<anyhow::Result<Vec<_>>>()
@@ -373,44 +841,253 @@ impl Entity {
             }
         }
 
-        Ok(Self {
+        //Custom indexes with the same fields, method and order would get the same name
+        let mut custom_indexes_set = HashSet::new();
+        for custom_index in multi_field_indexes
+            .iter()
+            .filter(|index| !index.is_default())
+        {
+            let is_new_insert = custom_indexes_set.insert((
+                custom_index.get_field_names(),
+                custom_index.get_method(),
+                custom_index.get_orders(),
+            ));
+            if !is_new_insert {
+                return Err(anyhow!(
+                    "EE222: Duplicate index found on fields {:?} in entity '{}'. Indexes with the \
+                     same fields, method and order can't be differentiated by their where \
+                     predicate",
+                    custom_index.get_field_names(),
+                    name
+                ));
+            }
+        }
+
+        let unique_constraints = unique_constraints
+            .into_iter()
+            .map(|unique_constraint| {
+                unique_constraint
+                    .validate_no_duplicates()?
+                    .validate_field_names_exist(&fields)?
+                    .validate_no_derived_field(&fields)?
+                    .validate_not_only_id_field()
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context(format!("Invalid unique constraints on Entity {name}"))?;
+
+        let mut unique_constraints_set = HashSet::new();
+        for unique_constraint in &unique_constraints {
+            let is_new_insert = unique_constraints_set.insert(unique_constraint);
+            if !is_new_insert {
+                return Err(anyhow!(
+                    "EE220: Duplicate unique constraint found on fields {:?} in entity '{}'",
+                    unique_constraint.get_field_names(),
+                    name
+                ));
+            }
+        }
+
+        let mut entity = Self {
             name: name.to_string(),
             fields,
             multi_field_indexes,
-        })
+            unique_constraints,
+            fulltext_searches: vec![],
+            implements,
+            is_immutable,
+            is_timeseries: false,
+        };
+        for fulltext_search in fulltext_searches {
+            entity.add_fulltext_search(fulltext_search)?;
+        }
+        Ok(entity)
     }
 
-    fn from_object(obj: &ObjectType<String>) -> anyhow::Result<Self> {
-        let name = &obj.name;
-
-        let has_id = obj.fields.iter().any(|field| field.name == "id");
-        if !has_id {
+    fn with_timeseries(self, is_timeseries: bool) -> anyhow::Result<Self> {
+        if !is_timeseries {
+            return Ok(self);
+        }
+        if self.is_immutable {
             return Err(anyhow!(
-                "No 'id' field found on entity {}. Please add an 'id' field to your entity.",
-                name
+                "EE224: The timeseries entity {} can't also be immutable. Timeseries rows are \
+                 already insert only, please remove the immutable argument",
+                self.name
+            ));
+        }
+        let has_timestamp_field = self.fields.get(TIMESTAMP_FIELD_NAME).is_some_and(|field| {
+            field.field_type.to_user_defined_field_type()
+                == UserDefinedFieldType::NonNullType(Box::new(UserDefinedFieldType::Single(
+                    GqlScalar::Timestamp,
+                )))
+        });
+        if !has_timestamp_field {
+            return Err(anyhow!(
+                "EE224: The timeseries entity {} needs a '{TIMESTAMP_FIELD_NAME}: Timestamp!' \
+                 field",
+                self.name
             ));
         }
+        Ok(Self {
+            is_timeseries,
+            ..self
+        })
+    }
 
-        let multi_field_indexes = obj
-            .directives
+    fn add_fulltext_search(&mut self, fulltext_search: FulltextSearch) -> anyhow::Result<()> {
+        let fulltext_search = fulltext_search
+            .validate_fields(&self.fields)
+            .context(format!("Invalid full text search on Entity {}", self.name))?;
+        self.fulltext_searches.push(fulltext_search);
+        Ok(())
+    }
+
+    /// Parses the @fulltext(name: ..., language: ..., fields: [...]) directives of an object type
+    fn parse_fulltext_searches(obj: &ObjectType<String>) -> anyhow::Result<Vec<FulltextSearch>> {
+        obj.directives
             .iter()
-            .filter(|directive| directive.name == "index")
-            .map(
-                |directive| match directive.arguments.iter().find(|(key, _)| key == "fields") {
-                    Some((_, Value::List(fields))) => {
-                        let index_fields = fields
-                            .iter()
-                            .map(|v| {
-                                if let Value::String(field_name) = v {
-                                    Ok(field_name.clone())
-                                } else {
-                                    Err(anyhow!("Listed index field should be a string"))
-                                }
-                            })
-                            .collect::<anyhow::Result<Vec<String>>>()
+            .filter(|directive| directive.name == "fulltext")
+            .map(FulltextSearch::from_entity_directive)
+            .collect()
+    }
+
+    /// Collects the @unique directives of the fields and the
+    /// @unique(fields: [...]) directives of the entity.
+    /// Like in Postgres, rows with a null value in any of the constraint fields
+    /// never conflict, so nullable fields can repeat as long as one of them is null
+    fn parse_unique_constraints(obj: &ObjectType<String>) -> anyhow::Result<Vec<UniqueConstraint>> {
+        let mut unique_constraints = vec![];
+
+        for field in &obj.fields {
+            let unique_directives = field
+                .directives
+                .iter()
+                .filter(|directive| directive.name == "unique")
+                .collect::<Vec<_>>();
+
+            match unique_directives.as_slice() {
+                [] => (),
+                [directive] if directive.arguments.is_empty() => {
+                    unique_constraints.push(UniqueConstraint::new(vec![field.name.clone()]))
+                }
+                [_] => {
+                    return Err(anyhow!(
+                        "EE220: The @unique directive on field {} doesn't take any arguments",
+                        field.name
+                    ))
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "EE202: Cannot use more than one of the same directive on field {}",
+                        field.name
+                    ))
+                }
+            }
+        }
+
+        for directive in obj
+            .directives
+            .iter()
+            .filter(|directive| directive.name == "unique")
+        {
+            match directive.arguments.as_slice() {
+                [(key, Value::List(fields))] if key == "fields" => {
+                    let unique_fields = fields
+                        .iter()
+                        .map(|v| match v {
+                            Value::String(field_name) => Ok(field_name.clone()),
+                            _ => Err(anyhow!("EE220: Listed unique field should be a string")),
+                        })
+                        .collect::<anyhow::Result<Vec<String>>>()?;
+                    unique_constraints.push(UniqueConstraint::new(unique_fields));
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "EE220: Invalid @unique directive. Please ensure unique has a key of \
+                         fields with a list of strings matching field names in your entity. Eg. \
+                         @unique(fields: [\"fieldA\", \"fieldB\"])"
+                    ))
+                }
+            }
+        }
+
+        Ok(unique_constraints)
+    }
+
+    /// Parses the @entity(immutable: Boolean, timeseries: Boolean) directive of an
+    /// object type and returns whether the entity is immutable and a timeseries.
+    /// Entities without the directive are mutable.
+    fn parse_entity_directive(obj: &ObjectType<String>) -> anyhow::Result<(bool, bool)> {
+        let entity_directives = obj
+            .directives
+            .iter()
+            .filter(|directive| directive.name == "entity")
+            .collect::<Vec<_>>();
+
+        match entity_directives.as_slice() {
+            [] => Ok((false, false)),
+            [directive] => {
+                let mut is_immutable = false;
+                let mut is_timeseries = false;
+                for (key, value) in &directive.arguments {
+                    match (key.as_str(), value) {
+                        ("immutable", Value::Boolean(b)) => is_immutable = *b,
+                        ("timeseries", Value::Boolean(b)) => is_timeseries = *b,
+                        _ => {
+                            return Err(anyhow!(
+                                "EE219: Invalid @entity directive on entity {}. The supported \
+                                 arguments are the booleans immutable and timeseries. Eg. \
+                                 @entity(immutable: true)",
+                                obj.name
+                            ))
+                        }
+                    }
+                }
+                Ok((is_immutable, is_timeseries))
+            }
+            _ => Err(anyhow!(
+                "EE219: Cannot use more than one @entity directive on entity {}",
+                obj.name
+            )),
+        }
+    }
+
+    fn from_object(obj: &ObjectType<String>) -> anyhow::Result<Self> {
+        let name = &obj.name;
+
+        let has_id = obj.fields.iter().any(|field| field.name == "id");
+        if !has_id {
+            return Err(anyhow!(
+                "No 'id' field found on entity {}. Please add an 'id' field to your entity.",
+                name
+            ));
+        }
+
+        let multi_field_indexes = obj
+            .directives
+            .iter()
+            .filter(|directive| directive.name == "index")
+            .map(
+                |directive| match directive.arguments.iter().find(|(key, _)| key == "fields") {
+                    Some((_, Value::List(fields))) => {
+                        let options = directive
+                            .arguments
+                            .iter()
+                            .filter(|(key, _)| key != "fields")
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        let index_fields = fields
+                            .iter()
+                            .map(|v| {
+                                if let Value::String(field_name) = v {
+                                    Ok(field_name.clone())
+                                } else {
+                                    Err(anyhow!("Listed index field should be a string"))
+                                }
+                            })
+                            .collect::<anyhow::Result<Vec<String>>>()
                             .context("Failed to get fields in index")?;
 
-                        Ok(MultiFieldIndex::new(index_fields))
+                        MultiFieldIndex::new(index_fields).with_options(&options)
                     }
                     _ => Err(anyhow!(
                         "Invalid @index directive. Please ensure index has a key of fields with a \
@@ -419,11 +1096,28 @@ impl Entity {
                     )),
                 },
             )
+            //@index directives with arguments on fields are single field indexes
+            .chain(obj.fields.iter().flat_map(|field| {
+                field
+                    .directives
+                    .iter()
+                    .filter(|directive| {
+                        directive.name == "index" && !directive.arguments.is_empty()
+                    })
+                    .map(|directive| {
+                        MultiFieldIndex::new(vec![field.name.clone()])
+                            .with_options(&directive.arguments)
+                    })
+            }))
             .collect::<anyhow::Result<Vec<_>>>()
             .context(format!(
                 "Failed parsing multi field indexes on entity {name}"
             ))?;
 
+        let unique_constraints = Self::parse_unique_constraints(obj).context(format!(
+            "Failed parsing unique constraints on entity {name}"
+        ))?;
+
         // Map each field in the ObjectType to a Field, passing the indexed status
         let fields = obj
             .fields
@@ -434,8 +1128,23 @@ impl Entity {
             .collect::<anyhow::Result<Vec<Field>>>()
             .context(format!("Failed parsing fields on entity {name}"))?;
 
-        let entity = Self::new(name, fields, multi_field_indexes)
-            .context(format!("Failed constructing entity {name}",))?;
+        let fulltext_searches = Self::parse_fulltext_searches(obj).context(format!(
+            "Failed parsing full text searches on entity {name}"
+        ))?;
+
+        let (is_immutable, is_timeseries) = Self::parse_entity_directive(obj)?;
+
+        let entity = Self::new(
+            name,
+            fields,
+            multi_field_indexes,
+            unique_constraints,
+            fulltext_searches,
+            obj.implements_interfaces.clone(),
+            is_immutable,
+        )
+        .context(format!("Failed constructing entity {name}",))?
+        .with_timeseries(is_timeseries)?;
 
         // Here, store indexed information somewhere within your entity structure or handle them accordingly
         Ok(entity)
@@ -484,7 +1193,7 @@ impl Entity {
                         |e| Some(Err(e)),
                         |type_def| match type_def {
                             TypeDef::Entity(entity) => Some(Ok((field, entity))),
-                            TypeDef::Enum => None,
+                            TypeDef::Enum | TypeDef::Interface(_) | TypeDef::Union(_) => None,
                         },
                     )
                 } else {
@@ -514,14 +1223,22 @@ impl Entity {
             .iter()
             .cloned()
             .filter_map(|multi_field_index| {
-                if multi_field_index.0.len() > 1 {
-                    Some(multi_field_index.0)
+                if multi_field_index.is_default() && multi_field_index.field_names.len() > 1 {
+                    Some(multi_field_index.field_names)
                 } else {
                     None
                 }
             })
             .collect()
     }
+
+    ///Returns the indexes with a custom method, order or predicate
+    pub fn get_custom_indices(&self) -> Vec<&MultiFieldIndex> {
+        self.multi_field_indexes
+            .iter()
+            .filter(|multi_field_index| !multi_field_index.is_default())
+            .collect()
+    }
 }
 
 ///  used to get the positive integers in the directives from the GraphQL schema.
@@ -544,6 +1261,7 @@ fn get_positive_integer(arg_value: &Value<String>) -> anyhow::Result<u32> {
 pub struct Field {
     pub name: String,
     pub field_type: FieldType,
+    pub default_value: Option<FieldDefaultValue>,
 }
 
 impl Field {
@@ -567,12 +1285,19 @@ impl Field {
             .filter(|&directive| directive.name == "config")
             .collect::<Vec<&Directive<'_, String>>>();
 
+        let default_directives = field
+            .directives
+            .iter()
+            .filter(|&directive| directive.name == "default")
+            .collect::<Vec<&Directive<'_, String>>>();
+
         // Validate directive usage
         let derived_from_count = derived_from_directives.len();
         let indexed_count = indexed_directives.len();
         let config_count = config_directives.len();
+        let default_count = default_directives.len();
 
-        if derived_from_count > 1 || indexed_count > 1 || config_count > 1 {
+        if derived_from_count > 1 || indexed_count > 1 || config_count > 1 || default_count > 1 {
             return Err(anyhow!(
                 "EE202: Cannot use more than one of the same directive on field {}",
                 field.name
@@ -617,7 +1342,25 @@ impl Field {
             }
         };
 
-        let is_indexed = indexed_count > 0;
+        let default_value = match default_directives.first() {
+            None => None,
+            Some(d) => match d.arguments.as_slice() {
+                [(key, value)] if key == "value" => Some(
+                    FieldDefaultValue::from_value(value)
+                        .context(format!("Invalid @default value on field {}", field.name))?,
+                ),
+                _ => Err(anyhow!(
+                    "EE221: The @default directive on field {} should only take a single argument \
+                     called 'value'. Eg. @default(value: 0)",
+                    field.name
+                ))?,
+            },
+        };
+
+        //An @index with arguments is parsed as an index of the entity
+        let is_indexed = indexed_directives
+            .first()
+            .is_some_and(|directive| directive.arguments.is_empty());
 
         // Parse the field type into UserDefinedFieldType
         let underlying_scalar = UserDefinedFieldType::from_obj_field_type(
@@ -727,6 +1470,7 @@ impl Field {
         Ok(Field {
             name: field.name.clone(),
             field_type,
+            default_value,
         })
     }
 
@@ -738,7 +1482,128 @@ impl Field {
     }
 
     fn validate_field_type(&self, schema: &Schema) -> anyhow::Result<()> {
-        self.field_type.validate_type(schema)
+        self.field_type.validate_type(schema)?;
+        self.get_default_value_codes(schema)?;
+        Ok(())
+    }
+
+    /// Checks the @default value against the type of the field and returns
+    /// it as a Postgres DEFAULT expression and as ReScript code
+    fn get_default_value_codes(&self, schema: &Schema) -> anyhow::Result<Option<(String, String)>> {
+        let Some(default_value) = &self.default_value else {
+            return Ok(None);
+        };
+
+        let field_type = match &self.field_type {
+            FieldType::DerivedFromField { .. } => Err(anyhow!(
+                "EE221: The @derivedFrom field '{}' can't have a default value",
+                self.name
+            ))?,
+            FieldType::RegularField { field_type, .. } => field_type,
+        };
+
+        if field_type.is_array() {
+            return Err(anyhow!(
+                "EE221: The list field '{}' can't have a default value",
+                self.name
+            ));
+        }
+
+        if self.is_primary_key() {
+            return Err(anyhow!(
+                "EE221: The primary key field '{}' can't have a default value",
+                self.name
+            ));
+        }
+
+        let invalid_default_value = || {
+            anyhow!(
+                "EE221: The default value {} of the field '{}' doesn't match its type {}",
+                default_value,
+                self.name,
+                field_type
+            )
+        };
+
+        use FieldDefaultValue as V;
+        let (pg_code, res_code) = match (field_type.get_underlying_scalar(), default_value) {
+            (GqlScalar::ID | GqlScalar::String | GqlScalar::Bytes, V::String(v)) => {
+                (to_pg_string(v), to_string_literal(v))
+            }
+            (GqlScalar::Int, V::Int(v)) if i32::try_from(*v).is_ok() => {
+                (v.to_string(), v.to_string())
+            }
+            (GqlScalar::Float, V::Int(v)) => (v.to_string(), format!("{v}.")),
+            (GqlScalar::Float, V::Float(v)) => {
+                let res_code = if v.contains(['.', 'e']) {
+                    v.clone()
+                } else {
+                    format!("{v}.")
+                };
+                (v.clone(), res_code)
+            }
+            (GqlScalar::Boolean, V::Boolean(v)) => (v.to_string(), v.to_string()),
+            (GqlScalar::BigInt(_), V::Int(v)) => {
+                (v.to_string(), format!("BigInt.fromStringUnsafe(\"{v}\")"))
+            }
+            (GqlScalar::BigInt(_), V::String(v)) if v.parse::<i128>().is_ok() => {
+                (v.clone(), format!("BigInt.fromStringUnsafe(\"{v}\")"))
+            }
+            (GqlScalar::BigDecimal(_), v) => match v.as_number_string() {
+                Some(v) => (v.clone(), format!("BigDecimal.fromStringUnsafe(\"{v}\")")),
+                None => Err(invalid_default_value())?,
+            },
+            (GqlScalar::Timestamp, V::String(v)) if is_valid_timestamp(v) => (
+                to_pg_string(v),
+                format!("Js.Date.fromString({})", to_string_literal(v)),
+            ),
+            (GqlScalar::Timestamp, _) => Err(anyhow!(
+                "EE221: The default value {} of the field '{}' should be a date-time with a \
+                 timezone. Eg. @default(value: \"2024-01-01T00:00:00Z\")",
+                default_value,
+                self.name
+            ))?,
+            (GqlScalar::Custom(name), V::Enum(v) | V::String(v)) => match schema.enums.get(&name) {
+                Some(gql_enum) if gql_enum.values.contains(v) => (
+                    to_pg_string(v),
+                    format!(
+                        "Enums.{}.{}",
+                        name.to_capitalized_options().capitalized,
+                        v.to_capitalized_options().capitalized
+                    ),
+                ),
+                _ => Err(invalid_default_value())?,
+            },
+            _ => Err(invalid_default_value())?,
+        };
+
+        let res_code = if field_type.is_optional() {
+            format!("Some({res_code})")
+        } else {
+            res_code
+        };
+
+        Ok(Some((pg_code, res_code)))
+    }
+
+    /// The Postgres DEFAULT expression of the column
+    pub fn get_postgres_default_value(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .get_default_value_codes(schema)?
+            .map(|(pg_code, _)| pg_code))
+    }
+
+    /// The ReScript code of the default value used in the entity constructor
+    pub fn get_rescript_default_value(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .get_default_value_codes(schema)?
+            .map(|(_, res_code)| res_code))
+    }
+
+    fn has_same_type(&self, other: &Self) -> bool {
+        self.field_type.is_derived_from() == other.field_type.is_derived_from()
+            && self.field_type.to_user_defined_field_type()
+                == other.field_type.to_user_defined_field_type()
     }
 
     pub fn get_relational_key(&self, schema: &Schema) -> anyhow::Result<String> {
@@ -764,7 +1629,7 @@ impl Field {
                     //In the case where there is a recipracol lookup, the actual
                     //underlying field contains _id at the end
                     GqlScalar::Custom(name)
-                        if matches!(schema.try_get_type_def(&name)?, TypeDef::Entity(_)) =>
+                        if schema.try_get_type_def(&name)?.is_entity_reference() =>
                     {
                         Ok(format!("{derived_from_field}_id"))
                     }
@@ -788,12 +1653,30 @@ impl Field {
         let has_single_field_index_directive = entity
             .multi_field_indexes
             .iter()
+            .filter(|multi_field_index| multi_field_index.is_default())
             .filter_map(MultiFieldIndex::get_single_field_index)
             .any(|single_field_index| single_field_index == self.name);
 
         has_indexed_directive || has_single_field_index_directive
     }
 
+    ///Whether the field has a single field index with a custom method or order
+    ///that can serve the eq and gt loaders. Gin indexes only serve containment queries,
+    ///hash indexes only equality and partial indexes only a part of the rows
+    pub fn has_custom_index(&self, entity: &Entity) -> bool {
+        entity
+            .get_custom_indices()
+            .into_iter()
+            .filter(|custom_index| {
+                !matches!(
+                    custom_index.get_method(),
+                    IndexMethod::Gin | IndexMethod::Hash
+                ) && custom_index.get_where_predicate().is_none()
+            })
+            .filter_map(MultiFieldIndex::get_single_field_index)
+            .any(|single_field_index| single_field_index == self.name)
+    }
+
     pub fn is_derived_lookup_field(&self, entity: &Entity, schema: &Schema) -> bool {
         schema.entities.values().any(|entity_inner| {
             entity_inner.get_fields().iter().any(|field| {
@@ -812,6 +1695,17 @@ impl Field {
         self.name.as_str().to_lowercase() == "id"
     }
 
+    ///Whether the field holds an array of entity references. These are
+    ///many-to-many relationships, backed by a join table
+    pub fn is_many_to_many_field(&self, schema: &Schema) -> anyhow::Result<bool> {
+        match &self.field_type {
+            FieldType::DerivedFromField { .. } => Ok(false),
+            FieldType::RegularField { field_type, .. } => {
+                Ok(field_type.is_array() && field_type.is_entity_field(schema)?)
+            }
+        }
+    }
+
     ///Returns None if it is a derived field
     pub fn get_postgres_field(
         &self,
@@ -826,11 +1720,12 @@ impl Field {
             } => Ok(Some(PGField {
                 field_name: self.name.clone(),
                 field_type: gql_field_type.to_underlying_postgres_primitive(schema)?,
-                is_array: gql_field_type.is_array(),
+                is_array: gql_field_type.is_array() && !gql_field_type.is_stored_as_json(),
                 is_index: self.is_indexed_field(entity),
                 linked_entity: gql_field_type.get_linked_entity(schema)?,
                 is_primary_key: self.is_primary_key(),
                 is_nullable: gql_field_type.is_optional(),
+                default_value: self.get_postgres_default_value(schema)?,
             })),
         }
     }
@@ -850,28 +1745,330 @@ impl Field {
     }
 }
 
+///SQL keywords allowed in the where predicate of an index next to the column names
+const WHERE_PREDICATE_KEYWORDS: [&str; 15] = [
+    "and", "or", "not", "is", "null", "true", "false", "in", "like", "ilike", "between",
+    "distinct", "from", "any", "all",
+];
+
+///Replaces the field names of a partial index predicate with the quoted column names.
+///Fails on the names which are neither fields of the entity nor allowed keywords,
+///function names or type casts, and on anything that could end the statement.
+fn quote_where_predicate_columns(
+    predicate: &str,
+    column_names: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+    let get_column = |name: &str| {
+        column_names
+            .get(name)
+            .map(|column_name| format!("\"{column_name}\""))
+            .ok_or_else(|| {
+                anyhow!(
+                    "EE222: The where predicate of an index references '{name}', which is not a \
+                     field of the entity"
+                )
+            })
+    };
+
+    let chars = predicate.chars().collect::<Vec<_>>();
+    let mut sql = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            //String literals are kept as they are. A quote is escaped by doubling it
+            '\'' => {
+                let start = i;
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => i += 2,
+                        Some('\'') => break,
+                        Some(_) => i += 1,
+                        None => {
+                            return Err(anyhow!(
+                                "EE222: The where predicate of an index has an unterminated \
+                                 string literal"
+                            ))
+                        }
+                    }
+                }
+                sql.extend(&chars[start..=i]);
+                i += 1;
+            }
+            //Dollar-quoted string literals ($$...$$ or $tag$...$tag$) are kept as they are
+            '$' => match dollar_quote_tag_end(&chars, i) {
+                Some(tag_end) => {
+                    let tag = &chars[i..=tag_end];
+                    let end = chars[tag_end + 1..]
+                        .windows(tag.len())
+                        .position(|window| window == tag)
+                        .map(|position| tag_end + 1 + position + tag.len())
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "EE222: The where predicate of an index has an unterminated \
+                                 dollar-quoted string literal"
+                            )
+                        })?;
+                    sql.extend(&chars[i..end]);
+                    i = end;
+                }
+                None => {
+                    sql.push(c);
+                    i += 1;
+                }
+            },
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .map(|position| i + 1 + position)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "EE222: The where predicate of an index has an unterminated quoted \
+                             name"
+                        )
+                    })?;
+                let name = chars[i + 1..end].iter().collect::<String>();
+                sql.push_str(&get_column(&name)?);
+                i = end + 1;
+            }
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                sql.extend(&chars[start..i]);
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let name = chars[start..i].iter().collect::<String>();
+                let is_type_cast = sql.trim_end().ends_with("::");
+                let is_function_call = chars[i..]
+                    .iter()
+                    .find(|c| !c.is_whitespace())
+                    .is_some_and(|c| *c == '(');
+                let is_keyword = WHERE_PREDICATE_KEYWORDS.contains(&name.to_lowercase().as_str());
+                if is_type_cast || is_function_call || is_keyword {
+                    sql.push_str(&name);
+                } else {
+                    sql.push_str(&get_column(&name)?);
+                }
+            }
+            ';' => {
+                return Err(anyhow!(
+                    "EE222: The where predicate of an index should be a single SQL expression \
+                     without ';'"
+                ))
+            }
+            '-' | '/' if matches!((c, chars.get(i + 1)), ('-', Some('-')) | ('/', Some('*'))) => {
+                return Err(anyhow!(
+                    "EE222: The where predicate of an index can't contain SQL comments"
+                ))
+            }
+            c => {
+                sql.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok(sql)
+}
+
+///Returns the position of the closing '$' of a dollar quote tag ($$ or $tag$)
+///starting at the given position
+fn dollar_quote_tag_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    while chars
+        .get(i)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+        i += 1;
+    }
+    (chars.get(i) == Some(&'$')).then_some(i)
+}
+
+///The access method of an index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexMethod {
+    BTree,
+    Hash,
+    Gin,
+    Gist,
+    Brin,
+}
+
+impl IndexMethod {
+    fn from_value(value: &Value<String>) -> anyhow::Result<Self> {
+        match value {
+            Value::String(method) | Value::Enum(method) => match method.to_lowercase().as_str() {
+                "btree" => Ok(Self::BTree),
+                "hash" => Ok(Self::Hash),
+                "gin" => Ok(Self::Gin),
+                "gist" => Ok(Self::Gist),
+                "brin" => Ok(Self::Brin),
+                _ => Err(anyhow!(
+                    "EE222: Invalid index method '{method}'. The supported methods are btree, \
+                     hash, gin, gist and brin"
+                )),
+            },
+            _ => Err(anyhow!(
+                "EE222: The method of an index should be a string. Eg. @index(method: \"brin\")"
+            )),
+        }
+    }
+
+    ///The name of the variant in Table.res
+    pub fn to_rescript_constructor(&self) -> &'static str {
+        match self {
+            Self::BTree => "BTree",
+            Self::Hash => "Hash",
+            Self::Gin => "Gin",
+            Self::Gist => "Gist",
+            Self::Brin => "Brin",
+        }
+    }
+
+    ///The name of the access method in Postgres
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Self::BTree => "btree",
+            Self::Hash => "hash",
+            Self::Gin => "gin",
+            Self::Gist => "gist",
+            Self::Brin => "brin",
+        }
+    }
+}
+
+///The sort order of a field in a btree index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexOrder {
+    Asc,
+    Desc,
+}
+
+impl IndexOrder {
+    fn from_value(value: &Value<String>) -> anyhow::Result<Self> {
+        match value {
+            Value::String(order) | Value::Enum(order) => match order.to_lowercase().as_str() {
+                "asc" => Ok(Self::Asc),
+                "desc" => Ok(Self::Desc),
+                _ => Err(anyhow!(
+                    "EE222: Invalid index order '{order}'. The order should either be asc or desc"
+                )),
+            },
+            _ => Err(anyhow!(
+                "EE222: The order of an index should be a string or a list of strings with an \
+                 order for each field. Eg. @index(order: \"desc\")"
+            )),
+        }
+    }
+
+    ///The name of the variant in Table.res
+    pub fn to_rescript_constructor(&self) -> &'static str {
+        match self {
+            Self::Asc => "Asc",
+            Self::Desc => "Desc",
+        }
+    }
+
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct MultiFieldIndex(Vec<String>);
+pub struct MultiFieldIndex {
+    field_names: Vec<String>,
+    method: IndexMethod,
+    orders: Vec<IndexOrder>,
+    where_predicate: Option<String>,
+}
 
 impl MultiFieldIndex {
     fn new(field_names: Vec<String>) -> Self {
-        Self(field_names.into_iter().collect())
+        Self {
+            field_names,
+            method: IndexMethod::BTree,
+            orders: vec![],
+            where_predicate: None,
+        }
+    }
+
+    ///Parses the method, order and where arguments of an @index directive
+    fn with_options(mut self, arguments: &[(String, Value<String>)]) -> anyhow::Result<Self> {
+        for (key, value) in arguments {
+            match (key.as_str(), value) {
+                ("method", value) => self.method = IndexMethod::from_value(value)?,
+                ("order", Value::List(orders)) => {
+                    self.orders = orders
+                        .iter()
+                        .map(IndexOrder::from_value)
+                        .collect::<anyhow::Result<_>>()?
+                }
+                //A single order applies to every field of the index
+                ("order", value) => {
+                    self.orders = vec![IndexOrder::from_value(value)?; self.field_names.len()]
+                }
+                ("where", Value::String(predicate)) => {
+                    self.where_predicate = Some(predicate.clone())
+                }
+                ("where", _) => Err(anyhow!(
+                    "EE222: The where argument of an index should be a string with a SQL \
+                     predicate. Eg. @index(where: \"amount > 0\")"
+                ))?,
+                (key, _) => Err(anyhow!(
+                    "EE222: Invalid argument '{key}' on @index directive. The supported arguments \
+                     are method, order and where"
+                ))?,
+            }
+        }
+        Ok(self)
     }
 
     pub fn get_field_names(&self) -> &Vec<String> {
-        &self.0
+        &self.field_names
+    }
+
+    pub fn get_method(&self) -> IndexMethod {
+        self.method
+    }
+
+    pub fn get_orders(&self) -> &Vec<IndexOrder> {
+        &self.orders
+    }
+
+    pub fn get_where_predicate(&self) -> Option<&String> {
+        self.where_predicate.as_ref()
+    }
+
+    ///Whether it's a btree index with no ordering or predicate
+    pub fn is_default(&self) -> bool {
+        self.method == IndexMethod::BTree
+            && self.orders.is_empty()
+            && self.where_predicate.is_none()
     }
 
     fn get_single_field_index(&self) -> Option<String> {
-        if self.0.len() == 1 {
-            self.0.first().cloned()
+        if self.field_names.len() == 1 {
+            self.field_names.first().cloned()
         } else {
             None
         }
     }
 
     pub fn get_multi_field_index(&self) -> Option<&Self> {
-        if self.0.len() > 1 {
+        if self.field_names.len() > 1 {
             Some(self)
         } else {
             None
@@ -883,7 +2080,7 @@ impl MultiFieldIndex {
         fields: &HashMap<String, Field>,
         allowed_names: &[String],
     ) -> anyhow::Result<Self> {
-        for field_name in &self.0 {
+        for field_name in &self.field_names {
             if !fields.contains_key(field_name) && !allowed_names.contains(field_name) {
                 return Err(anyhow!(
                     "Index error: Field '{}' does not exist in entity, please remove it from the \
@@ -897,7 +2094,7 @@ impl MultiFieldIndex {
 
     fn validate_no_duplicates(self, fields: &HashMap<String, Field>) -> anyhow::Result<Self> {
         let mut field_names_set = HashSet::new();
-        for field_name in &self.0 {
+        for field_name in &self.field_names {
             //Check for duplicate fields inside multi field index
             let is_new_insert = field_names_set.insert(field_name);
             if !is_new_insert {
@@ -928,7 +2125,7 @@ impl MultiFieldIndex {
         self,
         fields: &HashMap<String, Field>,
     ) -> anyhow::Result<Self> {
-        for field_name in &self.0 {
+        for field_name in &self.field_names {
             if let Some(field) = fields.get(field_name) {
                 if field.field_type.is_derived_from() {
                     return Err(anyhow!(
@@ -942,6 +2139,51 @@ impl MultiFieldIndex {
         Ok(self)
     }
 
+    fn validate_options(self, fields: &HashMap<String, Field>) -> anyhow::Result<Self> {
+        let field_names = self.field_names.join(", ");
+        if !self.orders.is_empty() && self.orders.len() != self.field_names.len() {
+            return Err(anyhow!(
+                "EE222: The index on fields {field_names} should have an order for each of its \
+                 fields"
+            ));
+        }
+        if !self.orders.is_empty() && self.method != IndexMethod::BTree {
+            return Err(anyhow!(
+                "EE222: The index on fields {field_names} can't have an order. Only btree indexes \
+                 support ordering"
+            ));
+        }
+        if self.method == IndexMethod::Hash && self.field_names.len() > 1 {
+            return Err(anyhow!(
+                "EE222: The hash index on fields {field_names} can only have a single field"
+            ));
+        }
+        if self.method == IndexMethod::Gin {
+            for field_name in &self.field_names {
+                let is_gin_indexable = fields.get(field_name).is_some_and(|field| {
+                    field.field_type.is_array()
+                        || field.field_type.get_underlying_scalar() == GqlScalar::Json
+                });
+                if !is_gin_indexable {
+                    return Err(anyhow!(
+                        "EE222: The gin index can't be used on the field '{field_name}'. Gin \
+                         indexes are only supported on list and Json fields"
+                    ));
+                }
+            }
+        }
+        if self
+            .where_predicate
+            .as_ref()
+            .is_some_and(|predicate| predicate.trim().is_empty())
+        {
+            return Err(anyhow!(
+                "EE222: The where predicate of the index on fields {field_names} can't be empty"
+            ));
+        }
+        Ok(self)
+    }
+
     fn validate_no_index_on_id_field(self) -> anyhow::Result<Self> {
         if let Some(single_field_index) = self.get_single_field_index() {
             if single_field_index == "id" {
@@ -955,1398 +2197,3108 @@ impl MultiFieldIndex {
     }
 }
 
+/// The value of a @default(value: ...) directive on a field. Its type is
+/// checked against the type of the field when validating the schema.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum UserDefinedFieldType {
-    Single(GqlScalar),
-    ListType(Box<UserDefinedFieldType>),
-    NonNullType(Box<UserDefinedFieldType>),
+pub enum FieldDefaultValue {
+    Int(i64),
+    //Kept as a string so the value can be hashed
+    Float(String),
+    String(String),
+    Boolean(bool),
+    Enum(String),
 }
 
-impl UserDefinedFieldType {
-    fn from_obj_field_type(
-        obj_field_type: &ObjType<'_, String>,
-        pg_type_modifications: &PgTypeModifications,
-    ) -> Self {
-        match obj_field_type {
-            ObjType::NamedType(name) => {
-                UserDefinedFieldType::Single(GqlScalar::from_str(name, pg_type_modifications))
-            }
-            ObjType::NonNullType(obj_field_type) => UserDefinedFieldType::NonNullType(Box::new(
-                Self::from_obj_field_type(obj_field_type, pg_type_modifications),
+impl FieldDefaultValue {
+    fn from_value(value: &Value<String>) -> anyhow::Result<Self> {
+        match value {
+            Value::Int(v) => Ok(Self::Int(
+                v.as_i64()
+                    .context("EE221: Failed to convert value to i64")?,
             )),
-            ObjType::ListType(obj_field_type) => UserDefinedFieldType::ListType(Box::new(
-                Self::from_obj_field_type(obj_field_type, pg_type_modifications),
+            Value::Float(v) => Ok(Self::Float(v.to_string())),
+            Value::String(v) => Ok(Self::String(v.clone())),
+            Value::Boolean(v) => Ok(Self::Boolean(*v)),
+            Value::Enum(v) => Ok(Self::Enum(v.clone())),
+            _ => Err(anyhow!(
+                "EE221: A default value should be a number, string, boolean or enum value"
             )),
         }
     }
 
-    pub fn validate_type(&self, schema: &Schema) -> anyhow::Result<()> {
+    fn as_number_string(&self) -> Option<String> {
         match self {
-            Self::Single(_) => Ok(()),
-            Self::ListType(field_type) => match field_type.as_ref() {
-                //Postgres doesn't support nullable types inside of arrays
-                Self::NonNullType(inner_field_type) => match inner_field_type.as_ref() {
-                    //Don't allow non derived from enity relationships inside arrays
-                    Self::Single(GqlScalar::Custom(name))
-                        if matches!(schema.try_get_type_def(name)?, TypeDef::Entity(_)) =>
-                    {
-                        Err(anyhow!(
-                            "EE211: Arrays of entities is unsupported. Please use one of the \
-                             methods for referencing entities outlined in the docs. The entity \
-                             being referenced in the array is '{}'.",
-                            name
-                        ))
-                    }
-                    //TODO: add support for these types
-                    //currently we would need to use explicid casts in the queries to make these
-                    //work https://github.com/porsager/postgres/pull/392
-                    Self::Single(GqlScalar::Boolean) => {
-                        Err(anyhow!("Arrays of booleans are not yet supported."))
-                    }
-                    Self::Single(GqlScalar::Timestamp) => {
-                        Err(anyhow!("Arrays of timestamps are not yet supported."))
-                    }
-                    _ => field_type.validate_type(schema),
-                },
-                Self::Single(gql_scalar) => Err(anyhow!(
-                    "EE208: Nullable scalars inside lists are unsupported. Please include a '!' \
-                     after your '{}' scalar",
-                    gql_scalar
-                )),
-                Self::ListType(_) => Err(anyhow!(
-                    "EE209: Nullable multidimensional lists types are unsupported,please include \
-                     a '!' for your inner list type eg. [[Int!]!]"
-                )),
-            },
-            Self::NonNullType(field_type) => match field_type.as_ref() {
-                Self::NonNullType(_) => Err(anyhow!(
-                    "Nested Not Null types are unsupported. Please remove any sequential '!' \
-                     symbols after types in schema"
-                )),
-                _ => field_type.validate_type(schema),
-            },
+            Self::Int(v) => Some(v.to_string()),
+            Self::Float(v) => Some(v.clone()),
+            Self::String(v) if v.parse::<f64>().is_ok() => Some(v.clone()),
+            _ => None,
         }
     }
+}
 
-    pub fn to_underlying_postgres_primitive(&self, schema: &Schema) -> anyhow::Result<PGPrimitive> {
+impl fmt::Display for FieldDefaultValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Single(gql_scalar) => gql_scalar.to_underlying_postgres_primitive(schema),
-            Self::ListType(field_type) | Self::NonNullType(field_type) => {
-                field_type.to_underlying_postgres_primitive(schema)
-            }
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "\"{v}\""),
+            Self::Boolean(v) => write!(f, "{v}"),
+            Self::Enum(v) => write!(f, "{v}"),
         }
     }
+}
 
-    pub fn is_optional(&self) -> bool {
-        !matches!(self, Self::NonNullType(_))
+fn to_pg_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Accepts the ISO 8601 date-times with an explicit timezone, which are read
+/// the same way by Postgres and by Js.Date.fromString
+fn is_valid_timestamp(value: &str) -> bool {
+    let timestamp_regex = regex::Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T([01]\d|2[0-3]):[0-5]\d:[0-5]\d(\.\d{1,6})?(Z|[+-]\d{2}:\d{2})$",
+    )
+    .expect("Timestamp regex should be valid");
+
+    let Some(captures) = timestamp_regex.captures(value) else {
+        return false;
+    };
+    let [year, month, day] = [1, 2, 3].map(|i| {
+        captures[i]
+            .parse::<u32>()
+            .expect("Captured digits should parse")
+    });
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+/// Fields which values have to be unique as a combination across all rows
+/// of an entity. Declared with @unique on a field or @unique(fields: [...])
+/// on an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UniqueConstraint(Vec<String>);
+
+impl UniqueConstraint {
+    fn new(field_names: Vec<String>) -> Self {
+        Self(field_names)
     }
 
-    pub fn is_array(&self) -> bool {
-        match self {
-            Self::ListType(_) => true,
-            Self::NonNullType(field_type) => field_type.is_array(),
-            Self::Single(_) => false,
-        }
+    pub fn get_field_names(&self) -> &Vec<String> {
+        &self.0
     }
 
-    pub fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
-        let composed_type_name = match self {
-            //Only types in here should be non optional
-            Self::NonNullType(field_type) => match field_type.as_ref() {
-                Self::Single(gql_scalar) => gql_scalar.to_rescript_type(schema)?,
-                Self::ListType(field_type) => {
-                    RescriptTypeIdent::Array(Box::new(field_type.to_rescript_type(schema)?))
-                }
-                //This case shouldn't happen, and should recurse without adding any types if so
-                //A double non null would be !! in gql
-                Self::NonNullType(field_type) => field_type.to_rescript_type(schema)?,
-            },
-            //If we match this case it missed the non null path entirely and should be optional
-            Self::Single(gql_scalar) => {
-                RescriptTypeIdent::Option(Box::new(gql_scalar.to_rescript_type(schema)?))
+    fn validate_no_duplicates(self) -> anyhow::Result<Self> {
+        let mut field_names_set = HashSet::new();
+        for field_name in &self.0 {
+            let is_new_insert = field_names_set.insert(field_name);
+            if !is_new_insert {
+                return Err(anyhow!(
+                    "EE220: Field {field_name} is listed multiple times in unique constraint"
+                ));
             }
-            //If we match this case it missed the non null path entirely and should be optional
-            Self::ListType(field_type) => RescriptTypeIdent::Option(Box::new(
-                RescriptTypeIdent::Array(Box::new(field_type.to_rescript_type(schema)?)),
-            )),
-        };
-        Ok(composed_type_name)
+        }
+        Ok(self)
     }
 
-    fn get_underlying_scalar(&self) -> GqlScalar {
-        match self {
-            Self::Single(gql_scalar) => gql_scalar.clone(),
-            Self::ListType(field_type) | Self::NonNullType(field_type) => {
-                field_type.get_underlying_scalar()
+    fn validate_field_names_exist(self, fields: &HashMap<String, Field>) -> anyhow::Result<Self> {
+        if self.0.is_empty() {
+            return Err(anyhow!(
+                "EE220: A unique constraint needs at least one field"
+            ));
+        }
+        for field_name in &self.0 {
+            if !fields.contains_key(field_name) {
+                return Err(anyhow!(
+                    "EE220: Field '{}' does not exist in entity, please remove it from the \
+                     `@unique` directive.",
+                    field_name,
+                ));
             }
         }
+        Ok(self)
     }
 
-    pub fn is_entity_field(&self, schema: &Schema) -> anyhow::Result<bool> {
-        self.get_underlying_scalar().is_entity(schema)
+    fn validate_no_derived_field(self, fields: &HashMap<String, Field>) -> anyhow::Result<Self> {
+        for field_name in &self.0 {
+            if let Some(field) = fields.get(field_name) {
+                if field.field_type.is_derived_from() {
+                    return Err(anyhow!(
+                        "EE220: Field '{}' is a @derivedFrom field and cannot be unique, please \
+                         remove it from the `@unique` directive.",
+                        field_name
+                    ));
+                }
+            }
+        }
+        Ok(self)
     }
 
-    ///Returns None if field is not a linked entity and   Some(<ENTITY_NAME>) if it is
-    pub fn get_linked_entity(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
-        self.get_underlying_scalar().get_linked_entity(schema)
+    fn validate_not_only_id_field(self) -> anyhow::Result<Self> {
+        if self.0 == ["id"] {
+            return Err(anyhow!(
+                "EE220: Field 'id' is unique by default in all entities, please remove the \
+                 `@unique` directive on it.",
+            ));
+        }
+        Ok(self)
     }
+}
 
-    fn to_string_internal(&self) -> String {
-        match &self {
-            Self::Single(gql_scalar) => gql_scalar.to_string(),
-            Self::ListType(field_type) => format!("[{}]", field_type),
-            Self::NonNullType(field_type) => format!("{}!", field_type),
+///A full text search over String fields of an entity. Stored as a generated
+///tsvector column with a gin index and queried through a function of the same name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FulltextSearch {
+    pub name: String,
+    ///The Postgres text search configuration, eg. english
+    pub language: String,
+    pub field_names: Vec<String>,
+}
+
+impl FulltextSearch {
+    ///The type subgraph schemas use to define full text searches
+    const SUBGRAPH_SCHEMA_TYPE_NAME: &'static str = "_Schema_";
+
+    fn new(name: String, language: String, field_names: Vec<String>) -> anyhow::Result<Self> {
+        let is_valid_name = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_name {
+            return Err(anyhow!(
+                "EE223: Invalid full text search name '{name}'. The name should start with a \
+                 letter and only contain letters, numbers and underscores"
+            ));
         }
+        Ok(Self {
+            language: Self::get_text_search_config(&language)?,
+            name,
+            field_names,
+        })
     }
 
-    /// Returns the name of the entity when @derivedFrom derivtive is used
-    /// Returns None in the case that it does not conform to the correct
-    /// structure of a derived entity
-    fn get_name_of_derived_from_entity(&self) -> Option<String> {
-        match self {
-            Self::NonNullType(f) => match f.as_ref() {
-                Self::ListType(f) => match f.as_ref() {
-                    Self::NonNullType(f) => match f.as_ref() {
-                        Self::Single(GqlScalar::Custom(name)) => Some(name.clone()),
-                        _ => None,
-                    },
-                    _ => None,
-                },
-                _ => None,
-            },
-            _ => None,
-        }
+    ///Maps both the subgraph language codes and the Postgres text search
+    ///configuration names to the Postgres configuration
+    fn get_text_search_config(language: &str) -> anyhow::Result<String> {
+        let config = match language.to_lowercase().as_str() {
+            "simple" => "simple",
+            "da" | "danish" => "danish",
+            "nl" | "dutch" => "dutch",
+            "en" | "english" => "english",
+            "fi" | "finnish" => "finnish",
+            "fr" | "french" => "french",
+            "de" | "german" => "german",
+            "hu" | "hungarian" => "hungarian",
+            "it" | "italian" => "italian",
+            "no" | "norwegian" => "norwegian",
+            "pt" | "portuguese" => "portuguese",
+            "ro" | "romanian" => "romanian",
+            "ru" | "russian" => "russian",
+            "es" | "spanish" => "spanish",
+            "sv" | "swedish" => "swedish",
+            "tr" | "turkish" => "turkish",
+            _ => {
+                return Err(anyhow!(
+                    "EE223: Unsupported full text search language '{language}'"
+                ))
+            }
+        };
+        Ok(config.to_string())
     }
 
-    pub fn from_ethabi_type(abi_type: &EthAbiParamType) -> anyhow::Result<Self> {
-        match abi_type {
-            EthAbiParamType::Uint(_size) | EthAbiParamType::Int(_size) => Ok(Self::NonNullType(
-                Box::new(Self::Single(GqlScalar::BigInt(None))),
+    fn get_string_arg(value: &Value<String>, arg_name: &str) -> anyhow::Result<String> {
+        match value {
+            Value::String(s) | Value::Enum(s) => Ok(s.clone()),
+            _ => Err(anyhow!(
+                "EE223: The {arg_name} argument of the @fulltext directive should be a string"
             )),
-            EthAbiParamType::Bool => Ok(Self::NonNullType(Box::new(Self::Single(
-                GqlScalar::Boolean,
-            )))),
-            EthAbiParamType::Address
-            | EthAbiParamType::Bytes
-            | EthAbiParamType::String
-            | EthAbiParamType::FixedBytes(_) => {
-                Ok(Self::NonNullType(Box::new(Self::Single(GqlScalar::String))))
-            }
-            EthAbiParamType::Array(abi_type) | EthAbiParamType::FixedArray(abi_type, _) => {
-                //Validate no nested arrays or
-                match abi_type.as_ref() {
-                    EthAbiParamType::Tuple(_) => {
-                        Err(anyhow!("Unhandled contract import type 'array of tuple'"))?
-                    }
-                    EthAbiParamType::Array(_) => {
-                        Err(anyhow!("Unhandled contract import type 'array of array'"))?
-                    }
-                    _ => (),
+        }
+    }
+
+    ///Parses @fulltext(name: "bandSearch", language: en, fields: ["name", "bio"]) on an entity
+    fn from_entity_directive(directive: &Directive<'_, String>) -> anyhow::Result<Self> {
+        let mut name = None;
+        let mut language = None;
+        let mut field_names = None;
+        for (key, value) in &directive.arguments {
+            match (key.as_str(), value) {
+                ("name", value) => name = Some(Self::get_string_arg(value, "name")?),
+                ("language", value) => language = Some(Self::get_string_arg(value, "language")?),
+                ("fields", Value::List(fields)) => {
+                    field_names = Some(
+                        fields
+                            .iter()
+                            .map(|field| Self::get_string_arg(field, "fields"))
+                            .collect::<anyhow::Result<Vec<_>>>()?,
+                    )
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "EE223: Invalid argument '{key}' on @fulltext directive. Eg. \
+                         @fulltext(name: \"bandSearch\", language: en, fields: [\"name\", \
+                         \"bio\"])"
+                    ))
                 }
-                let inner_type = Self::from_ethabi_type(abi_type)
-                    .context("Unhandled contract import nested type in array")?;
-                Ok(Self::NonNullType(Box::new(Self::ListType(Box::new(
-                    inner_type,
-                )))))
             }
-            EthAbiParamType::Tuple(_abi_types) =>
-            //This case should be flattened out unless it is nested inside an array
-            {
-                Err(anyhow!("Unhandled contract import type 'tuple'"))
+        }
+        match (name, language, field_names) {
+            (Some(name), Some(language), Some(field_names)) => {
+                Self::new(name, language, field_names)
             }
+            _ => Err(anyhow!(
+                "EE223: The @fulltext directive requires a name, language and fields argument. \
+                 Eg. @fulltext(name: \"bandSearch\", language: en, fields: [\"name\", \"bio\"])"
+            )),
         }
     }
-}
 
-// Implement the Display trait for the custom struct
-impl fmt::Display for UserDefinedFieldType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string_internal())
+    ///Parses the @fulltext directive of a subgraph _Schema_ type and returns it
+    ///with the name of the included entity. Eg. @fulltext(name: "bandSearch", language: en,
+    ///algorithm: rank, include: [{entity: "Band", fields: [{name: "name"}, {name: "bio"}]}])
+    fn from_subgraph_directive(
+        directive: &Directive<'_, String>,
+    ) -> anyhow::Result<(String, Self)> {
+        let mut name = None;
+        let mut language = None;
+        let mut include = None;
+        for (key, value) in &directive.arguments {
+            match (key.as_str(), value) {
+                ("name", value) => name = Some(Self::get_string_arg(value, "name")?),
+                ("language", value) => language = Some(Self::get_string_arg(value, "language")?),
+                //Results are always ordered by ts_rank
+                ("algorithm", _) => (),
+                ("include", Value::List(include_list)) => include = Some(include_list),
+                _ => {
+                    return Err(anyhow!(
+                        "EE223: Invalid argument '{key}' on the @fulltext directive of the {} type",
+                        Self::SUBGRAPH_SCHEMA_TYPE_NAME
+                    ))
+                }
+            }
+        }
+        let (Some(name), Some(language), Some(include)) = (name, language, include) else {
+            return Err(anyhow!(
+                "EE223: The @fulltext directive of the {} type requires a name, language and \
+                 include argument",
+                Self::SUBGRAPH_SCHEMA_TYPE_NAME
+            ));
+        };
+        let invalid_include_err = || {
+            anyhow!(
+                "EE223: The include argument of the full text search '{name}' should contain a \
+                 single entity with its fields. Eg. include: [{{entity: \"Band\", fields: \
+                 [{{name: \"name\"}}]}}]"
+            )
+        };
+        let [Value::Object(included_entity)] = include.as_slice() else {
+            return Err(invalid_include_err());
+        };
+        let (Some(Value::String(entity_name)), Some(Value::List(fields))) =
+            (included_entity.get("entity"), included_entity.get("fields"))
+        else {
+            return Err(invalid_include_err());
+        };
+        let field_names = fields
+            .iter()
+            .map(|field| match field {
+                Value::Object(field) => match field.get("name") {
+                    Some(Value::String(field_name)) => Ok(field_name.clone()),
+                    _ => Err(invalid_include_err()),
+                },
+                _ => Err(invalid_include_err()),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok((entity_name.clone(), Self::new(name, language, field_names)?))
     }
-}
 
-impl Serialize for UserDefinedFieldType {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(self.to_string().as_str())
+    fn validate_fields(self, fields: &HashMap<String, Field>) -> anyhow::Result<Self> {
+        if self.field_names.is_empty() {
+            return Err(anyhow!(
+                "EE223: The full text search '{}' needs at least one field",
+                self.name
+            ));
+        }
+        if fields.contains_key(&self.name) {
+            return Err(anyhow!(
+                "EE223: The full text search '{}' has the same name as a field of the entity",
+                self.name
+            ));
+        }
+        let mut field_names_set = HashSet::new();
+        for field_name in &self.field_names {
+            if !field_names_set.insert(field_name) {
+                return Err(anyhow!(
+                    "EE223: Field {field_name} is listed multiple times in the full text search \
+                     '{}'",
+                    self.name
+                ));
+            }
+            let is_string_field = fields.get(field_name).is_some_and(|field| {
+                !field.field_type.is_derived_from()
+                    && !field.field_type.is_array()
+                    && field.field_type.get_underlying_scalar() == GqlScalar::String
+            });
+            if !is_string_field {
+                return Err(anyhow!(
+                    "EE223: Field '{field_name}' of the full text search '{}' should be an \
+                     existing String field of the entity",
+                    self.name
+                ));
+            }
+        }
+        Ok(self)
     }
 }
 
-#[derive(Default)]
-pub struct PgTypeModifications {
-    pub big_int_precision: Option<u32>,
-    pub big_decimal_precision_scale: Option<(u32, u32)>,
+///The field holding the time of timeseries rows and the start of aggregation buckets
+pub const TIMESTAMP_FIELD_NAME: &str = "timestamp";
+
+///The interval of the buckets timeseries rows are rolled up into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AggregationInterval {
+    Hour,
+    Day,
 }
 
-pub struct FieldTypeParams {
-    pub derived_from_field: Option<String>,
-    pub has_indexed_directive: bool,
-    pub pg_type_modifications: PgTypeModifications,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum FieldType {
-    DerivedFromField {
-        entity_name: String,
-        derived_from_field: String,
-    },
-    RegularField {
-        field_type: UserDefinedFieldType,
-        has_indexed_directive: bool,
-    },
-}
+impl AggregationInterval {
+    fn from_value(value: &Value<String>) -> anyhow::Result<Self> {
+        match value {
+            Value::String(interval) | Value::Enum(interval) => match interval.as_str() {
+                "hour" => Ok(Self::Hour),
+                "day" => Ok(Self::Day),
+                _ => Err(anyhow!(
+                    "EE224: Invalid aggregation interval '{interval}'. The supported intervals \
+                     are hour and day"
+                )),
+            },
+            _ => Err(anyhow!(
+                "EE224: The intervals of an aggregation should be a list of strings. Eg. \
+                 intervals: [\"hour\", \"day\"]"
+            )),
+        }
+    }
 
-impl FieldType {
-    fn to_user_defined_field_type(&self) -> UserDefinedFieldType {
+    ///The name of the variant in Table.res
+    pub fn to_rescript_constructor(&self) -> &'static str {
         match self {
-            Self::RegularField { field_type: t, .. } => t.clone(),
-            Self::DerivedFromField { entity_name, .. } => {
-                use UserDefinedFieldType::*;
-                NonNullType(Box::new(ListType(Box::new(NonNullType(Box::new(Single(
-                    GqlScalar::Custom(entity_name.clone()),
-                )))))))
-            }
+            Self::Hour => "Hour",
+            Self::Day => "Day",
         }
     }
 
-    fn from_obj_field_type(
-        obj_field_type: &ObjType<'_, String>,
-        params: FieldTypeParams,
-    ) -> anyhow::Result<Self> {
-        let field_type = UserDefinedFieldType::from_obj_field_type(
-            obj_field_type,
-            &params.pg_type_modifications,
-        );
+    ///The date_trunc unit of the interval, which also suffixes the aggregation table name
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
 
-        match params.derived_from_field {
-            None => Ok(Self::RegularField {
-                field_type,
-                has_indexed_directive: params.has_indexed_directive,
-            }),
-            Some(derived_from_field) => match field_type.get_name_of_derived_from_entity() {
-                None => {
-                    let example_str = Self::DerivedFromField {
-                        entity_name: "<MY_ENTITY>".to_string(),
-                        derived_from_field,
-                    }
-                    .to_string();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AggregateFn {
+    Sum,
+    Count,
+    Min,
+    Max,
+    First,
+    Last,
+}
 
-                    Err(anyhow!(
-                        "Field marked with @derivedFrom directive does not meet the required \
-                         structure. Field should contain a non nullable list of non nullable \
-                         entities for example: {example_str}"
-                    ))
+impl AggregateFn {
+    fn from_value(value: &Value<String>) -> anyhow::Result<Self> {
+        match value {
+            Value::String(aggregate_fn) | Value::Enum(aggregate_fn) => {
+                match aggregate_fn.as_str() {
+                    "sum" => Ok(Self::Sum),
+                    "count" => Ok(Self::Count),
+                    "min" => Ok(Self::Min),
+                    "max" => Ok(Self::Max),
+                    "first" => Ok(Self::First),
+                    "last" => Ok(Self::Last),
+                    _ => Err(anyhow!(
+                        "EE224: Invalid aggregate function '{aggregate_fn}'. The supported \
+                         functions are sum, count, min, max, first and last"
+                    )),
                 }
-                Some(entity_name) => Ok(Self::DerivedFromField {
-                    entity_name,
-                    derived_from_field,
-                }),
-            },
+            }
+            _ => Err(anyhow!(
+                "EE224: The fn argument of the @aggregate directive should be a string"
+            )),
         }
     }
 
-    pub fn validate_type(&self, schema: &Schema) -> anyhow::Result<()> {
+    ///The name of the variant in Table.res
+    pub fn to_rescript_constructor(&self) -> &'static str {
         match self {
-            Self::DerivedFromField { .. } => Ok(()), //Already validated
-            Self::RegularField { field_type: t, .. } => t.validate_type(schema),
+            Self::Sum => "Sum",
+            Self::Count => "Count",
+            Self::Min => "Min",
+            Self::Max => "Max",
+            Self::First => "First",
+            Self::Last => "Last",
         }
     }
+}
 
-    pub fn is_optional(&self) -> bool {
-        self.to_user_defined_field_type().is_optional()
-    }
+///A field of an aggregation computed from a field of the source timeseries rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aggregate {
+    pub field_name: String,
+    pub aggregate_fn: AggregateFn,
+    ///The field of the source to aggregate, count doesn't need one
+    pub arg: Option<String>,
+}
 
-    pub fn is_derived_from(&self) -> bool {
-        matches!(self, Self::DerivedFromField { .. })
-    }
+impl Aggregate {
+    const DIRECTIVE_NAME: &'static str = "aggregate";
 
-    fn has_indexed_directive(&self) -> bool {
-        match self {
-            Self::DerivedFromField { .. } => false,
-            Self::RegularField {
-                has_indexed_directive,
-                ..
-            } => *has_indexed_directive,
+    fn from_obj_field(field: &ObjField<String>) -> anyhow::Result<Option<Self>> {
+        let aggregate_directives = field
+            .directives
+            .iter()
+            .filter(|directive| directive.name == Self::DIRECTIVE_NAME)
+            .collect::<Vec<_>>();
+
+        let directive = match aggregate_directives.as_slice() {
+            [] => return Ok(None),
+            [directive] => directive,
+            _ => {
+                return Err(anyhow!(
+                    "EE202: Cannot use more than one of the same directive on field {}",
+                    field.name
+                ))
+            }
+        };
+
+        let mut aggregate_fn = None;
+        let mut arg = None;
+        for (key, value) in &directive.arguments {
+            match (key.as_str(), value) {
+                ("fn", value) => aggregate_fn = Some(AggregateFn::from_value(value)?),
+                ("arg", Value::String(field_name)) => arg = Some(field_name.clone()),
+                ("cumulative", Value::Boolean(false)) => (),
+                ("cumulative", _) => {
+                    return Err(anyhow!(
+                        "EE224: Cumulative aggregates are not supported on field {}",
+                        field.name
+                    ))
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "EE224: Invalid argument '{key}' on the @aggregate directive of field {}. \
+                         Eg. @aggregate(fn: \"sum\", arg: \"amount\")",
+                        field.name
+                    ))
+                }
+            }
         }
-    }
 
-    pub fn is_array(&self) -> bool {
-        match self {
-            Self::DerivedFromField { .. } => true,
-            Self::RegularField { field_type: t, .. } => t.is_array(),
+        let aggregate_fn = aggregate_fn.ok_or_else(|| {
+            anyhow!(
+                "EE224: The @aggregate directive of field {} requires a fn argument",
+                field.name
+            )
+        })?;
+        if arg.is_none() && aggregate_fn != AggregateFn::Count {
+            return Err(anyhow!(
+                "EE224: The @aggregate directive of field {} requires an arg argument with the \
+                 name of the aggregated field",
+                field.name
+            ));
         }
-    }
 
-    pub fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
-        self.to_user_defined_field_type().to_rescript_type(schema)
+        Ok(Some(Self {
+            field_name: field.name.clone(),
+            aggregate_fn,
+            arg,
+        }))
     }
 
-    fn get_underlying_scalar(&self) -> GqlScalar {
-        self.to_user_defined_field_type().get_underlying_scalar()
-    }
+    fn validate(&self, aggregation: &Entity, source: &Entity) -> anyhow::Result<()> {
+        let field_type = aggregation
+            .fields
+            .get(&self.field_name)
+            .map(|field| field.field_type.to_user_defined_field_type())
+            .expect("Aggregate field should be in the aggregation");
+        if field_type.is_array() {
+            return Err(anyhow!(
+                "EE224: The aggregate field {} can't be a list",
+                self.field_name
+            ));
+        }
+        let field_scalar = field_type.get_underlying_scalar();
+
+        let Some(arg) = &self.arg else {
+            //Counting doesn't need an arg
+            return match field_scalar {
+                GqlScalar::Int | GqlScalar::BigInt(_) => Ok(()),
+                _ => Err(anyhow!(
+                    "EE224: The count aggregate field {} should be an Int or a BigInt",
+                    self.field_name
+                )),
+            };
+        };
 
-    pub fn is_entity_field(&self, schema: &Schema) -> anyhow::Result<bool> {
-        self.to_user_defined_field_type().is_entity_field(schema)
-    }
+        let arg_type = source
+            .fields
+            .get(arg)
+            .filter(|field| !field.field_type.is_derived_from() && !field.field_type.is_array())
+            .map(|field| field.field_type.to_user_defined_field_type())
+            .ok_or_else(|| {
+                anyhow!(
+                    "EE224: The arg '{arg}' of the aggregate field {} should be a field of the \
+                     source entity {}",
+                    self.field_name,
+                    source.name
+                )
+            })?;
+        let arg_scalar = arg_type.get_underlying_scalar();
 
-    fn to_string_internal(&self) -> String {
-        match self {
-            Self::DerivedFromField { entity_name, .. } => {
-                let field_str = self.to_user_defined_field_type().to_string();
-                format!("{field_str} @derivedFrom(field: \"{entity_name}\")")
+        let is_numeric_arg = matches!(
+            arg_scalar,
+            GqlScalar::Int | GqlScalar::BigInt(_) | GqlScalar::Float | GqlScalar::BigDecimal(_)
+        );
+        match self.aggregate_fn {
+            AggregateFn::Count => (),
+            AggregateFn::Sum | AggregateFn::Min | AggregateFn::Max if !is_numeric_arg => {
+                return Err(anyhow!(
+                    "EE224: The arg '{arg}' of the aggregate field {} should be a numeric field",
+                    self.field_name
+                ))
             }
-            Self::RegularField { field_type: t, .. } => t.to_string(),
+            _ if field_scalar != arg_scalar => {
+                return Err(anyhow!(
+                    "EE224: The aggregate field {} should have the same type as its arg '{arg}'",
+                    self.field_name
+                ))
+            }
+            _ => (),
         }
-    }
-
-    pub fn from_ethabi_type(abi_type: &EthAbiParamType) -> anyhow::Result<Self> {
-        Ok(Self::RegularField {
-            field_type: UserDefinedFieldType::from_ethabi_type(abi_type)?,
-            has_indexed_directive: false,
-        })
+        Ok(())
     }
 }
 
-// Implement the Display trait for the custom struct
-impl fmt::Display for FieldType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string_internal())
-    }
+///An entity which rolls up the rows of a timeseries entity into buckets of the given
+///intervals, grouped by the fields without an @aggregate directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aggregation {
+    ///The fields of the aggregation, parsed like the fields of an entity
+    pub entity: Entity,
+    pub source: String,
+    pub intervals: Vec<AggregationInterval>,
+    pub aggregates: Vec<Aggregate>,
 }
 
-impl Serialize for FieldType {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(self.to_string().as_str())
-    }
-}
+impl Aggregation {
+    const DIRECTIVE_NAME: &'static str = "aggregation";
 
-#[subenum(BuiltInGqlScalar, AdditionalGqlScalar)]
-#[derive(Debug, Clone, PartialEq, strum_macros::Display, Eq, Hash)]
-pub enum GqlScalar {
-    #[subenum(BuiltInGqlScalar)]
-    ID,
-    #[subenum(BuiltInGqlScalar)]
-    String,
-    #[subenum(BuiltInGqlScalar)]
-    Int,
-    #[subenum(BuiltInGqlScalar)]
-    Float,
-    #[subenum(BuiltInGqlScalar)]
-    Boolean,
-    #[subenum(AdditionalGqlScalar)]
-    BigInt(Option<u32>), // Optional argument, max digits (base 10) this number can have.
-    #[subenum(AdditionalGqlScalar)]
-    BigDecimal(Option<(u32, u32)>),
-    #[subenum(AdditionalGqlScalar)]
-    Timestamp,
-    #[subenum(AdditionalGqlScalar)]
-    Bytes,
-    Custom(String),
-}
+    fn from_object(obj: &ObjectType<String>) -> anyhow::Result<Self> {
+        let name = &obj.name;
+        let directive = obj
+            .directives
+            .iter()
+            .find(|directive| directive.name == Self::DIRECTIVE_NAME)
+            .expect("Aggregation should have an @aggregation directive");
+
+        let mut source = None;
+        let mut intervals = None;
+        for (key, value) in &directive.arguments {
+            match (key.as_str(), value) {
+                ("source", Value::String(source_name)) => source = Some(source_name.clone()),
+                ("intervals", Value::List(interval_values)) => {
+                    intervals = Some(
+                        interval_values
+                            .iter()
+                            .map(AggregationInterval::from_value)
+                            .collect::<anyhow::Result<Vec<_>>>()?
+                            .into_iter()
+                            .unique()
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "EE224: Invalid @aggregation directive on {name}. Eg. \
+                         @aggregation(intervals: [\"hour\", \"day\"], source: \"Transfer\")"
+                    ))
+                }
+            }
+        }
+        let (Some(source), Some(intervals)) = (source, intervals) else {
+            return Err(anyhow!(
+                "EE224: The @aggregation directive on {name} requires a source and intervals \
+                 argument"
+            ));
+        };
+        if intervals.is_empty() {
+            return Err(anyhow!(
+                "EE224: The aggregation {name} needs at least one interval"
+            ));
+        }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BigIntPrecisionScale {
-    pub precision: Option<u32>,
-    pub scale: Option<u32>,
-}
+        let aggregates = obj
+            .fields
+            .iter()
+            .map(Aggregate::from_obj_field)
+            .filter_map(Result::transpose)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context(format!("Failed parsing aggregates of aggregation {name}"))?;
+        if aggregates.is_empty() {
+            return Err(anyhow!(
+                "EE224: The aggregation {name} needs at least one field with an @aggregate \
+                 directive"
+            ));
+        }
 
-#[derive(Debug, Clone)]
-pub enum Relationship {
-    TypeDef {
-        name: String,
-    },
-    DerivedFrom {
-        name: String,
-        derived_from_field: String,
-    },
-}
+        let entity = Entity::from_object(obj)?;
 
-impl GqlScalar {
-    fn is_entity(&self, schema: &Schema) -> anyhow::Result<bool> {
-        match self {
-            GqlScalar::Custom(name) => {
-                Ok(matches!(schema.try_get_type_def(name)?, TypeDef::Entity(_)))
-            }
-            _ => Ok(false),
-        }
+        Ok(Self {
+            entity,
+            source,
+            intervals,
+            aggregates,
+        })
     }
 
-    fn from_str(name: &str, pg_type_modifications: &PgTypeModifications) -> Self {
-        match name {
-            "ID" => GqlScalar::ID,
-            "String" => GqlScalar::String,
-            "Int" => GqlScalar::Int,
-            "Float" => GqlScalar::Float,
-            "Boolean" => GqlScalar::Boolean,
-            "BigInt" => GqlScalar::BigInt(pg_type_modifications.big_int_precision),
-            "BigDecimal" => {
-                GqlScalar::BigDecimal(pg_type_modifications.big_decimal_precision_scale)
-            }
-            "Timestamp" => GqlScalar::Timestamp,
-            "Bytes" => GqlScalar::Bytes,
-            name => GqlScalar::Custom(name.to_string()),
-        }
+    ///The fields the source rows are grouped by, besides the bucket timestamp
+    pub fn get_dimension_field_names(&self) -> Vec<String> {
+        self.entity
+            .get_fields()
+            .into_iter()
+            .map(|field| field.name.clone())
+            .filter(|field_name| {
+                field_name != "id"
+                    && field_name != TIMESTAMP_FIELD_NAME
+                    && !self.aggregates.iter().any(|a| &a.field_name == field_name)
+            })
+            .collect()
     }
 
-    pub fn to_underlying_postgres_primitive(&self, schema: &Schema) -> anyhow::Result<PGPrimitive> {
-        let converted = match self {
-            GqlScalar::ID => PGPrimitive::Text,
-            GqlScalar::String => PGPrimitive::Text,
-            GqlScalar::Int => PGPrimitive::Integer,
-            GqlScalar::Float => PGPrimitive::DoublePrecision, // Should we allow this type? Rounding issues will abound.
-            GqlScalar::Boolean => PGPrimitive::Boolean,
-            GqlScalar::Bytes => PGPrimitive::Text,
-            GqlScalar::BigInt(None) => PGPrimitive::Numeric(None),
-            GqlScalar::BigInt(Some(precision)) => PGPrimitive::Numeric(Some((*precision, 0))), //  We leave the scale as zero since it is not relevant for integers.
-            GqlScalar::BigDecimal(None) => PGPrimitive::Numeric(None),
-            GqlScalar::BigDecimal(Some((precision, scale))) => {
-                PGPrimitive::Numeric(Some((*precision, *scale)))
+    fn validate_source(&self, schema: &Schema) -> anyhow::Result<()> {
+        let source = schema
+            .entities
+            .get(&self.source)
+            .filter(|source| source.is_timeseries)
+            .ok_or_else(|| {
+                anyhow!(
+                    "EE224: The source {} should be an entity with @entity(timeseries: true)",
+                    self.source
+                )
+            })?;
+
+        let id_type = self
+            .entity
+            .fields
+            .get("id")
+            .map(|field| field.field_type.to_user_defined_field_type());
+        let timestamp_type = self
+            .entity
+            .fields
+            .get(TIMESTAMP_FIELD_NAME)
+            .map(|field| field.field_type.to_user_defined_field_type());
+        let non_null = |scalar| {
+            Some(UserDefinedFieldType::NonNullType(Box::new(
+                UserDefinedFieldType::Single(scalar),
+            )))
+        };
+        if id_type != non_null(GqlScalar::ID) || timestamp_type != non_null(GqlScalar::Timestamp) {
+            return Err(anyhow!(
+                "EE224: An aggregation needs an 'id: ID!' and a '{TIMESTAMP_FIELD_NAME}: \
+                 Timestamp!' field"
+            ));
+        }
+
+        for dimension_field_name in self.get_dimension_field_names() {
+            let dimension_type = self.entity.fields[&dimension_field_name]
+                .field_type
+                .to_user_defined_field_type();
+            let is_valid_dimension = !dimension_type.is_array()
+                && source
+                    .fields
+                    .get(&dimension_field_name)
+                    .is_some_and(|field| {
+                        field.field_type.to_user_defined_field_type() == dimension_type
+                    });
+            if !is_valid_dimension {
+                return Err(anyhow!(
+                    "EE224: The field {dimension_field_name} isn't aggregated, so it should be a \
+                     field with the same type in the source entity {} to group by. Lists can't be \
+                     grouped by",
+                    source.name
+                ));
             }
-            GqlScalar::Timestamp => PGPrimitive::Timestamp,
-            GqlScalar::Custom(name) => match schema.try_get_type_def(name)? {
-                TypeDef::Entity(_) => PGPrimitive::Text,
-                TypeDef::Enum => PGPrimitive::Enum(name.clone()),
+        }
+
+        for aggregate in &self.aggregates {
+            aggregate.validate(&self.entity, source)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UserDefinedFieldType {
+    Single(GqlScalar),
+    ListType(Box<UserDefinedFieldType>),
+    NonNullType(Box<UserDefinedFieldType>),
+}
+
+impl UserDefinedFieldType {
+    fn from_obj_field_type(
+        obj_field_type: &ObjType<'_, String>,
+        pg_type_modifications: &PgTypeModifications,
+    ) -> Self {
+        match obj_field_type {
+            ObjType::NamedType(name) => {
+                UserDefinedFieldType::Single(GqlScalar::from_str(name, pg_type_modifications))
+            }
+            ObjType::NonNullType(obj_field_type) => UserDefinedFieldType::NonNullType(Box::new(
+                Self::from_obj_field_type(obj_field_type, pg_type_modifications),
+            )),
+            ObjType::ListType(obj_field_type) => UserDefinedFieldType::ListType(Box::new(
+                Self::from_obj_field_type(obj_field_type, pg_type_modifications),
+            )),
+        }
+    }
+
+    pub fn validate_type(&self, schema: &Schema) -> anyhow::Result<()> {
+        match self {
+            Self::Single(_) => Ok(()),
+            Self::ListType(field_type) => {
+                //Elements of a list can be nullable, so look through the non null type
+                let element_type = match field_type.as_ref() {
+                    Self::NonNullType(inner_field_type) => inner_field_type.as_ref(),
+                    element_type => element_type,
+                };
+                match element_type {
+                    //Arrays of entities are many-to-many relationships backed by a join table
+                    Self::Single(GqlScalar::Custom(name))
+                        if schema.try_get_type_def(name)?.is_entity_reference() =>
+                    {
+                        match (schema.try_get_type_def(name)?, field_type.as_ref()) {
+                            (TypeDef::Entity(_), Self::NonNullType(_)) => Ok(()),
+                            (TypeDef::Entity(_), _) => Err(anyhow!(
+                                "EE211: Entities referenced in an array can't be nullable. Please \
+                                 include a '!' after the entity '{}' eg. [{}!]!",
+                                name,
+                                name
+                            )),
+                            _ => Err(anyhow!(
+                                "EE211: Arrays of interfaces or unions are unsupported. The type \
+                                 being referenced in the array is '{}'.",
+                                name
+                            )),
+                        }
+                    }
+                    Self::ListType(_) if element_type.is_entity_field(schema)? => Err(anyhow!(
+                        "EE211: Multidimensional arrays of entities are unsupported. The type of \
+                         the field is '{}'.",
+                        self
+                    )),
+                    //TODO: add support for these types
+                    //currently we would need to use explicid casts in the queries to make these
+                    //work https://github.com/porsager/postgres/pull/392
+                    Self::Single(GqlScalar::Boolean) => {
+                        Err(anyhow!("Arrays of booleans are not yet supported."))
+                    }
+                    Self::Single(GqlScalar::Timestamp) => {
+                        Err(anyhow!("Arrays of timestamps are not yet supported."))
+                    }
+                    Self::Single(GqlScalar::Json) => Err(anyhow!(
+                        "Arrays of Json are not supported. Please use a Json field holding an \
+                         array instead."
+                    )),
+                    _ => field_type.validate_type(schema),
+                }
+            }
+            Self::NonNullType(field_type) => match field_type.as_ref() {
+                Self::NonNullType(_) => Err(anyhow!(
+                    "Nested Not Null types are unsupported. Please remove any sequential '!' \
+                     symbols after types in schema"
+                )),
+                _ => field_type.validate_type(schema),
             },
-        };
-        Ok(converted)
+        }
     }
 
-    fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
-        let res_type = match self {
-            GqlScalar::ID => RescriptTypeIdent::ID,
-            GqlScalar::String => RescriptTypeIdent::String,
-            GqlScalar::Int => RescriptTypeIdent::Int,
-            GqlScalar::BigInt(_) => RescriptTypeIdent::BigInt,
-            GqlScalar::BigDecimal(_) => RescriptTypeIdent::BigDecimal,
-            GqlScalar::Float => RescriptTypeIdent::Float,
-            GqlScalar::Bytes => RescriptTypeIdent::String,
-            GqlScalar::Boolean => RescriptTypeIdent::Bool,
-            GqlScalar::Timestamp => RescriptTypeIdent::Timestamp,
-            GqlScalar::Custom(name) => match schema.try_get_type_def(name)? {
-                TypeDef::Entity(_) => RescriptTypeIdent::ID,
-                TypeDef::Enum => RescriptTypeIdent::SchemaEnum(name.to_capitalized_options()),
+    pub fn to_underlying_postgres_primitive(&self, schema: &Schema) -> anyhow::Result<PGPrimitive> {
+        if self.is_stored_as_json() {
+            return Ok(PGPrimitive::Json);
+        }
+        match self {
+            Self::Single(gql_scalar) => gql_scalar.to_underlying_postgres_primitive(schema),
+            Self::ListType(field_type) | Self::NonNullType(field_type) => {
+                field_type.to_underlying_postgres_primitive(schema)
+            }
+        }
+    }
+
+    pub fn is_optional(&self) -> bool {
+        !matches!(self, Self::NonNullType(_))
+    }
+
+    ///Multidimensional Postgres arrays can't hold null sub arrays and have to be
+    ///rectangular, so all the lists of lists are stored as jsonb instead
+    pub fn is_stored_as_json(&self) -> bool {
+        match self {
+            Self::Single(_) => false,
+            Self::NonNullType(field_type) => field_type.is_stored_as_json(),
+            Self::ListType(field_type) => field_type.is_array(),
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        match self {
+            Self::ListType(_) => true,
+            Self::NonNullType(field_type) => field_type.is_array(),
+            Self::Single(_) => false,
+        }
+    }
+
+    pub fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
+        let composed_type_name = match self {
+            //Only types in here should be non optional
+            Self::NonNullType(field_type) => match field_type.as_ref() {
+                Self::Single(gql_scalar) => gql_scalar.to_rescript_type(schema)?,
+                Self::ListType(field_type) => {
+                    RescriptTypeIdent::Array(Box::new(field_type.to_rescript_type(schema)?))
+                }
+                //This case shouldn't happen, and should recurse without adding any types if so
+                //A double non null would be !! in gql
+                Self::NonNullType(field_type) => field_type.to_rescript_type(schema)?,
             },
+            //If we match this case it missed the non null path entirely and should be optional
+            Self::Single(gql_scalar) => {
+                RescriptTypeIdent::Option(Box::new(gql_scalar.to_rescript_type(schema)?))
+            }
+            //If we match this case it missed the non null path entirely and should be optional
+            Self::ListType(field_type) => RescriptTypeIdent::Option(Box::new(
+                RescriptTypeIdent::Array(Box::new(field_type.to_rescript_type(schema)?)),
+            )),
         };
-        Ok(res_type)
+        Ok(composed_type_name)
     }
 
-    fn get_linked_entity(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
-        let opt_entity_name = match self {
-            Self::Custom(name) => match schema.try_get_type_def(name)? {
-                TypeDef::Entity(entity) => Some(entity.name.clone()),
-                TypeDef::Enum => None,
+    fn get_underlying_scalar(&self) -> GqlScalar {
+        match self {
+            Self::Single(gql_scalar) => gql_scalar.clone(),
+            Self::ListType(field_type) | Self::NonNullType(field_type) => {
+                field_type.get_underlying_scalar()
+            }
+        }
+    }
+
+    pub fn is_entity_field(&self, schema: &Schema) -> anyhow::Result<bool> {
+        self.get_underlying_scalar().is_entity(schema)
+    }
+
+    ///Returns None if field is not a linked entity and   Some(<ENTITY_NAME>) if it is
+    pub fn get_linked_entity(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
+        self.get_underlying_scalar().get_linked_entity(schema)
+    }
+
+    fn to_string_internal(&self) -> String {
+        match &self {
+            Self::Single(GqlScalar::Custom(name)) => name.clone(),
+            Self::Single(gql_scalar) => gql_scalar.to_string(),
+            Self::ListType(field_type) => format!("[{}]", field_type),
+            Self::NonNullType(field_type) => format!("{}!", field_type),
+        }
+    }
+
+    /// Returns the name of the entity when @derivedFrom derivtive is used
+    /// Returns None in the case that it does not conform to the correct
+    /// structure of a derived entity
+    fn get_name_of_derived_from_entity(&self) -> Option<String> {
+        match self {
+            Self::NonNullType(f) => match f.as_ref() {
+                Self::ListType(f) => match f.as_ref() {
+                    Self::NonNullType(f) => match f.as_ref() {
+                        Self::Single(GqlScalar::Custom(name)) => Some(name.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
             },
             _ => None,
-        };
+        }
+    }
 
-        Ok(opt_entity_name)
+    pub fn from_ethabi_type(abi_type: &EthAbiParamType) -> anyhow::Result<Self> {
+        match abi_type {
+            EthAbiParamType::Uint(_size) | EthAbiParamType::Int(_size) => Ok(Self::NonNullType(
+                Box::new(Self::Single(GqlScalar::BigInt(None))),
+            )),
+            EthAbiParamType::Bool => Ok(Self::NonNullType(Box::new(Self::Single(
+                GqlScalar::Boolean,
+            )))),
+            EthAbiParamType::Address
+            | EthAbiParamType::Bytes
+            | EthAbiParamType::String
+            | EthAbiParamType::FixedBytes(_) => {
+                Ok(Self::NonNullType(Box::new(Self::Single(GqlScalar::String))))
+            }
+            EthAbiParamType::Array(abi_type) | EthAbiParamType::FixedArray(abi_type, _) => {
+                //Validate no nested arrays or
+                match abi_type.as_ref() {
+                    EthAbiParamType::Tuple(_) => {
+                        Err(anyhow!("Unhandled contract import type 'array of tuple'"))?
+                    }
+                    EthAbiParamType::Array(_) => {
+                        Err(anyhow!("Unhandled contract import type 'array of array'"))?
+                    }
+                    _ => (),
+                }
+                let inner_type = Self::from_ethabi_type(abi_type)
+                    .context("Unhandled contract import nested type in array")?;
+                Ok(Self::NonNullType(Box::new(Self::ListType(Box::new(
+                    inner_type,
+                )))))
+            }
+            EthAbiParamType::Tuple(_abi_types) =>
+            //This case should be flattened out unless it is nested inside an array
+            {
+                Err(anyhow!("Unhandled contract import type 'tuple'"))
+            }
+        }
+    }
+}
+
+// Implement the Display trait for the custom struct
+impl fmt::Display for UserDefinedFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_internal())
+    }
+}
+
+impl Serialize for UserDefinedFieldType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+#[derive(Default)]
+pub struct PgTypeModifications {
+    pub big_int_precision: Option<u32>,
+    pub big_decimal_precision_scale: Option<(u32, u32)>,
+}
+
+pub struct FieldTypeParams {
+    pub derived_from_field: Option<String>,
+    pub has_indexed_directive: bool,
+    pub pg_type_modifications: PgTypeModifications,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    DerivedFromField {
+        entity_name: String,
+        derived_from_field: String,
+    },
+    RegularField {
+        field_type: UserDefinedFieldType,
+        has_indexed_directive: bool,
+    },
+}
+
+impl FieldType {
+    fn to_user_defined_field_type(&self) -> UserDefinedFieldType {
+        match self {
+            Self::RegularField { field_type: t, .. } => t.clone(),
+            Self::DerivedFromField { entity_name, .. } => {
+                use UserDefinedFieldType::*;
+                NonNullType(Box::new(ListType(Box::new(NonNullType(Box::new(Single(
+                    GqlScalar::Custom(entity_name.clone()),
+                )))))))
+            }
+        }
+    }
+
+    fn from_obj_field_type(
+        obj_field_type: &ObjType<'_, String>,
+        params: FieldTypeParams,
+    ) -> anyhow::Result<Self> {
+        let field_type = UserDefinedFieldType::from_obj_field_type(
+            obj_field_type,
+            &params.pg_type_modifications,
+        );
+
+        match params.derived_from_field {
+            None => Ok(Self::RegularField {
+                field_type,
+                has_indexed_directive: params.has_indexed_directive,
+            }),
+            Some(derived_from_field) => match field_type.get_name_of_derived_from_entity() {
+                None => {
+                    let example_str = Self::DerivedFromField {
+                        entity_name: "<MY_ENTITY>".to_string(),
+                        derived_from_field,
+                    }
+                    .to_string();
+
+                    Err(anyhow!(
+                        "Field marked with @derivedFrom directive does not meet the required \
+                         structure. Field should contain a non nullable list of non nullable \
+                         entities for example: {example_str}"
+                    ))
+                }
+                Some(entity_name) => Ok(Self::DerivedFromField {
+                    entity_name,
+                    derived_from_field,
+                }),
+            },
+        }
+    }
+
+    pub fn validate_type(&self, schema: &Schema) -> anyhow::Result<()> {
+        match self {
+            Self::DerivedFromField { .. } => Ok(()), //Already validated
+            Self::RegularField { field_type: t, .. } => t.validate_type(schema),
+        }
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.to_user_defined_field_type().is_optional()
+    }
+
+    pub fn is_derived_from(&self) -> bool {
+        matches!(self, Self::DerivedFromField { .. })
+    }
+
+    fn has_indexed_directive(&self) -> bool {
+        match self {
+            Self::DerivedFromField { .. } => false,
+            Self::RegularField {
+                has_indexed_directive,
+                ..
+            } => *has_indexed_directive,
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        match self {
+            Self::DerivedFromField { .. } => true,
+            Self::RegularField { field_type: t, .. } => t.is_array(),
+        }
+    }
+
+    pub fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
+        self.to_user_defined_field_type().to_rescript_type(schema)
+    }
+
+    fn get_underlying_scalar(&self) -> GqlScalar {
+        self.to_user_defined_field_type().get_underlying_scalar()
+    }
+
+    pub fn is_entity_field(&self, schema: &Schema) -> anyhow::Result<bool> {
+        self.to_user_defined_field_type().is_entity_field(schema)
+    }
+
+    fn to_string_internal(&self) -> String {
+        match self {
+            Self::DerivedFromField { entity_name, .. } => {
+                let field_str = self.to_user_defined_field_type().to_string();
+                format!("{field_str} @derivedFrom(field: \"{entity_name}\")")
+            }
+            Self::RegularField { field_type: t, .. } => t.to_string(),
+        }
+    }
+
+    pub fn from_ethabi_type(abi_type: &EthAbiParamType) -> anyhow::Result<Self> {
+        Ok(Self::RegularField {
+            field_type: UserDefinedFieldType::from_ethabi_type(abi_type)?,
+            has_indexed_directive: false,
+        })
+    }
+}
+
+// Implement the Display trait for the custom struct
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_internal())
+    }
+}
+
+impl Serialize for FieldType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+#[subenum(BuiltInGqlScalar, AdditionalGqlScalar)]
+#[derive(Debug, Clone, PartialEq, strum_macros::Display, Eq, Hash)]
+pub enum GqlScalar {
+    #[subenum(BuiltInGqlScalar)]
+    ID,
+    #[subenum(BuiltInGqlScalar)]
+    String,
+    #[subenum(BuiltInGqlScalar)]
+    Int,
+    #[subenum(BuiltInGqlScalar)]
+    Float,
+    #[subenum(BuiltInGqlScalar)]
+    Boolean,
+    #[subenum(AdditionalGqlScalar)]
+    BigInt(Option<u32>), // Optional argument, max digits (base 10) this number can have.
+    #[subenum(AdditionalGqlScalar)]
+    BigDecimal(Option<(u32, u32)>),
+    #[subenum(AdditionalGqlScalar)]
+    Timestamp,
+    #[subenum(AdditionalGqlScalar)]
+    Bytes,
+    #[subenum(AdditionalGqlScalar)]
+    Json,
+    Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigIntPrecisionScale {
+    pub precision: Option<u32>,
+    pub scale: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Relationship {
+    TypeDef {
+        name: String,
+    },
+    DerivedFrom {
+        name: String,
+        derived_from_field: String,
+    },
+}
+
+impl GqlScalar {
+    fn is_entity(&self, schema: &Schema) -> anyhow::Result<bool> {
+        match self {
+            GqlScalar::Custom(name) => Ok(schema.try_get_type_def(name)?.is_entity_reference()),
+            _ => Ok(false),
+        }
+    }
+
+    fn from_str(name: &str, pg_type_modifications: &PgTypeModifications) -> Self {
+        match name {
+            "ID" => GqlScalar::ID,
+            "String" => GqlScalar::String,
+            "Int" => GqlScalar::Int,
+            "Float" => GqlScalar::Float,
+            "Boolean" => GqlScalar::Boolean,
+            "BigInt" => GqlScalar::BigInt(pg_type_modifications.big_int_precision),
+            "BigDecimal" => {
+                GqlScalar::BigDecimal(pg_type_modifications.big_decimal_precision_scale)
+            }
+            "Timestamp" => GqlScalar::Timestamp,
+            "Bytes" => GqlScalar::Bytes,
+            "Json" => GqlScalar::Json,
+            name => GqlScalar::Custom(name.to_string()),
+        }
+    }
+
+    pub fn to_underlying_postgres_primitive(&self, schema: &Schema) -> anyhow::Result<PGPrimitive> {
+        let converted = match self {
+            GqlScalar::ID => PGPrimitive::Text,
+            GqlScalar::String => PGPrimitive::Text,
+            GqlScalar::Int => PGPrimitive::Integer,
+            GqlScalar::Float => PGPrimitive::DoublePrecision, // Should we allow this type? Rounding issues will abound.
+            GqlScalar::Boolean => PGPrimitive::Boolean,
+            GqlScalar::Bytes => PGPrimitive::Text,
+            GqlScalar::Json => PGPrimitive::Json,
+            GqlScalar::BigInt(None) => PGPrimitive::Numeric(None),
+            GqlScalar::BigInt(Some(precision)) => PGPrimitive::Numeric(Some((*precision, 0))), //  We leave the scale as zero since it is not relevant for integers.
+            GqlScalar::BigDecimal(None) => PGPrimitive::Numeric(None),
+            GqlScalar::BigDecimal(Some((precision, scale))) => {
+                PGPrimitive::Numeric(Some((*precision, *scale)))
+            }
+            GqlScalar::Timestamp => PGPrimitive::Timestamp,
+            GqlScalar::Custom(name) => match schema.try_get_type_def(name)? {
+                TypeDef::Entity(_) | TypeDef::Interface(_) | TypeDef::Union(_) => PGPrimitive::Text,
+                TypeDef::Enum => PGPrimitive::Enum(name.clone()),
+            },
+        };
+        Ok(converted)
+    }
+
+    fn to_rescript_type(&self, schema: &Schema) -> anyhow::Result<RescriptTypeIdent> {
+        let res_type = match self {
+            GqlScalar::ID => RescriptTypeIdent::ID,
+            GqlScalar::String => RescriptTypeIdent::String,
+            GqlScalar::Int => RescriptTypeIdent::Int,
+            GqlScalar::BigInt(_) => RescriptTypeIdent::BigInt,
+            GqlScalar::BigDecimal(_) => RescriptTypeIdent::BigDecimal,
+            GqlScalar::Float => RescriptTypeIdent::Float,
+            GqlScalar::Bytes => RescriptTypeIdent::String,
+            GqlScalar::Json => RescriptTypeIdent::Json,
+            GqlScalar::Boolean => RescriptTypeIdent::Bool,
+            GqlScalar::Timestamp => RescriptTypeIdent::Timestamp,
+            GqlScalar::Custom(name) => match schema.try_get_type_def(name)? {
+                TypeDef::Entity(_) | TypeDef::Interface(_) | TypeDef::Union(_) => {
+                    RescriptTypeIdent::ID
+                }
+                TypeDef::Enum => RescriptTypeIdent::SchemaEnum(name.to_capitalized_options()),
+            },
+        };
+        Ok(res_type)
+    }
+
+    fn get_linked_entity(&self, schema: &Schema) -> anyhow::Result<Option<String>> {
+        let opt_entity_name = match self {
+            Self::Custom(name) => schema.try_get_type_def(name)?.get_linked_name(),
+            _ => None,
+        };
+
+        Ok(opt_entity_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        anyhow, Aggregate, AggregateFn, AggregationInterval, Entity, Field, FieldType,
+        FulltextSearch, GqlScalar, GraphQLEnum, IndexMethod, IndexOrder, Schema, UniqueConstraint,
+        UserDefinedFieldType,
+    };
+    use crate::{
+        config_parsing::postgres_types::Primitive as PGPrimitive,
+        rescript_types::RescriptSchemaMode,
+    };
+    use graphql_parser::schema::{parse_schema, Definition, Document, ObjectType, TypeDefinition};
+
+    fn setup_document(schema: &str) -> anyhow::Result<Document<String>> {
+        parse_schema::<String>(schema)
+            .map_err(|e| anyhow!("EE201: Failed to parse schema: {:?}", e))
+    }
+
+    fn get_entities_from_document(gql_doc: Document<String>) -> Vec<ObjectType<String>> {
+        gql_doc
+            .definitions
+            .into_iter()
+            .filter_map(|d| {
+                if let Definition::TypeDefinition(TypeDefinition::Object(obj)) = d {
+                    Some(obj)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn get_first_entity_from_string(schema_str: &str) -> ObjectType<String> {
+        let gql_doc = setup_document(schema_str).unwrap();
+        let entities = get_entities_from_document(gql_doc);
+        entities.first().unwrap().clone()
+    }
+
+    #[test]
+    fn test_field_does_not_exist_in_entity() {
+        let schema_str = r#"
+type TestEntity
+  @index(fields: ["field_that_doesnt_exist", "id", "tokenId"]) {
+  id: ID!
+  tokenId: BigInt! @index
+  collection: String!
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema);
+
+        assert!(parsed_entity.is_err());
+        let err_message = format!("{:?}", parsed_entity.unwrap_err());
+        assert!(err_message.contains("Field 'field_that_doesnt_exist' does not exist"));
+    }
+
+    #[test]
+    fn test_missing_id_field() {
+        let schema_str = r#"
+type TestEntity {
+  testField: String
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema);
+
+        assert!(parsed_entity.is_err());
+        let err_message = format!("{:?}", parsed_entity.unwrap_err());
+        assert_eq!(
+            err_message,
+            "No 'id' field found on entity TestEntity. Please add an 'id' field to your entity."
+        );
+    }
+
+    #[test]
+    fn test_field_is_derived_from_and_indexed() {
+        let schema_str = r#"
+type TestEntity
+  @index(fields: ["collection"]) {
+  id: ID!
+  tokenId: BigInt!
+  collection: [Collection!]! @derivedFrom(field: "owner")
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema);
+
+        assert!(parsed_entity.is_err());
+        let err_message = format!("{:?}", parsed_entity.unwrap_err());
+        println!("{err_message}");
+        assert!(err_message.contains("Index error: Field 'collection' is a @derivedFrom field"));
+    }
+
+    #[test]
+    fn test_duplicate_index_definition() {
+        let schema_str = r#"
+type TestEntity
+  @index(fields: ["id", "tokenId"])
+  @index(fields: ["id", "tokenId"]) {
+  id: ID!
+  tokenId: BigInt! @index
+  collection: String!
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema);
+
+        assert!(parsed_entity.is_err());
+        let err_message = format!("{:?}", parsed_entity.unwrap_err());
+        assert!(err_message.contains(
+            "Index error: Duplicate index found on fields [\"id\", \"tokenId\"] in entity \
+             'TestEntity'"
+        ));
+    }
+
+    #[test]
+    fn test_field_marked_as_indexed_and_index_directive() {
+        let schema_str = r#"
+type TestEntity @index(fields: ["tokenId"]) {
+  id: ID!
+  tokenId: BigInt! @index
+  collection: String!
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema);
+
+        assert!(parsed_entity.is_err());
+        let err_message = format!("{:?}", parsed_entity.unwrap_err());
+        println!("{err_message}");
+        assert!(err_message.contains(
+            "EE202: The field 'tokenId' is marked as an index. Please either remove the @index \
+             directive on the field, or the @index(fields: [\"tokenId\"]) directive on the entity"
+        ));
+    }
+
+    #[test]
+    fn parses_immutable_entity_directive() {
+        let schema_str = r#"
+type TestEntity @entity(immutable: true) {
+  id: ID!
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema).unwrap();
+        assert!(parsed_entity.is_immutable);
+
+        let schema_str = r#"
+type TestEntity @entity(immutable: false) {
+  id: ID!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema).unwrap();
+        assert!(!parsed_entity.is_immutable);
+    }
+
+    #[test]
+    fn fails_with_invalid_entity_directive() {
+        let schema_str = r#"
+type TestEntity @entity(immutable: "yes") {
+  id: ID!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let err = Entity::from_object(&first_entity_schema).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "EE219: Invalid @entity directive on entity TestEntity. The supported arguments are \
+             the booleans immutable and timeseries. Eg. @entity(immutable: true)"
+        );
+    }
+
+    #[test]
+    fn parses_unique_directives() {
+        let schema_str = r#"
+type TestEntity @unique(fields: ["tokenId", "collection"]) {
+  id: ID!
+  tokenId: BigInt!
+  collection: String!
+  owner: String! @unique
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let parsed_entity = Entity::from_object(&first_entity_schema).unwrap();
+
+        assert_eq!(
+            parsed_entity.unique_constraints,
+            vec![
+                UniqueConstraint::new(vec!["owner".to_string()]),
+                UniqueConstraint::new(vec!["tokenId".to_string(), "collection".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_with_invalid_unique_directives() {
+        let schema_str = r#"
+type TestEntity @unique(fields: ["owner", "owner"]) {
+  id: ID!
+  owner: String!
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let err = Entity::from_object(&first_entity_schema).unwrap_err();
+        assert!(format!("{err:?}")
+            .contains("EE220: Field owner is listed multiple times in unique constraint"));
+
+        let schema_str = r#"
+type TestEntity @unique(fields: ["owner"]) {
+  id: ID!
+  owner: String! @unique
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let err = Entity::from_object(&first_entity_schema).unwrap_err();
+        assert!(format!("{err:?}").contains(
+            "EE220: Duplicate unique constraint found on fields [\"owner\"] in entity 'TestEntity'"
+        ));
+
+        let schema_str = r#"
+type TestEntity {
+  id: ID! @unique
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let err = Entity::from_object(&first_entity_schema).unwrap_err();
+        assert!(
+            format!("{err:?}").contains("EE220: Field 'id' is unique by default in all entities")
+        );
+    }
+
+    #[test]
+    fn more_than_one_derived_from_directive() {
+        let schema_str = r#"
+type TestEntity {
+  id: ID!
+  testField: String @derivedFrom(field: "someField") @derivedFrom(field: "anotherField")
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let result = Entity::from_object(&first_entity_schema);
+
+        assert!(
+            result.is_err(),
+            "Should error with more than one @derivedFrom directive"
+        );
+    }
+
+    #[test]
+    fn more_than_one_indexed_directive() {
+        let schema_str = r#"
+type TestEntity {
+  id: ID!
+  testField: String @index @index
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let result = Entity::from_object(&first_entity_schema);
+
+        assert!(
+            result.is_err(),
+            "Should error with more than one @index directive"
+        );
+    }
+
+    #[test]
+    fn fail_derived_from_and_indexed_directive() {
+        let schema_str = r#"
+type TestEntity {
+  id: ID!
+  testField: String @derivedFrom(field: "someField") @index
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let result = Entity::from_object(&first_entity_schema);
+
+        assert!(
+            result.is_err(),
+            "Should error with both @derivedFrom and @index directives"
+        );
+    }
+
+    #[test]
+    fn fail_id_field_with_derived_from_or_indexed_directive() {
+        let schema_str = r#"
+type TestEntity {
+  id: ID! @derivedFrom(field: "someField")
+  ID: ID! @index
+}
+        "#;
+        let first_entity_schema = get_first_entity_from_string(schema_str);
+        let result = Entity::from_object(&first_entity_schema);
+
+        assert!(
+            result.is_err(),
+            "Should error when 'id' or 'ID' field is indexed or derived from"
+        );
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_string() {
+        let empty_schema = Schema::empty();
+        let rescript_type = UserDefinedFieldType::Single(GqlScalar::String)
+            .to_rescript_type(&empty_schema)
+            .expect("expected rescript option string");
+
+        assert_eq!(rescript_type.to_string(), "option<string>".to_owned());
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_int() {
+        let empty_schema = Schema::empty();
+        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Int)
+            .to_rescript_type(&empty_schema)
+            .expect("expected rescript option string");
+
+        assert_eq!(rescript_type.to_string(), "option<int>".to_owned());
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_non_null_int() {
+        let empty_schema = Schema::empty();
+        let rescript_type = UserDefinedFieldType::NonNullType(Box::new(
+            UserDefinedFieldType::Single(GqlScalar::Int),
+        ))
+        .to_rescript_type(&empty_schema)
+        .expect("expected rescript type string");
+
+        assert_eq!(rescript_type.to_string(), "int".to_owned());
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_non_null_json() {
+        let empty_schema = Schema::empty();
+        let field_type = UserDefinedFieldType::NonNullType(Box::new(UserDefinedFieldType::Single(
+            GqlScalar::from_str("Json", &Default::default()),
+        )));
+        let rescript_type = field_type
+            .to_rescript_type(&empty_schema)
+            .expect("expected rescript type string");
+
+        assert_eq!(rescript_type.to_string(), "Js.Json.t".to_owned());
+        assert_eq!(
+            rescript_type.to_rescript_schema(&RescriptSchemaMode::ForDb),
+            "S.json(~validate=true)".to_owned()
+        );
+        assert_eq!(
+            field_type
+                .to_underlying_postgres_primitive(&empty_schema)
+                .unwrap(),
+            PGPrimitive::Json
+        );
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_non_null_array() {
+        let empty_schema = Schema::empty();
+        let rescript_type = UserDefinedFieldType::NonNullType(Box::new(
+            UserDefinedFieldType::ListType(Box::new(UserDefinedFieldType::NonNullType(Box::new(
+                UserDefinedFieldType::Single(GqlScalar::Int),
+            )))),
+        ))
+        .to_rescript_type(&empty_schema)
+        .expect("expected rescript type string");
+
+        assert_eq!(rescript_type.to_string(), "array<int>".to_owned());
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_null_array_int() {
+        let empty_schema = Schema::empty();
+
+        let rescript_type =
+            UserDefinedFieldType::ListType(Box::new(UserDefinedFieldType::Single(GqlScalar::Int)))
+                .to_rescript_type(&empty_schema)
+                .expect("expected rescript type string");
+
+        assert_eq!(
+            rescript_type.to_string(),
+            "option<array<option<int>>>".to_owned()
+        );
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_entity() {
+        let test_entity_string = String::from("TestEntity");
+        let test_entity = Entity::new(
+            &test_entity_string,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            false,
+        )
+        .unwrap();
+        let schema = Schema::new(vec![test_entity], vec![], vec![], vec![], vec![]).unwrap();
+        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Custom(test_entity_string))
+            .to_rescript_type(&schema)
+            .expect("expected rescript type string");
+
+        assert_eq!(rescript_type.to_string(), "option<id>".to_owned());
+    }
+
+    #[test]
+    fn gql_type_to_rescript_type_enum() {
+        let name = String::from("TestEnum");
+        let test_enum = GraphQLEnum::new(name.clone(), vec![]).unwrap();
+        let schema = Schema::new(vec![], vec![test_enum], vec![], vec![], vec![]).unwrap();
+        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Custom(name))
+            .to_rescript_type(&schema)
+            .expect("expected rescript type string");
+
+        assert_eq!(
+            rescript_type.to_string(),
+            "option<Enums.TestEnum.t>".to_owned()
+        );
+    }
+
+    #[test]
+    fn field_type_is_optional_test() {
+        let test_scalar = GqlScalar::Custom(String::from("TestEntity"));
+        let test_field_type = UserDefinedFieldType::Single(test_scalar);
+        assert!(
+            test_field_type.is_optional(),
+            "single field should have been optional"
+        );
+
+        // ListType:
+        let test_list_type = UserDefinedFieldType::ListType(Box::new(test_field_type));
+        assert!(
+            test_list_type.is_optional(),
+            "list field should have been optional"
+        );
+
+        // NonNullType
+        let gql_array_non_null_type = UserDefinedFieldType::NonNullType(Box::new(test_list_type));
+        assert!(
+            !gql_array_non_null_type.is_optional(),
+            "non-null field should not be optional"
+        );
+    }
+
+    fn get_field_type_helper_with_additional(
+        gql_field_str: &str,
+        enum_types: Vec<GraphQLEnum>,
+    ) -> FieldType {
+        let enum_type_defs: String = enum_types
+            .iter()
+            .map(|e| format!("enum {} {{\n{}\n}}", e.name, e.values.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let schema_string = format!(
+            r#"
+        type TestEntity {{
+          id: ID!
+          test_field: {gql_field_str}
+        }}
+        {enum_type_defs}
+        "#,
+        );
+        let schema_doc = graphql_parser::schema::parse_schema::<String>(&schema_string).unwrap();
+
+        let schema = Schema::from_document(schema_doc).expect("bad schema");
+
+        let test_field = schema
+            .entities
+            .get("TestEntity")
+            .expect("No test entity in schema")
+            .fields
+            .get("test_field")
+            .expect("No field test_field on entity")
+            .clone();
+
+        test_field.field_type
+    }
+
+    fn get_field_type_helper(gql_field_str: &str) -> FieldType {
+        get_field_type_helper_with_additional(gql_field_str, vec![])
+    }
+
+    #[test]
+    fn gql_enum_type_to_pgprimitive() {
+        let name = String::from("TestEnum");
+        let test_enum = GraphQLEnum::new(name.clone(), vec!["TEST_VALUE".to_string()]).unwrap();
+        let field_type =
+            get_field_type_helper_with_additional("TestEnum!", vec![test_enum.clone()]);
+        let schema = Schema::new(vec![], vec![test_enum], vec![], vec![], vec![]).unwrap();
+        let pg_primitive = field_type
+            .to_user_defined_field_type()
+            .to_underlying_postgres_primitive(&schema)
+            .expect("unable to get postgres primitive");
+        assert_eq!(pg_primitive, PGPrimitive::Enum("TestEnum".to_string()));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::{
-        anyhow, Entity, Field, FieldType, GqlScalar, GraphQLEnum, Schema, UserDefinedFieldType,
-    };
-    use crate::config_parsing::postgres_types::Primitive as PGPrimitive;
-    use graphql_parser::schema::{parse_schema, Definition, Document, ObjectType, TypeDefinition};
 
-    fn setup_document(schema: &str) -> anyhow::Result<Document<String>> {
-        parse_schema::<String>(schema)
-            .map_err(|e| anyhow!("EE201: Failed to parse schema: {:?}", e))
+    #[test]
+    fn gql_single_not_null_array_to_pgprimitive() {
+        let gql_type = "[String!]!";
+        let field_type = get_field_type_helper(gql_type);
+        let empty_schema = Schema::empty();
+        let pg_primitive = field_type
+            .to_user_defined_field_type()
+            .to_underlying_postgres_primitive(&empty_schema)
+            .expect("unable to get postgres primitive");
+        assert_eq!(pg_primitive, PGPrimitive::Text);
+        assert!(field_type.to_user_defined_field_type().is_array());
     }
 
-    fn get_entities_from_document(gql_doc: Document<String>) -> Vec<ObjectType<String>> {
-        gql_doc
-            .definitions
-            .into_iter()
-            .filter_map(|d| {
-                if let Definition::TypeDefinition(TypeDefinition::Object(obj)) = d {
-                    Some(obj)
-                } else {
-                    None
-                }
-            })
-            .collect()
+    #[test]
+    fn gql_multi_not_null_array_to_pgprimitive() {
+        // Postgres arrays of arrays have to be rectangular, so the list is stored as jsonb
+        let gql_type = "[[Int!]!]!";
+        let field_type = get_field_type_helper(gql_type);
+        let empty_schema = Schema::empty();
+        let pg_primitive = field_type
+            .to_user_defined_field_type()
+            .to_underlying_postgres_primitive(&empty_schema)
+            .expect("unable to get postgres primitive");
+        assert_eq!(pg_primitive, PGPrimitive::Json);
+        assert!(field_type.to_user_defined_field_type().is_array());
+        assert!(field_type.to_user_defined_field_type().is_stored_as_json());
     }
 
-    fn get_first_entity_from_string(schema_str: &str) -> ObjectType<String> {
-        let gql_doc = setup_document(schema_str).unwrap();
-        let entities = get_entities_from_document(gql_doc);
-        entities.first().unwrap().clone()
+    #[test]
+    fn gql_single_nullable_array_to_pgprimitive() {
+        let gql_type = "[Int]!";
+        let field_type = get_field_type_helper(gql_type);
+        let empty_schema = Schema::empty();
+        let pg_primitive = field_type
+            .to_user_defined_field_type()
+            .to_underlying_postgres_primitive(&empty_schema)
+            .expect("unable to get postgres primitive");
+        assert_eq!(pg_primitive, PGPrimitive::Integer);
+        assert!(!field_type.to_user_defined_field_type().is_stored_as_json());
     }
 
     #[test]
-    fn test_field_does_not_exist_in_entity() {
-        let schema_str = r#"
-type TestEntity
-  @index(fields: ["field_that_doesnt_exist", "id", "tokenId"]) {
-  id: ID!
-  tokenId: BigInt! @index
-  collection: String!
-  owner: String!
-}
-        "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let parsed_entity = Entity::from_object(&first_entity_schema);
-
-        assert!(parsed_entity.is_err());
-        let err_message = format!("{:?}", parsed_entity.unwrap_err());
-        assert!(err_message.contains("Field 'field_that_doesnt_exist' does not exist"));
+    fn gql_multi_nullable_array_to_pgprimitive() {
+        // Postgres can't hold null sub arrays, so the list is stored as jsonb
+        let gql_type = "[[Int!]]!";
+        let field_type = get_field_type_helper(gql_type);
+        let empty_schema = Schema::empty();
+        let pg_primitive = field_type
+            .to_user_defined_field_type()
+            .to_underlying_postgres_primitive(&empty_schema)
+            .expect("unable to get postgres primitive");
+        assert_eq!(pg_primitive, PGPrimitive::Json);
+        assert!(field_type.to_user_defined_field_type().is_stored_as_json());
     }
 
     #[test]
-    fn test_missing_id_field() {
-        let schema_str = r#"
-type TestEntity {
-  testField: String
-}
-        "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let parsed_entity = Entity::from_object(&first_entity_schema);
+    fn test_nullability_to_string() {
+        use UserDefinedFieldType::{ListType, NonNullType, Single};
+        let scalar = NonNullType(Box::new(ListType(Box::new(Single(GqlScalar::Int)))));
 
-        assert!(parsed_entity.is_err());
-        let err_message = format!("{:?}", parsed_entity.unwrap_err());
-        assert_eq!(
-            err_message,
-            "No 'id' field found on entity TestEntity. Please add an 'id' field to your entity."
-        );
+        let expected_output = "[Int]!".to_string();
+
+        assert_eq!(scalar.to_string(), expected_output);
     }
 
     #[test]
-    fn test_field_is_derived_from_and_indexed() {
-        let schema_str = r#"
-type TestEntity
-  @index(fields: ["collection"]) {
-  id: ID!
-  tokenId: BigInt!
-  collection: [Collection!]! @derivedFrom(field: "owner")
-  owner: String!
-}
-        "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let parsed_entity = Entity::from_object(&first_entity_schema);
+    fn gql_type_to_rescript_nullable() {
+        let field_type = get_field_type_helper("Int");
 
-        assert!(parsed_entity.is_err());
-        let err_message = format!("{:?}", parsed_entity.unwrap_err());
-        println!("{err_message}");
-        assert!(err_message.contains("Index error: Field 'collection' is a @derivedFrom field"));
+        let empty_schema = Schema::empty();
+        let rescript_type = field_type.to_rescript_type(&empty_schema).unwrap();
+        assert_eq!("option<int>".to_string(), rescript_type.to_string());
     }
 
     #[test]
-    fn test_duplicate_index_definition() {
-        let schema_str = r#"
-type TestEntity
-  @index(fields: ["id", "tokenId"])
-  @index(fields: ["id", "tokenId"]) {
-  id: ID!
-  tokenId: BigInt! @index
-  collection: String!
-  owner: String!
-}
-        "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let parsed_entity = Entity::from_object(&first_entity_schema);
+    fn gql_type_to_rescript_array_nullable_string() {
+        let field_type = get_field_type_helper("[String]!");
 
-        assert!(parsed_entity.is_err());
-        let err_message = format!("{:?}", parsed_entity.unwrap_err());
-        assert!(err_message.contains(
-            "Index error: Duplicate index found on fields [\"id\", \"tokenId\"] in entity \
-             'TestEntity'"
-        ));
+        let empty_schema = Schema::empty();
+        let rescript_type = field_type.to_rescript_type(&empty_schema).unwrap();
+        assert_eq!(
+            "array<option<string>>".to_string(),
+            rescript_type.to_string()
+        );
+        assert_eq!(
+            "S.array(S.null(S.string))".to_string(),
+            rescript_type.to_rescript_schema(&RescriptSchemaMode::ForDb)
+        );
     }
 
     #[test]
-    fn test_field_marked_as_indexed_and_index_directive() {
-        let schema_str = r#"
-type TestEntity @index(fields: ["tokenId"]) {
-  id: ID!
-  tokenId: BigInt! @index
-  collection: String!
-  owner: String!
-}
-        "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let parsed_entity = Entity::from_object(&first_entity_schema);
+    fn gql_type_to_rescript_nullable_array_nullable_string() {
+        let field_type = get_field_type_helper("[String]");
 
-        assert!(parsed_entity.is_err());
-        let err_message = format!("{:?}", parsed_entity.unwrap_err());
-        println!("{err_message}");
-        assert!(err_message.contains(
-            "EE202: The field 'tokenId' is marked as an index. Please either remove the @index \
-             directive on the field, or the @index(fields: [\"tokenId\"]) directive on the entity"
-        ));
+        let empty_schema = Schema::empty();
+        let rescript_type = field_type.to_rescript_type(&empty_schema).unwrap();
+        assert_eq!(
+            "option<array<option<string>>>".to_string(),
+            rescript_type.to_string()
+        );
+        assert_eq!(
+            "S.null(S.array(S.null(S.string)))".to_string(),
+            rescript_type.to_rescript_schema(&RescriptSchemaMode::ForDb)
+        );
     }
 
     #[test]
-    fn more_than_one_derived_from_directive() {
+    fn test_get_postgres_field_basic() {
         let schema_str = r#"
 type TestEntity {
   id: ID!
-  testField: String @derivedFrom(field: "someField") @derivedFrom(field: "anotherField")
+  name: String! @index
 }
         "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let result = Entity::from_object(&first_entity_schema);
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("TestEntity").unwrap();
+        let field = entity.fields.get("name").unwrap();
+        let pg_field = field
+            .get_postgres_field(&schema, entity)
+            .expect("Failed to get postgres field")
+            .unwrap();
 
-        assert!(
-            result.is_err(),
-            "Should error with more than one @derivedFrom directive"
-        );
+        assert_eq!(pg_field.field_name, "name");
+        assert_eq!(pg_field.field_type, PGPrimitive::Text);
+        assert!(pg_field.is_index);
+        assert!(!pg_field.is_array);
+        assert!(!pg_field.is_nullable);
+        assert_eq!(pg_field.linked_entity, None);
     }
 
     #[test]
-    fn more_than_one_indexed_directive() {
+    fn test_get_postgres_field_with_linked_entity() {
         let schema_str = r#"
 type TestEntity {
   id: ID!
-  testField: String @index @index
+  relatedEntity: RelatedEntity!
+}
+
+type RelatedEntity {
+  id: ID!
 }
         "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let result = Entity::from_object(&first_entity_schema);
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("TestEntity").unwrap();
+        let field = entity.fields.get("relatedEntity").unwrap();
+        let pg_field = field
+            .get_postgres_field(&schema, entity)
+            .expect("Failed to get postgres field")
+            .unwrap();
 
-        assert!(
-            result.is_err(),
-            "Should error with more than one @index directive"
-        );
+        assert_eq!(pg_field.field_name, "relatedEntity");
+        assert_eq!(pg_field.field_type, PGPrimitive::Text);
+        assert!(!pg_field.is_index);
+        assert!(!pg_field.is_array);
+        assert!(!pg_field.is_nullable);
+        assert_eq!(pg_field.linked_entity, Some("RelatedEntity".to_string()));
     }
 
     #[test]
-    fn fail_derived_from_and_indexed_directive() {
+    fn test_get_postgres_field_array_type() {
         let schema_str = r#"
 type TestEntity {
   id: ID!
-  testField: String @derivedFrom(field: "someField") @index
+  tags: [String!]!
 }
         "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let result = Entity::from_object(&first_entity_schema);
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("TestEntity").unwrap();
+        let field = entity.fields.get("tags").unwrap();
+        let pg_field = field
+            .get_postgres_field(&schema, entity)
+            .expect("Failed to get postgres field")
+            .unwrap();
 
-        assert!(
-            result.is_err(),
-            "Should error with both @derivedFrom and @index directives"
-        );
+        assert_eq!(pg_field.field_name, "tags");
+        assert_eq!(pg_field.field_type, PGPrimitive::Text);
+        assert!(!pg_field.is_index);
+        assert!(pg_field.is_array);
+        assert!(!pg_field.is_nullable);
+        assert_eq!(pg_field.linked_entity, None);
     }
 
     #[test]
-    fn fail_id_field_with_derived_from_or_indexed_directive() {
+    fn test_get_postgres_field_nullable_array_types() {
         let schema_str = r#"
 type TestEntity {
-  id: ID! @derivedFrom(field: "someField")
-  ID: ID! @index
+  id: ID!
+  requiredListOfRequired: [String!]!
+  requiredListOfOptional: [String]!
+  optionalListOfRequired: [String!]
+  optionalListOfOptional: [String]
+  requiredMatrix: [[Int!]!]!
+  matrixOfOptionalElements: [[Int]!]!
+  matrixOfOptionalLists: [[Int!]]!
+  optionalMatrixOfOptional: [[Int]]
 }
         "#;
-        let first_entity_schema = get_first_entity_from_string(schema_str);
-        let result = Entity::from_object(&first_entity_schema);
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("TestEntity").unwrap();
 
-        assert!(
-            result.is_err(),
-            "Should error when 'id' or 'ID' field is indexed or derived from"
-        );
+        // (field name, postgres type, is postgres array, is nullable, rescript type, db schema)
+        let expected = [
+            (
+                "requiredListOfRequired",
+                PGPrimitive::Text,
+                true,
+                false,
+                "array<string>",
+                "S.array(S.string)",
+            ),
+            (
+                "requiredListOfOptional",
+                PGPrimitive::Text,
+                true,
+                false,
+                "array<option<string>>",
+                "S.array(S.null(S.string))",
+            ),
+            (
+                "optionalListOfRequired",
+                PGPrimitive::Text,
+                true,
+                true,
+                "option<array<string>>",
+                "S.null(S.array(S.string))",
+            ),
+            (
+                "optionalListOfOptional",
+                PGPrimitive::Text,
+                true,
+                true,
+                "option<array<option<string>>>",
+                "S.null(S.array(S.null(S.string)))",
+            ),
+            (
+                "requiredMatrix",
+                PGPrimitive::Json,
+                false,
+                false,
+                "array<array<int>>",
+                "S.array(S.array(S.int))",
+            ),
+            (
+                "matrixOfOptionalElements",
+                PGPrimitive::Json,
+                false,
+                false,
+                "array<array<option<int>>>",
+                "S.array(S.array(S.null(S.int)))",
+            ),
+            (
+                "matrixOfOptionalLists",
+                PGPrimitive::Json,
+                false,
+                false,
+                "array<option<array<int>>>",
+                "S.array(S.null(S.array(S.int)))",
+            ),
+            (
+                "optionalMatrixOfOptional",
+                PGPrimitive::Json,
+                false,
+                true,
+                "option<array<option<array<option<int>>>>>",
+                "S.null(S.array(S.null(S.array(S.null(S.int)))))",
+            ),
+        ];
+
+        for (field_name, pg_type, is_array, is_nullable, rescript_type, db_schema) in expected {
+            let field = entity.fields.get(field_name).unwrap();
+            let pg_field = field
+                .get_postgres_field(&schema, entity)
+                .expect("Failed to get postgres field")
+                .unwrap();
+            assert_eq!(pg_field.field_type, pg_type, "{field_name}");
+            assert_eq!(pg_field.is_array, is_array, "{field_name}");
+            assert_eq!(pg_field.is_nullable, is_nullable, "{field_name}");
+
+            let res_type = field.field_type.to_rescript_type(&schema).unwrap();
+            assert_eq!(res_type.to_string(), rescript_type, "{field_name}");
+            assert_eq!(
+                res_type.to_rescript_schema(&RescriptSchemaMode::ForDb),
+                db_schema,
+                "{field_name}"
+            );
+        }
     }
 
     #[test]
-    fn gql_type_to_rescript_type_string() {
-        let empty_schema = Schema::empty();
-        let rescript_type = UserDefinedFieldType::Single(GqlScalar::String)
-            .to_rescript_type(&empty_schema)
-            .expect("expected rescript option string");
+    fn test_many_to_many_field() {
+        let schema_str = r#"
+type Owner {
+  id: ID!
+  tokens: [Token!]!
+}
 
-        assert_eq!(rescript_type.to_string(), "option<string>".to_owned());
+type Token {
+  id: ID!
+  owners: [Owner!]! @derivedFrom(field: "tokens")
+}
+        "#;
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("Owner").unwrap();
+        let field = entity.fields.get("tokens").unwrap();
+        let pg_field = field
+            .get_postgres_field(&schema, entity)
+            .expect("Failed to get postgres field")
+            .unwrap();
+
+        assert!(field.is_many_to_many_field(&schema).unwrap());
+        assert!(field.is_derived_lookup_field(entity, &schema));
+        assert_eq!(pg_field.field_type, PGPrimitive::Text);
+        assert!(pg_field.is_array);
+        assert_eq!(pg_field.linked_entity, Some("Token".to_string()));
     }
 
     #[test]
-    fn gql_type_to_rescript_type_int() {
-        let empty_schema = Schema::empty();
-        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Int)
-            .to_rescript_type(&empty_schema)
-            .expect("expected rescript option string");
+    fn fails_with_invalid_many_to_many_fields() {
+        let get_err_message = |field_type: &str| {
+            let schema_str = format!(
+                r#"
+type Owner {{
+  id: ID!
+  tokens: {field_type}
+}}
 
-        assert_eq!(rescript_type.to_string(), "option<int>".to_owned());
+type Token {{
+  id: ID!
+}}
+        "#
+            );
+            let gql_doc = setup_document(&schema_str).unwrap();
+            format!("{:?}", Schema::from_document(gql_doc).unwrap_err())
+        };
+
+        assert!(get_err_message("[Token]!").contains(
+            "EE211: Entities referenced in an array can't be nullable. Please include a '!' after \
+             the entity 'Token' eg. [Token!]!"
+        ));
+        assert!(get_err_message("[[Token!]!]!").contains(
+            "EE211: Multidimensional arrays of entities are unsupported. The type of the field is \
+             '[[Token!]!]'."
+        ));
     }
 
     #[test]
-    fn gql_type_to_rescript_type_non_null_int() {
-        let empty_schema = Schema::empty();
-        let rescript_type = UserDefinedFieldType::NonNullType(Box::new(
-            UserDefinedFieldType::Single(GqlScalar::Int),
-        ))
-        .to_rescript_type(&empty_schema)
-        .expect("expected rescript type string");
+    fn test_get_postgres_field_enum_type() {
+        let schema_str = r#"
+enum Status {
+  ACTIVE
+  INACTIVE
+}
 
-        assert_eq!(rescript_type.to_string(), "int".to_owned());
+type TestEntity {
+  id: ID!
+  status: Status!
+}
+        "#;
+        let gql_doc = setup_document(schema_str).unwrap();
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let entity = schema.entities.get("TestEntity").unwrap();
+        let field = entity.fields.get("status").unwrap();
+        let pg_field = field
+            .get_postgres_field(&schema, entity)
+            .expect("Failed to get postgres field")
+            .unwrap();
+
+        assert_eq!(pg_field.field_name, "status");
+        assert_eq!(pg_field.field_type, PGPrimitive::Enum("Status".to_string()));
+        assert!(!pg_field.is_index);
+        assert!(!pg_field.is_array);
+        assert!(!pg_field.is_nullable);
+        assert_eq!(pg_field.linked_entity, None);
     }
 
     #[test]
-    fn gql_type_to_rescript_type_non_null_array() {
-        let empty_schema = Schema::empty();
-        let rescript_type = UserDefinedFieldType::NonNullType(Box::new(
-            UserDefinedFieldType::ListType(Box::new(UserDefinedFieldType::NonNullType(Box::new(
-                UserDefinedFieldType::Single(GqlScalar::Int),
-            )))),
-        ))
-        .to_rescript_type(&empty_schema)
-        .expect("expected rescript type string");
-
-        assert_eq!(rescript_type.to_string(), "array<int>".to_owned());
+    fn test_decimal_precision_config_happy_path() {
+        let schema_str = r#"
+    type Entity {
+        id: ID!
+        exampleBigInt: BigInt @config(precision: 76)
+        exampleBigIntRequired: BigInt! @config(precision: 77)
+        exampleBigIntArray: [BigInt!] @config(precision: 78)
+        exampleBigIntArrayRequired: [BigInt!]! @config(precision: 79)
+        exampleBigDecimal: BigDecimal @config(precision: 80, scale: 5)
+        exampleBigDecimalRequired: BigDecimal! @config(precision: 81, scale: 5)
+        exampleBigDecimalArray: [BigDecimal!] @config(precision: 82, scale: 5)
+        exampleBigDecimalArrayRequired: [BigDecimal!]! @config(precision: 83, scale: 5)
+        exampleBigDecimalOtherOrder: BigDecimal! @config(scale: 6, precision: 84)
     }
+    "#;
 
-    #[test]
-    fn gql_type_to_rescript_type_null_array_int() {
-        let empty_schema = Schema::empty();
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let schema = Schema::from_document(gql_doc).expect("Failed to create schema");
 
-        let rescript_type =
-            UserDefinedFieldType::ListType(Box::new(UserDefinedFieldType::Single(GqlScalar::Int)))
-                .to_rescript_type(&empty_schema)
-                .expect("expected rescript type string");
+        // Verify that the schema contains the entity and fields as expected
+        let entity = schema.entities.get("Entity").expect("Entity not found");
 
-        assert_eq!(
-            rescript_type.to_string(),
-            "option<array<option<int>>>".to_owned()
-        );
-    }
+        // Helper function -  tests that the types of each field is what we expect
+        fn check_field_type(
+            field: &Field,
+            expected_scalar: &str, // "BigInt" or "BigDecimal"
+            expected_precision: Option<u32>,
+            expected_scale: Option<u32>,
+            is_required: bool,
+            is_array: bool,
+        ) {
+            match &field.field_type {
+                FieldType::RegularField { field_type, .. } => {
+                    //  In this test, we strip this type from the outside to the inside like an onion and check that each layer is correct.
+                    let mut current_type = field_type;
 
-    #[test]
-    fn gql_type_to_rescript_type_entity() {
-        let test_entity_string = String::from("TestEntity");
-        let test_entity = Entity::new(&test_entity_string, vec![], vec![]).unwrap();
-        let schema = Schema::new(vec![test_entity], vec![]).unwrap();
-        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Custom(test_entity_string))
-            .to_rescript_type(&schema)
-            .expect("expected rescript type string");
+                    // Handle non-null types
+                    if is_required {
+                        match current_type {
+                            UserDefinedFieldType::NonNullType(inner) => {
+                                current_type = inner.as_ref();
+                            }
+                            _ => panic!("Field '{}' is expected to be non-null", field.name),
+                        }
+                    } else if matches!(current_type, UserDefinedFieldType::NonNullType(_)) {
+                        panic!("Field '{}' should be nullable", field.name);
+                    }
 
-        assert_eq!(rescript_type.to_string(), "option<id>".to_owned());
-    }
+                    // Handle array types
+                    if is_array {
+                        match current_type {
+                            UserDefinedFieldType::ListType(inner) => {
+                                current_type = inner.as_ref();
+                            }
+                            _ => panic!("Field '{}' is expected to be an array", field.name),
+                        }
+                        // Array elements should be non-null (e.g., [Type!])
+                        match current_type {
+                            UserDefinedFieldType::NonNullType(inner) => {
+                                current_type = inner.as_ref();
+                            }
+                            _ => panic!(
+                                "Array elements of field '{}' are expected to be non-null",
+                                field.name
+                            ),
+                        }
+                    } else if matches!(current_type, UserDefinedFieldType::ListType(_)) {
+                        panic!("Field '{}' should not be an array", field.name);
+                    }
 
-    #[test]
-    fn gql_type_to_rescript_type_enum() {
-        let name = String::from("TestEnum");
-        let test_enum = GraphQLEnum::new(name.clone(), vec![]).unwrap();
-        let schema = Schema::new(vec![], vec![test_enum]).unwrap();
-        let rescript_type = UserDefinedFieldType::Single(GqlScalar::Custom(name))
-            .to_rescript_type(&schema)
-            .expect("expected rescript type string");
+                    // Check the scalar type and precision/scale
+                    match current_type {
+                        UserDefinedFieldType::Single(scalar) => match (scalar, expected_scalar) {
+                            (GqlScalar::BigInt(Some(precision)), "BigInt") => {
+                                if let Some(expected_precision) = expected_precision {
+                                    assert_eq!(
+                                        *precision, expected_precision,
+                                        "Field '{}' has precision {}, expected {}",
+                                        field.name, precision, expected_precision
+                                    );
+                                } else {
+                                    panic!("Expected precision for BigInt field '{}'", field.name);
+                                }
+                            }
+                            (GqlScalar::BigDecimal(Some((precision, scale))), "BigDecimal") => {
+                                if let (Some(expected_precision), Some(expected_scale)) =
+                                    (expected_precision, expected_scale)
+                                {
+                                    assert_eq!(
+                                        (*precision, *scale),
+                                        (expected_precision, expected_scale),
+                                        "Field '{}' has precision {}, scale {}, expected \
+                                         precision {}, scale {}",
+                                        field.name,
+                                        precision,
+                                        scale,
+                                        expected_precision,
+                                        expected_scale
+                                    );
+                                } else {
+                                    panic!(
+                                        "Expected precision and scale for BigDecimal field '{}'",
+                                        field.name
+                                    );
+                                }
+                            }
+                            _ => panic!(
+                                "Field '{}' has unexpected scalar type or missing precision/scale",
+                                field.name
+                            ),
+                        },
+                        _ => panic!("Field '{}' has unexpected field type", field.name),
+                    }
+                }
+                _ => panic!("Field '{}' is not a regular field", field.name),
+            }
+        }
 
-        assert_eq!(
-            rescript_type.to_string(),
-            "option<Enums.TestEnum.t>".to_owned()
-        );
-    }
+        // Now use the helper function to test all fields
 
-    #[test]
-    fn field_type_is_optional_test() {
-        let test_scalar = GqlScalar::Custom(String::from("TestEntity"));
-        let test_field_type = UserDefinedFieldType::Single(test_scalar);
-        assert!(
-            test_field_type.is_optional(),
-            "single field should have been optional"
+        // BigInt fields
+        check_field_type(
+            entity.fields.get("exampleBigInt").expect("Field not found"),
+            "BigInt",
+            Some(76),
+            None,
+            false, // is_required
+            false, // is_array
         );
 
-        // ListType:
-        let test_list_type = UserDefinedFieldType::ListType(Box::new(test_field_type));
-        assert!(
-            test_list_type.is_optional(),
-            "list field should have been optional"
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigIntRequired")
+                .expect("Field not found"),
+            "BigInt",
+            Some(77),
+            None,
+            true,  // is_required
+            false, // is_array
         );
 
-        // NonNullType
-        let gql_array_non_null_type = UserDefinedFieldType::NonNullType(Box::new(test_list_type));
-        assert!(
-            !gql_array_non_null_type.is_optional(),
-            "non-null field should not be optional"
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigIntArray")
+                .expect("Field not found"),
+            "BigInt",
+            Some(78),
+            None,
+            false, // is_required
+            true,  // is_array
         );
-    }
 
-    fn get_field_type_helper_with_additional(
-        gql_field_str: &str,
-        enum_types: Vec<GraphQLEnum>,
-    ) -> FieldType {
-        let enum_type_defs: String = enum_types
-            .iter()
-            .map(|e| format!("enum {} {{\n{}\n}}", e.name, e.values.join("\n")))
-            .collect::<Vec<_>>()
-            .join("\n");
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigIntArrayRequired")
+                .expect("Field not found"),
+            "BigInt",
+            Some(79),
+            None,
+            true, // is_required
+            true, // is_array
+        );
 
-        let schema_string = format!(
-            r#"
-        type TestEntity {{
-          id: ID!
-          test_field: {gql_field_str}
-        }}
-        {enum_type_defs}
-        "#,
+        // BigDecimal fields
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigDecimal")
+                .expect("Field not found"),
+            "BigDecimal",
+            Some(80),
+            Some(5),
+            false, // is_required
+            false, // is_array
         );
-        let schema_doc = graphql_parser::schema::parse_schema::<String>(&schema_string).unwrap();
 
-        let schema = Schema::from_document(schema_doc).expect("bad schema");
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigDecimalRequired")
+                .expect("Field not found"),
+            "BigDecimal",
+            Some(81),
+            Some(5),
+            true,  // is_required
+            false, // is_array
+        );
 
-        let test_field = schema
-            .entities
-            .get("TestEntity")
-            .expect("No test entity in schema")
-            .fields
-            .get("test_field")
-            .expect("No field test_field on entity")
-            .clone();
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigDecimalArray")
+                .expect("Field not found"),
+            "BigDecimal",
+            Some(82),
+            Some(5),
+            false, // is_required
+            true,  // is_array
+        );
 
-        test_field.field_type
-    }
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigDecimalArrayRequired")
+                .expect("Field not found"),
+            "BigDecimal",
+            Some(83),
+            Some(5),
+            true, // is_required
+            true, // is_array
+        );
 
-    fn get_field_type_helper(gql_field_str: &str) -> FieldType {
-        get_field_type_helper_with_additional(gql_field_str, vec![])
+        // exampleBigDecimalOtherOrder
+        check_field_type(
+            entity
+                .fields
+                .get("exampleBigDecimalOtherOrder")
+                .expect("Field not found"),
+            "BigDecimal",
+            Some(84),
+            Some(6),
+            true,  // is_required
+            false, // is_array
+        );
     }
 
     #[test]
-    fn gql_enum_type_to_pgprimitive() {
-        let name = String::from("TestEnum");
-        let test_enum = GraphQLEnum::new(name.clone(), vec!["TEST_VALUE".to_string()]).unwrap();
-        let field_type =
-            get_field_type_helper_with_additional("TestEnum!", vec![test_enum.clone()]);
-        let schema = Schema::new(vec![], vec![test_enum]).unwrap();
-        let pg_primitive = field_type
-            .to_user_defined_field_type()
-            .to_underlying_postgres_primitive(&schema)
-            .expect("unable to get postgres primitive");
-        assert_eq!(pg_primitive, PGPrimitive::Enum("TestEnum".to_string()));
-    }
+    fn test_error_case_config_on_non_bigint_or_bigdecimal() {
+        let schema_str = r#"
+        type Entity {
+            id: ID!
+        exampleString: String @config(precision: 76)
+        }
+        "#;
 
-    #[test]
-    fn gql_single_not_null_array_to_pgprimitive() {
-        let gql_type = "[String!]!";
-        let field_type = get_field_type_helper(gql_type);
-        let empty_schema = Schema::empty();
-        let pg_primitive = field_type
-            .to_user_defined_field_type()
-            .to_underlying_postgres_primitive(&empty_schema)
-            .expect("unable to get postgres primitive");
-        assert_eq!(pg_primitive, PGPrimitive::Text);
-        assert!(field_type.to_user_defined_field_type().is_array());
-    }
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let result = Schema::from_document(gql_doc);
 
-    #[test]
-    fn gql_multi_not_null_array_to_pgprimitive() {
-        let gql_type = "[[Int!]!]!";
-        let field_type = get_field_type_helper(gql_type);
-        let empty_schema = Schema::empty();
-        let pg_primitive = field_type
-            .to_user_defined_field_type()
-            .to_underlying_postgres_primitive(&empty_schema)
-            .expect("unable to get postgres primitive");
-        assert_eq!(pg_primitive, PGPrimitive::Integer);
-        assert!(field_type.to_user_defined_field_type().is_array());
+        assert!(result.is_err());
+        let err_message = format!("{:?}", result.unwrap_err());
+        assert!(err_message.contains("EE215"));
     }
 
     #[test]
-    #[should_panic]
-    fn gql_single_nullable_array_to_pgprimitive_should_panic() {
-        let gql_type = "[Int]!"; // Nested lists need to be not nullable
-        let field_type = get_field_type_helper(gql_type);
-        let empty_schema = Schema::empty();
-        let _pg_primitive = field_type
-            .to_user_defined_field_type()
-            .to_underlying_postgres_primitive(&empty_schema)
-            .expect("should panic due to validation error");
-    }
+    fn test_error_case_config_unknown_parameter() {
+        let schema_str = r#"
+        type Entity {
+            id: ID!
+        exampleBigDecimalWrongDirective: BigDecimal @config(wronglabel: 76, scale: 5)
+        }
+        "#;
 
-    #[test]
-    #[should_panic]
-    fn gql_multi_nullable_array_to_pgprimitive_should_panic() {
-        let gql_type = "[[Int!]]!"; // Nested lists need to be not nullable
-        let field_type = get_field_type_helper(gql_type);
-        let empty_schema = Schema::empty();
-        let _pg_primitive = field_type
-            .to_user_defined_field_type()
-            .to_underlying_postgres_primitive(&empty_schema)
-            .expect("should panic due to validation error");
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let result = Schema::from_document(gql_doc);
+
+        assert!(result.is_err());
+        let err_message = format!("{:?}", result.unwrap_err());
+        assert!(err_message.contains("EE216"));
     }
 
     #[test]
-    fn test_nullability_to_string() {
-        use UserDefinedFieldType::{ListType, NonNullType, Single};
-        let scalar = NonNullType(Box::new(ListType(Box::new(Single(GqlScalar::Int)))));
+    fn test_error_case_config_missing_parameters() {
+        let schema_str = r#"
+        type Entity {
+            id: ID!
+        exampleBigDecimalMissingParams: BigDecimal @config(precision: 76)
+        }
+        "#;
 
-        let expected_output = "[Int]!".to_string();
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let result = Schema::from_document(gql_doc);
 
-        assert_eq!(scalar.to_string(), expected_output);
+        assert!(result.is_err());
+        let err_message = format!("{:?}", result.unwrap_err());
+        assert!(err_message.contains("EE216"));
     }
 
     #[test]
-    fn gql_type_to_rescript_nullable() {
-        let field_type = get_field_type_helper("Int");
-
-        let empty_schema = Schema::empty();
-        let rescript_type = field_type.to_rescript_type(&empty_schema).unwrap();
-        assert_eq!("option<int>".to_string(), rescript_type.to_string());
-    }
+    fn test_error_case_numeric_unknown_parameter() {
+        let schema_str = r#"
+        type Entity {
+            id: ID!
+            exampleBigIntWrongDirective: BigDecimal @config(wronglabel: 76, scale: 5)
+        }
+        "#;
 
-    #[test]
-    #[ignore = "We don't support list types with nullable scalars due to postgres limitations"]
-    fn gql_type_to_rescript_array_nullable_string() {
-        let field_type = get_field_type_helper("[String]!");
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let result = Schema::from_document(gql_doc);
 
-        let empty_schema = Schema::empty();
-        let rescript_type = field_type.to_rescript_type(&empty_schema).unwrap();
-        assert_eq!(
-            "array<option<string>>".to_string(),
-            rescript_type.to_string()
-        );
+        assert!(result.is_err());
+        let err_message = format!("{:?}", result.unwrap_err());
+        assert!(err_message.contains("EE216"));
     }
 
     #[test]
-    fn test_get_postgres_field_basic() {
+    fn test_error_case_numeric_unknown_parameter_with_precision() {
         let schema_str = r#"
-type TestEntity {
-  id: ID!
-  name: String! @index
-}
+        type Entity {
+            id: ID!
+            exampleBigIntWrongDirective: BigDecimal @config(precision: 76, wronglabel: 4, scale: 5)
+        }
         "#;
-        let gql_doc = setup_document(schema_str).unwrap();
-        let schema = Schema::from_document(gql_doc).unwrap();
-        let entity = schema.entities.get("TestEntity").unwrap();
-        let field = entity.fields.get("name").unwrap();
-        let pg_field = field
-            .get_postgres_field(&schema, entity)
-            .expect("Failed to get postgres field")
-            .unwrap();
 
-        assert_eq!(pg_field.field_name, "name");
-        assert_eq!(pg_field.field_type, PGPrimitive::Text);
-        assert!(pg_field.is_index);
-        assert!(!pg_field.is_array);
-        assert!(!pg_field.is_nullable);
-        assert_eq!(pg_field.linked_entity, None);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
+        let result = Schema::from_document(gql_doc);
+
+        assert!(result.is_err());
+        let err_message = format!("{:?}", result.unwrap_err());
+        assert!(err_message.contains("EE216"));
     }
 
     #[test]
-    fn test_get_postgres_field_with_linked_entity() {
+    fn multifield_index_should_retain_original_order() {
         let schema_str = r#"
-type TestEntity {
-  id: ID!
-  relatedEntity: RelatedEntity!
-}
-
-type RelatedEntity {
-  id: ID!
-}
+        type Entity 
+        @index(fields: ["a", "b"])
+        @index(fields: ["b", "a"])
+        {
+            id: ID!
+            a: String!
+            b: String!
+        }
         "#;
-        let gql_doc = setup_document(schema_str).unwrap();
-        let schema = Schema::from_document(gql_doc).unwrap();
-        let entity = schema.entities.get("TestEntity").unwrap();
-        let field = entity.fields.get("relatedEntity").unwrap();
-        let pg_field = field
-            .get_postgres_field(&schema, entity)
-            .expect("Failed to get postgres field")
-            .unwrap();
 
-        assert_eq!(pg_field.field_name, "relatedEntity");
-        assert_eq!(pg_field.field_type, PGPrimitive::Text);
-        assert!(!pg_field.is_index);
-        assert!(!pg_field.is_array);
-        assert!(!pg_field.is_nullable);
-        assert_eq!(pg_field.linked_entity, Some("RelatedEntity".to_string()));
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+        let entity = schema.entities.get("Entity").expect("Entity not found");
+
+        assert_eq!(entity.multi_field_indexes.len(), 2);
+        assert_eq!(
+            *entity.multi_field_indexes[0].get_field_names(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            *entity.multi_field_indexes[1].get_field_names(),
+            vec!["b".to_string(), "a".to_string()]
+        );
     }
 
     #[test]
-    fn test_get_postgres_field_array_type() {
+    fn parses_index_options() {
         let schema_str = r#"
-type TestEntity {
-  id: ID!
-  tags: [String!]!
-}
+        type Transfer
+        @index(fields: ["from", "timestamp"], order: ["asc", "desc"])
+        @index(fields: ["amount"], where: "amount > 0")
+        {
+            id: ID!
+            from: String!
+            amount: BigInt!
+            timestamp: Int! @index(order: desc)
+            blockNumber: Int! @index(method: brin)
+            tags: [String!]! @index(method: gin)
+            memo: String @index
+        }
         "#;
-        let gql_doc = setup_document(schema_str).unwrap();
-        let schema = Schema::from_document(gql_doc).unwrap();
-        let entity = schema.entities.get("TestEntity").unwrap();
-        let field = entity.fields.get("tags").unwrap();
-        let pg_field = field
-            .get_postgres_field(&schema, entity)
-            .expect("Failed to get postgres field")
-            .unwrap();
 
-        assert_eq!(pg_field.field_name, "tags");
-        assert_eq!(pg_field.field_type, PGPrimitive::Text);
-        assert!(!pg_field.is_index);
-        assert!(pg_field.is_array);
-        assert!(!pg_field.is_nullable);
-        assert_eq!(pg_field.linked_entity, None);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+        let entity = schema.entities.get("Transfer").expect("Entity not found");
+
+        let custom_indices = entity.get_custom_indices();
+        assert_eq!(custom_indices.len(), 5);
+        assert_eq!(
+            custom_indices[0].get_field_names(),
+            &vec!["from", "timestamp"]
+        );
+        assert_eq!(
+            custom_indices[0].get_orders(),
+            &vec![IndexOrder::Asc, IndexOrder::Desc]
+        );
+        assert_eq!(
+            custom_indices[1].get_where_predicate(),
+            Some(&"\"amount\" > 0".to_string())
+        );
+        assert_eq!(custom_indices[2].get_field_names(), &vec!["timestamp"]);
+        assert_eq!(custom_indices[2].get_orders(), &vec![IndexOrder::Desc]);
+        assert_eq!(custom_indices[3].get_method(), IndexMethod::Brin);
+        assert_eq!(custom_indices[4].get_method(), IndexMethod::Gin);
+        assert!(entity.get_composite_indices().is_empty());
+
+        let timestamp_field = entity.fields.get("timestamp").unwrap();
+        assert!(!timestamp_field.is_indexed_field(entity));
+        assert!(timestamp_field.has_custom_index(entity));
+        let memo_field = entity.fields.get("memo").unwrap();
+        assert!(memo_field.is_indexed_field(entity));
+        assert!(!memo_field.has_custom_index(entity));
+        let tags_field = entity.fields.get("tags").unwrap();
+        assert!(!tags_field.has_custom_index(entity));
     }
 
     #[test]
-    fn test_get_postgres_field_enum_type() {
+    fn quotes_columns_in_index_where_predicates() {
         let schema_str = r#"
-enum Status {
-  ACTIVE
-  INACTIVE
-}
+        type Token {
+            id: ID!
+        }
 
-type TestEntity {
-  id: ID!
-  status: Status!
-}
+        type Transfer
+        @index(fields: ["blockNumber"], where: "tokenAmount::numeric > 0 AND lower(memo) LIKE 'it''s%' AND token IS NOT NULL")
+        {
+            id: ID!
+            blockNumber: Int!
+            tokenAmount: BigInt!
+            memo: String
+            token: Token
+            status: String! @index(method: hash)
+        }
         "#;
-        let gql_doc = setup_document(schema_str).unwrap();
-        let schema = Schema::from_document(gql_doc).unwrap();
-        let entity = schema.entities.get("TestEntity").unwrap();
-        let field = entity.fields.get("status").unwrap();
-        let pg_field = field
-            .get_postgres_field(&schema, entity)
-            .expect("Failed to get postgres field")
-            .unwrap();
 
-        assert_eq!(pg_field.field_name, "status");
-        assert_eq!(pg_field.field_type, PGPrimitive::Enum("Status".to_string()));
-        assert!(!pg_field.is_index);
-        assert!(!pg_field.is_array);
-        assert!(!pg_field.is_nullable);
-        assert_eq!(pg_field.linked_entity, None);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+        let entity = schema.entities.get("Transfer").expect("Entity not found");
+
+        let custom_indices = entity.get_custom_indices();
+        assert_eq!(
+            custom_indices[0].get_where_predicate(),
+            Some(
+                &"\"tokenAmount\"::numeric > 0 AND lower(\"memo\") LIKE 'it''s%' AND \"token_id\" \
+                  IS NOT NULL"
+                    .to_string()
+            )
+        );
+
+        let block_number_field = entity.fields.get("blockNumber").unwrap();
+        assert!(
+            !block_number_field.has_custom_index(entity),
+            "Partial indexes shouldn't be used for the loaders"
+        );
+        let status_field = entity.fields.get("status").unwrap();
+        assert!(
+            !status_field.has_custom_index(entity),
+            "Hash indexes shouldn't be used for the loaders"
+        );
     }
 
     #[test]
-    fn test_decimal_precision_config_happy_path() {
+    fn keeps_dollar_quoted_literals_in_index_where_predicates() {
         let schema_str = r#"
-    type Entity {
-        id: ID!
-        exampleBigInt: BigInt @config(precision: 76)
-        exampleBigIntRequired: BigInt! @config(precision: 77)
-        exampleBigIntArray: [BigInt!] @config(precision: 78)
-        exampleBigIntArrayRequired: [BigInt!]! @config(precision: 79)
-        exampleBigDecimal: BigDecimal @config(precision: 80, scale: 5)
-        exampleBigDecimalRequired: BigDecimal! @config(precision: 81, scale: 5)
-        exampleBigDecimalArray: [BigDecimal!] @config(precision: 82, scale: 5)
-        exampleBigDecimalArrayRequired: [BigDecimal!]! @config(precision: 83, scale: 5)
-        exampleBigDecimalOtherOrder: BigDecimal! @config(scale: 6, precision: 84)
-    }
-    "#;
-
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let schema = Schema::from_document(gql_doc).expect("Failed to create schema");
+        type Transfer
+        @index(fields: ["blockNumber"], where: "memo <> $$memo; it's$$ AND memo <> $tag$memo $$ memo$tag$")
+        {
+            id: ID!
+            blockNumber: Int!
+            memo: String
+        }
+        "#;
 
-        // Verify that the schema contains the entity and fields as expected
-        let entity = schema.entities.get("Entity").expect("Entity not found");
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+        let entity = schema.entities.get("Transfer").expect("Entity not found");
 
-        // Helper function -  tests that the types of each field is what we expect
-        fn check_field_type(
-            field: &Field,
-            expected_scalar: &str, // "BigInt" or "BigDecimal"
-            expected_precision: Option<u32>,
-            expected_scale: Option<u32>,
-            is_required: bool,
-            is_array: bool,
-        ) {
-            match &field.field_type {
-                FieldType::RegularField { field_type, .. } => {
-                    //  In this test, we strip this type from the outside to the inside like an onion and check that each layer is correct.
-                    let mut current_type = field_type;
+        assert_eq!(
+            entity.get_custom_indices()[0].get_where_predicate(),
+            Some(&"\"memo\" <> $$memo; it's$$ AND \"memo\" <> $tag$memo $$ memo$tag$".to_string())
+        );
+    }
 
-                    // Handle non-null types
-                    if is_required {
-                        match current_type {
-                            UserDefinedFieldType::NonNullType(inner) => {
-                                current_type = inner.as_ref();
-                            }
-                            _ => panic!("Field '{}' is expected to be non-null", field.name),
-                        }
-                    } else if matches!(current_type, UserDefinedFieldType::NonNullType(_)) {
-                        panic!("Field '{}' should be nullable", field.name);
-                    }
+    #[test]
+    fn fails_with_invalid_index_where_predicates() {
+        let invalid_predicates = [
+            "unknownField > 0",
+            "a > 0; DROP TABLE Entity",
+            "a > 0 -- comment",
+            "a = 'unterminated",
+            "a = $$unterminated",
+            r#"\"unknown\" > 0"#,
+        ];
+
+        for predicate in invalid_predicates {
+            let schema_str = format!(
+                r#"type Entity @index(fields: ["a"], where: "{predicate}") {{ id: ID! a: Int! }}"#
+            );
+            let gql_doc = setup_document(&schema_str).expect("Failed to parse schema string");
+            let result = Schema::from_document(gql_doc);
+            let err_message = format!("{:?}", result.expect_err(predicate));
+            assert!(err_message.contains("EE222"), "{predicate}: {err_message}");
+        }
+    }
 
-                    // Handle array types
-                    if is_array {
-                        match current_type {
-                            UserDefinedFieldType::ListType(inner) => {
-                                current_type = inner.as_ref();
-                            }
-                            _ => panic!("Field '{}' is expected to be an array", field.name),
-                        }
-                        // Array elements should be non-null (e.g., [Type!])
-                        match current_type {
-                            UserDefinedFieldType::NonNullType(inner) => {
-                                current_type = inner.as_ref();
-                            }
-                            _ => panic!(
-                                "Array elements of field '{}' are expected to be non-null",
-                                field.name
-                            ),
-                        }
-                    } else if matches!(current_type, UserDefinedFieldType::ListType(_)) {
-                        panic!("Field '{}' should not be an array", field.name);
-                    }
+    #[test]
+    fn fails_with_invalid_index_options() {
+        let invalid_schemas = [
+            r#"type Entity { id: ID! a: Int! @index(method: bitmap) }"#,
+            r#"type Entity { id: ID! a: Int! @index(unknown: true) }"#,
+            r#"type Entity { id: ID! a: Int! @index(where: "") }"#,
+            r#"type Entity { id: ID! a: Int! @index(method: gin) }"#,
+            r#"type Entity { id: ID! a: Int! @index(method: brin, order: desc) }"#,
+            r#"type Entity @index(fields: ["a", "b"], method: hash) { id: ID! a: Int! b: Int! }"#,
+            r#"type Entity @index(fields: ["a", "b"], order: ["desc"]) { id: ID! a: Int! b: Int! }"#,
+            r#"type Entity @index(fields: ["a"], order: desc, where: "a > 0") { id: ID! a: Int! @index(order: desc) }"#,
+        ];
+
+        for schema_str in invalid_schemas {
+            let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+            let result = Schema::from_document(gql_doc);
+            let err_message = format!("{:?}", result.expect_err(schema_str));
+            assert!(err_message.contains("EE222"), "{schema_str}: {err_message}");
+        }
+    }
 
-                    // Check the scalar type and precision/scale
-                    match current_type {
-                        UserDefinedFieldType::Single(scalar) => match (scalar, expected_scalar) {
-                            (GqlScalar::BigInt(Some(precision)), "BigInt") => {
-                                if let Some(expected_precision) = expected_precision {
-                                    assert_eq!(
-                                        *precision, expected_precision,
-                                        "Field '{}' has precision {}, expected {}",
-                                        field.name, precision, expected_precision
-                                    );
-                                } else {
-                                    panic!("Expected precision for BigInt field '{}'", field.name);
-                                }
-                            }
-                            (GqlScalar::BigDecimal(Some((precision, scale))), "BigDecimal") => {
-                                if let (Some(expected_precision), Some(expected_scale)) =
-                                    (expected_precision, expected_scale)
-                                {
-                                    assert_eq!(
-                                        (*precision, *scale),
-                                        (expected_precision, expected_scale),
-                                        "Field '{}' has precision {}, scale {}, expected \
-                                         precision {}, scale {}",
-                                        field.name,
-                                        precision,
-                                        scale,
-                                        expected_precision,
-                                        expected_scale
-                                    );
-                                } else {
-                                    panic!(
-                                        "Expected precision and scale for BigDecimal field '{}'",
-                                        field.name
-                                    );
-                                }
-                            }
-                            _ => panic!(
-                                "Field '{}' has unexpected scalar type or missing precision/scale",
-                                field.name
-                            ),
-                        },
-                        _ => panic!("Field '{}' has unexpected field type", field.name),
-                    }
-                }
-                _ => panic!("Field '{}' is not a regular field", field.name),
-            }
+    #[test]
+    fn parses_fulltext_searches() {
+        let schema_str = r#"
+        type Band @fulltext(name: "bandSearch", language: en, fields: ["name", "bio"]) {
+            id: ID!
+            name: String!
+            bio: String
         }
 
-        // Now use the helper function to test all fields
+        type Song {
+            id: ID!
+            title: String!
+        }
 
-        // BigInt fields
-        check_field_type(
-            entity.fields.get("exampleBigInt").expect("Field not found"),
-            "BigInt",
-            Some(76),
-            None,
-            false, // is_required
-            false, // is_array
-        );
+        type _Schema_
+          @fulltext(
+            name: "songSearch"
+            language: "french"
+            algorithm: rank
+            include: [{ entity: "Song", fields: [{ name: "title" }] }]
+          )
+        "#;
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigIntRequired")
-                .expect("Field not found"),
-            "BigInt",
-            Some(77),
-            None,
-            true,  // is_required
-            false, // is_array
-        );
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+        assert_eq!(schema.entities.len(), 2);
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigIntArray")
-                .expect("Field not found"),
-            "BigInt",
-            Some(78),
-            None,
-            false, // is_required
-            true,  // is_array
+        let band = schema.entities.get("Band").expect("Entity not found");
+        assert_eq!(
+            band.fulltext_searches,
+            vec![FulltextSearch {
+                name: "bandSearch".to_string(),
+                language: "english".to_string(),
+                field_names: vec!["name".to_string(), "bio".to_string()],
+            }]
         );
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigIntArrayRequired")
-                .expect("Field not found"),
-            "BigInt",
-            Some(79),
-            None,
-            true, // is_required
-            true, // is_array
+        let song = schema.entities.get("Song").expect("Entity not found");
+        assert_eq!(
+            song.fulltext_searches,
+            vec![FulltextSearch {
+                name: "songSearch".to_string(),
+                language: "french".to_string(),
+                field_names: vec!["title".to_string()],
+            }]
         );
+    }
 
-        // BigDecimal fields
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigDecimal")
-                .expect("Field not found"),
-            "BigDecimal",
-            Some(80),
-            Some(5),
-            false, // is_required
-            false, // is_array
-        );
+    #[test]
+    fn fails_with_invalid_fulltext_searches() {
+        let invalid_schemas = [
+            r#"type Band @fulltext(name: "bandSearch", fields: ["name"]) { id: ID! name: String! }"#,
+            r#"type Band @fulltext(name: "band search", language: en, fields: ["name"]) { id: ID! name: String! }"#,
+            r#"type Band @fulltext(name: "bandSearch", language: klingon, fields: ["name"]) { id: ID! name: String! }"#,
+            r#"type Band @fulltext(name: "bandSearch", language: en, fields: ["age"]) { id: ID! age: Int! }"#,
+            r#"type Band @fulltext(name: "bandSearch", language: en, fields: ["tags"]) { id: ID! tags: [String!]! }"#,
+            r#"type Band @fulltext(name: "bandSearch", language: en, fields: []) { id: ID! name: String! }"#,
+            r#"type Band @fulltext(name: "name", language: en, fields: ["name"]) { id: ID! name: String! }"#,
+            r#"type Band @fulltext(name: "Band", language: en, fields: ["name"]) { id: ID! name: String! }"#,
+            r#"
+            type Band @fulltext(name: "search", language: en, fields: ["name"]) { id: ID! name: String! }
+            type Song @fulltext(name: "search", language: en, fields: ["title"]) { id: ID! title: String! }
+            "#,
+            r#"
+            type Band { id: ID! name: String! }
+            type _Schema_ @fulltext(name: "search", language: en, include: [{ entity: "Song", fields: [{ name: "name" }] }])
+            "#,
+        ];
+
+        for schema_str in invalid_schemas {
+            let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+            let result = Schema::from_document(gql_doc);
+            let err_message = format!("{:?}", result.expect_err(schema_str));
+            assert!(err_message.contains("EE223"), "{schema_str}: {err_message}");
+        }
+    }
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigDecimalRequired")
-                .expect("Field not found"),
-            "BigDecimal",
-            Some(81),
-            Some(5),
-            true,  // is_required
-            false, // is_array
-        );
+    #[test]
+    fn parses_timeseries_aggregations() {
+        let schema_str = r#"
+        type Token {
+            id: ID!
+        }
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigDecimalArray")
-                .expect("Field not found"),
-            "BigDecimal",
-            Some(82),
-            Some(5),
-            false, // is_required
-            true,  // is_array
-        );
+        type Swap @entity(timeseries: true) {
+            id: ID!
+            timestamp: Timestamp!
+            token: Token!
+            amount: BigInt!
+            price: Float!
+        }
 
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigDecimalArrayRequired")
-                .expect("Field not found"),
-            "BigDecimal",
-            Some(83),
-            Some(5),
-            true, // is_required
-            true, // is_array
-        );
+        type SwapStats @aggregation(intervals: ["hour", "day"], source: "Swap") {
+            id: ID!
+            timestamp: Timestamp!
+            token: Token!
+            volume: BigInt! @aggregate(fn: "sum", arg: "amount")
+            high: Float! @aggregate(fn: "max", arg: "price")
+            close: Float! @aggregate(fn: "last", arg: "price")
+            count: Int! @aggregate(fn: "count")
+        }
+        "#;
 
-        // exampleBigDecimalOtherOrder
-        check_field_type(
-            entity
-                .fields
-                .get("exampleBigDecimalOtherOrder")
-                .expect("Field not found"),
-            "BigDecimal",
-            Some(84),
-            Some(6),
-            true,  // is_required
-            false, // is_array
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
+
+        assert!(schema.entities.get("Swap").unwrap().is_timeseries);
+        assert!(!schema.entities.contains_key("SwapStats"));
+
+        let aggregation = schema.aggregations.get("SwapStats").unwrap();
+        assert_eq!(aggregation.source, "Swap");
+        assert_eq!(
+            aggregation.intervals,
+            vec![AggregationInterval::Hour, AggregationInterval::Day]
+        );
+        assert_eq!(aggregation.get_dimension_field_names(), vec!["token"]);
+        assert_eq!(
+            aggregation.aggregates,
+            vec![
+                Aggregate {
+                    field_name: "volume".to_string(),
+                    aggregate_fn: AggregateFn::Sum,
+                    arg: Some("amount".to_string()),
+                },
+                Aggregate {
+                    field_name: "high".to_string(),
+                    aggregate_fn: AggregateFn::Max,
+                    arg: Some("price".to_string()),
+                },
+                Aggregate {
+                    field_name: "close".to_string(),
+                    aggregate_fn: AggregateFn::Last,
+                    arg: Some("price".to_string()),
+                },
+                Aggregate {
+                    field_name: "count".to_string(),
+                    aggregate_fn: AggregateFn::Count,
+                    arg: None,
+                },
+            ]
         );
     }
 
     #[test]
-    fn test_error_case_config_on_non_bigint_or_bigdecimal() {
+    fn fails_with_invalid_timeseries_aggregations() {
+        let source = r#"
+        type Swap @entity(timeseries: true) {
+            id: ID!
+            timestamp: Timestamp!
+            pool: String!
+            tags: [String!]!
+            amount: BigInt!
+        }
+        "#;
+        let invalid_aggregations = [
+            r#"type Stats @aggregation(intervals: ["week"], source: "Swap") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "sum", arg: "amount") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Missing") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "sum", arg: "amount") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! pool: String! }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! volume: BigInt! @aggregate(fn: "sum", arg: "amount") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "avg", arg: "amount") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "sum") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "sum", arg: "pool") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! volume: Int! @aggregate(fn: "first", arg: "amount") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! volume: BigInt! @aggregate(fn: "sum", arg: "amount", cumulative: true) }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! count: String! @aggregate(fn: "count") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! pool: Int! count: Int! @aggregate(fn: "count") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Swap") { id: ID! timestamp: Timestamp! tags: [String!]! count: Int! @aggregate(fn: "count") }"#,
+            r#"type Stats @aggregation(intervals: ["hour"], source: "Stats2") { id: ID! timestamp: Timestamp! count: Int! @aggregate(fn: "count") }
+            type Stats2 { id: ID! timestamp: Timestamp! }"#,
+            r#"type Swap2 @entity(timeseries: true) { id: ID! timestamp: Timestamp }"#,
+            r#"type Swap2 @entity(timeseries: true, immutable: true) { id: ID! timestamp: Timestamp! }"#,
+        ];
+
+        for aggregation_str in invalid_aggregations {
+            let schema_str = format!("{source}\n{aggregation_str}");
+            let gql_doc = setup_document(&schema_str).expect("Failed to parse schema string");
+            let result = Schema::from_document(gql_doc);
+            let err_message = format!("{:?}", result.expect_err(aggregation_str));
+            assert!(
+                err_message.contains("EE224"),
+                "{aggregation_str}: {err_message}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_default_values() {
         let schema_str = r#"
-        type Entity {
+        enum Status {
+            ACTIVE
+            CLOSED
+        }
+
+        type Account {
             id: ID!
-        exampleString: String @config(precision: 76)
+            balance: BigInt! @default(value: 0)
+            status: Status @default(value: ACTIVE)
+            name: String! @default(value: "it's")
         }
         "#;
 
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let result = Schema::from_document(gql_doc);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).unwrap();
+        let account = schema.entities.get("Account").unwrap();
 
-        assert!(result.is_err());
-        let err_message = format!("{:?}", result.unwrap_err());
-        assert!(err_message.contains("EE215"));
+        let get_defaults = |field_name: &str| {
+            let field = account.fields.get(field_name).unwrap();
+            (
+                field.get_postgres_default_value(&schema).unwrap(),
+                field.get_rescript_default_value(&schema).unwrap(),
+            )
+        };
+
+        assert_eq!(
+            get_defaults("balance"),
+            (
+                Some("0".to_string()),
+                Some("BigInt.fromStringUnsafe(\"0\")".to_string())
+            )
+        );
+        assert_eq!(
+            get_defaults("status"),
+            (
+                Some("'ACTIVE'".to_string()),
+                Some("Some(Enums.Status.ACTIVE)".to_string())
+            )
+        );
+        assert_eq!(
+            get_defaults("name"),
+            (Some("'it''s'".to_string()), Some("\"it's\"".to_string()))
+        );
+        assert_eq!(get_defaults("id"), (None, None));
     }
 
     #[test]
-    fn test_error_case_config_unknown_parameter() {
+    fn fails_when_default_value_does_not_match_type() {
         let schema_str = r#"
-        type Entity {
+        enum Status {
+            ACTIVE
+        }
+
+        type Account {
             id: ID!
-        exampleBigDecimalWrongDirective: BigDecimal @config(wronglabel: 76, scale: 5)
+            status: Status! @default(value: PAUSED)
         }
         "#;
 
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let result = Schema::from_document(gql_doc);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let err = Schema::from_document(gql_doc).unwrap_err();
 
-        assert!(result.is_err());
-        let err_message = format!("{:?}", result.unwrap_err());
-        assert!(err_message.contains("EE216"));
+        assert_eq!(
+            err.to_string(),
+            "EE221: The default value PAUSED of the field 'status' doesn't match its type Status!"
+        );
     }
 
     #[test]
-    fn test_error_case_config_missing_parameters() {
+    fn validates_timestamp_default_values() {
+        let get_default = |default_value: &str| {
+            let schema_str = format!(
+                r#"
+                type Account {{
+                    id: ID!
+                    createdAt: Timestamp! @default(value: "{default_value}")
+                }}
+                "#
+            );
+            let gql_doc = setup_document(&schema_str).expect("Failed to parse schema string");
+            let schema = Schema::from_document(gql_doc).map_err(|err| err.to_string())?;
+            let field = schema.entities["Account"].fields["createdAt"].clone();
+            Ok::<_, String>(field.get_postgres_default_value(&schema).unwrap())
+        };
+
+        assert_eq!(
+            get_default("2024-02-29T12:30:00.5+02:00"),
+            Ok(Some("'2024-02-29T12:30:00.5+02:00'".to_string()))
+        );
+        for invalid_value in [
+            "now",
+            "2024-01-01",
+            "2024-01-01T00:00:00",
+            "2023-02-29T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+        ] {
+            assert_eq!(
+                get_default(invalid_value),
+                Err(format!(
+                    "EE221: The default value \"{invalid_value}\" of the field 'createdAt' should \
+                     be a date-time with a timezone. Eg. @default(value: \"2024-01-01T00:00:00Z\")"
+                )),
+            );
+        }
+    }
+
+    #[test]
+    fn fails_with_default_value_on_primary_key() {
         let schema_str = r#"
-        type Entity {
-            id: ID!
-        exampleBigDecimalMissingParams: BigDecimal @config(precision: 76)
+        type Account {
+            id: ID! @default(value: "account")
         }
         "#;
 
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let result = Schema::from_document(gql_doc);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let err = Schema::from_document(gql_doc).unwrap_err();
 
-        assert!(result.is_err());
-        let err_message = format!("{:?}", result.unwrap_err());
-        assert!(err_message.contains("EE216"));
+        assert_eq!(
+            err.to_string(),
+            "EE221: The primary key field 'id' can't have a default value"
+        );
     }
 
     #[test]
-    fn test_error_case_numeric_unknown_parameter() {
+    fn parses_interfaces_and_unions() {
         let schema_str = r#"
-        type Entity {
+        interface Event {
             id: ID!
-            exampleBigIntWrongDirective: BigDecimal @config(wronglabel: 76, scale: 5)
+            account: Account!
+            amount: BigInt!
+        }
+
+        union Action = Transfer | Mint
+
+        type Account {
+            id: ID!
+        }
+
+        type Transfer implements Event {
+            id: ID!
+            account: Account!
+            amount: BigInt!
+            to: String!
+        }
+
+        type Mint implements Event {
+            id: ID!
+            account: Account!
+            amount: BigInt!
+        }
+
+        type Log {
+            id: ID!
+            event: Event!
+            action: Action
         }
         "#;
 
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let result = Schema::from_document(gql_doc);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
 
-        assert!(result.is_err());
-        let err_message = format!("{:?}", result.unwrap_err());
-        assert!(err_message.contains("EE216"));
+        let interface = schema.interfaces.get("Event").expect("Interface not found");
+        assert_eq!(
+            schema
+                .get_interface_implementations(interface)
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>(),
+            vec!["Mint".to_string(), "Transfer".to_string()]
+        );
+        assert_eq!(
+            interface.get_db_field_names(&schema).unwrap(),
+            vec![
+                "account_id".to_string(),
+                "amount".to_string(),
+                "id".to_string()
+            ]
+        );
+        assert_eq!(
+            schema.unions.get("Action").expect("Union not found").types,
+            vec!["Mint".to_string(), "Transfer".to_string()]
+        );
+
+        let log = schema.entities.get("Log").expect("Entity not found");
+        let event_field = log.fields.get("event").unwrap();
+        let pg_field = event_field
+            .get_postgres_field(&schema, log)
+            .unwrap()
+            .unwrap();
+        assert_eq!(pg_field.linked_entity, Some("Event".to_string()));
+        assert_eq!(pg_field.field_type, PGPrimitive::Text);
+        assert!(event_field.field_type.is_entity_field(&schema).unwrap());
     }
 
     #[test]
-    fn test_error_case_numeric_unknown_parameter_with_precision() {
+    fn fails_when_implementation_misses_interface_field() {
         let schema_str = r#"
-        type Entity {
+        interface Event {
+            id: ID!
+            amount: BigInt!
+        }
+
+        type Transfer implements Event {
             id: ID!
-            exampleBigIntWrongDirective: BigDecimal @config(precision: 76, wronglabel: 4, scale: 5)
         }
         "#;
 
-        let gql_doc = setup_document(schema_str).expect("Failed to parse schema");
-        let result = Schema::from_document(gql_doc);
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let err = Schema::from_document(gql_doc).unwrap_err();
 
-        assert!(result.is_err());
-        let err_message = format!("{:?}", result.unwrap_err());
-        assert!(err_message.contains("EE216"));
+        assert_eq!(
+            err.to_string(),
+            "EE218: Entity 'Transfer' implements the interface 'Event' but doesn't define the \
+             field 'amount'"
+        );
     }
 
     #[test]
-    fn multifield_index_should_retain_original_order() {
+    fn fails_when_implementation_has_different_field_type() {
         let schema_str = r#"
-        type Entity 
-        @index(fields: ["a", "b"])
-        @index(fields: ["b", "a"])
-        {
+        interface Event {
             id: ID!
-            a: String!
-            b: String!
+            amount: BigInt!
+        }
+
+        type Transfer implements Event {
+            id: ID!
+            amount: Int!
         }
         "#;
 
         let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
-        let schema = Schema::from_document(gql_doc).expect("Failed to parse schema from doc");
-        let entity = schema.entities.get("Entity").expect("Entity not found");
+        let err = Schema::from_document(gql_doc).unwrap_err();
 
-        assert_eq!(entity.multi_field_indexes.len(), 2);
         assert_eq!(
-            entity.multi_field_indexes[0].0,
-            vec!["a".to_string(), "b".to_string()]
+            err.to_string(),
+            "EE218: The field 'amount' on entity 'Transfer' must have the type 'BigInt!' defined \
+             by the interface 'Event'"
         );
+    }
+
+    #[test]
+    fn fails_when_union_contains_non_entity() {
+        let schema_str = r#"
+        enum Kind {
+            A
+        }
+
+        union Action = Transfer | Kind
+
+        type Transfer {
+            id: ID!
+        }
+        "#;
+
+        let gql_doc = setup_document(schema_str).expect("Failed to parse schema string");
+        let err = Schema::from_document(gql_doc).unwrap_err();
+
         assert_eq!(
-            entity.multi_field_indexes[1].0,
-            vec!["b".to_string(), "a".to_string()]
+            err.to_string(),
+            "EE218: The union 'Action' contains 'Kind', union members must be entities defined in \
+             the schema"
         );
     }
 }
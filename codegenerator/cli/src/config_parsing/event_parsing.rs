@@ -1,4 +1,6 @@
-use ethers::abi::{EventParam as EthAbiEventParam, ParamType as EthAbiParamType};
+use ethers::abi::{
+    EventParam as EthAbiEventParam, Param as EthAbiParam, ParamType as EthAbiParamType,
+};
 
 use crate::rescript_types::RescriptTypeIdent;
 
@@ -16,6 +18,15 @@ impl<'a> From<&'a EthAbiEventParam> for EthereumEventParam<'a> {
     }
 }
 
+impl<'a> From<&'a EthAbiParam> for EthereumEventParam<'a> {
+    fn from(abi_type: &'a EthAbiParam) -> EthereumEventParam<'a> {
+        EthereumEventParam {
+            name: &abi_type.name,
+            abi_type: &abi_type.kind,
+        }
+    }
+}
+
 impl EthereumEventParam<'_> {
     /// Returns the depth of the nested type
     /// A value type would return 0
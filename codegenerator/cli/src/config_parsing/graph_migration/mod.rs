@@ -3,7 +3,7 @@ use crate::{
     config_parsing::{
         chain_helpers::{self, GraphNetwork},
         human_config::{
-            evm::{ContractConfig, EventConfig, HumanConfig, Network},
+            evm::{ContractConfig, EventConfig, FunctionConfig, HumanConfig, Network},
             NetworkContract,
         },
     },
@@ -283,6 +283,7 @@ pub async fn generate_config_from_subgraph_id(
         save_full_history: None,
         field_selection: None,
         raw_events: None,
+        env_interpolation: None,
     };
     let mut networks: Vec<Network> = vec![];
 
@@ -313,6 +314,7 @@ pub async fn generate_config_from_subgraph_id(
             end_block: None,
             confirmed_block_threshold: None,
             contracts: vec![],
+            block_handlers: None,
         };
         // Iterate through contracts to get contract name, abi file path, address and event names
         for contract in contracts {
@@ -348,19 +350,62 @@ pub async fn generate_config_from_subgraph_id(
                                 event: event_name.to_string(),
                                 name: None,
                                 field_selection: None,
+                                start_block: None,
+                                end_block: None,
+                                filter: None,
                             };
 
                             Ok(event)
                         })
                         .collect::<anyhow::Result<Vec<_>>>()?;
 
+                    // Fetching function names of call handlers from config
+                    let functions = data_source
+                        .mapping
+                        .call_handlers
+                        .as_ref()
+                        .map(|call_handlers| {
+                            call_handlers
+                                .iter()
+                                .map(|call_handler| {
+                                    let start =
+                                        call_handler.function.as_str().find('(').ok_or_else(
+                                            || {
+                                                anyhow!(
+                                                    "Unexepected function definition without a \
+                                                     '(' char"
+                                                )
+                                            },
+                                        )?;
+
+                                    // Same as for events, only the function name is taken so
+                                    // the signature is resolved from the contract abi
+                                    let function_name = &call_handler
+                                        .function
+                                        .as_str()
+                                        .chars()
+                                        .take(start)
+                                        .collect::<String>();
+                                    Ok(FunctionConfig {
+                                        function: function_name.to_string(),
+                                        name: None,
+                                    })
+                                })
+                                .collect::<anyhow::Result<Vec<_>>>()
+                        })
+                        .transpose()?;
+
                     let contract = NetworkContract {
                         name: data_source.name.to_string(),
                         address: vec![data_source.source.address.to_string()].into(),
+                        start_block: None,
+                        end_block: None,
                         config: Some(ContractConfig {
                             abi_file_path: Some(format!("abis/{}.json", data_source.name)),
                             handler: get_event_handler_directory(language),
                             events,
+                            functions,
+                            factory: None,
                         }),
                     };
 
@@ -1,7 +1,8 @@
 use crate::utils::normalized_list::{NormalizedList, SingleOrList};
+use anyhow::Context;
 use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, fmt::Display};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display};
 
 impl<T: Clone + JsonSchema> JsonSchema for SingleOrList<T> {
     fn schema_name() -> Cow<'static, str> {
@@ -82,6 +83,18 @@ pub struct NetworkContract<T> {
                        dynamically."
     )]
     pub address: Addresses,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "The block at which the indexer should start ingesting data for this \
+                       contract (default: the network start_block)"
+    )]
+    pub start_block: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "The block at which the indexer should stop ingesting data for this \
+                       contract (default: the network end_block)"
+    )]
+    pub end_block: Option<u64>,
     #[serde(flatten)]
     //If this is "None" it should be expected that
     //there is a global config for the contract
@@ -93,6 +106,19 @@ pub struct ConfigDiscriminant {
     pub ecosystem: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum EnvInterpolation {
+    Codegen,
+    Runtime,
+}
+
+// Read from the raw config, since it's needed before the interpolation
+#[derive(Deserialize)]
+pub struct EnvInterpolationDiscriminant {
+    pub env_interpolation: Option<EnvInterpolation>,
+}
+
 #[derive(Debug)]
 pub enum HumanConfig {
     Evm(evm::HumanConfig),
@@ -112,12 +138,98 @@ impl Display for HumanConfig {
     }
 }
 
+///Top level options which only affect how the indexer runs, not the data it indexes
+const RUNTIME_OPTIONS: [&str; 5] = [
+    "name",
+    "description",
+    "unordered_multichain_mode",
+    "event_decoder",
+    "env_interpolation",
+];
+///Network options which only affect how the network is synced, not the data it indexes.
+///The sync tuning options are part of the sync source configs. The confirmed block
+///threshold isn't one of them, since it decides how far reorgs are rolled back and how
+///much entity history is kept
+const RUNTIME_NETWORK_OPTIONS: [&str; 3] = ["rpc_config", "hypersync_config", "hyperfuel_config"];
+
+///The serialized config, split by how its options affect the indexed data
+pub struct SplitConfigOptions {
+    ///Options affecting the data of all networks
+    pub indexing: String,
+    ///Options only affecting the data of a single network, by network id
+    pub networks: BTreeMap<NetworkId, String>,
+    ///Options only affecting how the indexer runs, like rpc urls or sync tuning
+    pub runtime: String,
+}
+
+impl HumanConfig {
+    ///Splits the serialized config by how its options affect the indexed data
+    pub fn split_options(&self) -> anyhow::Result<SplitConfigOptions> {
+        let mut indexing_options = match self {
+            HumanConfig::Evm(config) => serde_yaml::to_value(config),
+            HumanConfig::Fuel(config) => serde_yaml::to_value(config),
+        }
+        .context("Failed serializing config")?;
+
+        let mut network_options = BTreeMap::new();
+        let mut runtime_options = serde_yaml::Mapping::new();
+        if let Some(indexing_options) = indexing_options.as_mapping_mut() {
+            for key in RUNTIME_OPTIONS {
+                if let Some(value) = indexing_options.remove(key) {
+                    runtime_options.insert(key.into(), value);
+                }
+            }
+
+            let mut runtime_network_options = vec![];
+            if let Some(serde_yaml::Value::Sequence(networks)) = indexing_options.remove("networks")
+            {
+                for mut network in networks {
+                    let Some(network_mapping) = network.as_mapping_mut() else {
+                        continue;
+                    };
+                    let id = network_mapping.get("id").cloned().unwrap_or_default();
+
+                    let mut runtime_network_mapping = serde_yaml::Mapping::new();
+                    runtime_network_mapping.insert("id".into(), id.clone());
+                    for key in RUNTIME_NETWORK_OPTIONS {
+                        if let Some(value) = network_mapping.remove(key) {
+                            runtime_network_mapping.insert(key.into(), value);
+                        }
+                    }
+                    runtime_network_options
+                        .push(serde_yaml::Value::Mapping(runtime_network_mapping));
+
+                    if let Some(id) = id.as_u64() {
+                        network_options.insert(
+                            id,
+                            serde_yaml::to_string(&network)
+                                .context("Failed serializing network config")?,
+                        );
+                    }
+                }
+            }
+            runtime_options.insert("networks".into(), runtime_network_options.into());
+        }
+
+        Ok(SplitConfigOptions {
+            indexing: serde_yaml::to_string(&indexing_options)
+                .context("Failed serializing config")?,
+            networks: network_options,
+            runtime: serde_yaml::to_string(&runtime_options)
+                .context("Failed serializing config")?,
+        })
+    }
+}
+
 pub mod evm {
-    use super::{GlobalContract, NetworkContract, NetworkId};
+    use super::{EnvInterpolation, GlobalContract, NetworkContract, NetworkId};
     use crate::utils::normalized_list::SingleOrList;
-    use schemars::JsonSchema;
-    use serde::{Deserialize, Serialize};
-    use std::fmt::Display;
+    use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+    use serde::{
+        de::{self, Visitor},
+        Deserialize, Deserializer, Serialize,
+    };
+    use std::{collections::BTreeMap, fmt, fmt::Display};
     use strum::Display;
     use subenum::subenum;
 
@@ -174,7 +286,9 @@ pub mod evm {
         #[schemars(
             description = "A flag to indicate if the indexer should save the full history of \
                            events. This is useful for debugging but will increase the size of the \
-                           database (default: false)"
+                           database. It's also required to reset only the data of the networks \
+                           with config changes, otherwise the whole database is reset (default: \
+                           false)"
         )]
         pub save_full_history: Option<bool>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -191,6 +305,12 @@ pub mod evm {
                            false)"
         )]
         pub raw_events: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Where to resolve ${ENV_VAR} interpolations (default: \
+                                  codegen). With runtime, the RPC and HyperSync urls are looked \
+                                  up from the environment when the indexer starts, so their \
+                                  values don't end up in the generated code")]
+        pub env_interpolation: Option<EnvInterpolation>,
     }
 
     impl Display for HumanConfig {
@@ -362,7 +482,9 @@ pub mod evm {
         #[serde(skip_serializing_if = "Option::is_none")]
         #[schemars(
             description = "If a fallback RPC is provided, the amount of time in ms to wait before \
-                           kicking off the next provider"
+                           kicking off the next provider. With the historical or realtime role, \
+                           also the time to wait before a stalled query is handed over to the \
+                           other source"
         )]
         pub fallback_stall_timeout: Option<u32>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -376,14 +498,44 @@ pub mod evm {
         #[schemars(
             description = "URL of the RPC endpoint. Can be a single URL or an array of URLs. If \
                            multiple URLs are provided, the first one will be used as the primary \
-                           RPC endpoint and the rest will be used as fallbacks."
+                           RPC endpoint and the rest will be used as fallbacks. WebSocket URLs \
+                           (ws:// or wss://) are used to subscribe to new blocks instead of \
+                           polling for them."
         )]
         pub url: SingleOrList<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "The role of the RPC when it's used together with HyperSync. Historical \
+                           and realtime split the indexing between the two sources, while \
+                           fallback only uses the RPC when HyperSync is unresponsive (default: \
+                           fallback when hypersync_config is defined)"
+        )]
+        pub role: Option<RpcRole>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "How many blocks behind the chain head the realtime source takes over \
+                           from the historical one (default: 100)"
+        )]
+        pub realtime_threshold_blocks: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "How long in ms to wait for a response from HyperSync before switching \
+                           to the fallback RPC (default: 20000)"
+        )]
+        pub fallback_threshold_millis: Option<u32>,
         #[serde(flatten, skip_serializing_if = "Option::is_none")]
         #[schemars(description = "Config options for RPC syncing")]
         pub sync_config: Option<RpcSyncConfig>,
     }
 
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+    #[serde(rename_all = "snake_case", deny_unknown_fields)]
+    pub enum RpcRole {
+        Historical,
+        Realtime,
+        Fallback,
+    }
+
     #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
     #[serde(deny_unknown_fields)]
     pub struct Network {
@@ -413,6 +565,41 @@ pub mod evm {
         pub end_block: Option<u64>,
         #[schemars(description = "All the contracts that should be indexed on the given network")]
         pub contracts: Vec<NetworkContract<ContractConfig>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "Handlers that should be run on every Nth block of the given network, \
+                           independently of any contract events"
+        )]
+        pub block_handlers: Option<Vec<BlockHandlerConfig>>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct BlockHandlerConfig {
+        #[schemars(
+            description = "Name of the block handler in the HyperIndex generated code. Should be \
+                           unique per network"
+        )]
+        pub name: String,
+        #[schemars(
+            description = "The relative path to a file where the block handler is registered"
+        )]
+        pub handler: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Run the handler on every Nth block (default: 1)")]
+        pub interval: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "The block at which the handler should start running (default: the \
+                           network start_block)"
+        )]
+        pub start_block: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "The block at which the handler should stop running (default: the \
+                           network end_block)"
+        )]
+        pub end_block: Option<u64>,
     }
 
     #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -431,10 +618,60 @@ pub mod evm {
         pub handler: String,
         #[schemars(description = "A list of events that should be indexed on this contract")]
         pub events: Vec<EventConfig>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "A list of contract functions whose calls should be indexed on this \
+                           contract. The calls are taken from the input of the transactions sent \
+                           to the contract, so internal calls are not indexed. Only supported on \
+                           networks synced with HyperSync alone. Networks with an rpc_config, \
+                           including the ones using HyperSync with an RPC fallback, fail with \
+                           EE120, since the RPC data source doesn't fetch transaction inputs."
+        )]
+        pub functions: Option<Vec<FunctionConfig>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "Automatically register addresses of this contract from an event of a \
+                           factory contract, without a contractRegister in the handler code"
+        )]
+        pub factory: Option<FactoryConfig>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct FactoryConfig {
+        #[schemars(description = "The name of the factory contract")]
+        pub contract: String,
+        #[schemars(
+            description = "The name of the factory contract event which creates this contract"
+        )]
+        pub event: String,
+        #[schemars(
+            description = "The name of the event param with the address (or a list of addresses) \
+                           of the created contract"
+        )]
+        pub param: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct FunctionConfig {
+        #[schemars(description = "The human readable signature of a function 'eg. \
+                                  transfer(address to, uint256 amount)' OR a reference to the \
+                                  name of a function in a json ABI file defined in your \
+                                  contract config. A provided signature will take precedence \
+                                  over what is defined in the json ABI")]
+        pub function: String,
+        #[schemars(
+            description = "Name of the function call in the HyperIndex generated code. When \
+                           ommitted, the function name will be used. Should be unique per contract"
+        )]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
     }
 
     #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
     #[serde(deny_unknown_fields)]
+    #[schemars(transform = add_where_alias)]
     pub struct EventConfig {
         #[schemars(description = "The human readable signature of an event 'eg. \
                                   Transfer(address indexed from, address indexed to, uint256 \
@@ -454,13 +691,108 @@ pub mod evm {
                            event"
         )]
         pub field_selection: Option<FieldSelection>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "The block at which the indexer should start ingesting the event \
+                           (default: the contract start_block)"
+        )]
+        pub start_block: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "The block at which the indexer should stop ingesting the event \
+                           (default: the contract end_block)"
+        )]
+        pub end_block: Option<u64>,
+        #[serde(alias = "where", skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "Only index events matching the given values of indexed params. Each \
+                           key is the name of an indexed param and the value is a single value or \
+                           a list of values to match (eg. from: [0xabc...])"
+        )]
+        pub filter: Option<BTreeMap<String, SingleOrList<EventFilterValue>>>,
+    }
+
+    //schemars doesn't pick up serde aliases, so "where" is added as a copy of "filter"
+    fn add_where_alias(schema: &mut Schema) {
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(|properties| properties.as_object_mut())
+        {
+            if let Some(filter) = properties.get("filter").cloned() {
+                properties.insert("where".to_string(), filter);
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Clone, PartialEq, JsonSchema)]
+    #[serde(untagged)]
+    pub enum EventFilterValue {
+        Bool(bool),
+        //The decimal representation of the integer, so it can hold the whole uint256 and
+        //int256 range
+        Integer(#[schemars(schema_with = "integer_schema")] String),
+        String(String),
+    }
+
+    fn integer_schema(_gen: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+          "type": "integer"
+        })
+    }
+
+    impl<'de> Deserialize<'de> for EventFilterValue {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(EventFilterValueVisitor)
+        }
+    }
+
+    struct EventFilterValueVisitor;
+
+    impl Visitor<'_> for EventFilterValueVisitor {
+        type Value = EventFilterValue;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a boolean, an integer or a string")
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::Bool(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::Integer(v.to_string()))
+        }
+
+        fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::Integer(v.to_string()))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::Integer(v.to_string()))
+        }
+
+        fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::Integer(v.to_string()))
+        }
+
+        //Integers which don't fit into 128 bits are parsed as floats and lose precision
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Err(E::custom(format!(
+                "Invalid filter value {v}. Expected an integer, integers larger than 128 bits \
+                 should be quoted (eg. \"0x10000000000000000000000000000000000\")"
+            )))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(EventFilterValue::String(v.to_string()))
+        }
     }
 }
 
 pub mod fuel {
     use std::fmt::Display;
 
-    use super::{GlobalContract, NetworkContract, NetworkId};
+    use super::{EnvInterpolation, GlobalContract, NetworkContract, NetworkId};
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
     use strum::Display;
@@ -502,6 +834,12 @@ pub mod fuel {
                            false)"
         )]
         pub raw_events: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Where to resolve ${ENV_VAR} interpolations (default: \
+                                  codegen). With runtime, the HyperFuel urls are looked up from \
+                                  the environment when the indexer starts, so their values \
+                                  don't end up in the generated code")]
+        pub env_interpolation: Option<EnvInterpolation>,
     }
 
     impl Display for HumanConfig {
@@ -651,10 +989,14 @@ events: []
             address: NormalizedList::from(vec![
                 "0x2E645469f354BB4F5c8a05B3b30A929361cf77eC".to_string()
             ]),
+            start_block: None,
+            end_block: None,
             config: Some(ContractConfig {
                 abi_file_path: None,
                 handler: "./src/EventHandler.js".to_string(),
                 events: vec![],
+                functions: None,
+                factory: None,
             }),
         };
 
@@ -673,10 +1015,14 @@ events: []
         let expected = NetworkContract {
             name: "Contract1".to_string(),
             address: vec![].into(),
+            start_block: None,
+            end_block: None,
             config: Some(ContractConfig {
                 abi_file_path: None,
                 handler: "./src/EventHandler.js".to_string(),
                 events: vec![],
+                functions: None,
+                factory: None,
             }),
         };
 
@@ -696,10 +1042,14 @@ events: []
         let expected = NetworkContract {
             name: "Contract1".to_string(),
             address: vec!["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC".to_string()].into(),
+            start_block: None,
+            end_block: None,
             config: Some(ContractConfig {
                 abi_file_path: None,
                 handler: "./src/EventHandler.js".to_string(),
                 events: vec![],
+                functions: None,
+                factory: None,
             }),
         };
 
@@ -719,6 +1069,8 @@ address: ["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"]
             address: NormalizedList::from(vec![
                 "0x2E645469f354BB4F5c8a05B3b30A929361cf77eC".to_string()
             ]),
+            start_block: None,
+            end_block: None,
             config: None,
         };
 
@@ -791,6 +1143,7 @@ address: ["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"]
             ecosystem: fuel::EcosystemTag::Fuel,
             contracts: None,
             raw_events: None,
+            env_interpolation: None,
             networks: vec![fuel::Network {
                 id: 0,
                 start_block: 0,
@@ -801,6 +1154,8 @@ address: ["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"]
                     address: "0x4a2ce054e3e94155f7092f7365b212f7f45105b74819c623744ebcc5d065c6ac"
                         .to_string()
                         .into(),
+                    start_block: None,
+                    end_block: None,
                     config: Some(fuel::ContractConfig {
                         abi_file_path: "./abis/spark-orderbook.json".to_string(),
                         handler: "./src/OrderBookHandlers.ts".to_string(),
@@ -837,6 +1192,7 @@ address: ["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"]
             description: None,
             schema: None,
             raw_events: None,
+            env_interpolation: None,
             ecosystem: fuel::EcosystemTag::Fuel,
             contracts: None,
             networks: vec![],
@@ -888,9 +1244,69 @@ address: ["0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"]
                 start_block: 2_000,
                 confirmed_block_threshold: None,
                 end_block: Some(2_000_000),
-                contracts: vec![]
+                contracts: vec![],
+                block_handlers: None,
             },
             de
         );
     }
+
+    #[test]
+    fn splits_config_options() {
+        let split_options = |rpc_url: &str, start_block: u64, confirmed_block_threshold: u64| {
+            super::HumanConfig::Evm(
+                serde_yaml::from_str(&format!(
+                    r#"
+name: indexer
+unordered_multichain_mode: {}
+networks:
+  - id: 1
+    rpc_config:
+      url: {rpc_url}
+      initial_block_interval: {}
+    start_block: {start_block}
+    confirmed_block_threshold: {confirmed_block_threshold}
+    contracts: []
+  - id: 137
+    start_block: 0
+    contracts: []
+"#,
+                    //The sync tuning changes along with the rpc url
+                    rpc_url.ends_with('2'),
+                    if rpc_url.ends_with('2') { 500 } else { 1000 },
+                ))
+                .unwrap(),
+            )
+            .split_options()
+            .unwrap()
+        };
+
+        let options = split_options("https://rpc-1.com", 0, 200);
+        let rpc_changed_options = split_options("https://rpc-2.com", 0, 200);
+        let start_block_changed_options = split_options("https://rpc-1.com", 100, 200);
+        let threshold_changed_options = split_options("https://rpc-1.com", 0, 50);
+
+        assert_eq!(options.indexing, rpc_changed_options.indexing);
+        assert_eq!(options.networks, rpc_changed_options.networks);
+        assert_ne!(options.runtime, rpc_changed_options.runtime);
+
+        assert_eq!(options.indexing, start_block_changed_options.indexing);
+        assert_ne!(
+            options.networks.get(&1),
+            start_block_changed_options.networks.get(&1)
+        );
+        assert_eq!(
+            options.networks.get(&137),
+            start_block_changed_options.networks.get(&137)
+        );
+        assert_eq!(options.runtime, start_block_changed_options.runtime);
+
+        //The rollback depth and kept history depend on the confirmed block threshold
+        assert_eq!(options.indexing, threshold_changed_options.indexing);
+        assert_ne!(
+            options.networks.get(&1),
+            threshold_changed_options.networks.get(&1)
+        );
+        assert_eq!(options.runtime, threshold_changed_options.runtime);
+    }
 }
@@ -1,12 +1,25 @@
 use anyhow::Context;
 
-use super::chain_helpers::{HypersyncNetwork, Network};
+use super::{
+    chain_helpers::{HypersyncNetwork, Network},
+    chain_registry::ChainRegistry,
+};
 
 pub fn network_to_hypersync_url(network: &HypersyncNetwork) -> String {
     format!("https://{}.hypersync.xyz", *network as u64)
 }
 
-pub fn get_default_hypersync_endpoint(chain_id: u64) -> anyhow::Result<String> {
+pub fn get_default_hypersync_endpoint(
+    chain_id: u64,
+    chain_registry: &ChainRegistry,
+) -> anyhow::Result<String> {
+    if let Some(url) = chain_registry
+        .get(chain_id)
+        .and_then(|chain| chain.hypersync_url.clone())
+    {
+        return Ok(url);
+    }
+
     let network_name = Network::from_network_id(chain_id)
         .context(format!("Getting network name from id ({})", chain_id))?;
 
@@ -21,7 +34,9 @@ pub fn get_default_hypersync_endpoint(chain_id: u64) -> anyhow::Result<String> {
 #[cfg(test)]
 mod test {
 
-    use crate::config_parsing::hypersync_endpoints::get_default_hypersync_endpoint;
+    use crate::config_parsing::{
+        chain_registry::ChainRegistry, hypersync_endpoints::get_default_hypersync_endpoint,
+    };
 
     use super::HypersyncNetwork;
     use strum::IntoEnumIterator;
@@ -29,7 +44,37 @@ mod test {
     #[test]
     fn all_supported_chain_ids_return_a_hypersync_endpoint() {
         for network in HypersyncNetwork::iter() {
-            let _ = get_default_hypersync_endpoint(network as u64).unwrap();
+            let _ =
+                get_default_hypersync_endpoint(network as u64, &ChainRegistry::default()).unwrap();
         }
     }
+
+    #[test]
+    fn registered_chains_override_the_default_endpoint() {
+        let chain_registry = ChainRegistry::from_yaml_str(
+            r#"
+chains:
+  - id: 1
+    name: ethereum-mainnet
+    hypersync_url: https://eth.my-hypersync.xyz
+  - id: 1337
+    name: local-devnet
+    hypersync_url: http://localhost:1337
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_default_hypersync_endpoint(1, &chain_registry).unwrap(),
+            "https://eth.my-hypersync.xyz"
+        );
+        assert_eq!(
+            get_default_hypersync_endpoint(1337, &chain_registry).unwrap(),
+            "http://localhost:1337"
+        );
+        assert_eq!(
+            get_default_hypersync_endpoint(10, &chain_registry).unwrap(),
+            "https://10.hypersync.xyz"
+        );
+    }
 }
@@ -1,4 +1,5 @@
 pub mod chain_helpers;
+pub mod chain_registry;
 pub mod contract_import;
 pub mod entity_parsing;
 pub mod event_parsing;
@@ -1,3 +1,4 @@
+use crate::hbs_templating::hbs_dir_generator::to_string_literal;
 use core::fmt;
 use serde::Serialize;
 use std::fmt::Display;
@@ -26,7 +27,7 @@ impl Primitive {
                 format!("Custom(\"NUMERIC({}, {})\")", precision, scale)
             }
             Self::Serial => "Serial".to_string(),
-            Self::Json => "Json".to_string(),
+            Self::Json => "JsonB".to_string(),
             Self::Timestamp => "Timestamp".to_string(),
             Self::DoublePrecision => "DoublePrecision".to_string(),
             Self::Enum(enum_name) => format!("Custom(Enums.{enum_name}.enum.name)"),
@@ -58,4 +59,18 @@ pub struct Field {
     pub is_nullable: bool,
     pub is_array: bool,
     pub field_type: Primitive,
+    ///Postgres DEFAULT expression of the column,
+    ///templated as a ReScript string
+    #[serde(serialize_with = "serialize_default_value")]
+    pub default_value: Option<String>,
+}
+
+fn serialize_default_value<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    value
+        .as_deref()
+        .map(to_string_literal)
+        .serialize(serializer)
 }
@@ -1,14 +1,16 @@
 use super::{
     chain_helpers::get_confirmed_block_threshold_from_id,
-    entity_parsing::{Entity, GraphQLEnum, Schema},
+    chain_registry::{ChainDefinition, ChainRegistry},
+    entity_parsing::{Aggregation, Entity, GraphQLEnum, GraphQLInterface, GraphQLUnion, Schema},
     human_config::{
         self,
         evm::{
-            EventConfig as EvmEventConfig, EventDecoder, HumanConfig as EvmConfig,
-            Network as EvmNetwork,
+            EventConfig as EvmEventConfig, EventDecoder, EventFilterValue,
+            FactoryConfig as EvmFactoryConfig, FunctionConfig as EvmFunctionConfig,
+            HumanConfig as EvmConfig, Network as EvmNetwork, RpcRole,
         },
         fuel::{EventConfig as FuelEventConfig, HumanConfig as FuelConfig},
-        HumanConfig,
+        EnvInterpolation, HumanConfig,
     },
     hypersync_endpoints,
     validation::{self, validate_names_valid_rescript},
@@ -19,15 +21,18 @@ use crate::{
     fuel::abi::{FuelAbi, BURN_EVENT_NAME, CALL_EVENT_NAME, MINT_EVENT_NAME, TRANSFER_EVENT_NAME},
     project_paths::{path_utils, ParsedProjectPaths},
     rescript_types::RescriptTypeIdent,
-    utils::unique_hashmap,
+    utils::{normalized_list::SingleOrList, unique_hashmap},
 };
 use anyhow::{anyhow, Context, Result};
 use dotenvy::{EnvLoader, EnvMap, EnvSequence};
-use ethers::abi::{ethabi::Event as EthAbiEvent, EventExt, EventParam, HumanReadableParser};
+use ethers::abi::{
+    ethabi::{Event as EthAbiEvent, Function as EthAbiFunction},
+    EventExt, EventParam, FunctionExt, HumanReadableParser, Param, ParamType,
+};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
 };
@@ -102,6 +107,7 @@ impl EnvState {
 }
 
 mod interpolation {
+    use crate::config_parsing::human_config::{EnvInterpolation, EnvInterpolationDiscriminant};
     use anyhow::{anyhow, Result};
     use regex::{Captures, Regex};
 
@@ -205,6 +211,67 @@ mod interpolation {
         Ok(config_string.to_string())
     }
 
+    // Replaces the "${" of the variables which should be resolved at runtime,
+    // so they aren't touched by interpolate_config_variables
+    const RUNTIME_VARIABLE_ESCAPE: &str = "$ENVIO_RUNTIME_ENV{";
+
+    // The url key (and its endpoint_url alias) is only used by the sync source
+    // configs (rpc_config, hypersync_config and hyperfuel_config)
+    fn get_url_key_indent(line: &str) -> Option<usize> {
+        let re = Regex::new(r"^(\s*(?:-\s+)?)(?:endpoint_)?url\s*:").unwrap();
+        re.captures(line).map(|caps| caps[1].len())
+    }
+
+    // Escapes the variables of the url values, which are either on the line of the
+    // url key or on the following lines of its block sequence
+    pub fn escape_runtime_variables(config_string: String) -> String {
+        let mut url_key_indent = None;
+        config_string
+            .split_inclusive('\n')
+            .map(|line| {
+                let content = line.trim_start();
+                let indent = line.len() - content.len();
+                let is_url_value = match (get_url_key_indent(line), url_key_indent) {
+                    (Some(key_indent), _) => {
+                        url_key_indent = Some(key_indent);
+                        true
+                    }
+                    (None, Some(key_indent)) => {
+                        content.trim_end().is_empty()
+                            || indent > key_indent
+                            || (indent == key_indent && content.starts_with('-'))
+                    }
+                    (None, None) => false,
+                };
+                if is_url_value {
+                    line.replace("${", RUNTIME_VARIABLE_ESCAPE)
+                } else {
+                    url_key_indent = None;
+                    line.to_string()
+                }
+            })
+            .collect()
+    }
+
+    pub fn unescape_runtime_variables(config_string: String) -> String {
+        config_string.replace(RUNTIME_VARIABLE_ESCAPE, "${")
+    }
+
+    // Read from the raw config, since it decides how the config is interpolated
+    pub fn get_env_interpolation(config_string: &str) -> Result<Option<EnvInterpolation>> {
+        match serde_yaml::from_str::<EnvInterpolationDiscriminant>(config_string) {
+            Ok(discriminant) => Ok(discriminant.env_interpolation),
+            // Configs which are only valid yaml after the interpolation can't be read here,
+            // but falling back to the codegen interpolation when the runtime one is requested
+            // would write the resolved secrets into the generated code
+            Err(err) if config_string.contains("env_interpolation") => Err(anyhow!(err).context(
+                "EE105: Failed to deserialize the env_interpolation option of the config. Make \
+                 sure values with ${ENV_VAR} inside of [] or {} are quoted",
+            )),
+            Err(_) => Ok(None),
+        }
+    }
+
     #[cfg(test)]
     mod test {
         use pretty_assertions::assert_eq;
@@ -367,6 +434,188 @@ DefaultForMissingAndEmpty with empty env and empty default: ""
 "#
             );
         }
+
+        #[test]
+        fn test_get_env_interpolation() {
+            assert!(matches!(
+                super::get_env_interpolation("env_interpolation: runtime\nname: ${NAME}"),
+                Ok(Some(super::EnvInterpolation::Runtime))
+            ));
+            assert!(matches!(
+                super::get_env_interpolation("networks: [${NETWORK}]"),
+                Ok(None)
+            ));
+            assert_eq!(
+                super::get_env_interpolation("env_interpolation: runtime\nnetworks: [${NETWORK}]")
+                    .unwrap_err()
+                    .to_string(),
+                "EE105: Failed to deserialize the env_interpolation option of the config. Make \
+                 sure values with ${ENV_VAR} inside of [] or {} are quoted"
+            );
+        }
+
+        #[test]
+        fn test_runtime_variables_are_kept_for_sync_source_urls() {
+            let config_string = r#"
+description: https://eth.com/${RPC_KEY}
+networks:
+  - id: ${ENVIO_NETWORK_ID}
+    rpc_config:
+      url:
+        - https://eth.com/${RPC_KEY}
+        - ${FALLBACK_RPC_URL:-https://fallback.eth.com}
+    start_block: ${START_BLOCK}
+  - id: 10
+    hypersync_config:
+      endpoint_url: ${HYPERSYNC_URL}
+    start_block: ${START_BLOCK}
+"#;
+            let interpolated_config_string = super::unescape_runtime_variables(
+                super::interpolate_config_variables(
+                    super::escape_runtime_variables(config_string.to_string()),
+                    |name| match name {
+                        "ENVIO_NETWORK_ID" => Some("1".to_string()),
+                        "RPC_KEY" => Some("secret".to_string()),
+                        "START_BLOCK" => Some("0".to_string()),
+                        _ => None,
+                    },
+                )
+                .unwrap(),
+            );
+            assert_eq!(
+                interpolated_config_string,
+                r#"
+description: https://eth.com/secret
+networks:
+  - id: 1
+    rpc_config:
+      url:
+        - https://eth.com/${RPC_KEY}
+        - ${FALLBACK_RPC_URL:-https://fallback.eth.com}
+    start_block: 0
+  - id: 10
+    hypersync_config:
+      endpoint_url: ${HYPERSYNC_URL}
+    start_block: 0
+"#
+            );
+        }
+    }
+}
+
+mod profile {
+    use anyhow::{Context, Result};
+    use serde_yaml::Value;
+
+    ///Deep-merges the overlay onto the base value. Mappings are merged key by key,
+    ///any other value of the overlay (including lists) replaces the base value
+    fn merge_values(base: &mut Value, overlay: Value) {
+        match (base, overlay) {
+            (Value::Mapping(base), Value::Mapping(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(&key) {
+                        Some(base_value) => merge_values(base_value, value),
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (base, overlay) => *base = overlay,
+        }
+    }
+
+    pub fn merge_config_strings(
+        config_string: &str,
+        profile_config_string: &str,
+    ) -> Result<String> {
+        let mut config: Value = serde_yaml::from_str(config_string).context(
+            "EE105: Failed to deserialize config. The config.yaml file is not a valid yaml.",
+        )?;
+        let profile_config: Value = serde_yaml::from_str(profile_config_string).context(
+            "EE117: Failed to deserialize the profile config. The file is not a valid yaml.",
+        )?;
+
+        //An empty profile config doesn't override anything
+        if profile_config.is_null() {
+            return Ok(config_string.to_string());
+        }
+
+        merge_values(&mut config, profile_config);
+        serde_yaml::to_string(&config)
+            .context("Failed serializing the config merged with the profile config")
+    }
+
+    #[cfg(test)]
+    mod test {
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn test_merge_config_strings() {
+            let config_string = r#"
+name: indexer
+contracts:
+  - name: Greeter
+    handler: src/EventHandlers.ts
+    events:
+      - event: NewGreeting
+networks:
+  - id: 1
+    start_block: 0
+    contracts:
+      - name: Greeter
+        address: "0x1"
+field_selection:
+  transaction_fields:
+    - hash
+"#;
+            let profile_config_string = r#"
+description: staging
+networks:
+  - id: 11155111
+    start_block: 100
+    contracts:
+      - name: Greeter
+        address: "0x2"
+field_selection:
+  block_fields:
+    - parentHash
+"#;
+
+            let merged = super::merge_config_strings(config_string, profile_config_string).unwrap();
+
+            assert_eq!(
+                merged,
+                r#"name: indexer
+contracts:
+- name: Greeter
+  handler: src/EventHandlers.ts
+  events:
+  - event: NewGreeting
+networks:
+- id: 11155111
+  start_block: 100
+  contracts:
+  - name: Greeter
+    address: '0x2'
+field_selection:
+  transaction_fields:
+  - hash
+  block_fields:
+  - parentHash
+description: staging
+"#
+            );
+        }
+
+        #[test]
+        fn test_merge_empty_profile_config() {
+            let config_string = "name: indexer\n";
+
+            let merged = super::merge_config_strings(config_string, "").unwrap();
+
+            assert_eq!(merged, config_string);
+        }
     }
 }
 
@@ -384,6 +633,7 @@ pub struct SystemConfig {
     pub field_selection: FieldSelection,
     pub enable_raw_events: bool,
     pub human_config: HumanConfig,
+    pub chain_registry: ChainRegistry,
 }
 
 //Getter methods for system config
@@ -405,6 +655,16 @@ impl SystemConfig {
         self.contracts.get(name)
     }
 
+    ///The chains registry definitions of the configured networks, ordered by id
+    pub fn get_registered_chains(&self) -> Vec<&ChainDefinition> {
+        let mut network_ids: Vec<&u64> = self.networks.keys().collect();
+        network_ids.sort();
+        network_ids
+            .into_iter()
+            .filter_map(|id| self.chain_registry.get(*id))
+            .collect()
+    }
+
     pub fn get_entity_names(&self) -> Vec<EntityKey> {
         let mut entity_names: Vec<EntityKey> = self
             .schema
@@ -447,6 +707,27 @@ impl SystemConfig {
         enums
     }
 
+    pub fn get_gql_interfaces(&self) -> Vec<&GraphQLInterface> {
+        let mut interfaces: Vec<&GraphQLInterface> = self.schema.interfaces.values().collect();
+        //For consistent templating in alphabetical order
+        interfaces.sort_by_key(|i| i.name.clone());
+        interfaces
+    }
+
+    pub fn get_gql_unions(&self) -> Vec<&GraphQLUnion> {
+        let mut unions: Vec<&GraphQLUnion> = self.schema.unions.values().collect();
+        //For consistent templating in alphabetical order
+        unions.sort_by_key(|u| u.name.clone());
+        unions
+    }
+
+    pub fn get_aggregations(&self) -> Vec<&Aggregation> {
+        let mut aggregations: Vec<&Aggregation> = self.schema.aggregations.values().collect();
+        //For consistent templating in alphabetical order
+        aggregations.sort_by_key(|a| a.entity.name.clone());
+        aggregations
+    }
+
     pub fn get_gql_enum_names_set(&self) -> HashSet<EntityKey> {
         self.schema.enums.keys().cloned().collect()
     }
@@ -467,11 +748,28 @@ impl SystemConfig {
         Ok(schema_path)
     }
 
+    pub fn get_block_handlers(&self) -> Vec<&BlockHandler> {
+        let mut block_handlers: Vec<&BlockHandler> = self
+            .get_networks()
+            .into_iter()
+            .flat_map(|n| n.block_handlers.iter())
+            .collect();
+        //For consistent templating in alphabetical order
+        block_handlers.sort_by_key(|b| b.name.clone());
+        block_handlers.dedup_by_key(|b| b.name.clone());
+        block_handlers
+    }
+
     pub fn get_all_paths_to_handlers(&self) -> Result<Vec<PathBuf>> {
         let mut all_paths_to_handlers = self
             .get_contracts()
             .into_iter()
             .map(|c| c.get_path_to_handler(&self.parsed_project_paths))
+            .chain(
+                self.get_block_handlers()
+                    .into_iter()
+                    .map(|b| b.get_path_to_handler(&self.parsed_project_paths)),
+            )
             .collect::<Result<HashSet<_>>>()?
             .into_iter()
             .collect::<Vec<_>>();
@@ -501,6 +799,7 @@ impl SystemConfig {
     ) -> Result<Self> {
         let mut networks: NetworkMap = HashMap::new();
         let mut contracts: ContractMap = HashMap::new();
+        let mut block_handler_paths: HashMap<String, String> = HashMap::new();
 
         match human_config {
             HumanConfig::Evm(ref evm_config) => {
@@ -509,6 +808,9 @@ impl SystemConfig {
 
                 let has_rpc_sync_src = evm_config.networks.iter().any(|n| n.rpc_config.is_some());
 
+                let chain_registry = ChainRegistry::load(project_paths)
+                    .context("Failed loading the chains registry")?;
+
                 //Add all global contracts
                 if let Some(global_contracts) = &evm_config.contracts {
                     for g_contract in global_contracts {
@@ -523,11 +825,23 @@ impl SystemConfig {
                             g_contract.name,
                         ))?;
 
+                        let functions = Function::from_evm_functions_config(
+                            g_contract.config.functions.clone().unwrap_or_default(),
+                            &g_contract.config.abi_file_path,
+                            project_paths,
+                        )
+                        .context(format!(
+                            "Failed parsing abi types for functions in global contract {}",
+                            g_contract.name,
+                        ))?;
+
                         let contract = Contract::new(
                             g_contract.name.clone(),
                             g_contract.config.handler.clone(),
                             events,
-                            Abi::Evm(evm_abi),
+                            functions.clone(),
+                            Abi::Evm(evm_abi.with_functions(&functions)?),
+                            g_contract.config.factory.clone().map(Factory::from),
                         )
                         .context("Failed parsing globally defined contract")?;
 
@@ -554,11 +868,24 @@ impl SystemConfig {
                                     contract.name, network.id,
                                 ))?;
 
+                                let functions = Function::from_evm_functions_config(
+                                    l_contract.functions.unwrap_or_default(),
+                                    &l_contract.abi_file_path,
+                                    project_paths,
+                                )
+                                .context(format!(
+                                    "Failed parsing abi types for functions in contract {} on \
+                                     network {}",
+                                    contract.name, network.id,
+                                ))?;
+
                                 let contract = Contract::new(
                                     contract.name,
                                     l_contract.handler,
                                     events,
-                                    Abi::Evm(evm_abi),
+                                    functions.clone(),
+                                    Abi::Evm(evm_abi.with_functions(&functions)?),
+                                    l_contract.factory.map(Factory::from),
                                 )
                                 .context(format!(
                                     "Failed parsing locally defined network contract at network \
@@ -597,6 +924,7 @@ impl SystemConfig {
                     let sync_source = SyncSource::from_evm_network_config(
                         network.clone(),
                         evm_config.event_decoder.clone(),
+                        &chain_registry,
                     )?;
 
                     let contracts: Vec<NetworkContract> = network
@@ -606,24 +934,90 @@ impl SystemConfig {
                         .map(|c| NetworkContract {
                             name: c.name,
                             addresses: c.address.into(),
+                            start_block: c.start_block,
+                            end_block: c.end_block,
                         })
                         .collect();
 
+                    let mut block_handlers = vec![];
+                    for block_handler in network.block_handlers.clone().unwrap_or_default() {
+                        //The same block handler can be used on multiple networks, but it's
+                        //registered once in the generated code, so it must point to one file
+                        match block_handler_paths.get(&block_handler.name) {
+                            Some(handler_path) if handler_path != &block_handler.handler => {
+                                Err(anyhow!(
+                                    "EE112: The block handler \"{}\" is defined with different \
+                                     handler paths across networks. Please use the same handler \
+                                     path or a different name.",
+                                    block_handler.name
+                                ))?
+                            }
+                            _ => {
+                                block_handler_paths.insert(
+                                    block_handler.name.clone(),
+                                    block_handler.handler.clone(),
+                                );
+                            }
+                        }
+
+                        block_handlers.push(BlockHandler {
+                            name: block_handler.name,
+                            handler_path: block_handler.handler,
+                            interval: block_handler.interval.unwrap_or(1),
+                            start_block: block_handler.start_block.unwrap_or(network.start_block),
+                            end_block: block_handler.end_block.or(network.end_block),
+                        });
+                    }
+
                     let network = Network {
                         id: network.id,
                         confirmed_block_threshold: network
                             .confirmed_block_threshold
-                            .unwrap_or(get_confirmed_block_threshold_from_id(network.id)),
+                            .unwrap_or_else(|| {
+                                get_confirmed_block_threshold_from_id(network.id, &chain_registry)
+                            }),
                         start_block: network.start_block,
                         end_block: network.end_block,
                         sync_source,
                         contracts,
+                        block_handlers,
                     };
 
                     unique_hashmap::try_insert(&mut networks, network.id, network)
                         .context("Failed inserting network at networks map")?;
                 }
 
+                for contract in contracts.values() {
+                    if let Some(factory) = &contract.factory {
+                        factory.get_address_param(&contracts).context(format!(
+                            "Failed validating the factory of the contract {}",
+                            contract.name
+                        ))?;
+                    }
+                }
+
+                for network in networks.values() {
+                    let has_functions = network.contracts.iter().any(|network_contract| {
+                        contracts
+                            .get(&network_contract.name)
+                            .is_some_and(|contract| !contract.functions.is_empty())
+                    });
+                    if has_functions
+                        && !matches!(network.sync_source, SyncSource::HypersyncConfig(_))
+                    {
+                        Err(anyhow!(
+                            "EE120: The network {} has contracts with functions, but it uses an \
+                             RPC data source. Function calls are only supported on networks \
+                             synced with HyperSync alone, since they are read from the \
+                             transaction inputs, which the RPC data source doesn't fetch. Remove \
+                             the rpc_config of the network or the functions of its contracts. See \
+                             the functions option in the config schema (evm.schema.json) and the \
+                             Function Call Handlers section of the envio README.",
+                            network.id
+                        ))?
+                    }
+                }
+
                 let field_selection = FieldSelection::try_from_config_field_selection(
                     evm_config.field_selection.clone().unwrap_or(
                         human_config::evm::FieldSelection {
@@ -634,6 +1028,10 @@ impl SystemConfig {
                     has_rpc_sync_src,
                 )?;
 
+                for network in networks.values() {
+                    network.validate_block_ranges(&contracts)?;
+                }
+
                 Ok(SystemConfig {
                     name: evm_config.name.clone(),
                     parsed_project_paths: project_paths.clone(),
@@ -652,6 +1050,7 @@ impl SystemConfig {
                     field_selection,
                     enable_raw_events: evm_config.raw_events.unwrap_or(false),
                     human_config,
+                    chain_registry,
                 })
             }
             HumanConfig::Fuel(ref fuel_config) => {
@@ -672,7 +1071,9 @@ impl SystemConfig {
                             g_contract.name.clone(),
                             g_contract.config.handler.clone(),
                             events,
+                            vec![],
                             Abi::fuel(fuel_abi),
+                            None,
                         )?;
 
                         //Check if contract exists
@@ -701,7 +1102,9 @@ impl SystemConfig {
                                     contract.name.clone(),
                                     l_contract.handler,
                                     events,
+                                    vec![],
                                     Abi::fuel(fuel_abi),
+                                    None,
                                 )?;
 
                                 //Check if contract exists
@@ -752,11 +1155,20 @@ impl SystemConfig {
                         .contracts
                         .iter()
                         .cloned()
-                        .map(|c| NetworkContract {
-                            name: c.name,
-                            addresses: c.address.into(),
+                        .map(|c| {
+                            c.validate_block_range(
+                                network.id,
+                                network.start_block,
+                                network.end_block,
+                            )?;
+                            Ok(NetworkContract {
+                                name: c.name,
+                                addresses: c.address.into(),
+                                start_block: c.start_block,
+                                end_block: c.end_block,
+                            })
                         })
-                        .collect();
+                        .collect::<Result<_>>()?;
 
                     let network = Network {
                         id: network.id,
@@ -765,12 +1177,17 @@ impl SystemConfig {
                         confirmed_block_threshold: 0,
                         sync_source,
                         contracts,
+                        block_handlers: vec![],
                     };
 
                     unique_hashmap::try_insert(&mut networks, network.id, network)
                         .context("Failed inserting network at networks map")?;
                 }
 
+                for network in networks.values() {
+                    network.validate_block_ranges(&contracts)?;
+                }
+
                 Ok(SystemConfig {
                     name: fuel_config.name.clone(),
                     parsed_project_paths: project_paths.clone(),
@@ -787,6 +1204,7 @@ impl SystemConfig {
                     field_selection: FieldSelection::fuel(),
                     enable_raw_events: fuel_config.raw_events.unwrap_or(false),
                     human_config,
+                    chain_registry: ChainRegistry::default(),
                 })
             }
         }
@@ -800,11 +1218,55 @@ impl SystemConfig {
                 &project_paths.config.to_str().unwrap_or("{unknown}"),
             ))?;
 
+        let profile_config_string = match project_paths.get_profile_config_path() {
+            None => None,
+            Some(profile_config_path) => Some(
+                std::fs::read_to_string(&profile_config_path).context(format!(
+                    "EE117: Failed to resolve the config of the profile {} at {}. Make sure a \
+                     config file for the profile exists next to the config file",
+                    project_paths.profile.as_deref().unwrap_or("{unknown}"),
+                    profile_config_path.to_str().unwrap_or("{unknown}"),
+                ))?,
+            ),
+        };
+
+        // The profile config can override the interpolation mode
+        let env_interpolation = profile_config_string
+            .iter()
+            .chain(std::iter::once(&human_config_string))
+            .map(|config_string| interpolation::get_env_interpolation(config_string))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .next();
+
         let mut env_state = EnvState::new(&project_paths.project_root);
-        let human_config_string =
-            interpolation::interpolate_config_variables(human_config_string, |name| {
-                env_state.var(name)
-            })?;
+        let mut interpolate = |config_string: String| -> Result<String> {
+            match env_interpolation {
+                Some(EnvInterpolation::Runtime) => {
+                    let config_string = interpolation::escape_runtime_variables(config_string);
+                    let config_string =
+                        interpolation::interpolate_config_variables(config_string, |name| {
+                            env_state.var(name)
+                        })?;
+                    Ok(interpolation::unescape_runtime_variables(config_string))
+                }
+                Some(EnvInterpolation::Codegen) | None => {
+                    interpolation::interpolate_config_variables(config_string, |name| {
+                        env_state.var(name)
+                    })
+                }
+            }
+        };
+
+        let human_config_string = interpolate(human_config_string)?;
+        let human_config_string = match profile_config_string {
+            None => human_config_string,
+            Some(profile_config_string) => profile::merge_config_strings(
+                &human_config_string,
+                &interpolate(profile_config_string)?,
+            )?,
+        };
 
         let config_discriminant: human_config::ConfigDiscriminant =
             serde_yaml::from_str(&human_config_string).context(
@@ -893,20 +1355,60 @@ impl Default for SyncConfig {
 #[derive(Debug, Serialize, PartialEq, Clone)]
 pub struct RpcConfig {
     pub urls: Vec<String>,
+    pub ws_urls: Vec<String>,
     pub sync_config: SyncConfig,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcTransport {
+    Http,
+    WebSocket,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum SyncSourceRole {
+    Historical,
+    Realtime,
+    Fallback,
+}
+
+impl From<RpcRole> for SyncSourceRole {
+    fn from(role: RpcRole) -> Self {
+        match role {
+            RpcRole::Historical => Self::Historical,
+            RpcRole::Realtime => Self::Realtime,
+            RpcRole::Fallback => Self::Fallback,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HybridConfig {
+    pub hypersync_config: HypersyncConfig,
+    pub rpc_config: RpcConfig,
+    pub rpc_role: SyncSourceRole,
+    pub realtime_threshold_blocks: u32,
+    pub fallback_threshold_millis: u32,
+}
+
+impl HybridConfig {
+    const DEFAULT_REALTIME_THRESHOLD_BLOCKS: u32 = 100;
+    const DEFAULT_FALLBACK_THRESHOLD_MILLIS: u32 = 20_000;
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SyncSource {
     RpcConfig(RpcConfig),
     HypersyncConfig(HypersyncConfig),
     HyperfuelConfig(HyperfuelConfig),
+    HybridConfig(HybridConfig),
 }
 
 // Check if the given URL is valid in terms of formatting
 fn parse_url(url: &str) -> Option<String> {
-    // Check URL format
-    if !url.starts_with("http://") && !url.starts_with("https://") {
+    // Check URL format. Values starting with an env variable
+    // are resolved at runtime and can't be checked
+    if !url.starts_with("http://") && !url.starts_with("https://") && !url.starts_with("${") {
         return None;
     }
     // Trim any trailing slashes from the URL
@@ -914,93 +1416,169 @@ fn parse_url(url: &str) -> Option<String> {
     Some(trimmed_url)
 }
 
+// Same as parse_url, but additionally accepts WebSocket urls
+// which are used for newHeads subscriptions. The transport of the urls
+// resolved at runtime is unknown, so RpcSource classifies them after the interpolation
+fn parse_rpc_url(url: &str) -> Option<(RpcTransport, String)> {
+    if url.starts_with("ws://") || url.starts_with("wss://") {
+        return Some((
+            RpcTransport::WebSocket,
+            url.trim_end_matches('/').to_string(),
+        ));
+    }
+    parse_url(url).map(|url| (RpcTransport::Http, url))
+}
+
+impl RpcConfig {
+    fn from_human_config(
+        url: SingleOrList<String>,
+        sync_config: Option<human_config::evm::RpcSyncConfig>,
+    ) -> Result<Self> {
+        let config_urls: Vec<String> = url.into();
+        let mut urls = vec![];
+        let mut ws_urls = vec![];
+        for url in config_urls.iter() {
+            match parse_rpc_url(url) {
+                None => return Err(anyhow!("EE109: The RPC url \"{}\" is incorrect format. The RPC url needs to start with either http://, https://, ws:// or wss://", url)),
+                Some((RpcTransport::Http, endpoint_url)) => urls.push(endpoint_url),
+                Some((RpcTransport::WebSocket, endpoint_url)) => ws_urls.push(endpoint_url),
+            }
+        }
+        Ok(RpcConfig {
+            urls,
+            ws_urls,
+            sync_config: match sync_config {
+                None => SyncConfig::default(),
+                Some(c) => {
+                    let query_timeout_millis = c
+                        .query_timeout_millis
+                        .unwrap_or_else(|| SyncConfig::default().query_timeout_millis);
+                    SyncConfig {
+                        acceleration_additive: c
+                            .acceleration_additive
+                            .unwrap_or_else(|| SyncConfig::default().acceleration_additive),
+                        backoff_millis: c
+                            .backoff_millis
+                            .unwrap_or_else(|| SyncConfig::default().backoff_millis),
+                        backoff_multiplicative: c
+                            .backoff_multiplicative
+                            .unwrap_or_else(|| SyncConfig::default().backoff_multiplicative),
+                        initial_block_interval: c
+                            .initial_block_interval
+                            .unwrap_or_else(|| SyncConfig::default().initial_block_interval),
+                        interval_ceiling: c
+                            .interval_ceiling
+                            .unwrap_or_else(|| SyncConfig::default().interval_ceiling),
+                        query_timeout_millis,
+                        fallback_stall_timeout: c
+                            .fallback_stall_timeout
+                            .unwrap_or(query_timeout_millis / 2),
+                    }
+                }
+            },
+        })
+    }
+}
+
+impl HypersyncConfig {
+    fn from_human_config(
+        hypersync_config: Option<human_config::evm::HypersyncConfig>,
+        network_id: u64,
+        is_client_decoder: bool,
+        chain_registry: &ChainRegistry,
+    ) -> Result<Self> {
+        let endpoint_url = match hypersync_config {
+            None => hypersync_endpoints::get_default_hypersync_endpoint(network_id, chain_registry)
+                .context("EE106: Undefined network config, please provide rpc_config, read more in our docs https://docs.envio.dev/docs/configuration-file")?,
+            Some(human_config::evm::HypersyncConfig { url }) => parse_url(&url).ok_or_else(|| anyhow!("EE106: The HyperSync url \"{}\" is incorrect format. The HyperSync url needs to start with either http:// or https://", url))?,
+        };
+        Ok(HypersyncConfig {
+            endpoint_url,
+            is_client_decoder,
+        })
+    }
+}
+
 impl SyncSource {
     fn from_evm_network_config(
         network: EvmNetwork,
         event_decoder: Option<EventDecoder>,
+        chain_registry: &ChainRegistry,
     ) -> Result<Self> {
         let is_client_decoder = match event_decoder {
             Some(EventDecoder::HypersyncClient) | None => true,
             Some(EventDecoder::Viem) => false,
         };
-        match network {
-            human_config::evm::Network {
-                hypersync_config: Some(_),
-                rpc_config: Some(_),
-                ..
-            } => {
-                Err(anyhow!("EE106: Cannot define both rpc_config and hypersync_config for the same network, please choose only one of them, read more in our docs https://docs.envio.dev/docs/configuration-file"))
-            }
-            human_config::evm::Network {
-              hypersync_config: None,
-              rpc_config: None,
-                ..
-            } => {
-                let defualt_hypersync_endpoint = hypersync_endpoints::get_default_hypersync_endpoint(network.id)
-                    .context("EE106: Undefined network config, please provide rpc_config, read more in our docs https://docs.envio.dev/docs/configuration-file")?;
-                Ok(Self::HypersyncConfig(HypersyncConfig {
-                    endpoint_url: defualt_hypersync_endpoint,
+        let rpc_config = match network.rpc_config {
+            None => {
+                return Ok(Self::HypersyncConfig(HypersyncConfig::from_human_config(
+                    network.hypersync_config,
+                    network.id,
                     is_client_decoder,
-                }))
+                    chain_registry,
+                )?))
             }
-            human_config::evm::Network {
-              hypersync_config: None,
-              rpc_config: Some(human_config::evm::RpcConfig {
-                url,
-                sync_config
-              }),
-              ..
-            } => {
-              let config_urls: Vec<String> = url.into();
-              let mut urls = vec![];
-              for url in config_urls.iter() {
-                match parse_url(url) {
-                    None => return Err(anyhow!("EE109: The RPC url \"{}\" is incorrect format. The RPC url needs to start with either http:// or https://", url)),
-                    Some(endpoint_url) => urls.push(endpoint_url)
-                }
-              }
-              Ok(Self::RpcConfig(RpcConfig {
-                  urls,
-                  sync_config: match sync_config {
-                      None => SyncConfig::default(),
-                      Some(c) => {
-                        let query_timeout_millis = c
-                          .query_timeout_millis
-                          .unwrap_or_else(|| SyncConfig::default().query_timeout_millis);
-                        SyncConfig {
-                          acceleration_additive: c
-                              .acceleration_additive
-                              .unwrap_or_else(|| SyncConfig::default().acceleration_additive),
-                          backoff_millis: c
-                              .backoff_millis
-                              .unwrap_or_else(|| SyncConfig::default().backoff_millis),
-                          backoff_multiplicative: c
-                              .backoff_multiplicative
-                              .unwrap_or_else(|| SyncConfig::default().backoff_multiplicative),
-                          initial_block_interval: c
-                              .initial_block_interval
-                              .unwrap_or_else(|| SyncConfig::default().initial_block_interval),
-                          interval_ceiling: c
-                              .interval_ceiling
-                              .unwrap_or_else(|| SyncConfig::default().interval_ceiling),
-                          query_timeout_millis,
-                          fallback_stall_timeout: c
-                              .fallback_stall_timeout
-                              .unwrap_or(query_timeout_millis / 2),
-                      }},
-                  },
-              }))
-            },
-            human_config::evm::Network {
-              hypersync_config: Some(human_config::evm::HypersyncConfig { url }),
-              rpc_config: None,
-              ..
-            } => {
-              match parse_url(&url) {
-                  None => Err(anyhow!("EE106: The HyperSync url \"{}\" is incorrect format. The HyperSync url needs to start with either http:// or https://", url)),
-                  Some(endpoint_url) => Ok(Self::HypersyncConfig(HypersyncConfig { endpoint_url, is_client_decoder }))
-              }
+            Some(rpc_config) => rpc_config,
+        };
+
+        let rpc_role = match (&network.hypersync_config, rpc_config.role) {
+            (None, None) => None,
+            // Use the RPC as a fallback by default, when both sources are defined
+            (Some(_), None) => Some(SyncSourceRole::Fallback),
+            (_, Some(role)) => Some(role.into()),
+        };
+
+        match (
+            &rpc_role,
+            rpc_config.realtime_threshold_blocks,
+            rpc_config.fallback_threshold_millis,
+        ) {
+            (None, Some(_), _) | (None, _, Some(_)) => {
+                return Err(anyhow!(
+                    "EE118: The realtime_threshold_blocks and fallback_threshold_millis options \
+                     of the rpc_config are only allowed together with hypersync_config or the \
+                     role option"
+                ))
+            }
+            (Some(SyncSourceRole::Fallback), Some(_), _) => {
+                return Err(anyhow!(
+                    "EE118: The realtime_threshold_blocks option of the rpc_config is only \
+                     allowed for the historical and realtime roles"
+                ))
+            }
+            (Some(SyncSourceRole::Historical | SyncSourceRole::Realtime), _, Some(_)) => {
+                return Err(anyhow!(
+                    "EE118: The fallback_threshold_millis option of the rpc_config is only \
+                     allowed for the fallback role"
+                ))
             }
+            _ => (),
+        }
+
+        let rpc = RpcConfig::from_human_config(rpc_config.url, rpc_config.sync_config)?;
+
+        match rpc_role {
+            None => Ok(Self::RpcConfig(rpc)),
+            Some(rpc_role) => Ok(Self::HybridConfig(HybridConfig {
+                hypersync_config: HypersyncConfig::from_human_config(
+                    network.hypersync_config,
+                    network.id,
+                    is_client_decoder,
+                    chain_registry,
+                )
+                .context(
+                    "EE118: Failed to get HyperSync config for the rpc_config role. Please \
+                     provide hypersync_config for the network",
+                )?,
+                rpc_config: rpc,
+                rpc_role,
+                realtime_threshold_blocks: rpc_config
+                    .realtime_threshold_blocks
+                    .unwrap_or(HybridConfig::DEFAULT_REALTIME_THRESHOLD_BLOCKS),
+                fallback_threshold_millis: rpc_config
+                    .fallback_threshold_millis
+                    .unwrap_or(HybridConfig::DEFAULT_FALLBACK_THRESHOLD_MILLIS),
+            })),
         }
     }
 }
@@ -1013,12 +1591,96 @@ pub struct Network {
     pub end_block: Option<u64>,
     pub confirmed_block_threshold: i32,
     pub contracts: Vec<NetworkContract>,
+    pub block_handlers: Vec<BlockHandler>,
+}
+
+impl Network {
+    /// Validates that the block range of every event is within its contract one.
+    /// The contract block ranges are validated against the network on parsing.
+    fn validate_block_ranges(&self, contracts: &ContractMap) -> Result<()> {
+        let format_end_block =
+            |end_block: Option<u64>| end_block.map_or("unbounded".to_string(), |b| b.to_string());
+        let is_within = |start_block: u64,
+                         end_block: Option<u64>,
+                         range_start_block: u64,
+                         range_end_block: Option<u64>| {
+            start_block >= range_start_block
+                && match (end_block, range_end_block) {
+                    (_, None) => true,
+                    (None, Some(_)) => false,
+                    (Some(end_block), Some(range_end_block)) => end_block <= range_end_block,
+                }
+        };
+
+        for network_contract in &self.contracts {
+            let contract_start_block = network_contract.start_block.unwrap_or(self.start_block);
+            let contract_end_block = network_contract.end_block.or(self.end_block);
+
+            let Some(contract) = contracts.get(&network_contract.name) else {
+                continue;
+            };
+            for event in &contract.events {
+                if event.start_block.is_none() && event.end_block.is_none() {
+                    continue;
+                }
+                let event_start_block = event.start_block.unwrap_or(contract_start_block);
+                let event_end_block = event.end_block.or(contract_end_block);
+                if !is_within(
+                    event_start_block,
+                    event_end_block,
+                    contract_start_block,
+                    contract_end_block,
+                ) || event_end_block.is_some_and(|end_block| end_block < event_start_block)
+                {
+                    return Err(anyhow!(
+                        "EE114: The block range {}-{} of the event \"{}\" isn't within the block \
+                         range {}-{} of the contract \"{}\" on network id: {}.",
+                        event_start_block,
+                        format_end_block(event_end_block),
+                        event.name,
+                        contract_start_block,
+                        format_end_block(contract_end_block),
+                        contract.name,
+                        self.id
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHandler {
+    pub name: String,
+    pub handler_path: String,
+    pub interval: u64,
+    pub start_block: u64,
+    pub end_block: Option<u64>,
+}
+
+impl BlockHandler {
+    pub fn get_path_to_handler(&self, project_paths: &ParsedProjectPaths) -> Result<PathBuf> {
+        let handler_path = path_utils::get_config_path_relative_to_root(
+            project_paths,
+            PathBuf::from(&self.handler_path),
+        )
+        .context(format!(
+            "Failed creating a relative path to handler in block handler {}",
+            self.name
+        ))?;
+
+        Ok(handler_path)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NetworkContract {
     pub name: ContractNameKey,
     pub addresses: Vec<String>,
+    pub start_block: Option<u64>,
+    pub end_block: Option<u64>,
 }
 
 impl NetworkContract {
@@ -1072,13 +1734,42 @@ impl EvmAbi {
             .collect()
     }
 
-    pub fn from_file(
-        abi_file_path: &Option<String>,
-        project_paths: &ParsedProjectPaths,
-    ) -> Result<Option<Self>> {
-        match &abi_file_path {
-            None => Ok(None),
-            Some(abi_file_path) => {
+    // Functions are added to the abi so the call inputs can be decoded with it
+    pub fn with_functions(self, functions: &[Function]) -> Result<Self> {
+        if functions.is_empty() {
+            return Ok(self);
+        }
+
+        let mut typed = self.typed;
+        for function in functions {
+            let overloads = typed
+                .functions
+                .entry(function.abi_function.name.clone())
+                .or_default();
+            // The function might already be defined in the ABI file
+            let signature = function.abi_function.signature();
+            if !overloads.iter().any(|f| f.signature() == signature) {
+                overloads.push(function.abi_function.clone());
+            }
+        }
+
+        let raw = serde_json::to_string(&typed)
+            .context("Failed serializing ABI with configured functions")?;
+
+        Ok(Self {
+            path: self.path,
+            raw,
+            typed,
+        })
+    }
+
+    pub fn from_file(
+        abi_file_path: &Option<String>,
+        project_paths: &ParsedProjectPaths,
+    ) -> Result<Option<Self>> {
+        match &abi_file_path {
+            None => Ok(None),
+            Some(abi_file_path) => {
                 #[derive(Deserialize)]
                 #[serde(untagged)]
                 enum AbiOrNestedAbi {
@@ -1140,21 +1831,45 @@ pub struct Contract {
     pub handler_path: String,
     pub abi: Abi,
     pub events: Vec<Event>,
+    pub functions: Vec<Function>,
+    pub factory: Option<Factory>,
 }
 
 impl Contract {
-    pub fn new(name: String, handler_path: String, events: Vec<Event>, abi: Abi) -> Result<Self> {
+    pub fn new(
+        name: String,
+        handler_path: String,
+        events: Vec<Event>,
+        functions: Vec<Function>,
+        abi: Abi,
+        factory: Option<Factory>,
+    ) -> Result<Self> {
         // TODO: Validatate that all event names are unique
         validate_names_valid_rescript(
             &events.iter().map(|e| e.name.clone()).collect(),
             "event".to_string(),
         )?;
 
+        let function_names: Vec<String> = functions.iter().map(|f| f.name.clone()).collect();
+        validate_names_valid_rescript(&function_names, "function".to_string())?;
+
+        let duplicate_function_names: Vec<_> = function_names.iter().duplicates().collect();
+        if !duplicate_function_names.is_empty() {
+            return Err(anyhow!(
+                "EE113: Contract {} has the following duplicated function names: {}. Use the name \
+                 field to give overloaded functions unique names.",
+                name,
+                duplicate_function_names.iter().join(", ")
+            ));
+        }
+
         Ok(Self {
             name,
             events,
+            functions,
             handler_path,
             abi,
+            factory,
         })
     }
 
@@ -1186,6 +1901,78 @@ impl Contract {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factory {
+    pub contract_name: ContractNameKey,
+    pub event_name: String,
+    pub param_name: String,
+}
+
+impl From<EvmFactoryConfig> for Factory {
+    fn from(factory_config: EvmFactoryConfig) -> Self {
+        Factory {
+            contract_name: factory_config.contract,
+            event_name: factory_config.event,
+            param_name: factory_config.param,
+        }
+    }
+}
+
+impl Factory {
+    ///Gets the param of the factory event with the address of the created contract.
+    ///Fails when the factory contract, event or param doesn't exist, or the param isn't an address
+    pub fn get_address_param<'a>(&self, contracts: &'a ContractMap) -> Result<&'a EventParam> {
+        let factory_contract = contracts.get(&self.contract_name).ok_or_else(|| {
+            anyhow!(
+                "EE116: The factory contract '{}' is not defined in the config",
+                self.contract_name
+            )
+        })?;
+
+        let event = factory_contract
+            .events
+            .iter()
+            .find(|event| event.name == self.event_name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "EE116: The factory event '{}' is not defined on the contract '{}'",
+                    self.event_name,
+                    self.contract_name
+                )
+            })?;
+
+        let param = match &event.kind {
+            EventKind::Params(params) => params.iter().find(|param| param.name == self.param_name),
+            EventKind::Fuel(_) => None,
+        }
+        .ok_or_else(|| {
+            anyhow!(
+                "EE116: The param '{}' is not defined on the factory event '{}.{}'",
+                self.param_name,
+                self.contract_name,
+                self.event_name
+            )
+        })?;
+
+        match &param.kind {
+            ParamType::Address => Ok(param),
+            ParamType::Array(kind) | ParamType::FixedArray(kind, _)
+                if **kind == ParamType::Address =>
+            {
+                Ok(param)
+            }
+            kind => Err(anyhow!(
+                "EE116: The param '{}' of the factory event '{}.{}' should be an address or a \
+                 list of addresses, but it has the type {}",
+                self.param_name,
+                self.contract_name,
+                self.event_name,
+                kind
+            )),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum FuelEventKind {
     LogData(RescriptTypeIdent),
@@ -1207,6 +1994,9 @@ pub struct Event {
     pub name: String,
     pub sighash: String,
     pub field_selection: Option<FieldSelection>,
+    pub start_block: Option<u64>,
+    pub end_block: Option<u64>,
+    pub filter: Option<EventFilter>,
 }
 
 impl Event {
@@ -1284,6 +2074,15 @@ impl Event {
             // instead of an object with named fields.
             event.inputs = normalized_unnamed_params.clone();
 
+            let filter = match event_config.filter {
+                Some(ref filter_config) => Some(EventFilter::from_config(
+                    filter_config,
+                    &name,
+                    &normalized_unnamed_params,
+                )?),
+                None => None,
+            };
+
             events_abi.events.entry(abi_name).or_default().push(event);
             events.push(Event {
                 name,
@@ -1298,6 +2097,9 @@ impl Event {
                     }
                     None => None,
                 },
+                start_block: event_config.start_block,
+                end_block: event_config.end_block,
+                filter,
             })
         }
 
@@ -1377,6 +2179,9 @@ impl Event {
                         kind: EventKind::Fuel(FuelEventKind::LogData(log.data_type)),
                         sighash: log.id,
                         field_selection: None,
+                        start_block: None,
+                        end_block: None,
+                        filter: None,
                     }
                 }
                 EventType::Mint => Event {
@@ -1384,24 +2189,36 @@ impl Event {
                     kind: EventKind::Fuel(FuelEventKind::Mint),
                     sighash: "mint".to_string(),
                     field_selection: None,
+                    start_block: None,
+                    end_block: None,
+                    filter: None,
                 },
                 EventType::Burn => Event {
                     name: event_config.name.clone(),
                     kind: EventKind::Fuel(FuelEventKind::Burn),
                     sighash: "burn".to_string(),
                     field_selection: None,
+                    start_block: None,
+                    end_block: None,
+                    filter: None,
                 },
                 EventType::Transfer => Event {
                     name: event_config.name.clone(),
                     kind: EventKind::Fuel(FuelEventKind::Transfer),
                     sighash: "transfer".to_string(),
                     field_selection: None,
+                    start_block: None,
+                    end_block: None,
+                    filter: None,
                 },
                 EventType::Call => Event {
                     name: event_config.name.clone(),
                     kind: EventKind::Fuel(FuelEventKind::Call),
                     sighash: "call".to_string(),
                     field_selection: None,
+                    start_block: None,
+                    end_block: None,
+                    filter: None,
                 },
             };
 
@@ -1413,6 +2230,226 @@ impl Event {
     }
 }
 
+/// Topic values of indexed event params, statically set with the filter option in config.yaml
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventFilter {
+    //Encoded topic values for every indexed param in the order of the event signature.
+    //None when the param is not filtered
+    pub topics: Vec<Option<Vec<String>>>,
+}
+
+impl EventFilter {
+    pub fn from_config(
+        filter_config: &BTreeMap<String, SingleOrList<EventFilterValue>>,
+        event_name: &str,
+        params: &[EventParam],
+    ) -> Result<Self> {
+        let indexed_params = params.iter().filter(|p| p.indexed).collect::<Vec<_>>();
+
+        if let Some(param_name) = filter_config
+            .keys()
+            .find(|param_name| !indexed_params.iter().any(|p| &p.name == *param_name))
+        {
+            return Err(anyhow!(
+                "EE115: Invalid filter for the event '{}'. '{}' is not an indexed param of the \
+                 event. Available indexed params: [{}]",
+                event_name,
+                param_name,
+                indexed_params.iter().map(|p| p.name.clone()).join(", ")
+            ));
+        }
+
+        let topics = indexed_params
+            .iter()
+            .map(|param| match filter_config.get(&param.name) {
+                None => Ok(None),
+                Some(values) => {
+                    let values: Vec<EventFilterValue> = values.clone().into();
+                    if values.is_empty() {
+                        return Err(anyhow!(
+                            "EE115: Invalid filter for the event '{}'. The param '{}' should have \
+                             at least one value to match",
+                            event_name,
+                            param.name
+                        ));
+                    }
+                    values
+                        .iter()
+                        .map(|value| {
+                            Self::encode_topic(value, &param.kind).context(format!(
+                                "EE115: Invalid filter value for the param '{}' of the event '{}'",
+                                param.name, event_name
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                        .map(Some)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(EventFilter { topics })
+    }
+
+    fn encode_topic(value: &EventFilterValue, kind: &ParamType) -> Result<String> {
+        use ethers::{
+            abi::Token,
+            types::{Address, Sign, I256, U256},
+            utils::{hex, keccak256},
+        };
+
+        let decode_hex = |s: &str| -> Result<Vec<u8>> {
+            hex::decode(s.strip_prefix("0x").unwrap_or(s)).context(format!("Invalid hex value {s}"))
+        };
+
+        //Integers are accepted both in the decimal and the 0x prefixed hex form
+        let parse_u256 = |s: &str| match s.strip_prefix("0x") {
+            Some(hex_value) => U256::from_str_radix(hex_value, 16),
+            None => U256::from_str_radix(s, 10),
+        };
+
+        let token = match (kind, value) {
+            (ParamType::Address, EventFilterValue::String(s)) => Token::Address(
+                s.parse::<Address>()
+                    .context(format!("Invalid address {s}"))?,
+            ),
+            (ParamType::Bool, EventFilterValue::Bool(b)) => Token::Bool(*b),
+            (ParamType::Uint(_), EventFilterValue::Integer(s) | EventFilterValue::String(s)) => {
+                Token::Uint(parse_u256(s).context(format!("Invalid unsigned integer {s}"))?)
+            }
+            (ParamType::Int(_), EventFilterValue::Integer(s) | EventFilterValue::String(s)) => {
+                let (sign, abs) = match s.strip_prefix('-') {
+                    Some(abs) => (Sign::Negative, abs),
+                    None => (Sign::Positive, s.as_str()),
+                };
+                let abs = parse_u256(abs).context(format!("Invalid integer {s}"))?;
+                Token::Int(
+                    I256::checked_from_sign_and_abs(sign, abs)
+                        .context(format!("Integer {s} is out of the int256 range"))?
+                        .into_raw(),
+                )
+            }
+            (ParamType::FixedBytes(size), EventFilterValue::String(s)) => {
+                let bytes = decode_hex(s)?;
+                if bytes.len() != *size {
+                    return Err(anyhow!("Expected {size} bytes, got {}", bytes.len()));
+                }
+                Token::FixedBytes(bytes)
+            }
+            //Dynamic values are stored in topics as the keccak256 hash of their content
+            (ParamType::String, EventFilterValue::String(s)) => {
+                return Ok(hex::encode_prefixed(keccak256(s.as_bytes())));
+            }
+            (ParamType::Bytes, EventFilterValue::String(s)) => {
+                return Ok(hex::encode_prefixed(keccak256(decode_hex(s)?)));
+            }
+            (ParamType::Array(_) | ParamType::FixedArray(_, _) | ParamType::Tuple(_), _) => {
+                return Err(anyhow!(
+                    "Filtering by indexed params of type {kind} is not supported"
+                ));
+            }
+            _ => return Err(anyhow!("Expected a value of type {kind}")),
+        };
+
+        Ok(hex::encode_prefixed(ethers::abi::encode(&[token])))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub selector: String,
+    pub abi_function: EthAbiFunction,
+}
+
+impl Function {
+    fn get_abi_function(function_string: &str, opt_abi: &Option<EvmAbi>) -> Result<EthAbiFunction> {
+        let parse_function_sig = |sig: &str| -> Result<EthAbiFunction> {
+            match HumanReadableParser::parse_function(sig) {
+                Ok(function) => Ok(function),
+                Err(err) => Err(anyhow!(
+                    "EE113: Unable to parse function signature {} due to the following error: {}. \
+                     Please refer to our docs on how to correctly define a human readable ABI.",
+                    sig,
+                    err
+                )),
+            }
+        };
+
+        let function_string = function_string.trim();
+
+        if function_string.starts_with("function ") {
+            parse_function_sig(function_string)
+        } else if function_string.contains('(') {
+            let signature = format!("function {}", function_string);
+            parse_function_sig(&signature)
+        } else {
+            match opt_abi {
+                Some(abi) => {
+                    let function = abi.typed.function(function_string).context(format!(
+                        "Failed retrieving function {} from abi",
+                        function_string
+                    ))?;
+                    Ok(function.clone())
+                }
+                None => Err(anyhow!(
+                    "No abi file provided for function {}",
+                    function_string
+                )),
+            }
+        }
+    }
+
+    pub fn params(&self) -> &Vec<Param> {
+        &self.abi_function.inputs
+    }
+
+    pub fn from_evm_functions_config(
+        functions_config: Vec<EvmFunctionConfig>,
+        abi_file_path: &Option<String>,
+        project_paths: &ParsedProjectPaths,
+    ) -> Result<Vec<Self>> {
+        if functions_config.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let abi_from_file = EvmAbi::from_file(abi_file_path, project_paths)?;
+
+        functions_config
+            .iter()
+            .map(|function_config| {
+                let mut abi_function =
+                    Function::get_abi_function(&function_config.function, &abi_from_file)?;
+                let selector = ethers::core::utils::hex::encode_prefixed(abi_function.selector());
+
+                // Same as for events, unnamed params should be named
+                // so the decoded inputs are an object with named fields.
+                abi_function.inputs = abi_function
+                    .inputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        let name = if p.name.is_empty() {
+                            format!("_{}", i)
+                        } else {
+                            p.name
+                        };
+                        Param { name, ..p }
+                    })
+                    .collect();
+
+                Ok(Function {
+                    name: function_config
+                        .name
+                        .clone()
+                        .unwrap_or(abi_function.name.clone()),
+                    selector,
+                    abi_function,
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SelectedField {
     pub name: String,
@@ -1624,17 +2661,25 @@ impl FieldSelection {
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
+    use std::{collections::BTreeMap, path::PathBuf};
 
     use super::SystemConfig;
     use crate::{
         config_parsing::{
-            human_config::evm::HumanConfig as EvmConfig,
-            system_config::{Event, SyncConfig, SyncSource},
+            chain_registry::ChainRegistry,
+            entity_parsing::Schema,
+            human_config::{
+                evm::{EventConfig as EvmEventConfig, EventFilterValue, HumanConfig as EvmConfig},
+                HumanConfig,
+            },
+            system_config::{
+                BlockHandler, Event, EventFilter, Factory, Function, SyncConfig, SyncSource,
+            },
         },
         project_paths::ParsedProjectPaths,
+        utils::normalized_list::SingleOrList,
     };
-    use ethers::abi::{Event as EthAbiEvent, EventParam, ParamType};
+    use ethers::abi::{Event as EthAbiEvent, EventParam, HumanReadableParser, ParamType};
     use handlebars::Handlebars;
     use pretty_assertions::assert_eq;
     use serde_json::json;
@@ -1759,6 +2804,555 @@ mod test {
         assert_eq!(expected_abi, contract_abi);
     }
 
+    #[test]
+    fn test_parse_block_handlers() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/block-handlers-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+
+        let networks = config.get_networks();
+        assert_eq!(
+            networks[0].block_handlers,
+            vec![
+                BlockHandler {
+                    name: "Snapshot".to_string(),
+                    handler_path: "./src/BlockHandler.js".to_string(),
+                    interval: 100,
+                    start_block: 100,
+                    end_block: Some(10000),
+                },
+                BlockHandler {
+                    name: "PriceSample".to_string(),
+                    handler_path: "./src/BlockHandler.js".to_string(),
+                    interval: 1,
+                    start_block: 500,
+                    end_block: Some(1000),
+                },
+            ]
+        );
+        assert_eq!(
+            networks[1].block_handlers,
+            vec![BlockHandler {
+                name: "Snapshot".to_string(),
+                handler_path: "./src/BlockHandler.js".to_string(),
+                interval: 1000,
+                start_block: 0,
+                end_block: None,
+            }]
+        );
+
+        let block_handler_names: Vec<_> = config
+            .get_block_handlers()
+            .into_iter()
+            .map(|b| b.name.clone())
+            .collect();
+        assert_eq!(block_handler_names, vec!["PriceSample", "Snapshot"]);
+
+        assert_eq!(
+            config.get_all_paths_to_handlers().unwrap(),
+            vec![
+                PathBuf::from(&test_dir).join("configs/src/BlockHandler.js"),
+                PathBuf::from(&test_dir).join("configs/src/EventHandler.js"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_block_ranges() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/block-ranges-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+
+        let network_contracts = config.get_networks()[0]
+            .contracts
+            .iter()
+            .map(|c| (c.name.as_str(), c.start_block, c.end_block))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            network_contracts,
+            vec![
+                ("Contract1", Some(19000000), None),
+                ("Contract2", None, Some(15000000)),
+            ]
+        );
+
+        let event_block_ranges = |contract_name: &str| {
+            config
+                .get_contract(&contract_name.to_string())
+                .expect("Contract should exist")
+                .events
+                .iter()
+                .map(|e| (e.name.clone(), e.start_block, e.end_block))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            event_block_ranges("Contract1"),
+            vec![
+                ("NewGravatar".to_string(), None, None),
+                ("UpdatedGravatar".to_string(), Some(19500000), None),
+            ]
+        );
+        assert_eq!(
+            event_block_ranges("Contract2"),
+            vec![("NewGravatar".to_string(), None, Some(13000000))]
+        );
+    }
+
+    #[test]
+    fn test_validate_nested_block_ranges() {
+        let parse = |contract_range: &str, event_range: &str| {
+            let config_string = format!(
+                r#"
+name: block-ranges
+contracts:
+  - name: Greeter
+    handler: ./src/EventHandler.js
+    events:
+      - event: "NewGreeting(address user, string greeting)"
+        {event_range}
+networks:
+  - id: 1
+    start_block: 100
+    end_block: 1000
+    contracts:
+      - name: Greeter
+        address: "0x2E645469f354BB4F5c8a05B3b30A929361cf77eC"
+        {contract_range}
+"#
+            );
+            let evm_config: EvmConfig = serde_yaml::from_str(&config_string).unwrap();
+            SystemConfig::from_human_config(
+                HumanConfig::Evm(evm_config),
+                Schema::empty(),
+                &ParsedProjectPaths::default(),
+            )
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(parse("start_block: 200", "start_block: 300"), Ok(()));
+        assert_eq!(
+            parse("start_block: 200", "start_block: 150"),
+            Err(
+                "EE114: The block range 150-1000 of the event \"NewGreeting\" isn't within the \
+                 block range 200-1000 of the contract \"Greeter\" on network id: 1."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parse("end_block: 500", "end_block: 600"),
+            Err(
+                "EE114: The block range 100-600 of the event \"NewGreeting\" isn't within the \
+                 block range 100-500 of the contract \"Greeter\" on network id: 1."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parse("end_block: 500", "start_block: 700"),
+            Err(
+                "EE114: The block range 700-500 of the event \"NewGreeting\" isn't within the \
+                 block range 100-500 of the contract \"Greeter\" on network id: 1."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parse("end_block: 2000", ""),
+            Err(
+                "EE114: The contract \"Greeter\" on network id: 1 has an end_block of 2000 which \
+                 is greater than the network end_block of 1000."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_config_with_profile() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/profile-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+        assert_eq!(
+            config
+                .get_networks()
+                .iter()
+                .map(|n| (n.id, n.start_block))
+                .collect::<Vec<_>>(),
+            vec![(1, 12000000)]
+        );
+
+        let config = SystemConfig::parse_from_project_files(
+            &project_paths
+                .clone()
+                .with_profile(Some("staging".to_string())),
+        )
+        .expect("Failed parsing config with profile");
+        assert_eq!(
+            config
+                .get_networks()
+                .iter()
+                .map(|n| (n.id, n.start_block))
+                .collect::<Vec<_>>(),
+            vec![(11155111, 5000000)]
+        );
+        assert_eq!(config.name, "profile".to_string());
+
+        let err = SystemConfig::parse_from_project_files(
+            &project_paths.with_profile(Some("prod".to_string())),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("EE117: Failed to resolve the config of the profile prod"));
+    }
+
+    #[test]
+    fn test_parse_factories() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/factory-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+
+        let pool_factory = config
+            .get_contract(&"Pool".to_string())
+            .expect("Contract should exist")
+            .factory
+            .clone()
+            .expect("Pool should have a factory");
+
+        assert_eq!(
+            pool_factory,
+            Factory {
+                contract_name: "PoolFactory".to_string(),
+                event_name: "PoolCreated".to_string(),
+                param_name: "pool".to_string(),
+            }
+        );
+        assert_eq!(
+            config
+                .get_contract(&"PoolFactory".to_string())
+                .expect("Contract should exist")
+                .factory,
+            None
+        );
+
+        let err = Factory {
+            param_name: "fee".to_string(),
+            ..pool_factory.clone()
+        }
+        .get_address_param(&config.contracts)
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "EE116: The param 'fee' of the factory event 'PoolFactory.PoolCreated' should be an \
+             address or a list of addresses, but it has the type uint24"
+        );
+
+        let err = Factory {
+            event_name: "PoolDestroyed".to_string(),
+            ..pool_factory
+        }
+        .get_address_param(&config.contracts)
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "EE116: The factory event 'PoolDestroyed' is not defined on the contract 'PoolFactory'"
+        );
+    }
+
+    #[test]
+    fn test_parse_event_filters() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/event-filter-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+
+        let event_filters = config
+            .get_contract(&"Token".to_string())
+            .expect("Contract should exist")
+            .events
+            .iter()
+            .map(|e| (e.name.clone(), e.filter.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            event_filters,
+            vec![
+                (
+                    "Transfer".to_string(),
+                    Some(EventFilter {
+                        topics: vec![
+                            None,
+                            Some(vec![
+                                "0x0000000000000000000000002e645469f354bb4f5c8a05b3b30a929361cf77ec"
+                                    .to_string()
+                            ]),
+                        ]
+                    })
+                ),
+                (
+                    "Approval".to_string(),
+                    Some(EventFilter {
+                        topics: vec![
+                            Some(vec![
+                                "0x0000000000000000000000002e645469f354bb4f5c8a05b3b30a929361cf77ec"
+                                    .to_string(),
+                                "0x0000000000000000000000001e645469f354bb4f5c8a05b3b30a929361cf77ed"
+                                    .to_string()
+                            ]),
+                            None,
+                        ]
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_filter_encodes_topics() {
+        let event = HumanReadableParser::parse_event(
+            "event Custom(uint256 indexed amount, int8 indexed delta, string indexed label, bool \
+             indexed flag)",
+        )
+        .unwrap();
+        let filter_config: BTreeMap<String, SingleOrList<EventFilterValue>> =
+            serde_json::from_value(json!({
+                "amount": [5, "0x10"],
+                "delta": -1,
+                "label": "hello",
+                "flag": true,
+            }))
+            .unwrap();
+
+        let filter = EventFilter::from_config(&filter_config, "Custom", &event.inputs).unwrap();
+
+        assert_eq!(
+            filter.topics,
+            vec![
+                Some(vec![
+                    "0x0000000000000000000000000000000000000000000000000000000000000005"
+                        .to_string(),
+                    "0x0000000000000000000000000000000000000000000000000000000000000010"
+                        .to_string()
+                ]),
+                Some(vec![
+                    "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+                        .to_string()
+                ]),
+                Some(vec![
+                    "0x1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8"
+                        .to_string()
+                ]),
+                Some(vec![
+                    "0x0000000000000000000000000000000000000000000000000000000000000001"
+                        .to_string()
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_filter_encodes_uint256_topics() {
+        let event =
+            HumanReadableParser::parse_event("event Deposit(uint256 indexed amount)").unwrap();
+        let event_config: EvmEventConfig = serde_yaml::from_str(
+            r#"
+event: Deposit(uint256 indexed amount)
+where:
+  amount:
+    - 18446744073709551615
+    - 340282366920938463463374607431768211455
+    - "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+"#,
+        )
+        .unwrap();
+
+        let filter = EventFilter::from_config(
+            &event_config
+                .filter
+                .expect("The where alias should set the filter"),
+            "Deposit",
+            &event.inputs,
+        )
+        .unwrap();
+
+        assert_eq!(
+            filter.topics,
+            vec![Some(vec![
+                "0x000000000000000000000000000000000000000000000000ffffffffffffffff".to_string(),
+                "0x00000000000000000000000000000000ffffffffffffffffffffffffffffffff".to_string(),
+                "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff".to_string(),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_event_filter_encodes_int_topics_from_hex_and_decimal() {
+        let event = HumanReadableParser::parse_event("event Move(int256 indexed delta)").unwrap();
+        let filter_config: BTreeMap<String, SingleOrList<EventFilterValue>> =
+            serde_json::from_value(json!({
+                "delta": [-16, "-16", "-0x10", "0x10"],
+            }))
+            .unwrap();
+
+        let filter = EventFilter::from_config(&filter_config, "Move", &event.inputs).unwrap();
+
+        let minus_sixteen =
+            "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff0".to_string();
+        assert_eq!(
+            filter.topics,
+            vec![Some(vec![
+                minus_sixteen.clone(),
+                minus_sixteen.clone(),
+                minus_sixteen,
+                "0x0000000000000000000000000000000000000000000000000000000000000010".to_string(),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_event_filter_fails_for_non_indexed_param() {
+        let event = HumanReadableParser::parse_event(
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+        )
+        .unwrap();
+        let filter_config: BTreeMap<String, SingleOrList<EventFilterValue>> =
+            serde_json::from_value(json!({ "value": 100 })).unwrap();
+
+        let err = EventFilter::from_config(&filter_config, "Transfer", &event.inputs).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "EE115: Invalid filter for the event 'Transfer'. 'value' is not an indexed param of \
+             the event. Available indexed params: [from, to]"
+        );
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/function-calls-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let config =
+            SystemConfig::parse_from_project_files(&project_paths).expect("Failed parsing config");
+
+        let contract = config
+            .get_contract(&"ERC20".to_string())
+            .expect("Contract should exist");
+        let functions = contract
+            .functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.selector.as_str(),
+                    f.params()
+                        .iter()
+                        .map(|p| (p.name.as_str(), p.kind.to_string()))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            functions,
+            vec![
+                (
+                    "transfer",
+                    "0xa9059cbb",
+                    vec![
+                        ("to", "address".to_string()),
+                        ("amount", "uint256".to_string())
+                    ]
+                ),
+                (
+                    "MintTo",
+                    "0x40c10f19",
+                    vec![
+                        ("_0", "address".to_string()),
+                        ("amount", "uint256".to_string())
+                    ]
+                ),
+            ]
+        );
+
+        match &contract.abi {
+            super::Abi::Evm(abi) => {
+                // The transfer function is already in the ABI file
+                assert_eq!(abi.typed.functions["transfer"].len(), 1);
+                assert!(abi.typed.function("mint").is_ok());
+                assert!(abi.typed.function("approve").is_err());
+            }
+            super::Abi::Fuel(_) => panic!("Expected an evm abi"),
+        }
+    }
+
+    #[test]
+    fn test_parse_functions_fails_on_rpc_network() {
+        let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
+        let project_paths = ParsedProjectPaths::new(
+            test_dir.as_str(),
+            "generated/",
+            "configs/function-calls-rpc-config.yaml",
+        )
+        .expect("Failed creating parsed_paths");
+
+        let err = SystemConfig::parse_from_project_files(&project_paths).unwrap_err();
+        assert!(format!("{:#}", err).contains("EE120:"));
+        assert!(
+            format!("{:#}", err).contains("evm.schema.json"),
+            "The error should point to the documented limitation"
+        );
+    }
+
+    #[test]
+    fn test_parse_function_fails_without_abi() {
+        let err = Function::get_abi_function("transfer", &None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No abi file provided for function transfer"
+        );
+
+        let err = Function::get_abi_function("transfer(address to,", &None).unwrap_err();
+        assert!(err.to_string().starts_with("EE113:"));
+    }
+
     #[test]
     fn test_get_nested_contract_abi() {
         let test_dir = format!("{}/test", env!("CARGO_MANIFEST_DIR"));
@@ -1934,9 +3528,9 @@ mod test {
     }
 
     #[test]
-    fn deserializes_contract_config_with_multiple_sync_sources() {
-        let config_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("test/configs/invalid-multiple-sync-config.yaml");
+    fn deserializes_contract_config_with_hybrid_sync_sources() {
+        let config_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/configs/hybrid-sync-config.yaml");
 
         let file_str = std::fs::read_to_string(config_path).unwrap();
 
@@ -1946,10 +3540,115 @@ mod test {
         assert!(cfg.networks[0].rpc_config.is_some());
         assert!(cfg.networks[0].hypersync_config.is_some());
 
-        let error = SyncSource::from_evm_network_config(cfg.networks[0].clone(), cfg.event_decoder)
-            .unwrap_err();
+        let sync_source = SyncSource::from_evm_network_config(
+            cfg.networks[0].clone(),
+            cfg.event_decoder.clone(),
+            &ChainRegistry::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sync_source,
+            SyncSource::HybridConfig(super::HybridConfig {
+                hypersync_config: super::HypersyncConfig {
+                    endpoint_url: "https://myskar.com".to_string(),
+                    is_client_decoder: true,
+                },
+                rpc_config: super::RpcConfig {
+                    urls: vec!["https://eth.com".to_string()],
+                    ws_urls: vec![],
+                    sync_config: SyncConfig::default(),
+                },
+                rpc_role: super::SyncSourceRole::Fallback,
+                realtime_threshold_blocks: 100,
+                fallback_threshold_millis: 20_000,
+            })
+        );
+
+        let sync_source = SyncSource::from_evm_network_config(
+            cfg.networks[1].clone(),
+            cfg.event_decoder,
+            &ChainRegistry::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sync_source,
+            SyncSource::HybridConfig(super::HybridConfig {
+                hypersync_config: super::HypersyncConfig {
+                    endpoint_url: "https://59144.hypersync.xyz".to_string(),
+                    is_client_decoder: true,
+                },
+                rpc_config: super::RpcConfig {
+                    urls: vec![],
+                    ws_urls: vec!["wss://linea.com".to_string()],
+                    sync_config: SyncConfig::default(),
+                },
+                rpc_role: super::SyncSourceRole::Realtime,
+                realtime_threshold_blocks: 20,
+                fallback_threshold_millis: 20_000,
+            })
+        );
+    }
+
+    #[test]
+    fn fails_with_threshold_not_matching_rpc_role() {
+        use crate::config_parsing::human_config::evm::{
+            Network as EvmNetwork, RpcConfig as EvmRpcConfig, RpcRole,
+        };
 
-        assert_eq!(error.to_string(), "EE106: Cannot define both rpc_config and hypersync_config for the same network, please choose only one of them, read more in our docs https://docs.envio.dev/docs/configuration-file");
+        let network = |role, realtime_threshold_blocks, fallback_threshold_millis| EvmNetwork {
+            id: 1,
+            hypersync_config: None,
+            rpc_config: Some(EvmRpcConfig {
+                url: SingleOrList::Single("https://eth.com".to_string()),
+                role,
+                realtime_threshold_blocks,
+                fallback_threshold_millis,
+                sync_config: None,
+            }),
+            start_block: 0,
+            end_block: None,
+            confirmed_block_threshold: None,
+            contracts: vec![],
+            block_handlers: None,
+        };
+
+        let error = SyncSource::from_evm_network_config(
+            network(None, Some(10), None),
+            None,
+            &ChainRegistry::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "EE118: The realtime_threshold_blocks and fallback_threshold_millis options of the \
+             rpc_config are only allowed together with hypersync_config or the role option"
+        );
+
+        let error = SyncSource::from_evm_network_config(
+            network(Some(RpcRole::Fallback), Some(10), None),
+            None,
+            &ChainRegistry::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "EE118: The realtime_threshold_blocks option of the rpc_config is only allowed for \
+             the historical and realtime roles"
+        );
+
+        let error = SyncSource::from_evm_network_config(
+            network(Some(RpcRole::Historical), None, Some(1_000)),
+            None,
+            &ChainRegistry::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "EE118: The fallback_threshold_millis option of the rpc_config is only allowed for \
+             the fallback role"
+        );
     }
 
     #[test]
@@ -1966,9 +3665,11 @@ mod test {
             end_block: None,
             confirmed_block_threshold: None,
             contracts: vec![],
+            block_handlers: None,
         };
 
-        let sync_source = SyncSource::from_evm_network_config(network, None).unwrap();
+        let sync_source =
+            SyncSource::from_evm_network_config(network, None, &ChainRegistry::default()).unwrap();
 
         match sync_source {
             SyncSource::HypersyncConfig(config) => {
@@ -1977,4 +3678,64 @@ mod test {
             _ => panic!("Expected HypersyncConfig"),
         }
     }
+
+    #[test]
+    fn test_rpc_config_splits_websocket_urls() {
+        use crate::config_parsing::human_config::evm::{
+            Network as EvmNetwork, RpcConfig as EvmRpcConfig,
+        };
+
+        let network = EvmNetwork {
+            id: 1,
+            hypersync_config: None,
+            rpc_config: Some(EvmRpcConfig {
+                url: SingleOrList::List(vec![
+                    "https://eth.com/".to_string(),
+                    "wss://eth.com/ws/".to_string(),
+                ]),
+                role: None,
+                realtime_threshold_blocks: None,
+                fallback_threshold_millis: None,
+                sync_config: None,
+            }),
+            start_block: 0,
+            end_block: None,
+            confirmed_block_threshold: None,
+            contracts: vec![],
+            block_handlers: None,
+        };
+
+        let sync_source =
+            SyncSource::from_evm_network_config(network.clone(), None, &ChainRegistry::default())
+                .unwrap();
+
+        match sync_source {
+            SyncSource::RpcConfig(config) => {
+                assert_eq!(config.urls, vec!["https://eth.com".to_string()]);
+                assert_eq!(config.ws_urls, vec!["wss://eth.com/ws".to_string()]);
+            }
+            _ => panic!("Expected RpcConfig"),
+        }
+
+        let invalid_network = EvmNetwork {
+            rpc_config: Some(EvmRpcConfig {
+                url: SingleOrList::Single("ftp://eth.com".to_string()),
+                role: None,
+                realtime_threshold_blocks: None,
+                fallback_threshold_millis: None,
+                sync_config: None,
+            }),
+            ..network
+        };
+
+        let error =
+            SyncSource::from_evm_network_config(invalid_network, None, &ChainRegistry::default())
+                .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "EE109: The RPC url \"ftp://eth.com\" is incorrect format. The RPC url needs to \
+             start with either http://, https://, ws:// or wss://"
+        );
+    }
 }
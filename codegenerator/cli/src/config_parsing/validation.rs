@@ -170,6 +170,138 @@ impl human_config::evm::Network {
         }
         Ok(())
     }
+
+    pub fn validate_block_handlers(&self) -> anyhow::Result<()> {
+        let block_handlers = match &self.block_handlers {
+            Some(block_handlers) => block_handlers,
+            None => return Ok(()),
+        };
+
+        let mut names = HashSet::new();
+        for block_handler in block_handlers {
+            if !names.insert(block_handler.name.clone()) {
+                return Err(anyhow!(
+                    "EE112: The block handler \"{}\" is defined more than once for network id: \
+                     {}. Block handler names must be unique per network.",
+                    block_handler.name,
+                    self.id
+                ));
+            }
+
+            if block_handler.interval == Some(0) {
+                return Err(anyhow!(
+                    "EE112: The block handler \"{}\" on network id: {} has an interval of 0. The \
+                     interval must be greater than 0.",
+                    block_handler.name,
+                    self.id
+                ));
+            }
+
+            let start_block = block_handler.start_block.unwrap_or(self.start_block);
+            if start_block < self.start_block {
+                return Err(anyhow!(
+                    "EE112: The block handler \"{}\" on network id: {} has a start_block of {} \
+                     which is less than the network start_block of {}.",
+                    block_handler.name,
+                    self.id,
+                    start_block,
+                    self.start_block
+                ));
+            }
+
+            if let Some(end_block) = block_handler.end_block {
+                if end_block < start_block {
+                    return Err(anyhow!(
+                        "EE112: The block handler \"{}\" on network id: {} has an end_block that \
+                         is less than its start_block.",
+                        block_handler.name,
+                        self.id
+                    ));
+                }
+                if let Some(network_end_block) = self.end_block {
+                    if end_block > network_end_block {
+                        return Err(anyhow!(
+                            "EE112: The block handler \"{}\" on network id: {} has an end_block \
+                             of {} which is greater than the network end_block of {}.",
+                            block_handler.name,
+                            self.id,
+                            end_block,
+                            network_end_block
+                        ));
+                    }
+                }
+            }
+        }
+
+        validate_names_valid_rescript(
+            &block_handlers.iter().map(|b| b.name.clone()).collect(),
+            "block handler".to_string(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<T> human_config::NetworkContract<T> {
+    pub fn validate_block_range(
+        &self,
+        network_id: u64,
+        network_start_block: u64,
+        network_end_block: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let start_block = self.start_block.unwrap_or(network_start_block);
+        if start_block < network_start_block {
+            return Err(anyhow!(
+                "EE114: The contract \"{}\" on network id: {} has a start_block of {} which is \
+                 less than the network start_block of {}.",
+                self.name,
+                network_id,
+                start_block,
+                network_start_block
+            ));
+        }
+
+        if let Some(end_block) = self.end_block {
+            if end_block < start_block {
+                return Err(anyhow!(
+                    "EE114: The contract \"{}\" on network id: {} has an end_block that is less \
+                     than its start_block.",
+                    self.name,
+                    network_id
+                ));
+            }
+            if let Some(network_end_block) = network_end_block {
+                if end_block > network_end_block {
+                    return Err(anyhow!(
+                        "EE114: The contract \"{}\" on network id: {} has an end_block of {} \
+                         which is greater than the network end_block of {}.",
+                        self.name,
+                        network_id,
+                        end_block,
+                        network_end_block
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl human_config::evm::EventConfig {
+    pub fn validate_block_range(&self, contract_name: &str) -> anyhow::Result<()> {
+        if let (Some(start_block), Some(end_block)) = (self.start_block, self.end_block) {
+            if end_block < start_block {
+                return Err(anyhow!(
+                    "EE114: The event \"{}\" of contract \"{}\" has an end_block that is less \
+                     than its start_block.",
+                    self.name.as_ref().unwrap_or(&self.event),
+                    contract_name
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn validate_deserialized_config_yaml(evm_config: &HumanConfig) -> anyhow::Result<()> {
@@ -178,6 +310,9 @@ pub fn validate_deserialized_config_yaml(evm_config: &HumanConfig) -> anyhow::Re
     if let Some(global_contracts) = &evm_config.contracts {
         for global_contract in global_contracts {
             contract_names.push(global_contract.name.clone());
+            for event in &global_contract.config.events {
+                event.validate_block_range(&global_contract.name)?;
+            }
         }
     }
 
@@ -185,12 +320,18 @@ pub fn validate_deserialized_config_yaml(evm_config: &HumanConfig) -> anyhow::Re
         // validate endblock is a greater than the startblock
         network.validate_endblock_lte_startblock()?;
         network.validate_finite_endblock_networks(evm_config)?;
+        network.validate_block_handlers()?;
 
         for contract in &network.contracts {
-            if contract.config.as_ref().is_some() {
+            if let Some(config) = &contract.config {
                 contract_names.push(contract.name.clone());
+                for event in &config.events {
+                    event.validate_block_range(&contract.name)?;
+                }
             }
 
+            contract.validate_block_range(network.id, network.start_block, network.end_block)?;
+
             // Checking if contract addresses are valid addresses
             for contract_address in contract.address.clone().into_iter() {
                 if !is_valid_ethereum_address(&contract_address) {
@@ -250,7 +391,12 @@ pub fn check_schema_enums_are_valid_postgres(enum_names: &Vec<String>) -> Vec<St
 
 #[cfg(test)]
 mod tests {
+    use crate::config_parsing::human_config::{
+        evm::{ContractConfig, EventConfig, Network},
+        NetworkContract,
+    };
     use pretty_assertions::assert_eq;
+    use serde_json::json;
 
     #[test]
     fn valid_postgres_db_name() {
@@ -442,4 +588,117 @@ mod tests {
              alphanumeric characters and underscores."
         );
     }
+
+    #[test]
+    fn test_validate_block_handlers() {
+        let network: Network = serde_json::from_value(json!({
+            "id": 1,
+            "start_block": 100,
+            "end_block": 1000,
+            "contracts": [],
+            "block_handlers": [
+                {"name": "Snapshot", "handler": "./src/BlockHandler.ts", "interval": 10},
+                {"name": "Sample", "handler": "./src/BlockHandler.ts", "start_block": 200}
+            ]
+        }))
+        .unwrap();
+        network.validate_block_handlers().unwrap();
+    }
+
+    #[test]
+    fn test_validate_block_handlers_fails() {
+        let validate = |block_handlers: serde_json::Value| {
+            let network: Network = serde_json::from_value(json!({
+                "id": 1,
+                "start_block": 100,
+                "end_block": 1000,
+                "contracts": [],
+                "block_handlers": block_handlers
+            }))
+            .unwrap();
+            network.validate_block_handlers().unwrap_err().to_string()
+        };
+
+        assert_eq!(
+            validate(json!([{"name": "Snapshot", "handler": "./h.ts", "interval": 0}])),
+            "EE112: The block handler \"Snapshot\" on network id: 1 has an interval of 0. The \
+             interval must be greater than 0."
+        );
+        assert_eq!(
+            validate(json!([{"name": "Snapshot", "handler": "./h.ts", "start_block": 50}])),
+            "EE112: The block handler \"Snapshot\" on network id: 1 has a start_block of 50 which \
+             is less than the network start_block of 100."
+        );
+        assert_eq!(
+            validate(json!([{"name": "Snapshot", "handler": "./h.ts", "end_block": 2000}])),
+            "EE112: The block handler \"Snapshot\" on network id: 1 has an end_block of 2000 \
+             which is greater than the network end_block of 1000."
+        );
+        assert_eq!(
+            validate(json!([
+                {"name": "Snapshot", "handler": "./h.ts"},
+                {"name": "Snapshot", "handler": "./h.ts", "interval": 5}
+            ])),
+            "EE112: The block handler \"Snapshot\" is defined more than once for network id: 1. \
+             Block handler names must be unique per network."
+        );
+    }
+
+    #[test]
+    fn test_validate_contract_block_range_fails() {
+        let validate = |contract: serde_json::Value| {
+            let contract: NetworkContract<ContractConfig> =
+                serde_json::from_value(contract).unwrap();
+            contract
+                .validate_block_range(1, 100, Some(1000))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            validate(json!({"name": "Contract1", "address": [], "start_block": 50})),
+            "EE114: The contract \"Contract1\" on network id: 1 has a start_block of 50 which is \
+             less than the network start_block of 100."
+        );
+        assert_eq!(
+            validate(json!({"name": "Contract1", "address": [], "end_block": 2000})),
+            "EE114: The contract \"Contract1\" on network id: 1 has an end_block of 2000 which is \
+             greater than the network end_block of 1000."
+        );
+        assert_eq!(
+            validate(json!({
+                "name": "Contract1",
+                "address": [],
+                "start_block": 500,
+                "end_block": 200
+            })),
+            "EE114: The contract \"Contract1\" on network id: 1 has an end_block that is less \
+             than its start_block."
+        );
+    }
+
+    #[test]
+    fn test_validate_event_block_range() {
+        let event: EventConfig = serde_json::from_value(json!({
+            "event": "Transfer",
+            "start_block": 500,
+            "end_block": 200
+        }))
+        .unwrap();
+        assert_eq!(
+            event
+                .validate_block_range("Contract1")
+                .unwrap_err()
+                .to_string(),
+            "EE114: The event \"Transfer\" of contract \"Contract1\" has an end_block that is \
+             less than its start_block."
+        );
+
+        let event: EventConfig = serde_json::from_value(json!({
+            "event": "Transfer",
+            "start_block": 500
+        }))
+        .unwrap();
+        assert!(event.validate_block_range("Contract1").is_ok());
+    }
 }
@@ -1,11 +1,16 @@
 use crate::{
     commands,
     config_parsing::system_config::SystemConfig,
-    persisted_state::{self, PersistedState, PersistedStateExists, CURRENT_CRATE_VERSION},
+    persisted_state::{
+        self, ChangeImpact, PersistedState, PersistedStateExists, StateField, CURRENT_CRATE_VERSION,
+    },
     project_paths::ParsedProjectPaths,
     service_health::{self, EndpointHealth},
 };
 use anyhow::{anyhow, Context, Result};
+use inquire::{validator::Validation, CustomType, InquireError, Select};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
     let config =
@@ -14,7 +19,36 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
     let current_state = PersistedState::get_current_state(&config)
         .context("Failed getting current indexer state")?;
 
-    let persisted_state_file = PersistedStateExists::get_persisted_state_file(&project_paths);
+    let prepared_indexer = prepare_indexer(&project_paths, &config, &current_state).await?;
+
+    println!("Starting indexer");
+
+    commands::start::start_indexer(
+        &project_paths,
+        prepared_indexer.should_open_hasura_console,
+        &prepared_indexer.indexer_envs,
+    )
+    .await
+    .context("Failed running start on the indexer")?;
+
+    Ok(())
+}
+
+///How to start the indexer once the local environment is prepared
+pub struct PreparedIndexer {
+    pub should_open_hasura_console: bool,
+    ///Env vars for the indexer process only, so they don't apply to any later restart
+    pub indexer_envs: Vec<(&'static str, String)>,
+}
+
+///Runs codegen, starts the local environment and migrates the db for the changes in
+///the current state
+pub async fn prepare_indexer(
+    project_paths: &ParsedProjectPaths,
+    config: &SystemConfig,
+    current_state: &PersistedState,
+) -> Result<PreparedIndexer> {
+    let persisted_state_file = PersistedStateExists::get_persisted_state_file(project_paths);
 
     let (should_run_codegen, changes_detected) = match &persisted_state_file {
         PersistedStateExists::Exists(persisted_state) => {
@@ -59,7 +93,7 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
 
         println!("Running codegen");
 
-        commands::codegen::run_codegen(&config, &project_paths)
+        commands::codegen::run_codegen(config, project_paths)
             .await
             .context("Failed running codegen")?;
     }
@@ -68,7 +102,7 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
 
     let should_open_hasura_console = if hasura_health_check_is_error {
         //Run docker commands to spin up container
-        commands::docker::docker_compose_up_d(&project_paths)
+        commands::docker::docker_compose_up_d(project_paths)
             .await
             .context("Failed running docker compose up after server liveness check")?;
         true
@@ -77,6 +111,7 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
     };
 
     let hasura_health = service_health::fetch_hasura_healthz_with_retry().await;
+    let mut indexer_envs = vec![];
 
     match hasura_health {
         EndpointHealth::Unhealthy(err_message) => {
@@ -84,7 +119,7 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
         }
         EndpointHealth::Healthy => {
             //Get the persisted state from the db
-            let persisted_state_db = PersistedStateExists::read_from_db()
+            let persisted_state_db = PersistedStateExists::read_from_db(project_paths)
                 .await
                 .context("Failed to read persisted state from the DB")?;
 
@@ -109,22 +144,242 @@ pub async fn run_dev(project_paths: ParsedProjectPaths) -> Result<()> {
                         println!("Db Migrations have not been run")
                     }
                     PersistedStateExists::Corrupted => println!("Invalid DB persisted state"),
-                    PersistedStateExists::Exists(_) => print_changes_detected(changes_detected),
+                    PersistedStateExists::Exists(_) => {
+                        print_changes_detected(changes_detected.clone())
+                    }
                 }
-                println!("Running db migrations");
+                match persisted_state_db {
+                    PersistedStateExists::Exists(persisted_state_db) => {
+                        indexer_envs = migrate_changes(
+                            project_paths,
+                            config,
+                            current_state,
+                            &persisted_state_db,
+                            &changes_detected,
+                        )
+                        .await?
+                    }
+                    PersistedStateExists::NotExists | PersistedStateExists::Corrupted => {
+                        println!("Running db migrations");
 
-                commands::db_migrate::run_db_setup(&project_paths, &current_state)
-                    .await
-                    .context("Failed running db setup command")?;
+                        commands::db_migrate::run_db_setup(project_paths, current_state)
+                            .await
+                            .context("Failed running db setup command")?;
+                    }
+                }
             }
+        }
+    }
 
-            println!("Starting indexer");
+    Ok(PreparedIndexer {
+        should_open_hasura_console,
+        indexer_envs,
+    })
+}
 
-            commands::start::start_indexer(&project_paths, should_open_hasura_console)
+#[derive(strum_macros::Display, EnumIter, Clone, Copy, PartialEq)]
+enum IndexedDataOption {
+    #[strum(serialize = "Keep the indexed data")]
+    Keep,
+    #[strum(serialize = "Reset the database and index from the start blocks")]
+    Reset,
+    #[strum(serialize = "Reprocess the events of a chain from a block")]
+    Reprocess,
+}
+
+///What to do with the indexed data, when the handlers could produce different results
+enum IndexedDataPolicy {
+    Keep,
+    Reset,
+    ReprocessFrom { chain_id: u64, block_number: u64 },
+}
+
+fn prompt_indexed_data_policy(config: &SystemConfig) -> Result<IndexedDataPolicy> {
+    let options = IndexedDataOption::iter()
+        //Rolling back to a block needs the entity history, which is pruned
+        //unless the full history is saved
+        .filter(|o| *o != IndexedDataOption::Reprocess || config.save_full_history)
+        .collect();
+
+    let selected = match Select::new(
+        "Handler or ABI changes could affect the indexed data. What would you like to do?",
+        options,
+    )
+    .prompt()
+    {
+        Ok(selected) => selected,
+        //Without a terminal nobody chose to lose the indexed data
+        Err(InquireError::NotTTY) => {
+            println!(
+                "Unable to prompt for the indexed data, keeping it. Run envio dev in a terminal \
+                 or envio local db-migrate setup to reset the database"
+            );
+            return Ok(IndexedDataPolicy::Keep);
+        }
+        Err(err) => return Err(err).context("Failed prompting for the indexed data policy"),
+    };
+
+    match selected {
+        IndexedDataOption::Keep => Ok(IndexedDataPolicy::Keep),
+        IndexedDataOption::Reset => Ok(IndexedDataPolicy::Reset),
+        IndexedDataOption::Reprocess => {
+            let chain_ids: Vec<u64> = config.get_networks().iter().map(|n| n.id).collect();
+            let chain_id = match chain_ids.as_slice() {
+                [chain_id] => *chain_id,
+                _ => Select::new("Which chain would you like to reprocess?", chain_ids)
+                    .prompt()
+                    .context("Failed prompting for the chain to reprocess")?,
+            };
+
+            let block_number = CustomType::<u64>::new("From which block?")
+                .with_validator(|block_number: &u64| {
+                    Ok(if *block_number > 0 {
+                        Validation::Valid
+                    } else {
+                        Validation::Invalid("The block should be greater than 0".into())
+                    })
+                })
+                .with_error_message("Invalid block input, please enter a number")
+                .prompt()
+                .context("Failed prompting for the block to reprocess from")?;
+
+            Ok(IndexedDataPolicy::ReprocessFrom {
+                chain_id,
+                block_number,
+            })
+        }
+    }
+}
+
+///Explains why the whole db is reset instead of only the networks with config changes,
+///and how to allow resetting single networks
+pub fn print_full_reset_reason(changed_network_ids: &[u64], reason: &str) {
+    println!(
+        "The config of the chains {} changed, but their indexed data can't be reset on its own, \
+         since {reason}. Resetting the whole database instead. Resetting single chains requires \
+         save_full_history: true in the config and no immutable entities",
+        changed_network_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+///Brings the db up to date with the detected changes, resetting it only when the changes
+///affect storage in a way that can't be migrated, or when chosen for the indexed data.
+///Networks with config changes get only their own data reset. Returns the env vars
+///the indexer needs to apply the chosen policy
+async fn migrate_changes(
+    project_paths: &ParsedProjectPaths,
+    config: &SystemConfig,
+    current_state: &PersistedState,
+    persisted_state_db: &PersistedState,
+    changes_detected: &[StateField],
+) -> Result<Vec<(&'static str, String)>> {
+    let migration_plan = current_state.get_schema_migration_plan(
+        persisted_state_db,
+        &persisted_state::get_public_schema(project_paths),
+    );
+
+    let requires_reset = match &migration_plan {
+        Some(migration_plan) if migration_plan.requires_reset() => {
+            println!("Destructive schema changes detected:");
+            for change in &migration_plan.destructive_changes {
+                println!("  {change}");
+            }
+            true
+        }
+        Some(_) => false,
+        None => !current_state.can_migrate(persisted_state_db),
+    };
+
+    let indexed_data_policy = if requires_reset {
+        IndexedDataPolicy::Reset
+    } else if changes_detected
+        .iter()
+        .any(|f| f.get_change_impact() == ChangeImpact::Results)
+    {
+        prompt_indexed_data_policy(config)?
+    } else {
+        IndexedDataPolicy::Keep
+    };
+
+    let changed_network_ids = current_state.get_changed_network_ids(persisted_state_db);
+    let chain_reset_blocker = persisted_state_db
+        .schema_snapshot
+        .get_chain_reset_blocker(config.save_full_history);
+    let indexed_data_policy = match chain_reset_blocker {
+        Some(reason)
+            if !changed_network_ids.is_empty()
+                && !matches!(indexed_data_policy, IndexedDataPolicy::Reset) =>
+        {
+            print_full_reset_reason(&changed_network_ids, &reason);
+            IndexedDataPolicy::Reset
+        }
+        _ => indexed_data_policy,
+    };
+
+    if let IndexedDataPolicy::Reset = indexed_data_policy {
+        println!("Running db migrations");
+
+        commands::db_migrate::run_db_setup(project_paths, current_state)
+            .await
+            .context("Failed running db setup command")?;
+        return Ok(vec![]);
+    }
+
+    if !changed_network_ids.is_empty() {
+        println!(
+            "Resetting the indexed data of the chains with config changes: {}",
+            changed_network_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        persisted_state_db
+            .schema_snapshot
+            .reset_chains(&changed_network_ids, project_paths)
+            .await
+            .context("Failed resetting the data of the changed chains")?;
+    }
+
+    match migration_plan {
+        Some(migration_plan) => {
+            println!("Running incremental db migrations");
+
+            commands::db_migrate::run_incremental_migrations(
+                project_paths,
+                current_state,
+                &migration_plan,
+            )
+            .await
+            .context("Failed running incremental db migrations")?;
+        }
+        None => {
+            println!("Keeping the indexed data");
+
+            current_state
+                .upsert_to_db(project_paths)
                 .await
-                .context("Failed running start on the indexer")?;
+                .context("Failed to upsert persisted state table")?;
         }
     }
 
-    Ok(())
+    match indexed_data_policy {
+        IndexedDataPolicy::ReprocessFrom {
+            chain_id,
+            block_number,
+        } => {
+            println!("Reprocessing the events of chain {chain_id} from block {block_number}");
+            //Picked up by the indexer on start, rolling back the chain before processing
+            Ok(vec![
+                ("ENVIO_REPROCESS_CHAIN_ID", chain_id.to_string()),
+                ("ENVIO_REPROCESS_FROM_BLOCK", block_number.to_string()),
+            ])
+        }
+        IndexedDataPolicy::Keep | IndexedDataPolicy::Reset => Ok(vec![]),
+    }
 }
@@ -2,10 +2,12 @@ use crate::{
     cli_args::clap_definitions::{DbMigrateSubcommands, LocalCommandTypes, LocalDockerSubcommands},
     commands,
     config_parsing::system_config::SystemConfig,
-    persisted_state::PersistedState,
+    persisted_state::{
+        get_public_schema, ChangeImpact, PersistedState, PersistedStateExists, StateField,
+    },
     project_paths::ParsedProjectPaths,
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 
 pub async fn run_local(
     local_commands: &LocalCommandTypes,
@@ -35,8 +37,42 @@ pub async fn run_local(
             match subcommand {
                 DbMigrateSubcommands::Up => {
                     let persisted_state = get_persisted_state()?;
-                    commands::db_migrate::run_up_migrations(project_paths, &persisted_state)
-                        .await?;
+                    let migration_plan = match PersistedStateExists::read_from_db(project_paths)
+                        .await
+                        .context("Failed to read persisted state from the DB")?
+                    {
+                        PersistedStateExists::Exists(persisted_state_db) => persisted_state
+                            .get_schema_migration_plan(
+                                &persisted_state_db,
+                                &get_public_schema(project_paths),
+                            ),
+                        PersistedStateExists::NotExists | PersistedStateExists::Corrupted => None,
+                    };
+
+                    match migration_plan {
+                        Some(migration_plan) if migration_plan.requires_reset() => {
+                            return Err(anyhow!(
+                                "The schema changes can't be migrated without resetting the DB. \
+                                 Please run `envio local db-migrate setup`:\n{}",
+                                migration_plan.destructive_changes.join("\n")
+                            ));
+                        }
+                        Some(migration_plan) => {
+                            commands::db_migrate::run_incremental_migrations(
+                                project_paths,
+                                &persisted_state,
+                                &migration_plan,
+                            )
+                            .await?;
+                        }
+                        None => {
+                            commands::db_migrate::run_up_migrations(
+                                project_paths,
+                                &persisted_state,
+                            )
+                            .await?;
+                        }
+                    }
                 }
 
                 DbMigrateSubcommands::Down => {
@@ -47,6 +83,65 @@ pub async fn run_local(
                     let persisted_state = get_persisted_state()?;
                     commands::db_migrate::run_db_setup(project_paths, &persisted_state).await?;
                 }
+
+                DbMigrateSubcommands::Plan => {
+                    let persisted_state = get_persisted_state()?;
+                    let PersistedStateExists::Exists(persisted_state_db) =
+                        PersistedStateExists::read_from_db(project_paths)
+                            .await
+                            .context("Failed to read persisted state from the DB")?
+                    else {
+                        println!(
+                            "-- No persisted state found in the DB. Run `envio local db-migrate \
+                             setup` to create the schema from scratch"
+                        );
+                        return Ok(());
+                    };
+
+                    let (should_run_db_migrations, changes_detected) =
+                        persisted_state.should_run_db_migrations(&persisted_state_db);
+                    if !should_run_db_migrations {
+                        println!("-- The DB is up to date");
+                        return Ok(());
+                    }
+
+                    let changes = changes_detected
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    match persisted_state.get_schema_migration_plan(
+                        &persisted_state_db,
+                        &get_public_schema(project_paths),
+                    ) {
+                        None if StateField::get_max_change_impact(&changes_detected)
+                            != Some(ChangeImpact::Storage) =>
+                        {
+                            println!("-- Changes to {changes} don't alter the DB schema")
+                        }
+                        None => println!(
+                            "-- Changes to {changes} require resetting the DB with `envio local \
+                             db-migrate setup`"
+                        ),
+                        Some(migration_plan) if migration_plan.requires_reset() => {
+                            println!(
+                                "-- The following schema changes require resetting the DB with \
+                                 `envio local db-migrate setup`:"
+                            );
+                            for change in &migration_plan.destructive_changes {
+                                println!("-- {change}");
+                            }
+                        }
+                        Some(migration_plan)
+                            if migration_plan.is_empty()
+                                && migration_plan.created_objects.is_empty() =>
+                        {
+                            println!("-- The schema changes don't alter the DB")
+                        }
+                        Some(migration_plan) => println!("{migration_plan}"),
+                    }
+                }
             }
         }
     }
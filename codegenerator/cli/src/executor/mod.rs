@@ -3,7 +3,9 @@ use crate::{
     cli_args::clap_definitions::{CommandLineArgs, CommandType},
     commands,
     config_parsing::{human_config, system_config::SystemConfig},
-    persisted_state::{PersistedState, PersistedStateExists, CURRENT_CRATE_VERSION},
+    persisted_state::{
+        self, ChangeImpact, PersistedState, PersistedStateExists, StateField, CURRENT_CRATE_VERSION,
+    },
     project_paths::ParsedProjectPaths,
     scripts,
 };
@@ -12,11 +14,17 @@ mod codegen;
 mod dev;
 pub mod init;
 mod local;
+mod watch;
 
 use anyhow::{Context, Result};
 use schemars::schema_for;
 
 pub async fn execute(command_line_args: CommandLineArgs) -> Result<()> {
+    //Passed on through the env, so the indexer processes started by the CLI use it as well
+    if let Some(database_url) = &command_line_args.database_url {
+        std::env::set_var(persisted_state::PG_URL_ENV_VAR, database_url);
+    }
+
     let global_project_paths = command_line_args.project_paths;
     let parsed_project_paths = ParsedProjectPaths::try_from(global_project_paths.clone())
         .context("Failed parsing project paths")?;
@@ -30,8 +38,12 @@ pub async fn execute(command_line_args: CommandLineArgs) -> Result<()> {
             codegen::run_codegen(&parsed_project_paths).await?;
         }
 
-        CommandType::Dev => {
-            dev::run_dev(parsed_project_paths).await?;
+        CommandType::Dev(dev_args) => {
+            if dev_args.watch {
+                watch::run_dev_watch(parsed_project_paths).await?;
+            } else {
+                dev::run_dev(parsed_project_paths).await?;
+            }
         }
 
         CommandType::Stop => {
@@ -73,9 +85,11 @@ pub async fn execute(command_line_args: CommandLineArgs) -> Result<()> {
                     .context("Failed constructing persisted state")?;
 
                 commands::db_migrate::run_db_setup(&parsed_project_paths, &persisted_state).await?;
+            } else {
+                reset_changed_networks(&parsed_project_paths).await?;
             }
             const SHOULD_OPEN_HASURA: bool = false;
-            commands::start::start_indexer(&parsed_project_paths, SHOULD_OPEN_HASURA).await?;
+            commands::start::start_indexer(&parsed_project_paths, SHOULD_OPEN_HASURA, &[]).await?;
         }
 
         CommandType::Local(local_commands) => {
@@ -116,3 +130,72 @@ pub async fn execute(command_line_args: CommandLineArgs) -> Result<()> {
 
     Ok(())
 }
+
+///Resets the data and sync state of the networks whose config changed since the db
+///was set up, when no other change affects the stored data. Falls back to resetting
+///the whole db, when the entity changes of the networks can't be rolled back
+async fn reset_changed_networks(project_paths: &ParsedProjectPaths) -> Result<()> {
+    let config =
+        SystemConfig::parse_from_project_files(project_paths).context("Failed parsing config")?;
+
+    let current_state = PersistedState::get_current_state(&config)
+        .context("Failed constructing persisted state")?;
+
+    let persisted_state_db = match PersistedStateExists::read_from_db(project_paths).await {
+        Ok(PersistedStateExists::Exists(persisted_state_db)) => *persisted_state_db,
+        Ok(PersistedStateExists::NotExists | PersistedStateExists::Corrupted) => return Ok(()),
+        Err(err) => {
+            println!(
+                "WARNING: Failed reading the persisted state from the DB, skipping the check for \
+                 network config changes: {err}"
+            );
+            return Ok(());
+        }
+    };
+
+    let (_, changes_detected) = current_state.should_run_db_migrations(&persisted_state_db);
+    let storage_changes: Vec<_> = changes_detected
+        .into_iter()
+        .filter(|f| f.get_change_impact() == ChangeImpact::Storage)
+        .collect();
+    if storage_changes != vec![StateField::NetworkConfigs] {
+        return Ok(());
+    }
+
+    let changed_network_ids = current_state.get_changed_network_ids(&persisted_state_db);
+    if let Some(reason) = persisted_state_db
+        .schema_snapshot
+        .get_chain_reset_blocker(config.save_full_history)
+    {
+        dev::print_full_reset_reason(&changed_network_ids, &reason);
+        return commands::db_migrate::run_db_setup(project_paths, &current_state)
+            .await
+            .context("Failed running db setup command");
+    }
+
+    println!(
+        "Resetting the indexed data of the chains with config changes: {}",
+        changed_network_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    persisted_state_db
+        .schema_snapshot
+        .reset_chains(&changed_network_ids, project_paths)
+        .await
+        .context("Failed resetting the data of the changed chains")?;
+
+    //Only the network configs are applied, other changes are left for the next migration
+    PersistedState {
+        network_config_hashes: current_state.network_config_hashes,
+        ..persisted_state_db
+    }
+    .upsert_to_db(project_paths)
+    .await
+    .context("Failed to upsert persisted state table")?;
+
+    Ok(())
+}
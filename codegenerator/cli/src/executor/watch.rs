@@ -0,0 +1,376 @@
+use super::dev;
+use crate::{
+    commands,
+    config_parsing::{chain_registry::ChainRegistry, system_config::SystemConfig},
+    persisted_state::{PersistedState, StateField},
+    project_paths::ParsedProjectPaths,
+};
+use anyhow::{Context, Result};
+use std::{
+    path::PathBuf,
+    process::ExitStatus,
+    time::{Duration, SystemTime},
+};
+
+///How often the watched files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+///How long the watched files need to stay unchanged before acting on a change,
+///so editors writing a file in several steps don't trigger multiple restarts
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(1000);
+
+///The modification times of the files affecting the indexer. Compared on every poll,
+///since it's much cheaper than hashing the files for the persisted state
+#[derive(PartialEq, Clone, Debug)]
+struct WatchedFiles(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl WatchedFiles {
+    fn read(project_paths: &ParsedProjectPaths, config: &SystemConfig) -> Self {
+        let mut paths = Self::get_project_file_paths(project_paths);
+        paths.extend(config.get_path_to_schema().ok());
+        paths.extend(config.get_all_paths_to_abi_files().unwrap_or_default());
+        paths.extend(config.get_all_paths_to_handlers().unwrap_or_default());
+
+        Self::from_paths(paths)
+    }
+
+    ///The files read along with the config: the overlay of the active profile
+    ///and the chains registries
+    fn get_project_file_paths(project_paths: &ParsedProjectPaths) -> Vec<PathBuf> {
+        let mut paths = vec![project_paths.config.clone()];
+        paths.extend(project_paths.get_profile_config_path());
+        paths.extend(project_paths.user_chains_path.clone());
+        paths.push(ChainRegistry::get_project_chains_path(project_paths));
+        paths
+    }
+
+    ///Missing files are watched too, so creating them counts as a change
+    fn from_paths(paths: Vec<PathBuf>) -> Self {
+        WatchedFiles(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let modified = std::fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok();
+                    (path, modified)
+                })
+                .collect(),
+        )
+    }
+}
+
+///Polls the files until they differ from the watched ones and then waits for them
+///to stay unchanged for the debounce duration. Returns the settled files
+async fn wait_for_settled_change(
+    read: impl Fn() -> WatchedFiles,
+    watched_files: &WatchedFiles,
+    poll_interval: Duration,
+    debounce_duration: Duration,
+) -> WatchedFiles {
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        if read() != *watched_files {
+            break;
+        }
+    }
+
+    let mut changed_files = read();
+    loop {
+        tokio::time::sleep(debounce_duration).await;
+        let settled_files = read();
+        if settled_files == changed_files {
+            return settled_files;
+        }
+        changed_files = settled_files;
+    }
+}
+
+struct Changes {
+    config: SystemConfig,
+    state: PersistedState,
+    fields: Vec<StateField>,
+}
+
+///Polls the watched files until they change the state of the indexer. Changes leaving
+///the project in an invalid state are reported and waited out
+async fn wait_for_changes(
+    project_paths: &ParsedProjectPaths,
+    config: &SystemConfig,
+    state: &PersistedState,
+    mut watched_files: WatchedFiles,
+) -> Changes {
+    loop {
+        watched_files = wait_for_settled_change(
+            || WatchedFiles::read(project_paths, config),
+            &watched_files,
+            POLL_INTERVAL,
+            DEBOUNCE_DURATION,
+        )
+        .await;
+
+        let changed = SystemConfig::parse_from_project_files(project_paths)
+            .context("Failed parsing config")
+            .and_then(|config| {
+                let state = PersistedState::get_current_state(&config)
+                    .context("Failed getting current indexer state")?;
+                Ok((config, state))
+            });
+
+        match changed {
+            Err(err) => println!("{err:?}\nWaiting for changes to restart the indexer"),
+            Ok((changed_config, changed_state)) => {
+                //Compares all the fields, since even handler changes need the indexer restarted
+                let (_, fields) = changed_state.should_run_db_migrations(state);
+                if !fields.is_empty() {
+                    return Changes {
+                        config: changed_config,
+                        state: changed_state,
+                        fields,
+                    };
+                }
+            }
+        }
+    }
+}
+
+fn print_indexer_exit(exit: std::io::Result<ExitStatus>) {
+    match exit {
+        Ok(status) if status.success() => println!(
+            "\nIndexer has successfully finished processing all events on all chains. Waiting for \
+             changes to restart the indexer"
+        ),
+        _ => println!(
+            "Indexer crashed. For more details see the error logs above the TUI. Waiting for \
+             changes to restart the indexer"
+        ),
+    }
+}
+
+///Runs the dev flow and restarts it, whenever the config (including its profile overlay
+///and the chains registries), schema, ABI or handler files change. Codegen and db
+///migrations are only rerun for the changes affecting them
+pub async fn run_dev_watch(project_paths: ParsedProjectPaths) -> Result<()> {
+    //Kept outside of the watch loop, so the indexer can be stopped on ctrl+c.
+    //It runs in its own process group and doesn't receive the signal itself
+    let mut indexer = None;
+
+    tokio::select! {
+        res = watch(&project_paths, &mut indexer) => res,
+        _ = tokio::signal::ctrl_c() => {
+            if let Some(indexer) = indexer.as_mut() {
+                println!("Stopping indexer");
+                indexer.stop().await.context("Failed stopping the indexer")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn watch(
+    project_paths: &ParsedProjectPaths,
+    indexer: &mut Option<commands::start::Indexer>,
+) -> Result<()> {
+    let mut config =
+        SystemConfig::parse_from_project_files(project_paths).context("Failed parsing config")?;
+
+    let mut current_state = PersistedState::get_current_state(&config)
+        .context("Failed getting current indexer state")?;
+
+    loop {
+        let watched_files = WatchedFiles::read(project_paths, &config);
+
+        *indexer = match dev::prepare_indexer(project_paths, &config, &current_state).await {
+            Ok(prepared_indexer) => {
+                if prepared_indexer.should_open_hasura_console {
+                    commands::start::open_hasura_console();
+                }
+                println!("Starting indexer");
+                Some(commands::start::spawn_indexer(
+                    project_paths,
+                    &prepared_indexer.indexer_envs,
+                )?)
+            }
+            Err(err) => {
+                println!("{err:?}\nWaiting for changes to restart the indexer");
+                None
+            }
+        };
+
+        let changes = match indexer.as_mut() {
+            Some(running_indexer) => tokio::select! {
+                changes = wait_for_changes(
+                    project_paths,
+                    &config,
+                    &current_state,
+                    watched_files.clone(),
+                ) => {
+                    println!("Stopping indexer");
+                    running_indexer
+                        .stop()
+                        .await
+                        .context("Failed stopping the indexer")?;
+                    changes
+                }
+                exit = running_indexer.wait() => {
+                    print_indexer_exit(exit);
+                    wait_for_changes(project_paths, &config, &current_state, watched_files).await
+                }
+            },
+            None => wait_for_changes(project_paths, &config, &current_state, watched_files).await,
+        };
+        *indexer = None;
+
+        println!(
+            "Changes to {} detected, restarting the indexer",
+            changes
+                .fields
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        config = changes.config;
+        current_state = changes.state;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{wait_for_settled_change, WatchedFiles};
+    use crate::project_paths::ParsedProjectPaths;
+    use std::{
+        cell::Cell,
+        fs::File,
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+    use tempdir::TempDir;
+
+    fn watched(files: &[(&str, Option<u64>)]) -> WatchedFiles {
+        WatchedFiles(
+            files
+                .iter()
+                .map(|(path, modified)| {
+                    (
+                        PathBuf::from(path),
+                        modified.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn watched_files_detect_modified_and_created_files() {
+        let dir = TempDir::new("watched_files_test").unwrap();
+        let config_path = dir.path().join("config.yaml");
+        let handler_path = dir.path().join("EventHandlers.ts");
+        let config = File::create(&config_path).unwrap();
+        config
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap();
+        let read = || WatchedFiles::from_paths(vec![config_path.clone(), handler_path.clone()]);
+
+        let initial = read();
+        assert_eq!(
+            initial,
+            WatchedFiles(vec![
+                (
+                    config_path.clone(),
+                    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+                ),
+                (handler_path.clone(), None),
+            ])
+        );
+        assert_eq!(read(), initial, "Unchanged files should be equal");
+
+        config
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(2))
+            .unwrap();
+        let modified = read();
+        assert_ne!(modified, initial, "A modified file should be a change");
+
+        File::create(&handler_path).unwrap();
+        assert_ne!(read(), modified, "A created file should be a change");
+    }
+
+    #[test]
+    fn watches_profile_overlay_and_chains_registries() {
+        let dir = TempDir::new("watched_project_files_test").unwrap();
+        let project_root = dir.path().join("project");
+        let user_chains_path = dir.path().join("chains.yaml");
+        std::fs::create_dir(&project_root).unwrap();
+        let project_paths =
+            ParsedProjectPaths::new(project_root.to_str().unwrap(), "generated", "config.yaml")
+                .unwrap()
+                .with_profile(Some("staging".to_string()))
+                .with_user_chains_path(Some(user_chains_path.clone()));
+
+        let paths = WatchedFiles::get_project_file_paths(&project_paths);
+        assert_eq!(
+            paths,
+            vec![
+                project_root.join("config.yaml"),
+                project_root.join("config.staging.yaml"),
+                user_chains_path.clone(),
+                project_root.join("chains.yaml"),
+            ]
+        );
+
+        let read = || WatchedFiles::from_paths(paths.clone());
+        let initial = read();
+        File::create(project_root.join("config.staging.yaml")).unwrap();
+        let overlay_created = read();
+        assert_ne!(
+            overlay_created, initial,
+            "A profile overlay edit should be a change"
+        );
+        File::create(project_root.join("chains.yaml")).unwrap();
+        let chains_created = read();
+        assert_ne!(
+            chains_created, overlay_created,
+            "A chains.yaml edit should be a change"
+        );
+        File::create(&user_chains_path).unwrap();
+        assert_ne!(
+            read(),
+            chains_created,
+            "A user chains.yaml edit should be a change"
+        );
+    }
+
+    #[tokio::test]
+    async fn waits_for_changed_files_to_settle() {
+        let states = [
+            watched(&[("config.yaml", Some(1))]),
+            watched(&[("config.yaml", Some(1))]),
+            watched(&[("config.yaml", Some(2))]),
+            watched(&[("config.yaml", Some(3))]),
+            watched(&[("config.yaml", Some(4))]),
+            watched(&[("config.yaml", Some(4))]),
+            watched(&[("config.yaml", Some(5))]),
+        ];
+        let reads = Cell::new(0);
+        let read = || {
+            let state = states[reads.get()].clone();
+            reads.set(reads.get() + 1);
+            state
+        };
+
+        let settled = wait_for_settled_change(
+            read,
+            &states[0],
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+        )
+        .await;
+
+        assert_eq!(settled, watched(&[("config.yaml", Some(4))]));
+        assert_eq!(
+            reads.get(),
+            6,
+            "Should stop reading once the files are unchanged for the debounce duration"
+        );
+    }
+}
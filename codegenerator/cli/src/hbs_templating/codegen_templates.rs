@@ -5,15 +5,18 @@ use std::{
     vec,
 };
 
-use super::hbs_dir_generator::HandleBarsDirGenerator;
+use super::hbs_dir_generator::{to_string_literal, HandleBarsDirGenerator};
 use crate::{
     config_parsing::{
-        entity_parsing::{Entity, Field, GraphQLEnum, MultiFieldIndex, Schema},
+        entity_parsing::{
+            Aggregation, Entity, Field, FulltextSearch, GraphQLEnum, GraphQLInterface,
+            GraphQLUnion, IndexMethod, MultiFieldIndex, Schema,
+        },
         event_parsing::{abi_to_rescript_type, EthereumEventParam},
         postgres_types,
         system_config::{
-            self, Abi, Ecosystem, EventKind, FuelEventKind, HyperfuelConfig, HypersyncConfig,
-            RpcConfig, SelectedField, SystemConfig,
+            self, Abi, Ecosystem, EventFilter, EventKind, FuelEventKind, HyperfuelConfig,
+            HypersyncConfig, RpcConfig, SelectedField, SyncSourceRole, SystemConfig,
         },
     },
     persisted_state::{PersistedState, PersistedStateJsonString},
@@ -42,6 +45,21 @@ pub struct EventParamTypeTemplate {
     pub is_eth_address: bool,
 }
 
+impl EventParamTypeTemplate {
+    fn from_abi_param(param: &EthereumEventParam) -> Self {
+        let res_type = abi_to_rescript_type(param);
+        let js_name = param.name.to_string();
+        EventParamTypeTemplate {
+            res_name: RescriptRecordField::to_valid_res_name(&js_name),
+            js_name,
+            default_value_rescript: res_type.get_default_value_rescript(),
+            default_value_non_rescript: res_type.get_default_value_non_rescript(),
+            res_type: res_type.to_string(),
+            is_eth_address: res_type == RescriptTypeIdent::Address,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct EventRecordTypeTemplate {
     pub name: CapitalizedOptions,
@@ -72,6 +90,98 @@ impl GraphQlEnumTypeTemplate {
     }
 }
 
+///Interfaces and unions, which are queried through a db view over the
+///tables of their entities
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct EntityViewTemplate {
+    pub name: CapitalizedOptions,
+    pub entities: Vec<CapitalizedOptions>,
+    pub db_field_names: Vec<String>,
+}
+
+impl EntityViewTemplate {
+    fn from_config_gql_interface(interface: &GraphQLInterface, schema: &Schema) -> Result<Self> {
+        Ok(EntityViewTemplate {
+            name: interface.name.to_capitalized_options(),
+            entities: schema
+                .get_interface_implementations(interface)
+                .iter()
+                .map(|entity| entity.name.to_capitalized_options())
+                .collect(),
+            db_field_names: interface.get_db_field_names(schema).context(format!(
+                "Failed getting db field names of interface {}",
+                interface.name
+            ))?,
+        })
+    }
+
+    fn from_config_gql_union(union: &GraphQLUnion) -> Self {
+        EntityViewTemplate {
+            name: union.name.to_capitalized_options(),
+            entities: union
+                .types
+                .iter()
+                .map(|type_name| type_name.to_capitalized_options())
+                .collect(),
+            db_field_names: vec!["id".to_string()],
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct AggregateTemplate {
+    field_name: String,
+    aggregate_fn: String,
+    arg: Option<String>,
+}
+
+///Aggregations, which roll up the rows of a timeseries entity
+///into a table per interval
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct AggregationTemplate {
+    pub name: CapitalizedOptions,
+    pub source: CapitalizedOptions,
+    pub intervals: Vec<String>,
+    pub dimension_field_names: Vec<String>,
+    pub aggregates: Vec<AggregateTemplate>,
+    pub postgres_fields: Vec<postgres_types::Field>,
+}
+
+impl AggregationTemplate {
+    fn from_config_aggregation(aggregation: &Aggregation, schema: &Schema) -> Result<Self> {
+        let postgres_fields = aggregation
+            .entity
+            .get_fields()
+            .iter()
+            .map(|gql_field| gql_field.get_postgres_field(schema, &aggregation.entity))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(AggregationTemplate {
+            name: aggregation.entity.name.to_capitalized_options(),
+            source: aggregation.source.to_capitalized_options(),
+            intervals: aggregation
+                .intervals
+                .iter()
+                .map(|interval| interval.to_rescript_constructor().to_string())
+                .collect(),
+            dimension_field_names: aggregation.get_dimension_field_names(),
+            aggregates: aggregation
+                .aggregates
+                .iter()
+                .map(|aggregate| AggregateTemplate {
+                    field_name: aggregate.field_name.clone(),
+                    aggregate_fn: aggregate.aggregate_fn.to_rescript_constructor().to_string(),
+                    arg: aggregate.arg.clone(),
+                })
+                .collect(),
+            postgres_fields,
+        })
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum RelationshipTypeTemplate {
@@ -164,6 +274,11 @@ pub struct EntityParamTypeTemplate {
     ///Used to determine if you can run a where
     ///query on this field.
     pub is_queryable_field: bool,
+    ///Whether the field is an array of entities, which can be
+    ///queried by the entities it holds
+    pub is_many_to_many_field: bool,
+    ///ReScript code of the @default value of the field
+    pub res_default_value: Option<String>,
 }
 
 impl HasIsDerivedFrom for EntityParamTypeTemplate {
@@ -186,9 +301,14 @@ impl EntityParamTypeTemplate {
         let is_entity_field = field.field_type.is_entity_field(schema)?;
         let is_indexed_field = field.is_indexed_field(entity);
         let is_derived_lookup_field = field.is_derived_lookup_field(entity, schema);
+        let is_many_to_many_field = field.is_many_to_many_field(schema)?;
+        let has_custom_index = field.has_custom_index(entity);
 
-        //Both of these cases have indexes on them and should exist
-        let is_queryable_field = is_indexed_field || is_derived_lookup_field;
+        //All of these cases have indexes on them and should exist
+        let is_queryable_field = is_indexed_field
+            || is_derived_lookup_field
+            || is_many_to_many_field
+            || has_custom_index;
 
         Ok(EntityParamTypeTemplate {
             field_name: field.name.to_capitalized_options(),
@@ -198,6 +318,8 @@ impl EntityParamTypeTemplate {
             is_entity_field,
             is_indexed_field,
             is_queryable_field,
+            is_many_to_many_field,
+            res_default_value: field.get_rescript_default_value(schema)?,
         })
     }
 }
@@ -213,6 +335,97 @@ pub struct EntityIndexParamGroup {
     params: Vec<EntityParamTypeTemplate>,
 }
 
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct EntityCustomIndexTemplate {
+    field_names: Vec<String>,
+    method: String,
+    orders: Vec<String>,
+    ///ReScript string of the partial index predicate
+    where_predicate: Option<String>,
+}
+
+impl EntityCustomIndexTemplate {
+    fn new(multi_field_index: &MultiFieldIndex) -> Self {
+        EntityCustomIndexTemplate {
+            field_names: multi_field_index.get_field_names().clone(),
+            //btree is the default method in Table.mkIndex
+            method: match multi_field_index.get_method() {
+                IndexMethod::BTree => String::new(),
+                method => method.to_rescript_constructor().to_string(),
+            },
+            orders: multi_field_index
+                .get_orders()
+                .iter()
+                .map(|order| order.to_rescript_constructor().to_string())
+                .collect(),
+            where_predicate: multi_field_index
+                .get_where_predicate()
+                .map(|predicate| to_string_literal(predicate)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct EntityFulltextSearchTemplate {
+    name: String,
+    language: String,
+    field_names: Vec<String>,
+}
+
+impl EntityFulltextSearchTemplate {
+    fn new(fulltext_search: &FulltextSearch) -> Self {
+        EntityFulltextSearchTemplate {
+            name: fulltext_search.name.clone(),
+            language: fulltext_search.language.clone(),
+            field_names: fulltext_search.field_names.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct EntityUniqueConstraintTemplate {
+    ///Key of the loader in getByUnique. The field name for single field
+    ///constraints or the field names joined with "And" for composite ones
+    name: String,
+    params: Vec<EntityParamTypeTemplate>,
+    is_single_field: bool,
+}
+
+impl EntityUniqueConstraintTemplate {
+    fn new(params: Vec<EntityParamTypeTemplate>) -> Self {
+        let db_field_names = params
+            .iter()
+            .map(|param| {
+                let name = param.field_name.uncapitalized.clone();
+                if param.is_entity_field {
+                    format!("{name}_id")
+                } else {
+                    name
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let name = db_field_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                if i == 0 {
+                    name.clone()
+                } else {
+                    name.to_capitalized_options().capitalized
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("And");
+
+        EntityUniqueConstraintTemplate {
+            name,
+            is_single_field: params.len() == 1,
+            params,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct DerivedFieldTemplate {
     pub field_name: String,
@@ -225,11 +438,19 @@ pub struct EntityRecordTypeTemplate {
     pub name: CapitalizedOptions,
     pub postgres_fields: Vec<postgres_types::Field>,
     pub composite_indices: Vec<Vec<String>>,
+    pub custom_indices: Vec<EntityCustomIndexTemplate>,
+    pub fulltext_searches: Vec<EntityFulltextSearchTemplate>,
     pub derived_fields: Vec<DerivedFieldTemplate>,
     pub params: Vec<EntityParamTypeTemplate>,
     pub index_groups: Vec<EntityIndexParamGroup>,
     pub relational_params: FilteredTemplateLists<EntityRelationalTypesTemplate>,
     pub filtered_params: FilteredTemplateLists<EntityParamTypeTemplate>,
+    pub unique_constraints: Vec<EntityUniqueConstraintTemplate>,
+    pub is_immutable: bool,
+    pub is_timeseries: bool,
+    ///Whether any field has a @default value to fill in when
+    ///the handler leaves it out
+    pub has_default_values: bool,
 }
 
 impl EntityRecordTypeTemplate {
@@ -303,6 +524,25 @@ impl EntityRecordTypeTemplate {
             })
             .collect();
 
+        let unique_constraints = entity
+            .unique_constraints
+            .iter()
+            .map(|unique_constraint| {
+                EntityUniqueConstraintTemplate::new(
+                    unique_constraint
+                        .get_field_names()
+                        .iter()
+                        .map(|param_name| {
+                            params_lookup
+                                .get(param_name)
+                                .cloned()
+                                .expect("param name should be in lookup")
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+
         let postgres_fields = entity
             .get_fields()
             .iter()
@@ -320,15 +560,35 @@ impl EntityRecordTypeTemplate {
 
         let composite_indices = entity.get_composite_indices();
 
+        let custom_indices = entity
+            .get_custom_indices()
+            .into_iter()
+            .map(EntityCustomIndexTemplate::new)
+            .collect();
+
+        let fulltext_searches = entity
+            .fulltext_searches
+            .iter()
+            .map(EntityFulltextSearchTemplate::new)
+            .collect();
+
+        let has_default_values = params.iter().any(|param| param.res_default_value.is_some());
+
         Ok(EntityRecordTypeTemplate {
             name: entity.name.to_capitalized_options(),
             postgres_fields,
             derived_fields,
             composite_indices,
+            custom_indices,
+            fulltext_searches,
             params,
             index_groups,
             relational_params,
             filtered_params,
+            unique_constraints,
+            is_immutable: entity.is_immutable,
+            is_timeseries: entity.is_timeseries,
+            has_default_values,
         })
     }
 }
@@ -343,6 +603,7 @@ pub struct EventMod {
     pub convert_hyper_sync_event_args_code: String,
     pub event_filter_type: String,
     pub get_topic_selection_code: String,
+    pub default_topic_selections_code: Option<String>,
     pub custom_field_selection: Option<system_config::FieldSelection>,
     pub fuel_event_kind: Option<FuelEventKind>,
 }
@@ -363,6 +624,10 @@ impl EventMod {
         let convert_hyper_sync_event_args_code = &self.convert_hyper_sync_event_args_code;
         let event_filter_type = &self.event_filter_type;
         let get_topic_selection_code = &self.get_topic_selection_code;
+        let default_topic_selections_arg = match &self.default_topic_selections_code {
+            None => "".to_string(),
+            Some(code) => format!("\n  ~defaultTopicSelections={code},"),
+        };
 
         let fuel_event_kind_code = match self.fuel_event_kind {
             None => None,
@@ -461,7 +726,7 @@ let convertHyperSyncEventArgs = {convert_hyper_sync_event_args_code}
 let handlerRegister: HandlerTypes.Register.t = HandlerTypes.Register.make(
   ~topic0=sighash->EvmTypes.Hex.fromStringUnsafe,
   ~contractName,
-  ~eventName=name,
+  ~eventName=name,{default_topic_selections_arg}
 )
 
 @genType
@@ -508,7 +773,40 @@ impl EventTemplate {
         format!("{{ {field_rows} }}")
     }
 
-    pub fn generate_get_topic_selection_code(params: &[EventParam]) -> String {
+    fn generate_static_topics_code(topics: &[String]) -> String {
+        let topics = topics
+            .iter()
+            .map(|topic| format!("\"{topic}\"->EvmTypes.Hex.fromStringUnsafe"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("[{topics}]")
+    }
+
+    pub fn generate_default_topic_selections_code(filter: &EventFilter) -> String {
+        let topic_filter_args =
+            filter
+                .topics
+                .iter()
+                .enumerate()
+                .fold(String::new(), |mut output, (i, topics)| {
+                    if let Some(topics) = topics {
+                        let topic_number = i + 1;
+                        let static_topics = Self::generate_static_topics_code(topics);
+                        let _ = write!(output, ", ~topic{topic_number}={static_topics}");
+                    }
+                    output
+                });
+
+        format!(
+            "[LogSelection.makeTopicSelection(~topic0=[sighash->EvmTypes.Hex.\
+             fromStringUnsafe]{topic_filter_args})->Utils.unwrapResultExn]"
+        )
+    }
+
+    pub fn generate_get_topic_selection_code(
+        params: &[EventParam],
+        filter: &Option<EventFilter>,
+    ) -> String {
         let indexed_params = params.iter().filter(|param| param.indexed);
 
         //Prefixed with underscore for cases where it is not used to avoid compiler warnings
@@ -526,13 +824,28 @@ impl EventTemplate {
                         depth if depth > 0 => format!("(~nestedArrayDepth={depth})"),
                         _ => "".to_string(),
                     };
-                    let _ = write!(
-                        output,
-                        "~topic{topic_number}=?{event_filter_arg}.{param_name}->Belt.Option.\
-                     map(topicFilters => \
-                     topicFilters->SingleOrMultiple.normalizeOrThrow{nested_type_flags}->Belt.\
-                     Array.map({topic_encoder})), "
+                    let encode_topic_filters = format!(
+                        "topicFilters => \
+                         topicFilters->SingleOrMultiple.normalizeOrThrow{nested_type_flags}->Belt.\
+                         Array.map({topic_encoder})"
                     );
+                    let static_topics = filter
+                        .as_ref()
+                        .and_then(|filter| filter.topics.get(i).cloned().flatten());
+                    let _ = match static_topics {
+                        //Static topics from the config are used unless the handler overrides them
+                        Some(static_topics) => write!(
+                            output,
+                            "~topic{topic_number}={event_filter_arg}.{param_name}->Belt.Option.\
+                             mapWithDefault({}, {encode_topic_filters}), ",
+                            Self::generate_static_topics_code(&static_topics)
+                        ),
+                        None => write!(
+                            output,
+                            "~topic{topic_number}=?{event_filter_arg}.{param_name}->Belt.Option.\
+                             map({encode_topic_filters}), "
+                        ),
+                    };
                     output
                 });
 
@@ -602,6 +915,7 @@ impl EventTemplate {
                 .to_string(),
             event_filter_type: Self::EVENT_FILTER_TYPE_STUB.to_string(),
             get_topic_selection_code: Self::GET_TOPIC_SELECTION_CODE_STUB.to_string(),
+            default_topic_selections_code: None,
             custom_field_selection: config_event.field_selection.clone(),
             fuel_event_kind: Some(fuel_event_kind),
         };
@@ -627,6 +941,7 @@ impl EventTemplate {
                 .to_string(),
             event_filter_type: Self::EVENT_FILTER_TYPE_STUB.to_string(),
             get_topic_selection_code: Self::GET_TOPIC_SELECTION_CODE_STUB.to_string(),
+            default_topic_selections_code: None,
             custom_field_selection: config_event.field_selection.clone(),
             fuel_event_kind: Some(fuel_event_kind),
         };
@@ -643,18 +958,7 @@ impl EventTemplate {
             EventKind::Params(params) => {
                 let template_params = params
                     .iter()
-                    .map(|input| {
-                        let res_type = abi_to_rescript_type(&input.into());
-                        let js_name = input.name.to_string();
-                        EventParamTypeTemplate {
-                            res_name: RescriptRecordField::to_valid_res_name(&js_name),
-                            js_name,
-                            default_value_rescript: res_type.get_default_value_rescript(),
-                            default_value_non_rescript: res_type.get_default_value_non_rescript(),
-                            res_type: res_type.to_string(),
-                            is_eth_address: res_type == RescriptTypeIdent::Address,
-                        }
-                    })
+                    .map(|input| EventParamTypeTemplate::from_abi_param(&input.into()))
                     .collect::<Vec<_>>();
 
                 let data_type_expr = if params.is_empty() {
@@ -685,7 +989,14 @@ impl EventTemplate {
                     convert_hyper_sync_event_args_code:
                         Self::generate_convert_hyper_sync_event_args_code(params),
                     event_filter_type: Self::generate_event_filter_type(params),
-                    get_topic_selection_code: Self::generate_get_topic_selection_code(params),
+                    get_topic_selection_code: Self::generate_get_topic_selection_code(
+                        params,
+                        &config_event.filter,
+                    ),
+                    default_topic_selections_code: config_event
+                        .filter
+                        .as_ref()
+                        .map(Self::generate_default_topic_selections_code),
                     custom_field_selection: config_event.field_selection.clone(),
                     fuel_event_kind: None,
                 };
@@ -714,6 +1025,7 @@ impl EventTemplate {
                             event_filter_type: Self::EVENT_FILTER_TYPE_STUB.to_string(),
                             get_topic_selection_code: Self::GET_TOPIC_SELECTION_CODE_STUB
                                 .to_string(),
+                            default_topic_selections_code: None,
                             custom_field_selection: config_event.field_selection.clone(),
                             fuel_event_kind: Some(fuel_event_kind),
                         };
@@ -736,13 +1048,97 @@ impl EventTemplate {
     }
 }
 
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct FunctionTemplate {
+    pub name: String,
+    pub module_code: String,
+    pub params: Vec<EventParamTypeTemplate>,
+}
+
+impl FunctionTemplate {
+    pub fn from_config_function(config_function: &system_config::Function) -> Self {
+        let function_name = config_function.name.capitalize();
+        let params = config_function.params();
+
+        let template_params = params
+            .iter()
+            .map(|input| EventParamTypeTemplate::from_abi_param(&input.into()))
+            .collect::<Vec<_>>();
+
+        let data_type_expr = if params.is_empty() {
+            RescriptTypeExpr::Identifier(RescriptTypeIdent::Unit)
+        } else {
+            RescriptTypeExpr::Record(
+                params
+                    .iter()
+                    .map(|p| {
+                        RescriptRecordField::new(
+                            p.name.to_string(),
+                            abi_to_rescript_type(&p.into()),
+                        )
+                    })
+                    .collect(),
+            )
+        };
+
+        let selector = &config_function.selector;
+        let abi_name = &config_function.abi_function.name;
+        // Viem decodes the inputs as an array, they are keyed by these names
+        let input_names = params
+            .iter()
+            .map(|p| format!("\"{}\"", p.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let data_type = data_type_expr.to_string();
+        let inputs_schema =
+            data_type_expr.to_rescript_schema(&"inputs".to_string(), &RescriptSchemaMode::ForDb);
+
+        let module_code = format!(
+            r#"
+let selector = "{selector}"
+let name = "{function_name}"
+let abiName = "{abi_name}"
+let contractName = contractName
+let inputNames = [{input_names}]
+
+@genType
+type inputs = {data_type}
+@genType
+type block = Block.t
+@genType
+type transaction = Transaction.t
+
+@genType
+type call = Internal.genericCall<inputs, block, transaction>
+@genType
+type handler = Internal.genericHandler<Internal.genericCallHandlerArgs<call, handlerContext>>
+
+let inputsSchema = {inputs_schema}
+
+let handlerRegister: OnCall.Register.t = OnCall.Register.make(
+  ~selector,
+  ~contractName,
+  ~functionName=name,
+)"#
+        );
+
+        FunctionTemplate {
+            name: function_name,
+            module_code,
+            params: template_params,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Clone)]
 pub struct ContractTemplate {
     pub name: CapitalizedOptions,
     pub codegen_events: Vec<EventTemplate>,
+    pub codegen_functions: Vec<FunctionTemplate>,
     pub chain_ids: Vec<u64>,
     pub module_code: String,
     pub handler: HandlerPathsTemplate,
+    pub factory: Option<FactoryTemplate>,
 }
 
 impl ContractTemplate {
@@ -759,6 +1155,11 @@ impl ContractTemplate {
             .iter()
             .map(EventTemplate::from_config_event)
             .collect::<Result<_>>()?;
+        let codegen_functions = contract
+            .functions
+            .iter()
+            .map(FunctionTemplate::from_config_function)
+            .collect();
 
         let module_code = match &contract.abi {
             Abi::Evm(abi) => {
@@ -795,12 +1196,60 @@ let eventSignatures = [{}]"#,
 
         let chain_ids = contract.get_chain_ids(config);
 
+        let factory = match &contract.factory {
+            Some(factory) => Some(
+                FactoryTemplate::from_config_factory(factory, &contract.name, config).context(
+                    format!(
+                        "Failed building factory template for contract {}",
+                        contract.name
+                    ),
+                )?,
+            ),
+            None => None,
+        };
+
         Ok(ContractTemplate {
             name,
             handler,
             codegen_events,
+            codegen_functions,
             chain_ids,
             module_code,
+            factory,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct FactoryTemplate {
+    pub contract_name: CapitalizedOptions,
+    pub event_name: String,
+    pub contract_register_code: String,
+}
+
+impl FactoryTemplate {
+    fn from_config_factory(
+        factory: &system_config::Factory,
+        created_contract_name: &str,
+        config: &SystemConfig,
+    ) -> Result<Self> {
+        let param = factory.get_address_param(&config.contracts)?;
+        let param_name = RescriptRecordField::to_valid_res_name(&param.name);
+        let add_contract = format!(
+            "context.add{}",
+            created_contract_name.to_string().capitalize()
+        );
+
+        let contract_register_code = match param.kind {
+            ethers::abi::ParamType::Address => format!("{add_contract}(event.params.{param_name})"),
+            //Validated to be a list of addresses
+            _ => format!("event.params.{param_name}->Belt.Array.forEach({add_contract})"),
+        };
+
+        Ok(FactoryTemplate {
+            contract_name: factory.contract_name.to_capitalized_options(),
+            event_name: factory.event_name.capitalize(),
+            contract_register_code,
         })
     }
 }
@@ -808,12 +1257,37 @@ let eventSignatures = [{}]"#,
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct PerNetworkContractEventTemplate {
     pub name: String,
+    pub start_block: Option<u64>,
+    pub end_block: Option<u64>,
 }
 
 impl PerNetworkContractEventTemplate {
     fn new(event_name: String) -> Self {
         PerNetworkContractEventTemplate {
             name: event_name.capitalize(),
+            start_block: None,
+            end_block: None,
+        }
+    }
+
+    fn from_config_event(event: &system_config::Event) -> Self {
+        PerNetworkContractEventTemplate {
+            start_block: event.start_block,
+            end_block: event.end_block,
+            ..Self::new(event.name.clone())
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct PerNetworkContractFunctionTemplate {
+    pub name: String,
+}
+
+impl PerNetworkContractFunctionTemplate {
+    fn new(function_name: String) -> Self {
+        PerNetworkContractFunctionTemplate {
+            name: function_name.capitalize(),
         }
     }
 }
@@ -823,6 +1297,9 @@ pub struct PerNetworkContractTemplate {
     name: CapitalizedOptions,
     addresses: Vec<EthAddress>,
     events: Vec<PerNetworkContractEventTemplate>,
+    functions: Vec<PerNetworkContractFunctionTemplate>,
+    start_block: Option<u64>,
+    end_block: Option<u64>,
 }
 
 impl PerNetworkContractTemplate {
@@ -837,13 +1314,22 @@ impl PerNetworkContractTemplate {
         let events = contract
             .events
             .iter()
-            .map(|event| PerNetworkContractEventTemplate::new(event.name.clone()))
+            .map(PerNetworkContractEventTemplate::from_config_event)
+            .collect();
+
+        let functions = contract
+            .functions
+            .iter()
+            .map(|function| PerNetworkContractFunctionTemplate::new(function.name.clone()))
             .collect();
 
         Ok(PerNetworkContractTemplate {
             name: network_contract.name.to_capitalized_options(),
             addresses: network_contract.addresses.clone(),
             events,
+            functions,
+            start_block: network_contract.start_block,
+            end_block: network_contract.end_block,
         })
     }
 }
@@ -856,6 +1342,8 @@ struct NetworkTemplate {
     rpc_config: Option<RpcConfig>,
     hypersync_config: Option<HypersyncConfig>,
     hyperfuel_config: Option<HyperfuelConfig>,
+    rpc_role_code: Option<String>,
+    hypersync_role_code: Option<String>,
     confirmed_block_threshold: i32,
     start_block: u64,
     end_block: Option<u64>,
@@ -863,16 +1351,48 @@ struct NetworkTemplate {
 
 impl NetworkTemplate {
     fn from_config_network(network: &system_config::Network) -> Self {
+        let (rpc_role_code, hypersync_role_code) = match &network.sync_source {
+            system_config::SyncSource::HybridConfig(hybrid_config) => {
+                let realtime_code = format!(
+                    "Source.Realtime({{switchBlocksBehindHead: {}, stallTimeoutMillis: {}}})",
+                    hybrid_config.realtime_threshold_blocks,
+                    hybrid_config.rpc_config.sync_config.fallback_stall_timeout
+                );
+                match hybrid_config.rpc_role {
+                    SyncSourceRole::Historical => {
+                        (Some("Source.Historical".to_string()), Some(realtime_code))
+                    }
+                    SyncSourceRole::Realtime => {
+                        (Some(realtime_code), Some("Source.Historical".to_string()))
+                    }
+                    SyncSourceRole::Fallback => (
+                        Some(format!(
+                            "Source.Fallback({{stallTimeoutMillis: {}}})",
+                            hybrid_config.fallback_threshold_millis
+                        )),
+                        None,
+                    ),
+                }
+            }
+            _ => (None, None),
+        };
+
         NetworkTemplate {
             id: network.id,
             rpc_config: match &network.sync_source {
                 system_config::SyncSource::RpcConfig(rpc_config) => Some(rpc_config.clone()),
+                system_config::SyncSource::HybridConfig(hybrid_config) => {
+                    Some(hybrid_config.rpc_config.clone())
+                }
                 _ => None,
             },
             hypersync_config: match &network.sync_source {
                 system_config::SyncSource::HypersyncConfig(hypersync_config) => {
                     Some(hypersync_config.clone())
                 }
+                system_config::SyncSource::HybridConfig(hybrid_config) => {
+                    Some(hybrid_config.hypersync_config.clone())
+                }
                 _ => None,
             },
             hyperfuel_config: match &network.sync_source {
@@ -881,6 +1401,8 @@ impl NetworkTemplate {
                 }
                 _ => None,
             },
+            rpc_role_code,
+            hypersync_role_code,
             confirmed_block_threshold: network.confirmed_block_threshold,
             start_block: network.start_block,
             end_block: network.end_block,
@@ -888,10 +1410,30 @@ impl NetworkTemplate {
     }
 }
 
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct PerNetworkBlockHandlerTemplate {
+    name: CapitalizedOptions,
+    interval: u64,
+    start_block: u64,
+    end_block: Option<u64>,
+}
+
+impl PerNetworkBlockHandlerTemplate {
+    fn from_config_block_handler(block_handler: &system_config::BlockHandler) -> Self {
+        PerNetworkBlockHandlerTemplate {
+            name: block_handler.name.to_capitalized_options(),
+            interval: block_handler.interval,
+            start_block: block_handler.start_block,
+            end_block: block_handler.end_block,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Clone)]
 pub struct NetworkConfigTemplate {
     network_config: NetworkTemplate,
     codegen_contracts: Vec<PerNetworkContractTemplate>,
+    block_handlers: Vec<PerNetworkBlockHandlerTemplate>,
 }
 
 impl NetworkConfigTemplate {
@@ -909,9 +1451,16 @@ impl NetworkConfigTemplate {
             .collect::<Result<_>>()
             .context("Failed mapping network contracts")?;
 
+        let block_handlers = network
+            .block_handlers
+            .iter()
+            .map(PerNetworkBlockHandlerTemplate::from_config_block_handler)
+            .collect();
+
         Ok(NetworkConfigTemplate {
             network_config,
             codegen_contracts,
+            block_handlers,
         })
     }
 }
@@ -1032,11 +1581,35 @@ struct SelectedFieldTemplate {
     default_value_rescript: String,
 }
 
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct BlockHandlerTemplate {
+    name: CapitalizedOptions,
+    handler: HandlerPathsTemplate,
+}
+
+impl BlockHandlerTemplate {
+    fn from_config_block_handler(
+        block_handler: &system_config::BlockHandler,
+        project_paths: &ParsedProjectPaths,
+    ) -> Result<Self> {
+        let handler =
+            HandlerPathsTemplate::from_handler_path(&block_handler.handler_path, project_paths)
+                .context("Failed building handler paths template")?;
+        Ok(BlockHandlerTemplate {
+            name: block_handler.name.to_capitalized_options(),
+            handler,
+        })
+    }
+}
+
 #[derive(Serialize)]
 pub struct ProjectTemplate {
     project_name: String,
     codegen_contracts: Vec<ContractTemplate>,
+    block_handlers: Vec<BlockHandlerTemplate>,
     entities: Vec<EntityRecordTypeTemplate>,
+    entity_views: Vec<EntityViewTemplate>,
+    aggregations: Vec<AggregationTemplate>,
     gql_enums: Vec<GraphQlEnumTypeTemplate>,
     chain_configs: Vec<NetworkConfigTemplate>,
     codegen_out_path: String,
@@ -1085,6 +1658,15 @@ impl ProjectTemplate {
             .collect::<Result<_>>()
             .context("Failed generating contract template types")?;
 
+        let block_handlers: Vec<BlockHandlerTemplate> = cfg
+            .get_block_handlers()
+            .into_iter()
+            .map(|block_handler| {
+                BlockHandlerTemplate::from_config_block_handler(block_handler, project_paths)
+            })
+            .collect::<Result<_>>()
+            .context("Failed generating block handler template types")?;
+
         let entities: Vec<EntityRecordTypeTemplate> = cfg
             .get_entities()
             .iter()
@@ -1092,6 +1674,27 @@ impl ProjectTemplate {
             .collect::<Result<_>>()
             .context("Failed generating entity template types")?;
 
+        let mut entity_views: Vec<EntityViewTemplate> = cfg
+            .get_gql_interfaces()
+            .iter()
+            .map(|interface| EntityViewTemplate::from_config_gql_interface(interface, &cfg.schema))
+            .collect::<Result<_>>()
+            .context("Failed generating interface template types")?;
+        entity_views.extend(
+            cfg.get_gql_unions()
+                .into_iter()
+                .map(EntityViewTemplate::from_config_gql_union),
+        );
+
+        let aggregations: Vec<AggregationTemplate> = cfg
+            .get_aggregations()
+            .into_iter()
+            .map(|aggregation| {
+                AggregationTemplate::from_config_aggregation(aggregation, &cfg.schema)
+            })
+            .collect::<Result<_>>()
+            .context("Failed generating aggregation template types")?;
+
         let gql_enums: Vec<GraphQlEnumTypeTemplate> = cfg
             .get_gql_enums()
             .iter()
@@ -1141,7 +1744,10 @@ impl ProjectTemplate {
         Ok(ProjectTemplate {
             project_name: cfg.name.clone(),
             codegen_contracts,
+            block_handlers,
             entities,
+            entity_views,
+            aggregations,
             gql_enums,
             chain_configs,
             codegen_out_path: gitignore_path_str,
@@ -1207,6 +1813,8 @@ mod test {
                 rpc_config: None,
                 hypersync_config: None,
                 hyperfuel_config: None,
+                rpc_role_code: None,
+                hypersync_role_code: None,
                 confirmed_block_threshold: 200,
                 start_block: 0,
                 end_block: None,
@@ -1220,6 +1828,7 @@ mod test {
 
         let rpc_config1 = RpcConfig {
             urls: vec!["https://eth.com".to_string()],
+            ws_urls: vec![],
             sync_config: system_config::SyncConfig {
                 acceleration_additive: 2_000,
                 ..system_config::SyncConfig::default()
@@ -1237,11 +1846,15 @@ mod test {
             name: String::from("Contract1").to_capitalized_options(),
             addresses: vec![address1.clone()],
             events,
+            functions: vec![],
+            start_block: None,
+            end_block: None,
         };
 
         let chain_config_1 = super::NetworkConfigTemplate {
             network_config: network1,
             codegen_contracts: vec![contract1],
+            block_handlers: vec![],
         };
 
         let expected_chain_configs = vec![chain_config_1];
@@ -1267,6 +1880,7 @@ mod test {
 
         let rpc_config1 = RpcConfig {
             urls: vec!["https://eth.com".to_string()],
+            ws_urls: vec![],
             sync_config: system_config::SyncConfig {
                 acceleration_additive: 2_000,
                 ..system_config::SyncConfig::default()
@@ -1284,6 +1898,7 @@ mod test {
                 // Should support fallback urls
                 "https://eth.com/fallback".to_string(),
             ],
+            ws_urls: vec![],
             sync_config: system_config::SyncConfig {
                 acceleration_additive: 2_000,
                 ..system_config::SyncConfig::default()
@@ -1301,6 +1916,9 @@ mod test {
             name: String::from("Contract1").to_capitalized_options(),
             addresses: vec![address1.clone()],
             events,
+            functions: vec![],
+            start_block: None,
+            end_block: None,
         };
 
         let events = get_per_contract_events_vec_helper(vec!["NewGravatar", "UpdatedGravatar"]);
@@ -1308,15 +1926,20 @@ mod test {
             name: String::from("Contract2").to_capitalized_options(),
             addresses: vec![address2.clone()],
             events,
+            functions: vec![],
+            start_block: None,
+            end_block: None,
         };
 
         let chain_config_1 = super::NetworkConfigTemplate {
             network_config: network1,
             codegen_contracts: vec![contract1],
+            block_handlers: vec![],
         };
         let chain_config_2 = super::NetworkConfigTemplate {
             network_config: network2,
             codegen_contracts: vec![contract2],
+            block_handlers: vec![],
         };
 
         let expected_chain_configs = vec![chain_config_1, chain_config_2];
@@ -1345,11 +1968,15 @@ mod test {
             name: String::from("Contract1").to_capitalized_options(),
             addresses: vec![address1.clone()],
             events,
+            functions: vec![],
+            start_block: None,
+            end_block: None,
         };
 
         let chain_config_1 = super::NetworkConfigTemplate {
             network_config: network1,
             codegen_contracts: vec![contract1],
+            block_handlers: vec![],
         };
 
         let expected_chain_configs = vec![chain_config_1];
@@ -1382,11 +2009,13 @@ mod test {
         let chain_config_1 = super::NetworkConfigTemplate {
             network_config: network1,
             codegen_contracts: vec![],
+            block_handlers: vec![],
         };
 
         let chain_config_2 = super::NetworkConfigTemplate {
             network_config: network2,
             codegen_contracts: vec![],
+            block_handlers: vec![],
         };
 
         let expected_chain_configs = vec![chain_config_1, chain_config_2];
@@ -1482,6 +2111,119 @@ let getTopicSelection = (eventFilters) => eventFilters->SingleOrMultiple.normali
         }
     }
 
+    #[test]
+    fn chain_configs_with_block_handlers() {
+        let project_template = get_project_template_helper("block-handlers-config.yaml");
+
+        assert_eq!(
+            project_template.chain_configs[0].block_handlers,
+            vec![
+                super::PerNetworkBlockHandlerTemplate {
+                    name: String::from("Snapshot").to_capitalized_options(),
+                    interval: 100,
+                    start_block: 100,
+                    end_block: Some(10000),
+                },
+                super::PerNetworkBlockHandlerTemplate {
+                    name: String::from("PriceSample").to_capitalized_options(),
+                    interval: 1,
+                    start_block: 500,
+                    end_block: Some(1000),
+                },
+            ]
+        );
+        assert_eq!(
+            project_template.chain_configs[1].block_handlers,
+            vec![super::PerNetworkBlockHandlerTemplate {
+                name: String::from("Snapshot").to_capitalized_options(),
+                interval: 1000,
+                start_block: 0,
+                end_block: None,
+            }]
+        );
+
+        let block_handler_names: Vec<_> = project_template
+            .block_handlers
+            .iter()
+            .map(|b| b.name.capitalized.clone())
+            .collect();
+        assert_eq!(block_handler_names, vec!["PriceSample", "Snapshot"]);
+    }
+
+    #[test]
+    fn contract_templates_with_factory() {
+        let project_template = get_project_template_helper("factory-config.yaml");
+
+        let factories = project_template
+            .codegen_contracts
+            .iter()
+            .map(|contract| (contract.name.capitalized.clone(), contract.factory.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            factories,
+            vec![
+                (
+                    "BatchPool".to_string(),
+                    Some(FactoryTemplate {
+                        contract_name: "PoolFactory".to_string().to_capitalized_options(),
+                        event_name: "PoolsCreated".to_string(),
+                        contract_register_code: "event.params.pools->Belt.Array.forEach(context.\
+                                                 addBatchPool)"
+                            .to_string(),
+                    })
+                ),
+                (
+                    "Pool".to_string(),
+                    Some(FactoryTemplate {
+                        contract_name: "PoolFactory".to_string().to_capitalized_options(),
+                        event_name: "PoolCreated".to_string(),
+                        contract_register_code: "context.addPool(event.params.pool)".to_string(),
+                    })
+                ),
+                ("PoolFactory".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn chain_configs_with_block_ranges() {
+        let project_template = get_project_template_helper("block-ranges-config.yaml");
+        let address1 = String::from("0x2E645469f354BB4F5c8a05B3b30A929361cf77eC");
+        let address2 = String::from("0x1E645469f354BB4F5c8a05B3b30A929361cf77eD");
+
+        let contract1 = super::PerNetworkContractTemplate {
+            name: String::from("Contract1").to_capitalized_options(),
+            addresses: vec![address1],
+            events: vec![
+                PerNetworkContractEventTemplate::new("NewGravatar".to_string()),
+                PerNetworkContractEventTemplate {
+                    start_block: Some(19500000),
+                    ..PerNetworkContractEventTemplate::new("UpdatedGravatar".to_string())
+                },
+            ],
+            functions: vec![],
+            start_block: Some(19000000),
+            end_block: None,
+        };
+        let contract2 = super::PerNetworkContractTemplate {
+            name: String::from("Contract2").to_capitalized_options(),
+            addresses: vec![address2],
+            events: vec![PerNetworkContractEventTemplate {
+                end_block: Some(13000000),
+                ..PerNetworkContractEventTemplate::new("NewGravatar".to_string())
+            }],
+            functions: vec![],
+            start_block: None,
+            end_block: Some(15000000),
+        };
+
+        assert_eq!(
+            project_template.chain_configs[0].codegen_contracts,
+            vec![contract1, contract2]
+        );
+    }
+
     #[test]
     fn event_template_with_empty_params() {
         let event_template = EventTemplate::from_config_event(&system_config::Event {
@@ -1490,6 +2232,9 @@ let getTopicSelection = (eventFilters) => eventFilters->SingleOrMultiple.normali
             sighash: "0x50f7d27e90d1a5a38aeed4ceced2e8ec1ff185737aca96d15791b470d3f17363"
                 .to_string(),
             field_selection: None,
+            start_block: None,
+            end_block: None,
+            filter: None,
         })
         .unwrap();
 
@@ -1541,6 +2286,56 @@ let getTopicSelection = (eventFilters) => eventFilters->SingleOrMultiple.normali
         );
     }
 
+    #[test]
+    fn function_template_with_params() {
+        let function_template = FunctionTemplate::from_config_function(&system_config::Function {
+            name: "transfer".to_string(),
+            selector: "0xa9059cbb".to_string(),
+            abi_function: ethers::abi::HumanReadableParser::parse_function(
+                "function transfer(address to, uint256 amount)",
+            )
+            .unwrap(),
+        });
+
+        assert_eq!(
+            function_template,
+            FunctionTemplate {
+                name: "Transfer".to_string(),
+                params: vec![
+                    EventParamTypeTemplate::new("to", RESCRIPT_ADDRESS_TYPE),
+                    EventParamTypeTemplate::new("amount", RESCRIPT_BIG_INT_TYPE),
+                ],
+                module_code: r#"
+let selector = "0xa9059cbb"
+let name = "Transfer"
+let abiName = "transfer"
+let contractName = contractName
+let inputNames = ["to", "amount"]
+
+@genType
+type inputs = {to: Address.t, amount: bigint}
+@genType
+type block = Block.t
+@genType
+type transaction = Transaction.t
+
+@genType
+type call = Internal.genericCall<inputs, block, transaction>
+@genType
+type handler = Internal.genericHandler<Internal.genericCallHandlerArgs<call, handlerContext>>
+
+let inputsSchema = S.object((s): inputs => {to: s.field("to", Address.schema), amount: s.field("amount", BigInt.schema)})
+
+let handlerRegister: OnCall.Register.t = OnCall.Register.make(
+  ~selector,
+  ~contractName,
+  ~functionName=name,
+)"#
+                .to_string(),
+            }
+        );
+    }
+
     #[test]
     fn event_template_with_custom_field_selection() {
         let event_template = EventTemplate::from_config_event(&system_config::Event {
@@ -1555,6 +2350,9 @@ let getTopicSelection = (eventFilters) => eventFilters->SingleOrMultiple.normali
                     data_type: RescriptTypeIdent::option(RescriptTypeIdent::Address),
                 }],
             }),
+            start_block: None,
+            end_block: None,
+            filter: None,
         })
         .unwrap();
 
@@ -1606,6 +2404,52 @@ let getTopicSelection = (eventFilters) => eventFilters->SingleOrMultiple.normali
         );
     }
 
+    #[test]
+    fn topic_selection_with_static_event_filter() {
+        let params = vec![
+            EventParam {
+                name: "from".to_string(),
+                kind: ethers::abi::ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "to".to_string(),
+                kind: ethers::abi::ParamType::Address,
+                indexed: true,
+            },
+        ];
+        let filter = EventFilter {
+            topics: vec![
+                None,
+                Some(vec![
+                    "0x0000000000000000000000002e645469f354bb4f5c8a05b3b30a929361cf77ec"
+                        .to_string(),
+                ]),
+            ],
+        };
+
+        assert_eq!(
+            EventTemplate::generate_get_topic_selection_code(&params, &Some(filter.clone())),
+            "(eventFilters) => \
+             eventFilters->SingleOrMultiple.normalizeOrThrow->Belt.Array.map(_eventFilter => \
+             LogSelection.makeTopicSelection(~topic0=[sighash->EvmTypes.Hex.fromStringUnsafe], \
+             ~topic1=?_eventFilter.from->Belt.Option.map(topicFilters => \
+             topicFilters->SingleOrMultiple.normalizeOrThrow->Belt.Array.map(TopicFilter.\
+             fromAddress)), \
+             ~topic2=_eventFilter.to->Belt.Option.mapWithDefault([\"\
+             0x0000000000000000000000002e645469f354bb4f5c8a05b3b30a929361cf77ec\"->EvmTypes.Hex.\
+             fromStringUnsafe], topicFilters => \
+             topicFilters->SingleOrMultiple.normalizeOrThrow->Belt.Array.map(TopicFilter.\
+             fromAddress)), )->Utils.unwrapResultExn)"
+        );
+        assert_eq!(
+            EventTemplate::generate_default_topic_selections_code(&filter),
+            "[LogSelection.makeTopicSelection(~topic0=[sighash->EvmTypes.Hex.fromStringUnsafe], \
+             ~topic2=[\"0x0000000000000000000000002e645469f354bb4f5c8a05b3b30a929361cf77ec\"\
+             ->EvmTypes.Hex.fromStringUnsafe])->Utils.unwrapResultExn]"
+        );
+    }
+
     #[test]
     fn abi_event_to_record_1() {
         let project_template = get_project_template_helper("config1.yaml");
@@ -365,6 +365,7 @@ impl From<Param> for Field {
         Field {
             name: val.entity_key.original,
             field_type: val.graphql_type,
+            default_value: None,
         }
     }
 }
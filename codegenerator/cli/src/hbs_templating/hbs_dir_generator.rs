@@ -6,6 +6,21 @@ use serde::Serialize;
 use std::fs;
 use std::path::Path;
 
+/// Escapes the value into a ReScript string literal
+pub fn to_string_literal(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{value}\""))
+}
+
+// Values containing ${ENV_VAR} are kept by the runtime env interpolation
+// and resolved when the indexer starts
+fn to_string_code(value: &str) -> String {
+    if value.contains("${") {
+        format!("Env.interpolateOrThrow({})", to_string_literal(value))
+    } else {
+        to_string_literal(value)
+    }
+}
+
 pub struct HandleBarsDirGenerator<'a, T: Serialize> {
     handlebars: handlebars::Handlebars<'a>,
     templates_dir: &'a RelativeDir<'a>,
@@ -22,13 +37,16 @@ impl<'a, T: Serialize> HandleBarsDirGenerator<'a, T> {
         handlebars_helper!(vec_to_array_helper: |vec: Vec<String>| {
           let items = vec
             .iter()
-            .map(|v| format!("\"{}\"", v))
+            .map(|v| to_string_code(v))
             .collect::<Vec<_>>()
             .join(", ");
           format!("[{}]", items)
         });
         handlebars.register_helper("vec_to_array", Box::new(vec_to_array_helper));
 
+        handlebars_helper!(string_code_helper: |value: String| to_string_code(&value));
+        handlebars.register_helper("string_code", Box::new(string_code_helper));
+
         HandleBarsDirGenerator {
             handlebars,
             templates_dir,
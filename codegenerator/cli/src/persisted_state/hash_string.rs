@@ -1,7 +1,14 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Display},
     fs::File,
     io::Read,
@@ -68,12 +75,60 @@ impl Display for HashString {
     }
 }
 
+///Hashes of the per network config options by network id, stored as json text
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(transparent)]
+pub struct NetworkHashStrings(BTreeMap<u64, HashString>);
+
+impl NetworkHashStrings {
+    pub fn from_strings(strings: BTreeMap<u64, String>) -> Self {
+        NetworkHashStrings(
+            strings
+                .into_iter()
+                .map(|(network_id, string)| (network_id, HashString::from_string(string)))
+                .collect(),
+        )
+    }
+
+    ///The ids of the networks that were added, removed or have a different hash
+    pub fn get_changed_network_ids(&self, other: &Self) -> Vec<u64> {
+        self.0
+            .keys()
+            .chain(other.0.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|network_id| self.0.get(network_id) != other.0.get(network_id))
+            .copied()
+            .collect()
+    }
+}
+
+impl Type<Postgres> for NetworkHashStrings {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for NetworkHashStrings {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        let json = serde_json::to_string(self).expect("Network hashes should serialize to json");
+        <String as Encode<Postgres>>::encode(json, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for NetworkHashStrings {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let json = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
     use std::path::PathBuf;
 
-    use super::HashString;
+    use super::{HashString, NetworkHashStrings};
     const CONFIG_1: &str = "test/configs/config1.yaml";
     const CONFIG_2: &str = "test/configs/config2.yaml";
     const EMPTY_HANDLER: &str = "test/configs/empty_handlers.res";
@@ -113,4 +168,23 @@ mod test {
         let empty_handler_path = PathBuf::from(EMPTY_HANDLER);
         HashString::from_file_paths(vec![empty_handler_path], true).unwrap();
     }
+
+    #[test]
+    fn changed_network_ids() {
+        let previous = NetworkHashStrings::from_strings(
+            [(1, "a"), (10, "b"), (137, "c")]
+                .map(|(id, s)| (id, s.to_string()))
+                .into(),
+        );
+        let current = NetworkHashStrings::from_strings(
+            [(1, "a"), (10, "changed"), (8453, "d")]
+                .map(|(id, s)| (id, s.to_string()))
+                .into(),
+        );
+
+        assert_eq!(
+            current.get_changed_network_ids(&previous),
+            vec![10, 137, 8453]
+        );
+    }
 }
@@ -1,9 +1,12 @@
 mod db;
 mod hash_string;
+mod schema_migration;
 
 use crate::{config_parsing::system_config::SystemConfig, project_paths::ParsedProjectPaths};
 use anyhow::Context;
-use hash_string::HashString;
+pub use db::{get_public_schema, PG_URL_ENV_VAR};
+use hash_string::{HashString, NetworkHashStrings};
+pub use schema_migration::{MigrationPlan, SchemaSnapshot};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use std::{
@@ -16,8 +19,15 @@ use strum_macros::EnumIter;
 #[derive(Serialize, Deserialize, Debug, FromRow)]
 pub struct PersistedState {
     pub envio_version: String,
+    ///Hash of the config options affecting the indexed data of all networks
     pub config_hash: HashString,
+    ///Hashes of the config options only affecting the indexed data of a single network
+    pub network_config_hashes: NetworkHashStrings,
+    ///Hash of the config options only affecting how the indexer runs, like rpc urls
+    pub runtime_config_hash: HashString,
     pub schema_hash: HashString,
+    ///The db objects of the parsed schema, diffed against to migrate the db incrementally
+    pub schema_snapshot: SchemaSnapshot,
     pub handler_files_hash: HashString,
     pub abi_files_hash: HashString,
 }
@@ -29,11 +39,50 @@ pub static CURRENT_CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub enum StateField {
     EnvioVersion,
     Config,
+    NetworkConfigs,
+    RuntimeConfig,
     Schema,
     AbiFiles,
     HandlerFiles,
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+///How a change to the state affects the data already indexed in the db,
+///ordered from the least to the most disruptive
+pub enum ChangeImpact {
+    ///Only affects how the indexer runs, the indexed data stays valid
+    Runtime,
+    ///Could change the results of the handlers, the indexed data might be stale
+    Results,
+    ///Affects how the data is stored, the db needs to be migrated or reset
+    Storage,
+}
+
+impl StateField {
+    pub fn get_change_impact(&self) -> ChangeImpact {
+        match self {
+            StateField::EnvioVersion
+            | StateField::Config
+            | StateField::NetworkConfigs
+            | StateField::Schema => ChangeImpact::Storage,
+            StateField::AbiFiles | StateField::HandlerFiles => ChangeImpact::Results,
+            StateField::RuntimeConfig => ChangeImpact::Runtime,
+        }
+    }
+
+    ///Whether a change to the field can be applied without resetting the whole db.
+    ///Schema changes are migrated and network config changes only reset the data of
+    ///the changed networks
+    fn is_migratable(&self) -> bool {
+        !matches!(self, StateField::EnvioVersion | StateField::Config)
+    }
+
+    ///The impact of the most disruptive change in the given fields
+    pub fn get_max_change_impact(fields: &[Self]) -> Option<ChangeImpact> {
+        fields.iter().map(Self::get_change_impact).max()
+    }
+}
+
 ///Gets the path to the persisted file in generated folder
 fn get_generated_file_path(project_paths: &ParsedProjectPaths) -> PathBuf {
     project_paths.generated.join(PERSISTED_STATE_FILE_NAME)
@@ -45,6 +94,12 @@ impl PersistedState {
     fn compare_state_field(&self, other_state: &Self, field: &StateField) -> bool {
         match field {
             StateField::Config => self.config_hash == other_state.config_hash,
+            StateField::NetworkConfigs => {
+                self.network_config_hashes == other_state.network_config_hashes
+            }
+            StateField::RuntimeConfig => {
+                self.runtime_config_hash == other_state.runtime_config_hash
+            }
             StateField::EnvioVersion => self.envio_version == other_state.envio_version,
             StateField::Schema => self.schema_hash == other_state.schema_hash,
             StateField::AbiFiles => self.abi_files_hash == other_state.abi_files_hash,
@@ -81,14 +136,40 @@ impl PersistedState {
             .get_all_paths_to_abi_files()
             .context("Failed getting abi file paths")?;
 
+        let config_options = config
+            .human_config
+            .split_options()
+            .context("Failed splitting config options")?;
+
+        //The registered chains resolve the hypersync urls and confirmed block
+        //thresholds of the networks, so they are part of the runtime config
+        let registered_chains = config.get_registered_chains();
+        let runtime_config = if registered_chains.is_empty() {
+            config_options.runtime
+        } else {
+            format!(
+                "{}chains:\n{}",
+                config_options.runtime,
+                serde_yaml::to_string(&registered_chains)
+                    .context("Failed serializing registered chains")?
+            )
+        };
+
         const HANDLER_FILES_MUST_EXIST: bool = false;
         const ABI_FILES_MUST_EXIST: bool = true;
 
         Ok(PersistedState {
             envio_version: CURRENT_CRATE_VERSION.to_string(),
-            config_hash: HashString::from_string(config.human_config.to_string()),
+            config_hash: HashString::from_string(match &config.parsed_project_paths.profile {
+                None => config_options.indexing,
+                Some(profile) => format!("profile: {profile}\n{}", config_options.indexing),
+            }),
+            network_config_hashes: NetworkHashStrings::from_strings(config_options.networks),
+            runtime_config_hash: HashString::from_string(runtime_config),
             schema_hash: HashString::from_file_path(schema_path.clone())
                 .context("Failed hashing schema file")?,
+            schema_snapshot: SchemaSnapshot::from_schema(&config.schema)
+                .context("Failed getting schema snapshot")?,
             handler_files_hash: HashString::from_file_paths(
                 all_handler_paths,
                 HANDLER_FILES_MUST_EXIST,
@@ -105,6 +186,9 @@ impl PersistedState {
         let codegen_affecting_fields = vec![
             //If the config has changed, this could affect values in generated code
             StateField::Config,
+            StateField::NetworkConfigs,
+            //Rpc urls and sync options are code generated into the runtime config
+            StateField::RuntimeConfig,
             //If abi files have changed it could affect event types
             StateField::AbiFiles,
             //If schema has changed this will affect generated entity types
@@ -131,11 +215,42 @@ impl PersistedState {
 
         (!non_matching_fields.is_empty(), non_matching_fields)
     }
+
+    ///Whether the changes to the persisted state on the db can be applied without a reset
+    pub fn can_migrate(&self, persisted_state_db: &Self) -> bool {
+        let (_, changes_detected) = self.should_run_db_migrations(persisted_state_db);
+        changes_detected.iter().all(StateField::is_migratable)
+    }
+
+    ///The networks whose indexed data and sync state need to be reset
+    pub fn get_changed_network_ids(&self, persisted_state_db: &Self) -> Vec<u64> {
+        self.network_config_hashes
+            .get_changed_network_ids(&persisted_state_db.network_config_hashes)
+    }
+
+    ///Returns the plan migrating the db from its persisted schema to the current one,
+    ///when the schema changed and none of the other changes require a reset of the db
+    pub fn get_schema_migration_plan(
+        &self,
+        persisted_state_db: &Self,
+        pg_schema: &str,
+    ) -> Option<MigrationPlan> {
+        let (_, changes_detected) = self.should_run_db_migrations(persisted_state_db);
+
+        (changes_detected.contains(&StateField::Schema) && self.can_migrate(persisted_state_db))
+            .then(|| {
+                MigrationPlan::new(
+                    &persisted_state_db.schema_snapshot,
+                    &self.schema_snapshot,
+                    pg_schema,
+                )
+            })
+    }
 }
 
 #[derive(Debug)]
 pub enum PersistedStateExists {
-    Exists(PersistedState),
+    Exists(Box<PersistedState>),
     NotExists,
     Corrupted,
 }
@@ -155,7 +270,7 @@ impl PersistedStateExists {
                 //need to re-codegen so we can just treat it as an option type
                 match serde_json::from_str(&file_str) {
                     Err(_) => Self::Corrupted,
-                    Ok(state) => Self::Exists(state),
+                    Ok(state) => Self::Exists(Box::new(state)),
                 }
             }
         }
@@ -179,7 +294,7 @@ impl From<PersistedState> for PersistedStateJsonString {
 
 #[cfg(test)]
 mod test {
-    use super::PersistedState;
+    use super::{ChangeImpact, PersistedState, SchemaSnapshot, StateField};
     use serde_json::json;
 
     #[test]
@@ -187,7 +302,10 @@ mod test {
         let persisted_file: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -196,7 +314,10 @@ mod test {
         let current_state: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<CHANGED_HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -216,7 +337,10 @@ mod test {
         let persisted_file: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -225,7 +349,10 @@ mod test {
         let current_state: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<CHANGED_HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -245,7 +372,10 @@ mod test {
         let persisted_db: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -254,7 +384,10 @@ mod test {
         let current_state: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<CHANGED_HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<CHANGED_HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -274,7 +407,10 @@ mod test {
         let persisted_db: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -283,7 +419,10 @@ mod test {
         let current_state: PersistedState = serde_json::from_value(json!({
             "envio_version": "0.0.1",
             "config_hash": "<HASH_STRING>",
+            "network_config_hashes": {},
+            "runtime_config_hash": "<HASH_STRING>",
             "schema_hash": "<HASH_STRING>",
+            "schema_snapshot": SchemaSnapshot::default(),
             "handler_files_hash": "<HASH_STRING>",
             "abi_files_hash": "<HASH_STRING>",
         }))
@@ -297,4 +436,131 @@ mod test {
             "should run codegen should be false since nothing changed"
         );
     }
+
+    #[test]
+    fn only_plans_schema_migrations_for_schema_storage_changes() {
+        let state = |config_hash: &str, schema_hash: &str, handler_files_hash: &str| {
+            serde_json::from_value::<PersistedState>(json!({
+                "envio_version": "0.0.1",
+                "config_hash": config_hash,
+                "network_config_hashes": {},
+                "runtime_config_hash": "<HASH_STRING>",
+                "schema_hash": schema_hash,
+                "schema_snapshot": SchemaSnapshot::default(),
+                "handler_files_hash": handler_files_hash,
+                "abi_files_hash": "<HASH_STRING>",
+            }))
+            .unwrap()
+        };
+
+        let persisted_db = state("<HASH_STRING>", "<HASH_STRING>", "<HASH_STRING>");
+        let schema_changed_state = state("<HASH_STRING>", "<CHANGED_HASH_STRING>", "<HASH_STRING>");
+        let handlers_changed_state = state(
+            "<HASH_STRING>",
+            "<CHANGED_HASH_STRING>",
+            "<CHANGED_HASH_STRING>",
+        );
+        let config_changed_state = state(
+            "<CHANGED_HASH_STRING>",
+            "<CHANGED_HASH_STRING>",
+            "<HASH_STRING>",
+        );
+
+        assert!(
+            schema_changed_state
+                .get_schema_migration_plan(&persisted_db, "public")
+                .is_some(),
+            "should plan a schema migration since only the schema changed"
+        );
+        assert!(
+            handlers_changed_state
+                .get_schema_migration_plan(&persisted_db, "public")
+                .is_some(),
+            "should plan a schema migration since handler changes don't affect storage"
+        );
+        assert!(
+            config_changed_state
+                .get_schema_migration_plan(&persisted_db, "public")
+                .is_none(),
+            "should not plan a schema migration since the config changed as well"
+        );
+    }
+
+    #[test]
+    fn classifies_change_impact() {
+        let state = |runtime_config_hash: &str, handler_files_hash: &str| {
+            serde_json::from_value::<PersistedState>(json!({
+                "envio_version": "0.0.1",
+                "config_hash": "<HASH_STRING>",
+                "network_config_hashes": {},
+                "runtime_config_hash": runtime_config_hash,
+                "schema_hash": "<HASH_STRING>",
+                "schema_snapshot": SchemaSnapshot::default(),
+                "handler_files_hash": handler_files_hash,
+                "abi_files_hash": "<HASH_STRING>",
+            }))
+            .unwrap()
+        };
+
+        let persisted_db = state("<HASH_STRING>", "<HASH_STRING>");
+
+        let (_, runtime_changes) =
+            state("<CHANGED_HASH_STRING>", "<HASH_STRING>").should_run_db_migrations(&persisted_db);
+        assert_eq!(runtime_changes, vec![StateField::RuntimeConfig]);
+        assert_eq!(
+            StateField::get_max_change_impact(&runtime_changes),
+            Some(ChangeImpact::Runtime)
+        );
+
+        let (_, results_changes) = state("<CHANGED_HASH_STRING>", "<CHANGED_HASH_STRING>")
+            .should_run_db_migrations(&persisted_db);
+        assert_eq!(
+            StateField::get_max_change_impact(&results_changes),
+            Some(ChangeImpact::Results)
+        );
+
+        let (_, no_changes) =
+            state("<HASH_STRING>", "<HASH_STRING>").should_run_db_migrations(&persisted_db);
+        assert_eq!(StateField::get_max_change_impact(&no_changes), None);
+    }
+
+    #[test]
+    fn migrates_network_config_changes() {
+        let state = |config_hash: &str, network_config_hashes: serde_json::Value| {
+            serde_json::from_value::<PersistedState>(json!({
+                "envio_version": "0.0.1",
+                "config_hash": config_hash,
+                "network_config_hashes": network_config_hashes,
+                "runtime_config_hash": "<HASH_STRING>",
+                "schema_hash": "<HASH_STRING>",
+                "schema_snapshot": SchemaSnapshot::default(),
+                "handler_files_hash": "<HASH_STRING>",
+                "abi_files_hash": "<HASH_STRING>",
+            }))
+            .unwrap()
+        };
+
+        let persisted_db = state(
+            "<HASH_STRING>",
+            json!({"1": "<HASH_STRING>", "137": "<HASH_STRING>"}),
+        );
+        let network_changed_state = state(
+            "<HASH_STRING>",
+            json!({"1": "<HASH_STRING>", "137": "<CHANGED_HASH_STRING>"}),
+        );
+        let config_changed_state = state(
+            "<CHANGED_HASH_STRING>",
+            json!({"1": "<HASH_STRING>", "137": "<CHANGED_HASH_STRING>"}),
+        );
+
+        assert!(network_changed_state.can_migrate(&persisted_db));
+        assert_eq!(
+            network_changed_state.get_changed_network_ids(&persisted_db),
+            vec![137]
+        );
+        assert!(
+            !config_changed_state.can_migrate(&persisted_db),
+            "should require a reset since the config of all networks changed"
+        );
+    }
 }
@@ -0,0 +1,1151 @@
+use crate::config_parsing::{
+    entity_parsing::{Aggregation, Entity, Schema, TIMESTAMP_FIELD_NAME},
+    postgres_types::{Field as PGField, Primitive as PGPrimitive},
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display},
+};
+
+const DB_WRITE_TIMESTAMP_FIELD_NAME: &str = "db_write_timestamp";
+///The columns of an immutable entity tracking the event that created each row,
+///so its rows can be deleted on a rollback without keeping any history
+const IMMUTABLE_ROW_FIELD_NAMES: [&str; 3] =
+    ["envio_chain_id", "envio_block_number", "envio_log_index"];
+const HISTORY_TABLE_SUFFIX: &str = "_history";
+const HISTORY_CHAIN_ID_FIELD_NAME: &str = "entity_history_chain_id";
+///Ordered by the hierarchy of the event ordering
+const HISTORY_CHANGE_FIELD_NAMES: [&str; 4] = [
+    "entity_history_block_timestamp",
+    HISTORY_CHAIN_ID_FIELD_NAME,
+    "entity_history_block_number",
+    "entity_history_log_index",
+];
+const HISTORY_ACTION_FIELD_NAME: &str = "action";
+const HISTORY_ROW_ACTION_ENUM_NAME: &str = "ENTITY_HISTORY_ROW_ACTION";
+///The static tables storing the data and sync state of a single chain
+const CHAIN_SCOPED_TABLE_NAMES: [&str; 5] = [
+    "event_sync_state",
+    "chain_metadata",
+    "end_of_block_range_scanned_data",
+    "raw_events",
+    "dynamic_contract_registry",
+];
+
+///The type of a column, either a builtin sql type or an enum of the schema
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum ColumnType {
+    Builtin(String),
+    Enum(String),
+}
+
+impl ColumnType {
+    fn from_primitive(primitive: &PGPrimitive) -> Self {
+        let builtin = |sql_type: &str| Self::Builtin(sql_type.to_string());
+        match primitive {
+            PGPrimitive::Boolean => builtin("BOOLEAN"),
+            PGPrimitive::Text => builtin("TEXT"),
+            PGPrimitive::Integer => builtin("INTEGER"),
+            PGPrimitive::Numeric(None) => builtin("NUMERIC"),
+            PGPrimitive::Numeric(Some((precision, scale))) => {
+                builtin(&format!("NUMERIC({precision}, {scale})"))
+            }
+            PGPrimitive::DoublePrecision => builtin("DOUBLE PRECISION"),
+            PGPrimitive::Serial => builtin("SERIAL"),
+            PGPrimitive::Json => builtin("JSONB"),
+            PGPrimitive::Timestamp => builtin("TIMESTAMP WITH TIME ZONE"),
+            PGPrimitive::Enum(name) => Self::Enum(name.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ColumnSnapshot {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub is_array: bool,
+    ///Whether the column is created without a NOT NULL constraint
+    pub is_nullable: bool,
+    pub default_value: Option<String>,
+}
+
+impl ColumnSnapshot {
+    fn from_pg_field(field: &PGField) -> Self {
+        Self {
+            name: get_column_name(field),
+            column_type: ColumnType::from_primitive(&field.field_type),
+            is_array: field.is_array,
+            //Columns with a default are never created as NOT NULL
+            is_nullable: field.is_nullable || field.default_value.is_some(),
+            default_value: field.default_value.clone(),
+        }
+    }
+
+    fn builtin(name: &str, sql_type: &str, is_nullable: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            column_type: ColumnType::Builtin(sql_type.to_string()),
+            is_array: false,
+            is_nullable,
+            default_value: None,
+        }
+    }
+}
+
+///Linked entity fields store the id of the entity in an _id suffixed column
+fn get_column_name(field: &PGField) -> String {
+    match field.linked_entity {
+        Some(_) => format!("{}_id", field.field_name),
+        None => field.field_name.clone(),
+    }
+}
+
+///Postgres truncates identifiers longer than 63 bytes, so long index names could collide.
+///Such names are shortened and suffixed with a hash of the full name instead.
+fn shorten_index_name(index_name: String) -> String {
+    const MAX_IDENTIFIER_LENGTH: usize = 63;
+    const HASH_LENGTH: usize = 8;
+    if index_name.len() <= MAX_IDENTIFIER_LENGTH {
+        return index_name;
+    }
+    let hash = format!("{:x}", Sha256::digest(index_name.as_bytes()));
+    let mut prefix_length = MAX_IDENTIFIER_LENGTH - HASH_LENGTH - 1;
+    while !index_name.is_char_boundary(prefix_length) {
+        prefix_length -= 1;
+    }
+    format!("{}_{}", &index_name[..prefix_length], &hash[..HASH_LENGTH])
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct IndexSnapshot {
+    pub column_names: Vec<String>,
+    ///The access method of an index with options, None for the plain btree indices
+    pub method: Option<String>,
+    pub orders: Vec<String>,
+    pub predicate: Option<String>,
+    pub is_unique: bool,
+}
+
+impl IndexSnapshot {
+    ///A plain index, named the same way as the indices created by the migrations
+    fn plain(table_name: &str, column_names: Vec<String>, is_unique: bool) -> (String, Self) {
+        let name = format!(
+            "{table_name}_{}{}",
+            column_names.join("_"),
+            if is_unique { "_unique" } else { "" }
+        );
+        let index = Self {
+            column_names,
+            method: None,
+            orders: vec![],
+            predicate: None,
+            is_unique,
+        };
+        (name, index)
+    }
+
+    fn custom(
+        table_name: &str,
+        column_names: Vec<String>,
+        method: &str,
+        orders: Vec<String>,
+        predicate: Option<String>,
+    ) -> (String, Self) {
+        let name = shorten_index_name(format!(
+            "{table_name}_{}_{method}{}{}",
+            column_names.join("_"),
+            orders
+                .iter()
+                .map(|order| format!("_{}", order.to_lowercase()))
+                .collect::<String>(),
+            if predicate.is_some() { "_partial" } else { "" }
+        ));
+        let index = Self {
+            column_names,
+            method: Some(method.to_string()),
+            orders,
+            predicate,
+            is_unique: false,
+        };
+        (name, index)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct TableSnapshot {
+    pub columns: Vec<ColumnSnapshot>,
+    pub primary_key: Vec<String>,
+    pub indices: BTreeMap<String, IndexSnapshot>,
+}
+
+impl TableSnapshot {
+    fn from_pg_fields(pg_fields: &[PGField]) -> Self {
+        Self {
+            columns: pg_fields
+                .iter()
+                .map(ColumnSnapshot::from_pg_field)
+                .collect(),
+            primary_key: pg_fields
+                .iter()
+                .filter(|field| field.is_primary_key)
+                .map(get_column_name)
+                .collect(),
+            indices: BTreeMap::new(),
+        }
+    }
+
+    fn from_entity(entity: &Entity, schema: &Schema) -> anyhow::Result<Self> {
+        let pg_fields = get_pg_fields(entity, schema)?;
+        let column_names = get_column_names(&pg_fields);
+        let table_name = &entity.name;
+
+        let mut table = Self::from_pg_fields(&pg_fields);
+        table.columns.push(ColumnSnapshot {
+            default_value: Some("CURRENT_TIMESTAMP".to_string()),
+            ..ColumnSnapshot::builtin(DB_WRITE_TIMESTAMP_FIELD_NAME, "TIMESTAMP", true)
+        });
+        if entity.is_immutable {
+            table.columns.extend(
+                IMMUTABLE_ROW_FIELD_NAMES
+                    .iter()
+                    .map(|name| ColumnSnapshot::builtin(name, "INTEGER", false)),
+            );
+        }
+
+        let single_indices = pg_fields
+            .iter()
+            .filter(|field| field.is_index)
+            .map(|field| IndexSnapshot::plain(table_name, vec![get_column_name(field)], false));
+        let composite_indices = entity
+            .get_composite_indices()
+            .into_iter()
+            .map(|field_names| IndexSnapshot::plain(table_name, column_names(&field_names), false));
+        let immutable_row_index = entity.is_immutable.then(|| {
+            IndexSnapshot::plain(
+                table_name,
+                IMMUTABLE_ROW_FIELD_NAMES[..2]
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+                false,
+            )
+        });
+        let custom_indices = entity.get_custom_indices().into_iter().map(|index| {
+            IndexSnapshot::custom(
+                table_name,
+                column_names(index.get_field_names()),
+                index.get_method().to_sql(),
+                index
+                    .get_orders()
+                    .iter()
+                    .map(|order| order.to_sql().to_string())
+                    .collect(),
+                index.get_where_predicate().cloned(),
+            )
+        });
+        let unique_indices = entity.unique_constraints.iter().map(|unique_constraint| {
+            IndexSnapshot::plain(
+                table_name,
+                column_names(unique_constraint.get_field_names()),
+                true,
+            )
+        });
+        table.indices = single_indices
+            .chain(composite_indices)
+            .chain(immutable_row_index)
+            .chain(custom_indices)
+            .chain(unique_indices)
+            .collect();
+
+        Ok(table)
+    }
+
+    ///The history table of a mutable entity, keeping every change of its rows
+    ///with nullable copies of the entity columns
+    fn history_of(entity_table: &Self, history_table_name: &str) -> Self {
+        let current_change_columns = HISTORY_CHANGE_FIELD_NAMES
+            .iter()
+            .map(|name| ColumnSnapshot::builtin(name, "INTEGER", false));
+        let previous_change_columns = HISTORY_CHANGE_FIELD_NAMES
+            .iter()
+            .map(|name| ColumnSnapshot::builtin(&format!("previous_{name}"), "INTEGER", true));
+        let data_columns = entity_table
+            .columns
+            .iter()
+            .filter(|column| column.name != DB_WRITE_TIMESTAMP_FIELD_NAME)
+            .map(|column| ColumnSnapshot {
+                is_nullable: column.is_nullable || !entity_table.primary_key.contains(&column.name),
+                ..column.clone()
+            });
+        let action_column = ColumnSnapshot {
+            name: HISTORY_ACTION_FIELD_NAME.to_string(),
+            column_type: ColumnType::Enum(HISTORY_ROW_ACTION_ENUM_NAME.to_string()),
+            is_array: false,
+            is_nullable: false,
+            default_value: None,
+        };
+        let serial_column = ColumnSnapshot::builtin("serial", "SERIAL", true);
+
+        Self {
+            columns: current_change_columns
+                .chain(previous_change_columns)
+                .chain(data_columns)
+                .chain([action_column, serial_column])
+                .collect(),
+            primary_key: HISTORY_CHANGE_FIELD_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .chain(entity_table.primary_key.clone())
+                .collect(),
+            indices: [IndexSnapshot::plain(
+                history_table_name,
+                vec!["serial".to_string()],
+                false,
+            )]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    fn from_aggregation(
+        aggregation: &Aggregation,
+        table_name: &str,
+        schema: &Schema,
+    ) -> anyhow::Result<Self> {
+        let pg_fields = get_pg_fields(&aggregation.entity, schema)?;
+        let column_names = get_column_names(&pg_fields);
+        let mut table = Self::from_pg_fields(&pg_fields);
+        let bucket_field_names = aggregation
+            .get_dimension_field_names()
+            .into_iter()
+            .chain([TIMESTAMP_FIELD_NAME.to_string()])
+            .collect::<Vec<_>>();
+        table.indices = [IndexSnapshot::custom(
+            table_name,
+            column_names(&bucket_field_names),
+            "btree",
+            vec![],
+            None,
+        )]
+        .into_iter()
+        .collect();
+        Ok(table)
+    }
+}
+
+fn get_pg_fields(entity: &Entity, schema: &Schema) -> anyhow::Result<Vec<PGField>> {
+    Ok(entity
+        .get_fields()
+        .iter()
+        .map(|field| field.get_postgres_field(schema, entity))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context(format!("Failed getting the columns of {}", entity.name))?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+///Returns a function mapping field names to their column names
+fn get_column_names(pg_fields: &[PGField]) -> impl Fn(&[String]) -> Vec<String> {
+    let lookup: HashMap<String, String> = pg_fields
+        .iter()
+        .map(|field| (field.field_name.clone(), get_column_name(field)))
+        .collect();
+    move |field_names| {
+        field_names
+            .iter()
+            .map(|field_name| lookup.get(field_name).unwrap_or(field_name).clone())
+            .collect()
+    }
+}
+
+///The columns of the interface or union view and the tables it selects from
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ViewSnapshot {
+    pub table_names: Vec<String>,
+    pub column_names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FulltextSearchSnapshot {
+    pub table_name: String,
+    pub language: String,
+    pub field_names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AggregationSnapshot {
+    pub source: String,
+    pub table_names: Vec<String>,
+    ///Each aggregate as field: fn(arg)
+    pub aggregates: Vec<String>,
+}
+
+///The database objects of an indexer derived from its parsed schema. It's persisted
+///along with the schema hash, so the next schema can be diffed against it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct SchemaSnapshot {
+    pub enums: BTreeMap<String, Vec<String>>,
+    pub tables: BTreeMap<String, TableSnapshot>,
+    pub views: BTreeMap<String, ViewSnapshot>,
+    pub fulltext_searches: BTreeMap<String, FulltextSearchSnapshot>,
+    pub aggregations: BTreeMap<String, AggregationSnapshot>,
+}
+
+impl SchemaSnapshot {
+    pub fn from_schema(schema: &Schema) -> anyhow::Result<Self> {
+        let mut snapshot = Self::default();
+
+        for gql_enum in schema.enums.values() {
+            snapshot
+                .enums
+                .insert(gql_enum.name.clone(), gql_enum.values.clone());
+        }
+
+        for entity in schema.entities.values() {
+            let table = TableSnapshot::from_entity(entity, schema)?;
+            //Immutable entities are insert only and don't keep any history
+            if !entity.is_immutable {
+                let history_table_name = format!("{}{HISTORY_TABLE_SUFFIX}", entity.name);
+                let history_table = TableSnapshot::history_of(&table, &history_table_name);
+                snapshot.tables.insert(history_table_name, history_table);
+            }
+            snapshot.tables.insert(entity.name.clone(), table);
+
+            for fulltext_search in &entity.fulltext_searches {
+                snapshot.fulltext_searches.insert(
+                    fulltext_search.name.clone(),
+                    FulltextSearchSnapshot {
+                        table_name: entity.name.clone(),
+                        language: fulltext_search.language.clone(),
+                        field_names: fulltext_search.field_names.clone(),
+                    },
+                );
+            }
+        }
+
+        for interface in schema.interfaces.values() {
+            let view = ViewSnapshot {
+                table_names: schema
+                    .get_interface_implementations(interface)
+                    .iter()
+                    .map(|entity| entity.name.clone())
+                    .collect(),
+                column_names: interface.get_db_field_names(schema).context(format!(
+                    "Failed getting db field names of interface {}",
+                    interface.name
+                ))?,
+            };
+            snapshot.views.insert(interface.name.clone(), view);
+        }
+
+        for union in schema.unions.values() {
+            let view = ViewSnapshot {
+                table_names: union.types.clone(),
+                column_names: vec!["id".to_string()],
+            };
+            snapshot.views.insert(union.name.clone(), view);
+        }
+
+        for (name, aggregation) in &schema.aggregations {
+            let mut table_names = vec![];
+            for interval in &aggregation.intervals {
+                let table_name = format!("{name}_{}", interval.to_sql());
+                let table = TableSnapshot::from_aggregation(aggregation, &table_name, schema)?;
+                snapshot.tables.insert(table_name.clone(), table);
+                table_names.push(table_name);
+            }
+            let aggregates = aggregation
+                .aggregates
+                .iter()
+                .map(|aggregate| {
+                    format!(
+                        "{}: {}({})",
+                        aggregate.field_name,
+                        aggregate.aggregate_fn.to_rescript_constructor(),
+                        aggregate.arg.clone().unwrap_or_default()
+                    )
+                })
+                .collect();
+            snapshot.aggregations.insert(
+                name.clone(),
+                AggregationSnapshot {
+                    source: aggregation.source.clone(),
+                    table_names,
+                    aggregates,
+                },
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    ///The names of the entity tables along with the names of their history tables
+    fn get_entity_history_table_names(&self) -> Vec<(String, String)> {
+        self.tables
+            .keys()
+            .filter_map(|name| {
+                let history_table_name = format!("{name}{HISTORY_TABLE_SUFFIX}");
+                self.tables
+                    .contains_key(&history_table_name)
+                    .then(|| (name.clone(), history_table_name))
+            })
+            .collect()
+    }
+
+    ///Why the data of single chains can't be reset, if it can't. The entity changes of a
+    ///chain are rolled back from the entity history, so every entity needs its full history.
+    ///It's pruned unless save_full_history is enabled, and immutable entities don't keep any
+    ///history, so their rows can't be rolled back
+    pub fn get_chain_reset_blocker(&self, save_full_history: bool) -> Option<String> {
+        if !save_full_history {
+            return Some(
+                "save_full_history isn't enabled, so the entity history needed to roll back the \
+                 changes of single chains is pruned"
+                    .to_string(),
+            );
+        }
+
+        let aggregation_table_names = self
+            .aggregations
+            .values()
+            .flat_map(|aggregation| aggregation.table_names.clone())
+            .collect::<Vec<_>>();
+        let entity_history_table_names = self.get_entity_history_table_names();
+        let tables_without_history = self
+            .tables
+            .keys()
+            .filter(|name| {
+                !aggregation_table_names.contains(name)
+                    && !entity_history_table_names
+                        .iter()
+                        .any(|(entity, history)| entity == *name || history == *name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        (!tables_without_history.is_empty()).then(|| {
+            format!(
+                "the tables {} don't keep an entity history (eg. immutable entities), so their \
+                 rows can't be rolled back for single chains",
+                tables_without_history.join(", ")
+            )
+        })
+    }
+
+    ///The statements resetting the given chains, so they get indexed again from their start
+    ///blocks. Every entity changed by the chains is rolled back to its last change by another
+    ///chain, or deleted when there is none, before the history rows of the chains and the chain
+    ///scoped data and sync state are deleted. Join and aggregation tables are kept in sync with
+    ///the entity rows by their triggers. Only valid when the chains can be reset, see
+    ///get_chain_reset_blocker
+    pub fn get_chain_reset_statements(&self, chain_ids: &[u64], pg_schema: &str) -> Vec<String> {
+        let chain_ids = chain_ids
+            .iter()
+            .map(|chain_id| chain_id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let latest_change_first = HISTORY_CHANGE_FIELD_NAMES
+            .iter()
+            .map(|name| format!("{name} DESC"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let entity_rollbacks = self.get_entity_history_table_names().into_iter().flat_map(
+            |(table_name, history_table_name)| {
+                let table_path = format!("\"{pg_schema}\".\"{table_name}\"");
+                let history_table_path = format!("\"{pg_schema}\".\"{history_table_name}\"");
+                let columns = self.tables[&table_name]
+                    .columns
+                    .iter()
+                    .filter(|column| column.name != DB_WRITE_TIMESTAMP_FIELD_NAME)
+                    .map(|column| format!("\"{}\"", column.name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let changed_ids = format!(
+                    "SELECT id FROM {history_table_path} WHERE {HISTORY_CHAIN_ID_FIELD_NAME} IN \
+                     ({chain_ids})"
+                );
+                [
+                    format!("DELETE FROM {table_path} WHERE id IN ({changed_ids});"),
+                    format!(
+                        "INSERT INTO {table_path} ({columns}) SELECT {columns} FROM (SELECT \
+                         DISTINCT ON (id) * FROM {history_table_path} WHERE id IN ({changed_ids}) \
+                         AND {HISTORY_CHAIN_ID_FIELD_NAME} NOT IN ({chain_ids}) ORDER BY id, \
+                         {latest_change_first}) AS latest_change WHERE \
+                         {HISTORY_ACTION_FIELD_NAME} = 'SET';"
+                    ),
+                    format!(
+                        "DELETE FROM {history_table_path} WHERE {HISTORY_CHAIN_ID_FIELD_NAME} IN \
+                         ({chain_ids});"
+                    ),
+                ]
+            },
+        );
+
+        entity_rollbacks
+            .chain(CHAIN_SCOPED_TABLE_NAMES.iter().map(|table_name| {
+                format!(
+                    "DELETE FROM \"{pg_schema}\".\"{table_name}\" WHERE chain_id IN ({chain_ids});"
+                )
+            }))
+            .chain([format!(
+                "DELETE FROM \"{pg_schema}\".\"dynamic_contract_registry{HISTORY_TABLE_SUFFIX}\" \
+                 WHERE {HISTORY_CHAIN_ID_FIELD_NAME} IN ({chain_ids});"
+            )])
+            .collect()
+    }
+}
+
+//The snapshot is stored as json in a text column of the persisted state table
+impl Type<Postgres> for SchemaSnapshot {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for SchemaSnapshot {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        let json = serde_json::to_string(self).expect("Schema snapshot should serialize to json");
+        <String as Encode<Postgres>>::encode(json, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for SchemaSnapshot {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let json = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+///The statements migrating the database from one schema snapshot to the next.
+///The up migrations are the only source of the schema ddl, they create the missing
+///enums, tables, columns and indices. So the plan only alters or drops the existing
+///objects which changed, before the up migrations run. Changes which can't be applied
+///without losing or invalidating data are collected as destructive changes and
+///require a reset instead
+#[derive(Debug, Default, PartialEq)]
+pub struct MigrationPlan {
+    ///Values added to the existing enums. They can't be used in the transaction adding
+    ///them, so they are committed before the other statements
+    pub enum_statements: Vec<String>,
+    ///Applied in a single transaction
+    pub statements: Vec<String>,
+    ///The objects the up migrations create afterwards
+    pub created_objects: Vec<String>,
+    pub destructive_changes: Vec<String>,
+}
+
+impl MigrationPlan {
+    pub fn new(previous: &SchemaSnapshot, current: &SchemaSnapshot, pg_schema: &str) -> Self {
+        let mut plan = Self::default();
+        plan.add_enum_changes(previous, current, pg_schema);
+        plan.add_view_changes(previous, current, pg_schema);
+        plan.add_fulltext_search_changes(previous, current, pg_schema);
+        plan.add_aggregation_changes(previous, current);
+        plan.add_table_changes(previous, current, pg_schema);
+        //Enums can only be dropped once no column uses them anymore
+        for name in previous.enums.keys() {
+            if !current.enums.contains_key(name) {
+                plan.statements
+                    .push(format!("DROP TYPE IF EXISTS \"{pg_schema}\".{name};"));
+            }
+        }
+        plan
+    }
+
+    ///Whether the database needs to be dropped and set up from scratch
+    pub fn requires_reset(&self) -> bool {
+        !self.destructive_changes.is_empty()
+    }
+
+    ///Whether the plan has no statements to apply before the up migrations
+    pub fn is_empty(&self) -> bool {
+        self.enum_statements.is_empty() && self.statements.is_empty()
+    }
+
+    fn add_enum_changes(
+        &mut self,
+        previous: &SchemaSnapshot,
+        current: &SchemaSnapshot,
+        pg_schema: &str,
+    ) {
+        for (name, values) in &current.enums {
+            match previous.enums.get(name) {
+                None => self.created_objects.push(format!("The enum {name}")),
+                Some(previous_values) => {
+                    for value in previous_values {
+                        if !values.contains(value) {
+                            self.destructive_changes
+                                .push(format!("The value {value} was removed from enum {name}"));
+                        }
+                    }
+                    for value in values {
+                        if !previous_values.contains(value) {
+                            self.enum_statements.push(format!(
+                                "ALTER TYPE \"{pg_schema}\".{name} ADD VALUE IF NOT EXISTS \
+                                 '{value}';"
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ///Changed views are dropped and recreated by the up migrations, since the
+    ///columns of a view can't be replaced
+    fn add_view_changes(
+        &mut self,
+        previous: &SchemaSnapshot,
+        current: &SchemaSnapshot,
+        pg_schema: &str,
+    ) {
+        for (name, view) in &previous.views {
+            if current.views.get(name) != Some(view) {
+                self.statements
+                    .push(format!("DROP VIEW IF EXISTS \"{pg_schema}\".\"{name}\";"));
+            }
+        }
+    }
+
+    ///Full text search columns are generated, so changed searches can be dropped
+    ///and recreated by the up migrations without losing data
+    fn add_fulltext_search_changes(
+        &mut self,
+        previous: &SchemaSnapshot,
+        current: &SchemaSnapshot,
+        pg_schema: &str,
+    ) {
+        for (name, fulltext_search) in &previous.fulltext_searches {
+            if current.fulltext_searches.get(name) != Some(fulltext_search) {
+                self.statements.push(format!(
+                    "DROP FUNCTION IF EXISTS \"{pg_schema}\".\"{name}\"(text);"
+                ));
+                self.statements.push(format!(
+                    "ALTER TABLE \"{pg_schema}\".\"{}\" DROP COLUMN IF EXISTS \"{name}\";",
+                    fulltext_search.table_name
+                ));
+            }
+        }
+    }
+
+    ///The buckets of an aggregation are only maintained for new source rows,
+    ///so any aggregation over existing rows needs to be recomputed from scratch
+    fn add_aggregation_changes(&mut self, previous: &SchemaSnapshot, current: &SchemaSnapshot) {
+        for (name, aggregation) in &previous.aggregations {
+            match current.aggregations.get(name) {
+                None => self
+                    .destructive_changes
+                    .push(format!("The aggregation {name} was removed")),
+                Some(current_aggregation) if current_aggregation != aggregation => self
+                    .destructive_changes
+                    .push(format!("The aggregation {name} was changed")),
+                Some(_) => (),
+            }
+        }
+        for (name, aggregation) in &current.aggregations {
+            if !previous.aggregations.contains_key(name)
+                && previous.tables.contains_key(&aggregation.source)
+            {
+                self.destructive_changes.push(format!(
+                    "The aggregation {name} was added over the existing rows of {}",
+                    aggregation.source
+                ));
+            }
+        }
+    }
+
+    fn add_table_changes(
+        &mut self,
+        previous: &SchemaSnapshot,
+        current: &SchemaSnapshot,
+        pg_schema: &str,
+    ) {
+        for table_name in previous.tables.keys() {
+            if !current.tables.contains_key(table_name) {
+                self.destructive_changes
+                    .push(format!("The table {table_name} was removed"));
+            }
+        }
+        for (table_name, table) in &current.tables {
+            match previous.tables.get(table_name) {
+                None => self.created_objects.push(format!("The table {table_name}")),
+                Some(previous_table) => {
+                    self.add_column_changes(table_name, previous_table, table, pg_schema);
+                    self.add_index_changes(table_name, previous_table, table, pg_schema);
+                }
+            }
+        }
+    }
+
+    fn add_column_changes(
+        &mut self,
+        table_name: &str,
+        previous: &TableSnapshot,
+        current: &TableSnapshot,
+        pg_schema: &str,
+    ) {
+        if previous.primary_key != current.primary_key {
+            self.destructive_changes
+                .push(format!("The primary key of table {table_name} was changed"));
+        }
+        let table_path = format!("\"{pg_schema}\".\"{table_name}\"");
+        let find_column = |columns: &[ColumnSnapshot], name: &str| {
+            columns.iter().find(|column| column.name == name).cloned()
+        };
+
+        for column in &previous.columns {
+            if find_column(&current.columns, &column.name).is_none() {
+                self.destructive_changes.push(format!(
+                    "The column {} was removed from table {table_name}",
+                    column.name
+                ));
+            }
+        }
+
+        for column in &current.columns {
+            let name = &column.name;
+            let Some(previous_column) = find_column(&previous.columns, name) else {
+                if column.is_nullable {
+                    self.created_objects
+                        .push(format!("The column {name} of table {table_name}"));
+                } else {
+                    self.destructive_changes.push(format!(
+                        "The non nullable column {name} without a default was added to table \
+                         {table_name}"
+                    ));
+                }
+                continue;
+            };
+
+            if previous_column.column_type != column.column_type
+                || previous_column.is_array != column.is_array
+            {
+                self.destructive_changes.push(format!(
+                    "The type of column {name} in table {table_name} was changed"
+                ));
+                continue;
+            }
+            if previous_column.default_value != column.default_value {
+                self.statements.push(match &column.default_value {
+                    Some(default_value) => format!(
+                        "ALTER TABLE {table_path} ALTER COLUMN \"{name}\" SET DEFAULT \
+                         {default_value};"
+                    ),
+                    None => {
+                        format!("ALTER TABLE {table_path} ALTER COLUMN \"{name}\" DROP DEFAULT;")
+                    }
+                });
+            }
+            match (previous_column.is_nullable, column.is_nullable) {
+                (false, true) => self.statements.push(format!(
+                    "ALTER TABLE {table_path} ALTER COLUMN \"{name}\" DROP NOT NULL;"
+                )),
+                (true, false) => self.destructive_changes.push(format!(
+                    "The column {name} in table {table_name} was made non nullable"
+                )),
+                _ => (),
+            }
+        }
+    }
+
+    fn add_index_changes(
+        &mut self,
+        table_name: &str,
+        previous: &TableSnapshot,
+        current: &TableSnapshot,
+        pg_schema: &str,
+    ) {
+        for (index_name, index) in &previous.indices {
+            if current.indices.get(index_name) != Some(index) {
+                self.statements.push(format!(
+                    "DROP INDEX IF EXISTS \"{pg_schema}\".\"{index_name}\";"
+                ));
+            }
+        }
+        for (index_name, index) in &current.indices {
+            if previous.indices.get(index_name) != Some(index) {
+                self.created_objects
+                    .push(format!("The index {index_name} of table {table_name}"));
+            }
+        }
+    }
+}
+
+impl Display for MigrationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .enum_statements
+            .iter()
+            .chain(&self.statements)
+            .cloned()
+            .chain(
+                self.created_objects
+                    .iter()
+                    .map(|object| format!("-- Created by the up migrations: {object}")),
+            )
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MigrationPlan, SchemaSnapshot};
+    use crate::config_parsing::entity_parsing::Schema;
+    use sha2::{Digest, Sha256};
+
+    fn snapshot(schema_str: &str) -> SchemaSnapshot {
+        let schema_doc = graphql_parser::schema::parse_schema::<String>(schema_str).unwrap();
+        let schema = Schema::from_document(schema_doc).expect("bad schema");
+        SchemaSnapshot::from_schema(&schema).expect("failed getting schema snapshot")
+    }
+
+    #[test]
+    fn plans_additive_changes() {
+        let previous = snapshot(
+            r#"
+            enum Status { Active }
+            type Token @entity {
+              id: ID!
+              name: String!
+              symbol: String!
+            }
+            "#,
+        );
+        let current = snapshot(
+            r#"
+            enum Status { Active Paused }
+            type Token @entity {
+              id: ID!
+              name: String
+              symbol: String! @index
+              status: Status
+              decimals: Int! @default(value: 18)
+            }
+            type Pool @entity(immutable: true) {
+              id: ID!
+            }
+            "#,
+        );
+
+        let plan = MigrationPlan::new(&previous, &current, "public");
+
+        assert_eq!(plan.destructive_changes, Vec::<String>::new());
+        assert_eq!(
+            plan.enum_statements,
+            vec![r#"ALTER TYPE "public".Status ADD VALUE IF NOT EXISTS 'Paused';"#]
+        );
+        assert_eq!(
+            plan.statements,
+            vec![r#"ALTER TABLE "public"."Token" ALTER COLUMN "name" DROP NOT NULL;"#]
+        );
+        assert_eq!(
+            plan.created_objects,
+            vec![
+                "The table Pool",
+                "The column decimals of table Token",
+                "The column status of table Token",
+                "The index Token_symbol of table Token",
+                "The column decimals of table Token_history",
+                "The column status of table Token_history",
+            ]
+        );
+    }
+
+    #[test]
+    fn plans_reset_for_destructive_changes() {
+        let previous = snapshot(
+            r#"
+            enum Status { Active Paused }
+            type Token @entity {
+              id: ID!
+              name: String
+              symbol: String!
+              status: Status!
+            }
+            "#,
+        );
+        let current = snapshot(
+            r#"
+            enum Status { Active }
+            type Token @entity {
+              id: ID!
+              name: String!
+              symbol: Int!
+              decimals: Int!
+            }
+            "#,
+        );
+
+        let plan = MigrationPlan::new(&previous, &current, "public");
+
+        assert!(plan.requires_reset());
+        assert_eq!(
+            plan.destructive_changes,
+            vec![
+                "The value Paused was removed from enum Status",
+                "The column status was removed from table Token",
+                "The non nullable column decimals without a default was added to table Token",
+                "The column name in table Token was made non nullable",
+                "The type of column symbol in table Token was changed",
+                "The column status was removed from table Token_history",
+                "The type of column symbol in table Token_history was changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn serializes_schema_snapshot() {
+        let schema_snapshot = snapshot(
+            r#"
+            type Token @entity {
+              id: ID!
+              owner: User! @index
+            }
+            type User @entity {
+              id: ID!
+              tokens: [Token!]! @derivedFrom(field: "owner")
+            }
+            "#,
+        );
+
+        let json = serde_json::to_string(&schema_snapshot).unwrap();
+        let deserialized: SchemaSnapshot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized, schema_snapshot);
+        let plan = MigrationPlan::new(&schema_snapshot, &deserialized, "public");
+        assert!(
+            plan.is_empty() && plan.created_objects.is_empty(),
+            "An unchanged schema shouldn't need any migrations"
+        );
+    }
+
+    #[test]
+    fn shortens_long_index_names() {
+        let schema_snapshot = snapshot(
+            r#"
+            type VeryLongEntityNameForTesting @entity {
+              id: ID!
+              someVeryLongFieldNameForTheIndex: Int! @index(method: brin)
+              amount: Int! @index(method: brin)
+            }
+            "#,
+        );
+
+        let index_names = schema_snapshot.tables["VeryLongEntityNameForTesting"]
+            .indices
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            index_names,
+            vec![
+                "VeryLongEntityNameForTesting_amount_brin".to_string(),
+                format!(
+                    "VeryLongEntityNameForTesting_someVeryLongFieldNameForT_{}",
+                    &format!(
+                        "{:x}",
+                        Sha256::digest(
+                            "VeryLongEntityNameForTesting_someVeryLongFieldNameForTheIndex_brin"
+                        )
+                    )[..8]
+                ),
+            ]
+        );
+        assert!(index_names.iter().all(|index_name| index_name.len() <= 63));
+    }
+
+    #[test]
+    fn resets_chain_scoped_data() {
+        let schema_snapshot = snapshot(
+            r#"
+            type Token @entity {
+              id: ID!
+              owner: User!
+            }
+            type User @entity {
+              id: ID!
+            }
+            "#,
+        );
+
+        let statements = schema_snapshot.get_chain_reset_statements(&[1, 137], "public");
+
+        assert!(statements.contains(
+            &"DELETE FROM \"public\".\"event_sync_state\" WHERE chain_id IN (1, 137);".to_string()
+        ));
+        assert!(statements.contains(
+            &"DELETE FROM \"public\".\"Token\" WHERE id IN (SELECT id FROM \
+              \"public\".\"Token_history\" WHERE entity_history_chain_id IN (1, 137));"
+                .to_string()
+        ));
+        assert!(statements.contains(
+            &"INSERT INTO \"public\".\"Token\" (\"id\", \"owner_id\") SELECT \"id\", \"owner_id\" \
+              FROM (SELECT DISTINCT ON (id) * FROM \"public\".\"Token_history\" WHERE id IN \
+              (SELECT id FROM \"public\".\"Token_history\" WHERE entity_history_chain_id IN (1, \
+              137)) AND entity_history_chain_id NOT IN (1, 137) ORDER BY id, \
+              entity_history_block_timestamp DESC, entity_history_chain_id DESC, \
+              entity_history_block_number DESC, entity_history_log_index DESC) AS latest_change \
+              WHERE action = 'SET';"
+                .to_string()
+        ));
+        let history_delete_position = statements.iter().position(|s| {
+            s == "DELETE FROM \"public\".\"Token_history\" WHERE entity_history_chain_id IN (1, \
+                  137);"
+        });
+        let rollback_position = statements
+            .iter()
+            .position(|s| s.starts_with("INSERT INTO \"public\".\"Token\""));
+        assert!(
+            rollback_position < history_delete_position,
+            "The entities should be rolled back before their history is deleted"
+        );
+    }
+
+    #[test]
+    fn resets_chains_only_with_full_entity_history() {
+        let schema_snapshot = snapshot(
+            r#"
+            type Token @entity {
+              id: ID!
+            }
+            "#,
+        );
+        assert_eq!(schema_snapshot.get_chain_reset_blocker(true), None);
+        assert!(
+            schema_snapshot.get_chain_reset_blocker(false).is_some(),
+            "The history is pruned without save_full_history"
+        );
+
+        let schema_snapshot = snapshot(
+            r#"
+            type Token @entity {
+              id: ID!
+            }
+            type Transfer @entity(immutable: true) {
+              id: ID!
+            }
+            "#,
+        );
+        assert_eq!(
+            schema_snapshot.get_chain_reset_blocker(true),
+            Some(
+                "the tables Transfer don't keep an entity history (eg. immutable entities), so \
+                 their rows can't be rolled back for single chains"
+                    .to_string()
+            ),
+            "Immutable entities don't keep any history to roll back from"
+        );
+    }
+}
@@ -17,12 +17,19 @@ impl HandlerPathsTemplate {
     pub fn from_contract(
         contract: &system_config::Contract,
         project_paths: &ParsedProjectPaths,
+    ) -> anyhow::Result<Self> {
+        Self::from_handler_path(&contract.handler_path, project_paths)
+    }
+
+    pub fn from_handler_path(
+        handler_path: &str,
+        project_paths: &ParsedProjectPaths,
     ) -> anyhow::Result<Self> {
         let config_directory = project_paths
             .config
             .parent()
             .ok_or_else(|| anyhow!("Unexpected config file should have a parent directory"))?;
-        let handler_path_relative = PathBuf::from(&contract.handler_path);
+        let handler_path_relative = PathBuf::from(handler_path);
         let handler_path_joined = config_directory.join(handler_path_relative);
         let absolute_path = path_utils::normalize_path(handler_path_joined);
 
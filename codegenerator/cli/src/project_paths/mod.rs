@@ -3,6 +3,7 @@ use std::path::{Component, PathBuf};
 
 use crate::{
     cli_args::{clap_definitions::ProjectPaths, init_config::InitConfig},
+    config_parsing::chain_registry::ChainRegistry,
     constants::project_paths::{
         DEFAULT_CONFIG_PATH, DEFAULT_GENERATED_PATH, DEFAULT_PROJECT_ROOT_PATH,
     },
@@ -16,6 +17,10 @@ pub struct ParsedProjectPaths {
     pub project_root: PathBuf,
     pub config: PathBuf,
     pub generated: PathBuf,
+    pub profile: Option<String>,
+    ///The user level chains registry, kept out of the project paths by default
+    ///so that only the cli entry points read it
+    pub user_chains_path: Option<PathBuf>,
 }
 
 impl ParsedProjectPaths {
@@ -44,9 +49,34 @@ impl ParsedProjectPaths {
             project_root,
             generated,
             config,
+            profile: None,
+            user_chains_path: None,
         })
     }
 
+    pub fn with_profile(self, profile: Option<String>) -> Self {
+        ParsedProjectPaths { profile, ..self }
+    }
+
+    pub fn with_user_chains_path(self, user_chains_path: Option<PathBuf>) -> Self {
+        ParsedProjectPaths {
+            user_chains_path,
+            ..self
+        }
+    }
+
+    ///Gets the path to the config overlay of the active profile,
+    ///eg. config.staging.yaml for the config.yaml file and the staging profile
+    pub fn get_profile_config_path(&self) -> Option<PathBuf> {
+        let profile = self.profile.as_ref()?;
+        let file_stem = self.config.file_stem()?.to_string_lossy();
+        let file_name = match self.config.extension() {
+            Some(extension) => format!("{file_stem}.{profile}.{}", extension.to_string_lossy()),
+            None => format!("{file_stem}.{profile}"),
+        };
+        Some(self.config.with_file_name(file_name))
+    }
+
     pub fn default_with_root(project_root: &str) -> anyhow::Result<ParsedProjectPaths> {
         Self::new(project_root, DEFAULT_GENERATED_PATH, DEFAULT_CONFIG_PATH)
     }
@@ -75,13 +105,20 @@ impl TryFrom<ProjectPaths> for ParsedProjectPaths {
             &project_paths.output_directory,
             &project_paths.config,
         )
+        .map(|parsed_paths| {
+            parsed_paths
+                .with_profile(project_paths.profile)
+                .with_user_chains_path(ChainRegistry::get_user_chains_path())
+        })
     }
 }
 
 impl TryFrom<InitConfig> for ParsedProjectPaths {
     type Error = anyhow::Error;
     fn try_from(init_config: InitConfig) -> Result<Self, Self::Error> {
-        Self::default_with_root(&init_config.directory)
+        Self::default_with_root(&init_config.directory).map(|parsed_paths| {
+            parsed_paths.with_user_chains_path(ChainRegistry::get_user_chains_path())
+        })
     }
 }
 
@@ -99,6 +136,8 @@ mod tests {
             project_root: PathBuf::from("."),
             config: PathBuf::from("config.yaml"),
             generated: PathBuf::from("generated"),
+            profile: None,
+            user_chains_path: None,
         };
         assert_eq!(expected_project_paths, project_paths,)
     }
@@ -114,6 +153,8 @@ mod tests {
             config: PathBuf::from("my_dir/my_project/custom_config.yaml"),
 
             generated: PathBuf::from("my_dir/my_project/custom_gen/my_project_generated"),
+            profile: None,
+            user_chains_path: None,
         };
         assert_eq!(expected_project_paths, project_paths,)
     }
@@ -128,6 +169,8 @@ mod tests {
             project_root: PathBuf::from("../my_dir/my_project/"),
             config: PathBuf::from("../my_dir/my_project/custom_config.yaml"),
             generated: PathBuf::from("../my_dir/my_project/custom_gen/my_project_generated"),
+            profile: None,
+            user_chains_path: None,
         };
         assert_eq!(expected_project_paths, project_paths)
     }
@@ -150,6 +193,22 @@ mod tests {
         ParsedProjectPaths::new(project_root, config, generated).unwrap();
     }
 
+    #[test]
+    fn test_profile_config_path() {
+        let project_paths = ParsedProjectPaths::new("my_project", "generated", "config.yaml")
+            .unwrap()
+            .with_profile(Some("staging".to_string()));
+
+        assert_eq!(
+            project_paths.get_profile_config_path(),
+            Some(PathBuf::from("my_project/config.staging.yaml"))
+        );
+        assert_eq!(
+            project_paths.with_profile(None).get_profile_config_path(),
+            None
+        );
+    }
+
     #[test]
     fn check_default_does_not_panic() {
         ParsedProjectPaths::default();
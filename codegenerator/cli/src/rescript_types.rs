@@ -376,6 +376,7 @@ pub enum RescriptTypeIdent {
     Bool,
     Unknown,
     Timestamp,
+    Json,
     //Enums defined in the user's schema
     SchemaEnum(CapitalizedOptions),
     Array(Box<RescriptTypeIdent>),
@@ -416,6 +417,7 @@ impl RescriptTypeIdent {
             Self::ID => "id".to_string(),
             Self::Bool => "bool".to_string(),
             Self::Timestamp => "Js.Date.t".to_string(),
+            Self::Json => "Js.Json.t".to_string(),
             Self::Array(inner_type) => {
                 format!("array<{}>", inner_type)
             }
@@ -468,6 +470,7 @@ impl RescriptTypeIdent {
             Self::ID => "S.string".to_string(),
             Self::Bool => "S.bool".to_string(),
             Self::Timestamp => "Utils.Schema.dbDate".to_string(),
+            Self::Json => "S.json(~validate=true)".to_string(),
             Self::Array(inner_type) => {
                 format!("S.array({})", inner_type.to_rescript_schema(mode))
             }
@@ -523,6 +526,7 @@ impl RescriptTypeIdent {
             | Self::ID
             | Self::Bool
             | Self::Timestamp
+            | Self::Json
             | Self::SchemaEnum(_)
             | Self::GenericParam(_) => vec![],
             Self::TypeApplication {
@@ -555,6 +559,7 @@ impl RescriptTypeIdent {
             Self::ID => "\"my_id\"".to_string(),
             Self::Bool => "false".to_string(),
             Self::Timestamp => "Js.Date.fromFloat(0.)".to_string(),
+            Self::Json => "Js.Json.null".to_string(),
             Self::Array(_) => "[]".to_string(),
             Self::Option(_) => "None".to_string(),
             Self::SchemaEnum(enum_name) => {
@@ -621,6 +626,7 @@ impl RescriptTypeIdent {
             Self::ID => "\"my_id\"".to_string(),
             Self::Bool => "false".to_string(),
             Self::Timestamp => "new Date(0)".to_string(),
+            Self::Json => "null".to_string(),
             Self::Array(_) => "[]".to_string(),
             Self::Option(_) => "null".to_string(),
             Self::SchemaEnum(enum_name) => {
@@ -782,6 +788,30 @@ mod tests {
                 ),
             "S.option(BigInt.nativeSchema)".to_string()
         );
+        let nullable_list_of_nullable_lists =
+            RescriptTypeIdent::option(RescriptTypeIdent::array(RescriptTypeIdent::option(
+                RescriptTypeIdent::array(RescriptTypeIdent::option(RescriptTypeIdent::BigInt)),
+            )));
+        assert_eq!(
+            nullable_list_of_nullable_lists.to_string(),
+            "option<array<option<array<option<bigint>>>>>".to_string()
+        );
+        assert_eq!(
+            nullable_list_of_nullable_lists
+                .clone()
+                .get_expr()
+                .to_rescript_schema(&"eventArgs".to_string(), &RescriptSchemaMode::ForDb),
+            "S.null(S.array(S.null(S.array(S.null(BigInt.schema)))))".to_string()
+        );
+        assert_eq!(
+            nullable_list_of_nullable_lists
+                .get_expr()
+                .to_rescript_schema(
+                    &"eventArgs".to_string(),
+                    &RescriptSchemaMode::ForFieldSelection
+                ),
+            "S.option(S.array(S.option(S.array(S.option(BigInt.nativeSchema)))))".to_string()
+        );
         assert_eq!(
             RescriptTypeExpr::Identifier(RescriptTypeIdent::Tuple(vec![
                 RescriptTypeIdent::Int,
@@ -813,8 +843,8 @@ mod tests {
                 RescriptRecordField::new("fieldB".to_string(), RescriptTypeIdent::Bool),
             ])
             .to_rescript_schema(&"eventArgs".to_string(), &RescriptSchemaMode::ForDb),
-            "S.object((s): eventArgs => {fieldA: s.field(\"fieldA\", \
-             S.int), fieldB: s.field(\"fieldB\", S.bool)})"
+            "S.object((s): eventArgs => {fieldA: s.field(\"fieldA\", S.int), fieldB: \
+             s.field(\"fieldB\", S.bool)})"
                 .to_string()
         );
         assert_eq!(
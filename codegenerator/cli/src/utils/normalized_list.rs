@@ -1,12 +1,75 @@
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        IntoDeserializer, Visitor,
+    },
+    Deserialize, Deserializer, Serialize,
+};
+use std::{fmt, marker::PhantomData};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum SingleOrList<T: Clone> {
     Single(T),
     List(Vec<T>),
 }
 
+//Deserialized by hand instead of with #[serde(untagged)], since the untagged
+//variant buffers the value first and loses 128 bit integers (eg. uint256 event filters)
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for SingleOrList<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SingleOrListVisitor(PhantomData))
+    }
+}
+
+struct SingleOrListVisitor<T>(PhantomData<T>);
+
+fn single<'de, T, D>(deserializer: D) -> Result<SingleOrList<T>, D::Error>
+where
+    T: Clone + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(SingleOrList::Single)
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                single(v.into_deserializer())
+            }
+        )*
+    };
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Visitor<'de> for SingleOrListVisitor<T> {
+    type Value = SingleOrList<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a single value or a list of values")
+    }
+
+    forward_to_single!(
+        visit_bool: bool,
+        visit_i64: i64,
+        visit_i128: i128,
+        visit_u64: u64,
+        visit_u128: u128,
+        visit_f64: f64,
+        visit_str: &str,
+        visit_string: String
+    );
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        Vec::<T>::deserialize(SeqAccessDeserializer::new(seq)).map(SingleOrList::List)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        single(MapAccessDeserializer::new(map))
+    }
+}
+
 impl<T: Clone> From<SingleOrList<T>> for Vec<T> {
     fn from(single_or_list: SingleOrList<T>) -> Self {
         match single_or_list {
@@ -71,7 +134,7 @@ impl<T: Clone> IntoIterator for NormalizedList<T> {
 
 #[cfg(test)]
 mod test {
-    use super::{NormalizedList, OptSingleOrList};
+    use super::{NormalizedList, OptSingleOrList, SingleOrList};
     use serde::Deserialize;
 
     #[derive(Debug, Deserialize, PartialEq)]
@@ -128,4 +191,16 @@ mod test {
         };
         assert_eq!(expected, de);
     }
+
+    #[test]
+    fn deserializes_128_bit_integers() {
+        let single: SingleOrList<u128> = serde_yaml::from_str("1000000000000000000000").unwrap();
+        assert_eq!(SingleOrList::Single(1_000_000_000_000_000_000_000), single);
+
+        let list: SingleOrList<u128> = serde_yaml::from_str("[1, 1000000000000000000000]").unwrap();
+        assert_eq!(
+            SingleOrList::List(vec![1, 1_000_000_000_000_000_000_000]),
+            list
+        );
+    }
 }
@@ -0,0 +1,117 @@
+open Belt
+
+//Block handlers run after all the events of the block,
+//so they get the max log index for the entity history ordering and the event sync state
+let logIndex = 65535
+
+type plannedRun = {
+  blockNumber: int,
+  blockHandlerName: string,
+  handler: Types.OnBlock.handler,
+}
+
+type run = {
+  ...plannedRun,
+  blockTimestamp: int,
+}
+
+/**
+The block handler runs of a chain in a batch, ordered by block number.
+After the batch, the block handlers are processed up to the processedTo block
+*/
+type chainRuns<'run> = {
+  runs: array<'run>,
+  processedTo: int,
+}
+
+//The first block at or after the given one, the block handler runs on
+let getNextRunBlock = (blockHandler: Config.blockHandler, ~fromBlock) => {
+  let fromBlock = Pervasives.max(fromBlock, blockHandler.startBlock)
+  let offset = mod(fromBlock - blockHandler.startBlock, blockHandler.interval)
+  let blockNumber = offset === 0 ? fromBlock : fromBlock + blockHandler.interval - offset
+  switch blockHandler.endBlock {
+  | Some(endBlock) if blockNumber > endBlock => None
+  | _ => Some(blockNumber)
+  }
+}
+
+let getRegisteredBlockHandlers = (blockHandlers: array<Config.blockHandler>) =>
+  blockHandlers->Array.keepMap(blockHandler =>
+    blockHandler.handlerRegister
+    ->Types.OnBlock.Register.getHandler
+    ->Option.map(handler => (blockHandler, handler))
+  )
+
+//Whether any of the registered block handlers runs on a block in the range
+let hasRuns = (blockHandlers: array<Config.blockHandler>, ~fromBlock, ~toBlock) =>
+  blockHandlers
+  ->getRegisteredBlockHandlers
+  ->Array.some(((blockHandler, _)) =>
+    switch blockHandler->getNextRunBlock(~fromBlock) {
+    | Some(blockNumber) => blockNumber <= toBlock
+    | None => false
+    }
+  )
+
+/**
+Plans the runs of the registered block handlers from fromBlock to toBlock.
+Once there are maxRuns runs, the range is cut off at the next block with runs,
+but never before mustRunToBlock, so the runs between the events of a batch are always included.
+*/
+let planRuns = (
+  blockHandlers: array<Config.blockHandler>,
+  ~fromBlock,
+  ~toBlock,
+  ~mustRunToBlock,
+  ~maxRuns,
+): chainRuns<plannedRun> => {
+  let registeredBlockHandlers = blockHandlers->getRegisteredBlockHandlers
+  let nextRunBlocks =
+    registeredBlockHandlers->Array.map(((blockHandler, _)) =>
+      blockHandler->getNextRunBlock(~fromBlock)
+    )
+
+  let runs = []
+  let processedTo = ref(toBlock)
+  let isDone = ref(false)
+  while !isDone.contents {
+    let nextRunBlock = nextRunBlocks->Array.reduce(None, (acc, nextRunBlock) =>
+      switch (acc, nextRunBlock) {
+      | (Some(acc), Some(nextRunBlock)) => Some(Pervasives.min(acc, nextRunBlock))
+      | (None, nextRunBlock) => nextRunBlock
+      | (acc, None) => acc
+      }
+    )
+    switch nextRunBlock {
+    | Some(blockNumber) if blockNumber <= toBlock =>
+      if runs->Array.length >= maxRuns && blockNumber > mustRunToBlock {
+        processedTo := blockNumber - 1
+        isDone := true
+      } else {
+        registeredBlockHandlers->Array.forEachWithIndex((idx, (blockHandler, handler)) =>
+          if nextRunBlocks->Js.Array2.unsafe_get(idx) == Some(blockNumber) {
+            runs->Js.Array2.push({blockNumber, blockHandlerName: blockHandler.name, handler})->ignore
+            nextRunBlocks->Js.Array2.unsafe_set(
+              idx,
+              blockHandler->getNextRunBlock(~fromBlock=blockNumber + 1),
+            )
+          }
+        )
+      }
+    | _ => isDone := true
+    }
+  }
+
+  {runs, processedTo: processedTo.contents}
+}
+
+//Drops the runs after the given block, when a batch ends early
+let cutOff = (chainRuns: chainRuns<run>, ~toBlock) =>
+  if chainRuns.processedTo > toBlock {
+    {
+      runs: chainRuns.runs->Js.Array2.filter(run => run.blockNumber <= toBlock),
+      processedTo: toBlock,
+    }
+  } else {
+    chainRuns
+  }
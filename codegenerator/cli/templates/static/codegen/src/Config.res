@@ -2,11 +2,29 @@ open Belt
 
 type ecosystem = | @as("evm") Evm | @as("fuel") Fuel
 
+type blockHandler = {
+  name: string,
+  interval: int,
+  startBlock: int,
+  endBlock: option<int>,
+  handlerRegister: Types.OnBlock.Register.t,
+}
+
+type eventBlockRange = {
+  eventName: string,
+  startBlock: option<int>,
+  endBlock: option<int>,
+}
+
 type contract = {
   name: string,
   abi: Ethers.abi,
   addresses: array<Address.t>,
   events: array<module(Types.Event)>,
+  functions?: array<module(Types.Function)>,
+  startBlock?: int,
+  endBlock?: int,
+  eventBlockRanges?: array<eventBlockRange>,
 }
 
 type syncConfig = {
@@ -25,6 +43,7 @@ type chainConfig = {
   confirmedBlockThreshold: int,
   chain: ChainMap.Chain.t,
   contracts: array<contract>,
+  blockHandlers: array<blockHandler>,
   sources: array<Source.t>,
 }
 
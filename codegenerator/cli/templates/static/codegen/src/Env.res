@@ -20,6 +20,10 @@ let maxPartitionConcurrency =
 
 let metricsPort = envSafe->EnvSafe.get("METRICS_PORT", S.int->S.port, ~devFallback=9898)
 
+//Set by envio dev, to reprocess the events of a chain with changed handlers
+let reprocessChainId = envSafe->EnvSafe.get("ENVIO_REPROCESS_CHAIN_ID", S.option(S.int))
+let reprocessFromBlock = envSafe->EnvSafe.get("ENVIO_REPROCESS_FROM_BLOCK", S.option(S.int))
+
 let tuiOffEnvVar = envSafe->EnvSafe.get("TUI_OFF", S.bool, ~fallback=false)
 
 let logFilePath = envSafe->EnvSafe.get("LOG_FILE", S.string, ~fallback="logs/envio.log")
@@ -106,19 +110,46 @@ let logStrategy =
   )
 
 module Db = {
-  let host = envSafe->EnvSafe.get("ENVIO_PG_HOST", S.string, ~devFallback="localhost")
-  let port = envSafe->EnvSafe.get("ENVIO_PG_PORT", S.int->S.port, ~devFallback=5433)
-  let user = envSafe->EnvSafe.get("ENVIO_PG_USER", S.string, ~devFallback="postgres")
-  let password = envSafe->EnvSafe.get("ENVIO_POSTGRES_PASSWORD", S.string, ~devFallback="testing")
-  let database = envSafe->EnvSafe.get("ENVIO_PG_DATABASE", S.string, ~devFallback="envio-dev")
+  //The connection url takes precedence over the individual connection settings,
+  //the same way it does for the envio CLI
+  let url = envSafe->EnvSafe.get("ENVIO_PG_URL", S.option(S.string))
+  let getConnectionSetting = (name, schema, ~devFallback) =>
+    switch url {
+    | Some(_) => None
+    | None => Some(envSafe->EnvSafe.get(name, schema, ~devFallback))
+    }
+
+  let host = getConnectionSetting("ENVIO_PG_HOST", S.string, ~devFallback="localhost")
+  let port = getConnectionSetting("ENVIO_PG_PORT", S.int->S.port, ~devFallback=5433)
+  let user = getConnectionSetting("ENVIO_PG_USER", S.string, ~devFallback="postgres")
+  let password = getConnectionSetting("ENVIO_POSTGRES_PASSWORD", S.string, ~devFallback="testing")
+  let database = getConnectionSetting("ENVIO_PG_DATABASE", S.string, ~devFallback="envio-dev")
   let publicSchema = envSafe->EnvSafe.get("ENVIO_PG_PUBLIC_SCHEMA", S.string, ~fallback="public")
-  let ssl = envSafe->EnvSafe.get(
-    "ENVIO_PG_SSL_MODE",
-    Postgres.sslOptionsSchema,
-    //this is a dev fallback option for local deployments, shouldn't run in the prod env
-    //the SSL modes should be provided as string otherwise as 'require' | 'allow' | 'prefer' | 'verify-full'
-    ~devFallback=Bool(false),
-  )
+  let sslRootCert = envSafe->EnvSafe.get("ENVIO_PG_SSL_ROOT_CERT", S.option(S.string))
+  let ssl = switch sslRootCert {
+  //Verifies the server against the given CA certificate, like sslmode=verify-full
+  | Some(filepath) =>
+    Some(
+      Postgres.TLSConnectOptions({
+        ca: NodeJsLocal.Fs.readFileSync(~filepath, ~encoding=Utf8),
+        rejectUnauthorized: true,
+      }),
+    )
+  //The ssl mode can also be set with the sslmode param of the connection url
+  | None if url->Belt.Option.isSome =>
+    envSafe->EnvSafe.get("ENVIO_PG_SSL_MODE", S.option(Postgres.sslOptionsSchema))
+  | None =>
+    Some(
+      envSafe->EnvSafe.get(
+        "ENVIO_PG_SSL_MODE",
+        Postgres.sslOptionsSchema,
+        //this is a dev fallback option for local deployments, shouldn't run in the prod env
+        //the SSL modes should be provided as string otherwise as 'require' | 'allow' | 'prefer' | 'verify-full'
+        //true verifies the server with the default TLS options of node, the same as 'verify-full'
+        ~devFallback=Bool(false),
+      ),
+    )
+  }
 }
 
 module Hasura = {
@@ -194,5 +225,58 @@ module ThrottleWrites = {
     )
 }
 
+%%private(@val external processEnv: Js.Dict.t<string> = "process.env")
+
+/**
+Resolves the ${ENV_VAR} interpolations which are kept in the generated code
+when the config uses `env_interpolation: runtime`.
+Supports the same ${VAR:-default} and ${VAR-default} syntax as the config.
+*/
+let interpolateOrThrow = (value: string) => {
+  let missingVars = []
+  let interpolated = value->Js.String2.unsafeReplaceBy1(%re(`/[$][{]([^}]*)[}]/g`), (
+    _,
+    inner,
+    _,
+    _,
+  ) => {
+    let defaultForMissingAndEmptyPos = inner->Js.String2.indexOf(":-")
+    let defaultForMissingPos = inner->Js.String2.indexOf("-")
+    let (name, defaultValue, isDefaultForEmpty) = if (
+      defaultForMissingAndEmptyPos >= 0 && defaultForMissingAndEmptyPos < defaultForMissingPos
+    ) {
+      (
+        inner->Js.String2.slice(~from=0, ~to_=defaultForMissingAndEmptyPos),
+        Some(inner->Js.String2.sliceToEnd(~from=defaultForMissingAndEmptyPos + 2)),
+        true,
+      )
+    } else if defaultForMissingPos >= 0 {
+      (
+        inner->Js.String2.slice(~from=0, ~to_=defaultForMissingPos),
+        Some(inner->Js.String2.sliceToEnd(~from=defaultForMissingPos + 1)),
+        false,
+      )
+    } else {
+      (inner, None, false)
+    }
+    switch (processEnv->Js.Dict.get(name), defaultValue) {
+    | (Some(""), Some(defaultValue)) if isDefaultForEmpty => defaultValue
+    | (Some(envValue), _) => envValue
+    | (None, Some(defaultValue)) => defaultValue
+    | (None, None) =>
+      missingVars->Js.Array2.push(name)->ignore
+      ""
+    }
+  })
+  if missingVars->Js.Array2.length > 0 {
+    Js.Exn.raiseError(
+      `Failed to interpolate variables into your config at runtime. Environment variables are not present: ${missingVars->Js.Array2.joinWith(
+          ", ",
+        )}`,
+    )
+  }
+  interpolated
+}
+
 // You need to close the envSafe after you're done with it so that it immediately tells you about your  misconfigured environment on startup.
 envSafe->EnvSafe.close
@@ -4,13 +4,15 @@ module EventsProcessed = {
   type eventsProcessed = {
     numEventsProcessed: int,
     latestProcessedBlock: option<int>,
+    blockHandlersProcessedTo: int,
   }
   type t = ChainMap.t<eventsProcessed>
 
   let makeEmpty = (~config: Config.t) => {
-    config.chainMap->ChainMap.map(_ => {
+    config.chainMap->ChainMap.map(chainConfig => {
       numEventsProcessed: 0,
       latestProcessedBlock: None,
+      blockHandlersProcessedTo: chainConfig.startBlock - 1,
     })
   }
 
@@ -21,18 +23,36 @@ module EventsProcessed = {
   }
 
   let makeFromChainManager = (cm: ChainManager.t): t => {
-    cm.chainFetchers->ChainMap.map(({numEventsProcessed, latestProcessedBlock}) => {
+    cm.chainFetchers->ChainMap.map(({
       numEventsProcessed,
       latestProcessedBlock,
+      blockHandlersProcessedTo,
+    }) => {
+      numEventsProcessed,
+      latestProcessedBlock,
+      blockHandlersProcessedTo,
     })
   }
 
   let updateEventsProcessed = (self: t, ~chain, ~blockNumber) => {
-    self->ChainMap.update(chain, ({numEventsProcessed}) => {
-      numEventsProcessed: numEventsProcessed + 1,
+    self->ChainMap.update(chain, eventsProcessed => {
+      ...eventsProcessed,
+      numEventsProcessed: eventsProcessed.numEventsProcessed + 1,
       latestProcessedBlock: Some(blockNumber),
     })
   }
+
+  //All the blocks up to the one the block handlers processed to are fully processed
+  let updateBlockHandlersProcessed = (self: t, ~chain, ~processedTo) => {
+    self->ChainMap.update(chain, eventsProcessed => {
+      ...eventsProcessed,
+      latestProcessedBlock: switch eventsProcessed.latestProcessedBlock {
+      | Some(latestProcessedBlock) if latestProcessedBlock > processedTo => Some(latestProcessedBlock)
+      | _ => Some(processedTo)
+      },
+      blockHandlersProcessedTo: processedTo,
+    })
+  }
 }
 
 let updateEventSyncState = (
@@ -500,21 +520,93 @@ let runLoaders = (eventBatch: array<Internal.eventItem>, ~loadLayer, ~inMemorySt
   })
 }
 
+let runBlockHandlers = (
+  runs: array<BlockHandlers.run>,
+  ~chain,
+  ~inMemoryStore: InMemoryStore.t,
+  ~logger,
+  ~loadLayer,
+  ~config: Config.t,
+  ~isInReorgThreshold,
+) => {
+  open ErrorHandling.ResultPropogateEnv
+  runAsyncEnv(async () => {
+    let chainId = chain->ChainMap.Chain.toChainId
+    for i in 0 to runs->Array.length - 1 {
+      let {blockNumber, blockTimestamp, blockHandlerName, handler} = runs->Js.Array2.unsafe_get(i)
+      let handlerArgs = ContextEnv.getBlockHandlerArgs(
+        ~blockHandlerName,
+        ~chain,
+        ~blockNumber,
+        ~blockTimestamp,
+        ~logger,
+        ~inMemoryStore,
+        ~loadLayer,
+        ~shouldSaveHistory=config->Config.shouldSaveHistory(~isInReorgThreshold),
+      )
+      switch await handler(handlerArgs) {
+      | exception exn =>
+        exn
+        ->ErrorHandling.make(
+          ~msg="Block Handler failed, please fix the error to keep the indexer running smoothly",
+          ~logger,
+        )
+        ->Error
+        ->propogate
+      | () =>
+        //Saved with the block handler log index, so a restart continues after the block
+        let _ = inMemoryStore.eventSyncState->InMemoryTable.set(
+          chainId,
+          {
+            chainId,
+            blockTimestamp,
+            blockNumber,
+            logIndex: BlockHandlers.logIndex,
+            isPreRegisteringDynamicContracts: false,
+          },
+        )
+      }
+    }
+    Ok()
+  })
+}
+
 let runHandlers = (
   eventBatch: array<Internal.eventItem>,
+  ~blockHandlerRuns: ChainMap.t<BlockHandlers.chainRuns<BlockHandlers.run>>,
   ~inMemoryStore,
-  ~latestProcessedBlocks,
+  ~latestProcessedBlocks: EventsProcessed.t,
   ~logger,
   ~loadLayer,
-  ~config,
+  ~config: Config.t,
   ~isInReorgThreshold,
 ) => {
   open ErrorHandling.ResultPropogateEnv
+  //The block handler runs of a block come after all the events of the block
+  let pendingRuns = blockHandlerRuns->ChainMap.map(({runs}) => runs->Array.copy)
+  let runBlockHandlersBefore = (~chain, ~blockNumber) => {
+    let runs = pendingRuns->ChainMap.get(chain)
+    let dueRunsCount = ref(0)
+    while (
+      dueRunsCount.contents < runs->Array.length &&
+        (runs->Js.Array2.unsafe_get(dueRunsCount.contents)).blockNumber < blockNumber
+    ) {
+      dueRunsCount := dueRunsCount.contents + 1
+    }
+    runs
+    ->Js.Array2.spliceInPlace(~pos=0, ~remove=dueRunsCount.contents, ~add=[])
+    ->runBlockHandlers(~chain, ~inMemoryStore, ~logger, ~loadLayer, ~config, ~isInReorgThreshold)
+  }
   let latestProcessedBlocks = ref(latestProcessedBlocks)
   runAsyncEnv(async () => {
     for i in 0 to eventBatch->Array.length - 1 {
       let eventItem = eventBatch->Js.Array2.unsafe_get(i)
 
+      (await runBlockHandlersBefore(
+        ~chain=eventItem.chain,
+        ~blockNumber=eventItem.blockNumber,
+      ))->propogate
+
       latestProcessedBlocks :=
         (
           await runHandler(
@@ -528,6 +620,17 @@ let runHandlers = (
           )
         )->propogate
     }
+
+    let chainRuns = blockHandlerRuns->ChainMap.entries
+    for i in 0 to chainRuns->Array.length - 1 {
+      let (chain, {processedTo}) = chainRuns->Js.Array2.unsafe_get(i)
+      (await runBlockHandlersBefore(~chain, ~blockNumber=processedTo + 1))->propogate
+      latestProcessedBlocks :=
+        latestProcessedBlocks.contents->EventsProcessed.updateBlockHandlersProcessed(
+          ~chain,
+          ~processedTo,
+        )
+    }
     Ok(latestProcessedBlocks.contents)
   })
 }
@@ -616,6 +719,7 @@ let getDynamicContractRegistrations = (
 
 let processEventBatch = (
   ~eventBatch: array<Internal.eventItem>,
+  ~blockHandlerRuns: option<ChainMap.t<BlockHandlers.chainRuns<BlockHandlers.run>>>=?,
   ~inMemoryStore: InMemoryStore.t,
   ~isInReorgThreshold,
   ~latestProcessedBlocks: EventsProcessed.t,
@@ -653,6 +757,27 @@ let processEventBatch = (
     let elapsedAfterContractRegister =
       timeRef->Hrtime.timeSince->Hrtime.toMillis->Hrtime.intFromMillis
 
+    //The events after a dynamic contract registration are processed in a later batch,
+    //so are the block handler runs after them
+    let blockHandlerRuns = switch blockHandlerRuns {
+    | Some(blockHandlerRuns) => blockHandlerRuns
+    | None =>
+      latestProcessedBlocks->ChainMap.map(({blockHandlersProcessedTo}) => {
+        BlockHandlers.runs: [],
+        processedTo: blockHandlersProcessedTo,
+      })
+    }
+    let blockHandlerRuns = switch dynamicContractRegistrations {
+    | Some({unprocessedBatch}) =>
+      blockHandlerRuns->ChainMap.mapWithKey((chain, chainRuns) =>
+        switch unprocessedBatch->Js.Array2.find(item => item.chain == chain) {
+        | Some(item) => chainRuns->BlockHandlers.cutOff(~toBlock=item.blockNumber - 1)
+        | None => chainRuns
+        }
+      )
+    | None => blockHandlerRuns
+    }
+
     (await eventsBeforeDynamicRegistrations
     ->runLoaders(~loadLayer, ~inMemoryStore, ~logger))
     ->propogate
@@ -662,6 +787,7 @@ let processEventBatch = (
     let latestProcessedBlocks =
       (await eventsBeforeDynamicRegistrations
       ->runHandlers(
+        ~blockHandlerRuns,
         ~inMemoryStore,
         ~latestProcessedBlocks,
         ~logger,
@@ -0,0 +1,97 @@
+open Belt
+
+//Calls are taken from the transaction input, so they don't have a log index.
+//They are ordered by the transaction index after the logs of their block,
+//but before the block handlers
+let logIndexOffset = 32768
+
+//The call fields along with the event fields used by the event processing
+type callEvent = {
+  inputs: Internal.callInputs,
+  params: Internal.callInputs,
+  chainId: int,
+  srcAddress: Address.t,
+  logIndex: int,
+  transaction: Internal.eventTransaction,
+  block: Internal.eventBlock,
+}
+
+let getFunctions = (contract: Config.contract) => contract.functions->Option.getWithDefault([])
+
+//The selectors of the contract functions included in the selection
+let getSelectors = (contract: Config.contract, ~selection) =>
+  contract
+  ->getFunctions
+  ->Array.keepMap(function => {
+    let module(Function) = function
+    FetchState.checkIsInSelection(
+      ~selection,
+      ~contractName=contract.name,
+      ~eventId=Function.selector,
+      ~isWildcard=false,
+    )
+      ? Some(Function.selector)
+      : None
+  })
+
+let getFunctionBySelector = (contract: Config.contract, ~selector) =>
+  contract
+  ->getFunctions
+  ->Js.Array2.find(function => {
+    let module(Function) = function
+    Function.selector === selector
+  })
+
+//The first 4 bytes of the input
+let getSelector = (input: string) => input->Js.String2.slice(~from=0, ~to_=10)->Js.String2.toLowerCase
+
+let decodeInputsOrThrow = (function: module(Types.Function), ~abi, ~input) => {
+  let module(Function) = function
+  let {?args} = Viem.decodeFunctionDataOrThrow({abi, data: input})
+  let args = args->Option.getWithDefault([])
+  let inputs = Js.Dict.empty()
+  Function.inputNames->Array.forEachWithIndex((idx, inputName) =>
+    inputs->Js.Dict.set(inputName, args->Js.Array2.unsafe_get(idx))
+  )
+  inputs->(Utils.magic: dict<unknown> => Internal.callInputs)
+}
+
+let makeEventItem = (
+  function: module(Types.Function),
+  ~inputs,
+  ~chain,
+  ~srcAddress,
+  ~transactionIndex,
+  ~block,
+  ~transaction,
+): Internal.eventItem => {
+  let module(Function) = function
+  let logIndex = logIndexOffset + transactionIndex
+  {
+    eventName: Function.name,
+    contractName: Function.contractName,
+    loader: None,
+    handler: Function.handlerRegister
+    ->Types.OnCall.Register.getHandler
+    ->Option.map(handler => ({event, context}: Internal.handlerArgs) =>
+      handler({call: event->(Utils.magic: Internal.event => Internal.call), context})
+    ),
+    contractRegister: None,
+    paramsRawEventSchema: Function.inputsSchema->(
+      Utils.magic: S.schema<Function.inputs> => S.schema<Internal.eventParams>
+    ),
+    timestamp: block->Types.Block.getTimestamp,
+    chain,
+    blockNumber: block->Types.Block.getNumber,
+    logIndex,
+    event: {
+      inputs,
+      params: inputs,
+      chainId: chain->ChainMap.Chain.toChainId,
+      srcAddress,
+      logIndex,
+      transaction,
+      block,
+    }->(Utils.magic: callEvent => Internal.event),
+  }
+}
@@ -141,6 +141,35 @@ let executeDbFunctionsEntity = (
   promises->Promise.all->Promise.thenResolve(_ => ())
 }
 
+//Immutable entities are insert only and never write history,
+//instead each row keeps the event it was created by
+let executeInsertImmutableEntity = (
+  type entity,
+  sql: Postgres.sql,
+  ~inMemoryStore: InMemoryStore.t,
+  ~entityMod: module(Entities.Entity with type t = entity),
+): promise<unit> => {
+  let entityMod = entityMod->Entities.entityModToInternal
+  let rowsToInsert =
+    inMemoryStore.entities
+    ->InMemoryStore.EntityTables.get(entityMod)
+    ->InMemoryTable.Entity.rows
+    ->Array.keepMap(row =>
+      switch row {
+      | Updated({latest: {entityUpdateAction: Set(entity), eventIdentifier}}) =>
+        Some((entity, eventIdentifier))
+      //Rolled back rows are deleted by the event they were created by
+      | Updated({latest: {entityUpdateAction: Delete}})
+      | InitialReadFromDb(_) =>
+        None
+      }
+    )
+
+  rowsToInsert->Array.length > 0
+    ? sql->DbFunctionsEntities.batchInsertImmutable(~entityMod)(rowsToInsert)
+    : Promise.resolve()
+}
+
 let executeBatch = async (sql, ~inMemoryStore: InMemoryStore.t, ~isInReorgThreshold, ~config) => {
   let entityDbExecutionComposer =
     config->Config.shouldSaveHistory(~isInReorgThreshold)
@@ -160,13 +189,16 @@ let executeBatch = async (sql, ~inMemoryStore: InMemoryStore.t, ~isInReorgThresh
   )
 
   let setEntities = Entities.allEntities->Belt.Array.map(entityMod => {
-    entityDbExecutionComposer(_, ~entityMod, ~inMemoryStore)
+    let module(Entity) = entityMod
+    Entity.table.isImmutable
+      ? executeInsertImmutableEntity(_, ~entityMod, ~inMemoryStore)
+      : entityDbExecutionComposer(_, ~entityMod, ~inMemoryStore)
   })
 
   //In the event of a rollback, rollback all meta tables based on the given
   //valid event identifier, where all rows created after this eventIdentifier should
   //be deleted
-  let rollbackTables = switch inMemoryStore.rollBackEventIdentifier {
+  let rollbackMetaTables = switch inMemoryStore.rollBackEventIdentifier {
   | Some(eventIdentifier) => [
       DbFunctions.EntityHistory.deleteAllEntityHistoryAfterEventIdentifier(
         _,
@@ -182,10 +214,20 @@ let executeBatch = async (sql, ~inMemoryStore: InMemoryStore.t, ~isInReorgThresh
   | None => []
   }
 
+  //Immutable entities have no history, so their rows created at or after
+  //the first rolled back event of each chain are deleted
+  let rollbackImmutableTables = switch inMemoryStore.rollBackFirstChangeEventPerChain {
+  | Some(firstChangeEventPerChain) => [
+      DbFunctions.ImmutableRows.deleteRolledBackRows(_, ~firstChangeEventPerChain),
+    ]
+  | None => []
+  }
+
   let res = await sql->Postgres.beginSql(sql => {
     Belt.Array.concatMany([
       //Rollback tables need to happen first in the traction
-      rollbackTables,
+      rollbackMetaTables,
+      rollbackImmutableTables,
       [setEventSyncState, setRawEvents],
       setEntities,
     ])->Belt.Array.map(dbFunc => sql->dbFunc)
@@ -202,6 +244,7 @@ module RollBack = {
     ~blockNumber,
     ~logIndex,
     ~isUnorderedMultichainMode,
+    ~firstChangeEventPerChain,
   ) => {
     let rollBackEventIdentifier: Types.eventIdentifier = {
       chainId,
@@ -210,10 +253,34 @@ module RollBack = {
       logIndex,
     }
 
-    let inMemStore = InMemoryStore.make(~rollBackEventIdentifier)
+    let inMemStore = InMemoryStore.make(
+      ~rollBackEventIdentifier,
+      ~rollBackFirstChangeEventPerChain=firstChangeEventPerChain,
+    )
 
+    //Immutable entities have no history to diff, their rolled back rows are
+    //deleted so they can't be loaded while the events are processed again
     let _ =
       await Entities.allEntities
+      ->DbFunctions.ImmutableRows.getImmutableEntities
+      ->Belt.Array.map(async entityMod => {
+        let ids =
+          await Db.sql->DbFunctions.ImmutableRows.getRolledBackIds(
+            ~firstChangeEventPerChain,
+            ~entityMod,
+          )
+        let entityTable = inMemStore.entities->InMemoryStore.EntityTables.get(entityMod)
+        ids->Belt.Array.forEach(entityId => {
+          entityTable->InMemoryTable.Entity.set(
+            Delete->Types.mkEntityUpdate(~eventIdentifier=rollBackEventIdentifier, ~entityId),
+            ~shouldSaveHistory=false,
+          )
+        })
+      })
+      ->Promise.all
+
+    let _ =
+      await Entities.allEntitiesWithHistory
       ->Belt.Array.map(async entityMod => {
         let module(Entity) = entityMod
         let entityMod =
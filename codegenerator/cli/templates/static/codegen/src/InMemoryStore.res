@@ -49,16 +49,21 @@ type t = {
   rawEvents: InMemoryTable.t<rawEventsKey, TablesStatic.RawEvents.t>,
   entities: Js.Dict.t<InMemoryTable.Entity.t<Entities.internalEntity>>,
   rollBackEventIdentifier: option<Types.eventIdentifier>,
+  //The first rolled back event of each chain, rows of immutable entities
+  //created at or after it are deleted since they don't keep any history
+  rollBackFirstChangeEventPerChain: option<DbFunctions.EntityHistory.FirstChangeEventPerChain.t>,
 }
 
 let make = (
   ~entities: array<module(Entities.InternalEntity)>=Entities.allEntities,
   ~rollBackEventIdentifier=?,
+  ~rollBackFirstChangeEventPerChain=?,
 ): t => {
   eventSyncState: InMemoryTable.make(~hash=v => v->Belt.Int.toString),
   rawEvents: InMemoryTable.make(~hash=hashRawEventsKey),
   entities: EntityTables.make(entities),
   rollBackEventIdentifier,
+  rollBackFirstChangeEventPerChain,
 }
 
 let clone = (self: t) => {
@@ -66,6 +71,7 @@ let clone = (self: t) => {
   rawEvents: self.rawEvents->InMemoryTable.clone,
   entities: self.entities->EntityTables.clone,
   rollBackEventIdentifier: self.rollBackEventIdentifier->InMemoryTable.structuredClone,
+  rollBackFirstChangeEventPerChain: self.rollBackFirstChangeEventPerChain->InMemoryTable.structuredClone,
 }
 
 let getInMemTable = (
@@ -291,6 +291,33 @@ module Entity = {
     ->Array.keepMap(rowToEntity)
   }
 
+  /**
+  Finds another entity with the same values of the unique constraint fields.
+  Like in Postgres, the values with a null field never conflict.
+  */
+  let findUniqueConstraintConflict = (
+    inMemTable: t<'entity>,
+    ~entity: 'entity,
+    ~entityId,
+    ~fieldNames,
+  ) => {
+    let getFieldValues = entity => {
+      let entityDict = entity->(Utils.magic: 'entity => dict<TableIndices.FieldValue.t>)
+      fieldNames->Array.map(fieldName => entityDict->Js.Dict.unsafeGet(fieldName))
+    }
+    let fieldValues = entity->getFieldValues
+    if fieldValues->Js.Array2.some(Option.isNone) {
+      None
+    } else {
+      inMemTable
+      ->values
+      ->Js.Array2.find(otherEntity =>
+        otherEntity->Entities.getEntityIdUnsafe !== entityId &&
+          Array.every2(fieldValues, otherEntity->getFieldValues, TableIndices.FieldValue.eq)
+      )
+    }
+  }
+
   let clone = ({table, fieldNameIndices}: t<'entity>) => {
     table: table->clone,
     fieldNameIndices: {
@@ -161,6 +161,15 @@ let main = async () => {
     let chainManager = await ChainManager.makeFromDbState(~config)
     let loadLayer = LoadLayer.makeWithDbConnection()
     let globalState = GlobalState.make(~config, ~chainManager, ~loadLayer)
+    //Roll back the chain before fetching, so its events get reprocessed with the changed handlers
+    let globalState = switch (Env.reprocessChainId, Env.reprocessFromBlock) {
+    | (Some(chainId), Some(blockNumber)) =>
+      globalState->GlobalState.setReprocessingFrom(
+        ~chain=config->Config.getChain(~chainId),
+        ~blockNumber,
+      )
+    | _ => globalState
+    }
     let stateUpdatedHook = if shouldUseTui {
       let rerender = EnvioInkApp.startApp(makeAppState(globalState))
       Some(globalState => globalState->makeAppState->rerender)
@@ -168,6 +177,9 @@ let main = async () => {
       None
     }
     let gsManager = globalState->GlobalStateManager.make(~stateUpdatedHook?)
+    if globalState->GlobalState.isRollingBack {
+      gsManager->GlobalStateManager.dispatchTask(Rollback)
+    }
     gsManager->GlobalStateManager.dispatchTask(NextQuery(CheckAllChains))
     /*
     NOTE:
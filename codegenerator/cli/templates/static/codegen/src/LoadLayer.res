@@ -341,3 +341,45 @@ let makeWhereLoader = (
     ->(Utils.magic: promise<array<Entities.internalEntity>> => promise<array<entity>>)
   }
 }
+
+/**
+Loads the entity matching the values of a unique constraint.
+The entities are loaded by the first field of the constraint
+and the values of the rest of its fields are matched in memory
+*/
+let makeUniqueLoader = (
+  type entity,
+  loadLayer,
+  ~entityMod: module(Entities.Entity with type t = entity),
+  ~inMemoryStore,
+  ~logger,
+  ~fieldName,
+  ~fieldValueSchema,
+) => {
+  let loadWhereEq =
+    loadLayer->makeWhereLoader(
+      ~operator=Eq,
+      ~entityMod,
+      ~inMemoryStore,
+      ~logger,
+      ~fieldName,
+      ~fieldValueSchema,
+    )
+  async (fieldValue, ~otherFieldValues: array<(string, TableIndices.FieldValue.t)>) => {
+    let hasNullFieldValue =
+      fieldValue->TableIndices.FieldValue.castFrom->Option.isNone ||
+        otherFieldValues->Js.Array2.some(((_, fieldValue)) => fieldValue->Option.isNone)
+    // Like in Postgres, null values of the unique fields are never equal
+    if hasNullFieldValue {
+      None
+    } else {
+      let entities = await loadWhereEq(fieldValue)
+      entities->Js.Array2.find(entity => {
+        let entityDict = entity->(Utils.magic: entity => dict<TableIndices.FieldValue.t>)
+        otherFieldValues->Js.Array2.every(((fieldName, fieldValue)) =>
+          entityDict->Js.Dict.unsafeGet(fieldName)->TableIndices.FieldValue.eq(fieldValue)
+        )
+      })
+    }
+  }
+}
@@ -46,12 +46,26 @@ module FieldValue = {
     | (Some(BigDecimal(bdA)), Some(BigDecimal(bdB))) => BigDecimal.gt(bdA, bdB)
     | (a, b) => a > b
     }
+
+  //Checks whether the array value a contains the value b
+  let has = (a, b) =>
+    switch a {
+    | Some(Array(values)) => values->Js.Array2.some(value => Some(value)->eq(b))
+    | _ => false
+    }
+
+  //Gets the elements of an array value, or an empty array for any other value
+  let getArrayElements = (value: t): array<t> =>
+    switch value {
+    | Some(Array(values)) => values->Array.map(value => Some(value))
+    | _ => []
+    }
 }
 
 module Operator = {
-  type t = Eq | Gt
+  type t = Eq | Gt | Has
 
-  let values = [Eq, Gt]
+  let values = [Eq, Gt, Has]
 }
 
 module SingleIndex = {
@@ -71,6 +85,7 @@ module SingleIndex = {
       switch self.operator {
       | Eq => fieldValue->FieldValue.eq(self.fieldValue)
       | Gt => fieldValue->FieldValue.gt(self.fieldValue)
+      | Has => fieldValue->FieldValue.has(self.fieldValue)
       }
 }
 
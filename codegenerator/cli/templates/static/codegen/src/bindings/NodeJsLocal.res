@@ -51,4 +51,8 @@ module Fs = {
     @module("fs") @scope("promises")
     external readFile: (~filepath: Path.t, ~encoding: encoding) => promise<string> = "readFile"
   }
+
+  @module("fs")
+  external readFileSync: (~filepath: string, ~encoding: Promises.encoding) => string =
+    "readFileSync"
 }
@@ -2,18 +2,21 @@
 // Ideally it should be moved to the config and passed with it
 
 let config: Postgres.poolConfig = {
-  host: Env.Db.host,
-  port: Env.Db.port,
-  username: Env.Db.user,
-  password: Env.Db.password,
-  database: Env.Db.database,
-  ssl: Env.Db.ssl,
+  host: ?Env.Db.host,
+  port: ?Env.Db.port,
+  username: ?Env.Db.user,
+  password: ?Env.Db.password,
+  database: ?Env.Db.database,
+  ssl: ?Env.Db.ssl,
   // TODO: think how we want to pipe these logs to pino.
   onnotice: ?(Env.userLogLevel == #warn || Env.userLogLevel == #error ? None : Some(_str => ())),
   transform: {undefined: Null},
   max: 2,
 }
-let sql = Postgres.makeSql(~config)
+let sql = switch Env.Db.url {
+| Some(url) => Postgres.makeSqlWithUrl(url, ~config)
+| None => Postgres.makeSql(~config)
+}
 let publicSchema = Env.Db.publicSchema
 
 let allEntityTables: array<Table.table> = Entities.allEntities->Belt.Array.map(entityMod => {
@@ -21,10 +24,26 @@ let allEntityTables: array<Table.table> = Entities.allEntities->Belt.Array.map(e
   Entity.table
 })
 
+let allEntityViews: array<Table.view> = Entities.allViews
+
+//Join tables of all many-to-many relationship fields
+let allJoinTables: array<Table.table> =
+  allEntityTables
+  ->Belt.Array.map(table =>
+    table->Table.getManyToManyFields->Belt.Array.map(field => table->Table.JoinTable.make(field))
+  )
+  ->Belt.Array.concatMany
+
+//A table per interval of every aggregation
+let allAggregationTables: array<Table.table> =
+  Entities.allAggregations
+  ->Belt.Array.map(Table.Aggregation.getTables)
+  ->Belt.Array.concatMany
+
 let allEntityHistoryTables: array<Table.table> = []
 let allEntityHistory: array<
   EntityHistory.t<EntityHistory.entityInternal>,
-> = Entities.allEntities->Belt.Array.map(entityMod => {
+> = Entities.allEntitiesWithHistory->Belt.Array.map(entityMod => {
   let module(Entity) = entityMod
   let entityHistory = Entity.entityHistory->EntityHistory.castInternal
   allEntityHistoryTables->Js.Array2.push(entityHistory.table)->ignore
@@ -382,7 +382,7 @@ module EntityHistory = {
     sql,
     ~isUnorderedMultichainMode,
     ~eventIdentifier: Types.eventIdentifier,
-    ~allEntities=Entities.allEntities,
+    ~allEntities=Entities.allEntitiesWithHistory,
   ): unit => {
     let startTime = Hrtime.makeTimer()
 
@@ -489,7 +489,7 @@ module EntityHistory = {
   let getFirstChangeEventPerChain = async (
     sql,
     args: Args.t,
-    ~allEntities=Entities.allEntities,
+    ~allEntities=Entities.allEntitiesWithHistory,
   ) => {
     let startTime = Hrtime.makeTimer()
     let firstChangeEventPerChain = FirstChangeEventPerChain.make()
@@ -545,7 +545,7 @@ module EntityHistory = {
 
   let hasRows = async sql => {
     let all =
-      await Entities.allEntities
+      await Entities.allEntitiesWithHistory
       ->Belt.Array.map(async entityMod => {
         let module(Entity) = entityMod
         try await General.hasRows(sql, ~table=Entity.entityHistory.table) catch {
@@ -564,3 +564,98 @@ module EntityHistory = {
     all->Belt.Array.some(v => v)
   }
 }
+
+module ImmutableRows = {
+  type idRow = {id: string}
+
+  @module("./DbFunctionsImplementation.js")
+  external readImmutableRowIdsFromEvent: (
+    Postgres.sql,
+    ~table: Table.table,
+    ~chainId: int,
+    ~blockNumber: int,
+    ~logIndex: int,
+  ) => promise<array<idRow>> = "readImmutableRowIdsFromEvent"
+
+  @module("./DbFunctionsImplementation.js")
+  external deleteImmutableRowsFromEvent: (
+    Postgres.sql,
+    ~table: Table.table,
+    ~chainId: int,
+    ~blockNumber: int,
+    ~logIndex: int,
+  ) => promise<unit> = "deleteImmutableRowsFromEvent"
+
+  let getImmutableEntities = allEntities =>
+    allEntities->Belt.Array.keep(entityMod => {
+      let module(Entity) = entityMod
+      Entity.table.isImmutable
+    })
+
+  //Runs the query for the first change event of every chain,
+  //the rows created at or after it are the ones rolled back
+  let queryPerChain = (
+    ~firstChangeEventPerChain: EntityHistory.FirstChangeEventPerChain.t,
+    ~entityName: Enums.EntityType.t,
+    ~msg,
+    query,
+  ) =>
+    firstChangeEventPerChain
+    ->Js.Dict.entries
+    ->Belt.Array.map(async ((chainKey, event: FetchState.blockNumberAndLogIndex)) => {
+      try await query(
+        ~chainId=chainKey->Belt.Int.fromString->Belt.Option.getExn,
+        ~blockNumber=event.blockNumber,
+        ~logIndex=event.logIndex,
+      ) catch {
+      | exn =>
+        exn->ErrorHandling.mkLogAndRaise(
+          ~msg,
+          ~logger=Logging.createChild(
+            ~params={
+              "entityName": entityName,
+              "chainId": chainKey,
+            },
+          ),
+        )
+      }
+    })
+    ->Promise.all
+
+  let getRolledBackIds = async (
+    sql,
+    ~firstChangeEventPerChain,
+    ~entityMod: module(Entities.InternalEntity),
+  ) => {
+    let module(Entity) = entityMod
+    let idRowsPerChain = await queryPerChain(
+      ~firstChangeEventPerChain,
+      ~entityName=Entity.name,
+      ~msg=`Failed to read rolled back rows of immutable entity`,
+      readImmutableRowIdsFromEvent(sql, ~table=Entity.table, ...),
+    )
+    idRowsPerChain->Belt.Array.concatMany->Belt.Array.map(({id}) => id)
+  }
+
+  //Immutable entities don't keep any history, so on a rollback their rows created
+  //at or after the first change event of each chain are deleted
+  let deleteRolledBackRows = async (
+    sql,
+    ~firstChangeEventPerChain,
+    ~allEntities=Entities.allEntities,
+  ) => {
+    let _ =
+      await allEntities
+      ->getImmutableEntities
+      ->Belt.Array.map(entityMod => {
+        let module(Entity) = entityMod
+        queryPerChain(
+          ~firstChangeEventPerChain,
+          ~entityName=Entity.name,
+          ~msg=`Failed to delete rolled back rows of immutable entity`,
+          deleteImmutableRowsFromEvent(sql, ~table=Entity.table, ...),
+        )
+      })
+      ->Promise.all
+  }
+}
@@ -49,6 +49,41 @@ let makeBatchSet = (~table: Table.table, ~schema: S.t<'entity>) => {
   }
 }
 
+//Inserts the rows of an immutable entity along with the event that created each of them,
+//so they can be deleted on a rollback
+let makeBatchInsertImmutable = (~table: Table.table, ~schema: S.t<'entity>) => {
+  let query = DbFunctions.makeBatchSetEntityValues(table)
+  let toRow = (entity, eventIdentifier: Types.eventIdentifier) =>
+    entity
+    ->S.reverseConvertOrThrow(schema)
+    ->(Utils.magic: unknown => dict<unknown>)
+    ->Utils.Dict.merge(
+      Js.Dict.fromArray([
+        (Table.ImmutableRow.chainIdFieldName, eventIdentifier.chainId->Utils.magic),
+        (Table.ImmutableRow.blockNumberFieldName, eventIdentifier.blockNumber->Utils.magic),
+        (Table.ImmutableRow.logIndexFieldName, eventIdentifier.logIndex->Utils.magic),
+      ]),
+    )
+  async (sql: Postgres.sql, rows: array<('entity, Types.eventIdentifier)>, ~logger=?) => {
+    switch rows->Belt.Array.map(((entity, eventIdentifier)) => toRow(entity, eventIdentifier)) {
+    | exception exn =>
+      exn->ErrorHandling.mkLogAndRaise(
+        ~logger?,
+        ~msg=`Failed during batch serialization of entity ${table.tableName}`,
+      )
+    | rows =>
+      switch await query(sql, rows->Utils.magic) {
+      | exception exn =>
+        exn->ErrorHandling.mkLogAndRaise(
+          ~logger?,
+          ~msg=`Failed during batch insert of immutable entity ${table.tableName}, an entity with the same id might already exist`,
+        )
+      | res => res
+      }
+    }
+  }
+}
+
 @module("./DbFunctionsImplementation.js")
 external batchDeleteItemsInTable: (
   ~table: Table.table,
@@ -95,6 +130,15 @@ let batchSet = (type entity, ~entityMod: module(Entities.Entity with type t = en
   }
 }
 
+let batchInsertImmutable = (
+  type entity,
+  ~entityMod: module(Entities.Entity with type t = entity),
+) => {
+  let module(EntityMod) = entityMod
+  let {table, schema} = module(EntityMod)
+  makeBatchInsertImmutable(~table, ~schema)
+}
+
 let batchDelete = (type entity, ~entityMod: module(Entities.Entity with type t = entity)) => {
   let module(EntityMod) = entityMod
   let {table} = module(EntityMod)
@@ -117,6 +161,14 @@ external whereGtQuery: (
   ~value: Js.Json.t,
 ) => promise<Js.Json.t> = "whereGtQuery"
 
+@module("./DbFunctionsImplementation.js")
+external whereHasQuery: (
+  ~table: Table.table,
+  ~sql: Postgres.sql,
+  ~fieldName: string,
+  ~value: Js.Json.t,
+) => promise<Js.Json.t> = "whereHasQuery"
+
 let makeWhereQuery = (type entity, sql: Postgres.sql) => async (
   ~operator: TableIndices.Operator.t,
   ~entityMod: module(Entities.Entity with type t = entity),
@@ -130,11 +182,13 @@ let makeWhereQuery = (type entity, sql: Postgres.sql) => async (
   let queryType = switch operator {
   | Eq => "whereEq"
   | Gt => "whereGt"
+  | Has => "whereHas"
   }
 
   let query = switch operator {
   | Eq => whereEqQuery
   | Gt => whereGtQuery
+  | Has => whereHasQuery
   }
 
   let logger = Logging.createChildFrom(
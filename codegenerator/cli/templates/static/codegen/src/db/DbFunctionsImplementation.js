@@ -70,6 +70,22 @@ module.exports.whereGtQuery = (table, sql, fieldName, value) => {
     `;
 };
 
+module.exports.whereHasQuery = (table, sql, fieldName, value) => {
+  const joinTableName = TableModule.JoinTable.getNameByDbFieldName(
+    table,
+    fieldName
+  );
+  return sql`
+    SELECT *
+    FROM ${sql(publicSchema)}.${sql(table.tableName)}
+    WHERE "id" IN (
+      SELECT ${sql(TableModule.JoinTable.sourceFieldName)}
+      FROM ${sql(publicSchema)}.${sql(joinTableName)}
+      WHERE ${sql(TableModule.JoinTable.targetFieldName)} = ${value}
+    );
+    `;
+};
+
 module.exports.readLatestSyncedEventOnChainId = (sql, chainId) => sql`
   SELECT *
   FROM ${sql(publicSchema)}.event_sync_state
@@ -145,6 +161,25 @@ module.exports.makeBatchSetEntityValues = (table) => {
     (fieldName) => fieldName !== "db_write_timestamp"
   );
   const primaryKeyFieldNames = TableModule.getPrimaryKeyFieldNames(table);
+  // Values of JSONB fields might be arrays,
+  // which postgres would otherwise try to serialize as postgres arrays
+  const jsonFieldNames = TableModule.getFields(table)
+    .filter((field) => field.fieldType === "JSONB")
+    .map(TableModule.getDbFieldName);
+  const toRowData =
+    jsonFieldNames.length === 0
+      ? (_sql, rowDataArray) => rowDataArray
+      : (sql, rowDataArray) =>
+          rowDataArray.map((rowData) => {
+            const jsonRowData = { ...rowData };
+            jsonFieldNames.forEach((fieldName) => {
+              const value = rowData[fieldName];
+              if (value !== null && value !== undefined) {
+                jsonRowData[fieldName] = sql.json(value);
+              }
+            });
+            return jsonRowData;
+          });
   const fieldQueryConstructors = fieldNames.map(
     (fieldName) => (sql) => sql`${sql(fieldName)} = EXCLUDED.${sql(fieldName)}`
   );
@@ -152,10 +187,20 @@ module.exports.makeBatchSetEntityValues = (table) => {
     (pkField) => (sql) => sql(pkField)
   );
 
+  if (table.isImmutable) {
+    // Rows of immutable tables are never updated after the first insert,
+    // so an insert conflicting with an existing row fails
+    return chunkBatchQuery((sql, rowDataArray) => {
+      return sql`
+INSERT INTO ${sql(publicSchema)}.${sql(table.tableName)}
+${sql(toRowData(sql, rowDataArray), ...fieldNames)};`;
+    });
+  }
+
   return chunkBatchQuery((sql, rowDataArray) => {
     return sql`
 INSERT INTO ${sql(publicSchema)}.${sql(table.tableName)}
-${sql(rowDataArray, ...fieldNames)}
+${sql(toRowData(sql, rowDataArray), ...fieldNames)}
 ON CONFLICT(${sql`${commaSeparateDynamicMapQuery(
       sql,
       pkQueryConstructors
@@ -217,6 +262,38 @@ module.exports.rollbackEndOfBlockRangeScannedDataForChain = (
     AND block_number > ${knownBlockNumber};`;
 };
 
+module.exports.readImmutableRowIdsFromEvent = (
+  sql,
+  table,
+  chainId,
+  blockNumber,
+  logIndex
+) => {
+  return sql`
+    SELECT "id"
+    FROM ${sql(publicSchema)}.${sql(table.tableName)}
+    WHERE ${sql(TableModule.ImmutableRow.chainIdFieldName)} = ${chainId}
+    AND (${sql(TableModule.ImmutableRow.blockNumberFieldName)}, ${sql(
+    TableModule.ImmutableRow.logIndexFieldName
+  )}) >= (${blockNumber}, ${logIndex});`;
+};
+
+module.exports.deleteImmutableRowsFromEvent = (
+  sql,
+  table,
+  chainId,
+  blockNumber,
+  logIndex
+) => {
+  return sql`
+    DELETE
+    FROM ${sql(publicSchema)}.${sql(table.tableName)}
+    WHERE ${sql(TableModule.ImmutableRow.chainIdFieldName)} = ${chainId}
+    AND (${sql(TableModule.ImmutableRow.blockNumberFieldName)}, ${sql(
+    TableModule.ImmutableRow.logIndexFieldName
+  )}) >= (${blockNumber}, ${logIndex});`;
+};
+
 module.exports.deleteInvalidDynamicContractsOnRestart = (
   sql,
   chainId,
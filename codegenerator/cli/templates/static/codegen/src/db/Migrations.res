@@ -1,26 +1,37 @@
 let sql = Db.sql
 let unsafe = Postgres.unsafe
 
+// The up migrations are the only source of the table ddl. The columns missing from an
+// existing table are added as well, so the schema changes planned by the CLI only need to
+// alter or drop the existing columns. Primary key and serial columns are never added to an
+// existing table, re-adding a serial column would leave an orphan sequence behind
 let creatTableIfNotExists = (sql, table) => {
   open Belt
-  let fieldsMapped =
-    table
-    ->Table.getFields
-    ->Array.map(field => {
-      let {fieldType, isNullable, isArray, defaultValue} = field
-      let fieldName = field->Table.getDbFieldName
-
-      {
-        `"${fieldName}" ${switch fieldType {
-          | Custom(name) if !(name->Js.String2.startsWith("NUMERIC(")) => `"${Env.Db.publicSchema}".${name}`
-          | _ => (fieldType :> string)
-          }}${isArray ? "[]" : ""}${switch defaultValue {
-          | Some(defaultValue) => ` DEFAULT ${defaultValue}`
-          | None => isNullable ? `` : ` NOT NULL`
-          }}`
+  let fields = table->Table.getFields
+  let makeFieldDefinition = (field: Table.field) => {
+    let {fieldType, isNullable, isArray, defaultValue} = field
+    let fieldName = field->Table.getDbFieldName
+
+    {
+      `"${fieldName}" ${switch fieldType {
+        | Custom(name) if !(name->Js.String2.startsWith("NUMERIC(")) => `"${Env.Db.publicSchema}".${name}`
+        | _ => (fieldType :> string)
+        }}${isArray ? "[]" : ""}${switch defaultValue {
+        | Some(defaultValue) => ` DEFAULT ${defaultValue}`
+        | None => isNullable ? `` : ` NOT NULL`
+        }}`
+    }
+  }
+  let fieldsMapped = fields->Array.map(makeFieldDefinition)->Js.Array2.joinWith(", ")
+  let addMissingFields =
+    fields
+    ->Array.keep((field: Table.field) =>
+      switch field.fieldType {
+      | Serial => false
+      | _ => !field.isPrimaryKey
       }
-    })
-    ->Js.Array2.joinWith(", ")
+    )
+    ->Array.map(field => `ADD COLUMN IF NOT EXISTS ${field->makeFieldDefinition}`)
 
   let primaryKeyFieldNames = table->Table.getPrimaryKeyFieldNames
   let primaryKey =
@@ -31,15 +42,74 @@ let creatTableIfNotExists = (sql, table) => {
   let query = `
     CREATE TABLE IF NOT EXISTS "${Env.Db.publicSchema}"."${table.tableName}"(${fieldsMapped}${primaryKeyFieldNames->Array.length > 0
       ? `, PRIMARY KEY(${primaryKey})`
-      : ""});`
+      : ""});${addMissingFields->Array.length > 0
+      ? `
+    ALTER TABLE "${Env.Db.publicSchema}"."${table.tableName}" ${addMissingFields->Js.Array2.joinWith(", ")};`
+      : ""}`
 
   sql->unsafe(query)
 }
 
-let makeCreateIndexQuery = (~tableName, ~indexFields) => {
-  let indexName = tableName ++ "_" ++ indexFields->Js.Array2.joinWith("_")
+let makeCreateIndexQuery = (~tableName, ~indexFields, ~isUnique=false) => {
+  let indexName =
+    tableName ++ "_" ++ indexFields->Js.Array2.joinWith("_") ++ (isUnique ? "_unique" : "")
   let index = indexFields->Belt.Array.map(idx => `"${idx}"`)->Js.Array2.joinWith(", ")
-  `CREATE INDEX IF NOT EXISTS "${indexName}" ON "${Env.Db.publicSchema}"."${tableName}"(${index}); `
+  `CREATE ${isUnique ? "UNIQUE " : ""}INDEX IF NOT EXISTS "${indexName}" ON "${Env.Db.publicSchema}"."${tableName}"(${index}); `
+}
+
+module Crypto = {
+  type hash
+  @module("crypto") external createHash: string => hash = "createHash"
+  @send external update: (hash, string) => hash = "update"
+  @send external digest: (hash, string) => string = "digest"
+}
+
+/*
+Postgres truncates identifiers longer than 63 bytes, so long index names could collide.
+Such names are shortened and suffixed with a hash of the full name instead.
+Should match the index names of the schema snapshot in the CLI.
+*/
+let shortenIndexName = indexName => {
+  let maxIdentifierLength = 63
+  let hashLength = 8
+  if indexName->Js.String2.length <= maxIdentifierLength {
+    indexName
+  } else {
+    let hash = Crypto.createHash("sha256")->Crypto.update(indexName)->Crypto.digest("hex")
+    indexName->Js.String2.slice(~from=0, ~to_=maxIdentifierLength - hashLength - 1) ++
+    "_" ++
+    hash->Js.String2.slice(~from=0, ~to_=hashLength)
+  }
+}
+
+// The predicate is generated by the CLI with the quoted column names of the entity
+let makeCreateCustomIndexQuery = (~tableName, ~index: Table.customIndex) => {
+  open Belt
+  let {fieldNames, method, orders, predicate} = index
+  let indexName = shortenIndexName(
+    tableName ++
+    "_" ++
+    fieldNames->Js.Array2.joinWith("_") ++
+    "_" ++
+    (method :> string) ++
+    orders->Array.map(order => "_" ++ (order :> string)->Js.String2.toLowerCase)->Js.Array2.joinWith("") ++ (
+      predicate->Option.isSome ? "_partial" : ""
+    ),
+  )
+  let indexFields =
+    fieldNames
+    ->Array.mapWithIndex((i, fieldName) =>
+      switch orders->Array.get(i) {
+      | Some(order) => `"${fieldName}" ${(order :> string)}`
+      | None => `"${fieldName}"`
+      }
+    )
+    ->Js.Array2.joinWith(", ")
+  let wherePredicate = switch predicate {
+  | Some(predicate) => ` WHERE ${predicate}`
+  | None => ""
+  }
+  `CREATE INDEX IF NOT EXISTS "${indexName}" ON "${Env.Db.publicSchema}"."${tableName}" USING ${(method :> string)}(${indexFields})${wherePredicate}; `
 }
 
 let createTableIndices = (sql, table: Table.table) => {
@@ -50,12 +120,22 @@ let createTableIndices = (sql, table: Table.table) => {
     makeCreateIndexQuery(~tableName, ~indexFields)
   }
 
+  let createUniqueIndex = indexFields => {
+    makeCreateIndexQuery(~tableName, ~indexFields, ~isUnique=true)
+  }
+
   let singleIndices = table->Table.getSingleIndices
   let compositeIndices = table->Table.getCompositeIndices
+  let customIndices = table->Table.getCustomIndices
+  let uniqueConstraints = table->Table.getUniqueConstraints
 
   let query =
     singleIndices->Array.map(createIndex)->Js.Array2.joinWith("\n") ++
-      compositeIndices->Array.map(createCompositeIndex)->Js.Array2.joinWith("\n")
+      compositeIndices->Array.map(createCompositeIndex)->Js.Array2.joinWith("\n") ++
+      customIndices
+      ->Array.map(index => makeCreateCustomIndexQuery(~tableName, ~index))
+      ->Js.Array2.joinWith("\n") ++
+      uniqueConstraints->Array.map(createUniqueIndex)->Js.Array2.joinWith("\n")
 
   sql->unsafe(query)
 }
@@ -69,6 +149,18 @@ let createDerivedFromDbIndex = (~derivedFromField: Table.derivedFromField, ~sche
   sql->unsafe(query)
 }
 
+let createOrReplaceView = (sql, view: Table.view) => {
+  let fields =
+    view.fieldNames->Belt.Array.map(fieldName => `"${fieldName}"`)->Js.Array2.joinWith(", ")
+  let selects =
+    view.tableNames
+    ->Belt.Array.map(tableName =>
+      `SELECT ${fields}, '${tableName}' AS "${Table.viewEntityTypeFieldName}" FROM "${view.schemaName}"."${tableName}"`
+    )
+    ->Js.Array2.joinWith(" UNION ALL ")
+  sql->unsafe(`CREATE OR REPLACE VIEW "${view.schemaName}"."${view.viewName}" AS ${selects};`)
+}
+
 let createEnumIfNotExists = (sql, enum: Enum.enum<_>) => {
   open Belt
   let {variants, name} = enum
@@ -93,7 +185,7 @@ let deleteAllTables: unit => promise<unit> = async () => {
     BEGIN
       DROP SCHEMA IF EXISTS ${Env.Db.publicSchema} CASCADE;
       CREATE SCHEMA ${Env.Db.publicSchema};
-      GRANT ALL ON SCHEMA ${Env.Db.publicSchema} TO ${Env.Db.user};
+      GRANT ALL ON SCHEMA ${Env.Db.publicSchema} TO CURRENT_USER;
       GRANT ALL ON SCHEMA ${Env.Db.publicSchema} TO public;
     END $$;`
 
@@ -155,7 +247,13 @@ let runUpMigrations = async (
   })
 
   //Create all tables with indices
-  await [Db.allStaticTables, Db.allEntityTables, Db.allEntityHistoryTables]
+  await [
+    Db.allStaticTables,
+    Db.allEntityTables,
+    Db.allJoinTables,
+    Db.allAggregationTables,
+    Db.allEntityHistoryTables,
+  ]
   ->Belt.Array.concatMany
   ->awaitEach(async table => {
     await creatTableIfNotExists(Db.sql, table)->handleFailure(
@@ -172,14 +270,72 @@ let runUpMigrations = async (
     ->handleFailure(~msg=`EE800: Error creating ${entityHistory.table.tableName} insert function`)
   })
 
+  //Create the triggers keeping the join tables in sync with the many-to-many fields
+  await Db.allEntityTables->awaitEach(async table => {
+    await table
+    ->Table.getManyToManyFields
+    ->awaitEach(field => {
+      sql
+      ->unsafe(table->Table.JoinTable.makeSyncTriggerQuery(field))
+      ->handleFailure(
+        ~msg=`EE800: Error creating ${table->Table.JoinTable.getName(field)} sync trigger`,
+      )
+    })
+  })
+
+  //Create the full text search columns, indices and functions
+  await Db.allEntityTables->awaitEach(async table => {
+    await table.fulltextSearches->awaitEach((fulltextSearch: Table.fulltextSearch) => {
+      sql
+      ->unsafe(table->Table.FulltextSearch.makeCreateQuery(fulltextSearch))
+      ->handleFailure(~msg=`EE800: Error creating ${fulltextSearch.name} full text search`)
+    })
+  })
+
+  //Create the triggers rolling up the timeseries rows into the aggregation tables
+  await Entities.allAggregations->awaitEach(async aggregation => {
+    let {source, dimensionFieldNames} = aggregation
+    await sql
+    ->unsafe(
+      makeCreateIndexQuery(
+        ~tableName=source.tableName,
+        ~indexFields=source->Table.toDbFieldNamesUnsafe(
+          dimensionFieldNames->Js.Array2.concat([Table.Aggregation.timestampFieldName]),
+        ),
+      ),
+    )
+    ->handleFailure(~msg=`EE800: Error creating ${aggregation.name} source index`)
+    await aggregation.intervals->awaitEach(interval => {
+      sql
+      ->unsafe(aggregation->Table.Aggregation.makeSyncTriggerQuery(interval))
+      ->handleFailure(
+        ~msg=`EE800: Error creating ${aggregation->Table.Aggregation.getTableName(
+            interval,
+          )} aggregation trigger`,
+      )
+    })
+  })
+
+  //Create all interface and union views (must be done after all tables are created)
+  await Db.allEntityViews->awaitEach(view => {
+    createOrReplaceView(Db.sql, view)->handleFailure(
+      ~msg=`EE800: Error creating ${view.viewName} view`,
+    )
+  })
+
   //Create all derivedFromField indices (must be done after all tables are created)
   await Db.allEntityTables->awaitEach(async table => {
     await table
     ->Table.getDerivedFromFields
-    ->awaitEach(derivedFromField => {
-      createDerivedFromDbIndex(~derivedFromField, ~schema=Db.schema)->handleFailure(
-        ~msg=`Error creating derivedFrom index of "${derivedFromField.fieldName}" in entity "${table.tableName}"`,
-      )
+    ->awaitEach(async derivedFromField => {
+      switch Db.schema->Schema.getDerivedFromJoinTableName(derivedFromField) {
+      //The join table is already indexed
+      | Some(_) => ()
+      | None =>
+        await createDerivedFromDbIndex(~derivedFromField, ~schema=Db.schema)->handleFailure(
+          ~msg=`Error creating derivedFrom index of "${derivedFromField.fieldName}" in entity "${table.tableName}"`,
+        )
+      }
     })
   })
 
@@ -86,7 +86,10 @@ module PersistedState = {
     id: int,
     envio_version: string,
     config_hash: string,
+    network_config_hashes: string,
+    runtime_config_hash: string,
     schema_hash: string,
+    schema_snapshot: string,
     handler_files_hash: string,
     abi_files_hash: string,
   }
@@ -98,7 +101,10 @@ module PersistedState = {
       mkField("id", Serial, ~isPrimaryKey),
       mkField("envio_version", Text),
       mkField("config_hash", Text),
+      mkField("network_config_hashes", Text),
+      mkField("runtime_config_hash", Text),
       mkField("schema_hash", Text),
+      mkField("schema_snapshot", Text),
       mkField("handler_files_hash", Text),
       mkField("abi_files_hash", Text),
     ],
@@ -131,6 +131,56 @@ let trackTables = async (~tableNames: array<string>) => {
   }
 }
 
+let trackFunction = async (~functionName: string) => {
+  let body = {
+    "type": "pg_track_function",
+    "args": {
+      "function": {
+        "schema": Env.Db.publicSchema,
+        "name": functionName,
+      },
+      "source": "default",
+      "configuration": {
+        // Otherwise the function in gql will be prefixed with the schema name (when it's not public)
+        "custom_name": functionName,
+      },
+    },
+  }
+
+  let response = await fetch(
+    Env.Hasura.graphqlEndpoint,
+    {
+      method: #POST,
+      body: body->Js.Json.stringifyAny->Belt.Option.getExn->Body.string,
+      headers: Headers.fromObject(headers),
+    },
+  )
+
+  let responseJson = await response->Response.json
+  let statusCode = response->Response.status
+
+  switch validateHasuraResponse(~statusCode, ~responseJson) {
+  | Error(_) =>
+    Logging.error({
+      "msg": `EE809: There was an issue tracking the ${functionName} function in hasura - indexing may still work - but you may have issues querying the data in hasura.`,
+      "functionName": functionName,
+      "requestStatusCode": statusCode,
+      "requestResponseJson": responseJson,
+    })
+  | Ok(case) =>
+    let msg = switch case {
+    | QuerySucceeded => "Function Tracked"
+    | AlreadyDone => "Function Already Tracked"
+    }
+    Logging.trace({
+      "msg": msg,
+      "functionName": functionName,
+      "requestStatusCode": statusCode,
+      "requestResponseJson": responseJson,
+    })
+  }
+}
+
 let createSelectPermissions = async (~tableName: string) => {
   let body = {
     "type": "pg_create_select_permission",
@@ -234,31 +284,85 @@ let trackAllTables = async () => {
 
   let _ = await clearHasuraMetadata()
   let tableNames =
-    [Db.allStaticTables, Db.allEntityTables]
+    [Db.allStaticTables, Db.allEntityTables, Db.allJoinTables, Db.allAggregationTables]
     ->Belt.Array.concatMany
     ->Js.Array2.map(({tableName}) => tableName)
+    //Views are tracked the same way as tables
+    ->Js.Array2.concat(Db.allEntityViews->Js.Array2.map(({viewName}) => viewName))
   await trackTables(~tableNames)
   await tableNames->Utils.Array.awaitEach(async tableName => {
     await createSelectPermissions(~tableName)
   })
 
+  //Full text search functions inherit the select permissions of the entity table
+  await Db.allEntityTables->Utils.Array.awaitEach(async table => {
+    await table.fulltextSearches->Utils.Array.awaitEach(async ({name}: Table.fulltextSearch) => {
+      await trackFunction(~functionName=name)
+    })
+  })
+
   await Db.allEntityTables->Utils.Array.awaitEach(async table => {
     let {tableName} = table
     //Set array relationships
     await table
     ->Table.getDerivedFromFields
     ->Utils.Array.awaitEach(async derivedFromField => {
-      //determines the actual name of the underlying relational field (if it's an entity mapping then suffixes _id for eg.)
-      let relationalFieldName =
-        Db.schema->Schema.getDerivedFromFieldName(derivedFromField)->Utils.unwrapResultExn
+      switch Db.schema->Schema.getDerivedFromJoinTableName(derivedFromField) {
+      //Many-to-many relationships are looked up through the join table
+      | Some(joinTableName) =>
+        await createEntityRelationship(
+          ~tableName,
+          ~relationshipType="array",
+          ~isDerivedFrom=true,
+          ~objectName=derivedFromField.fieldName,
+          ~relationalKey=Table.JoinTable.targetFieldName,
+          ~mappedEntity=joinTableName,
+        )
+      | None =>
+        //determines the actual name of the underlying relational field (if it's an entity mapping then suffixes _id for eg.)
+        let relationalFieldName =
+          Db.schema->Schema.getDerivedFromFieldName(derivedFromField)->Utils.unwrapResultExn
 
+        await createEntityRelationship(
+          ~tableName,
+          ~relationshipType="array",
+          ~isDerivedFrom=true,
+          ~objectName=derivedFromField.fieldName,
+          ~relationalKey=relationalFieldName,
+          ~mappedEntity=derivedFromField.derivedFromEntity,
+        )
+      }
+    })
+
+    //Set many-to-many relationships through the join tables
+    await table
+    ->Table.getManyToManyFields
+    ->Utils.Array.awaitEach(async field => {
+      let joinTableName = table->Table.JoinTable.getName(field)
       await createEntityRelationship(
         ~tableName,
         ~relationshipType="array",
         ~isDerivedFrom=true,
-        ~objectName=derivedFromField.fieldName,
-        ~relationalKey=relationalFieldName,
-        ~mappedEntity=derivedFromField.derivedFromEntity,
+        ~objectName=field.fieldName,
+        ~relationalKey=Table.JoinTable.sourceFieldName,
+        ~mappedEntity=joinTableName,
+      )
+      //The object relationships of the join table to both of the entities
+      await createEntityRelationship(
+        ~tableName=joinTableName,
+        ~relationshipType="object",
+        ~isDerivedFrom=false,
+        ~objectName="source",
+        ~relationalKey="source",
+        ~mappedEntity=tableName,
+      )
+      await createEntityRelationship(
+        ~tableName=joinTableName,
+        ~relationshipType="object",
+        ~isDerivedFrom=false,
+        ~objectName="target",
+        ~relationalKey="target",
+        ~mappedEntity=field.linkedEntity->Belt.Option.getUnsafe,
       )
     })
 
@@ -276,4 +380,20 @@ let trackAllTables = async () => {
       )
     })
   })
+
+  //Set object relationships of the aggregation dimensions
+  await Db.allAggregationTables->Utils.Array.awaitEach(async table => {
+    await table
+    ->Table.getLinkedEntityFields
+    ->Utils.Array.awaitEach(async ((field, linkedEntityName)) => {
+      await createEntityRelationship(
+        ~tableName=table.tableName,
+        ~relationshipType="object",
+        ~isDerivedFrom=false,
+        ~objectName=field.fieldName,
+        ~relationalKey=field.fieldName,
+        ~mappedEntity=linkedEntityName,
+      )
+    })
+  })
 }
@@ -19,6 +19,8 @@ type t = {
   timestampCaughtUpToHeadOrEndblock: option<Js.Date.t>,
   dbFirstEventBlockNumber: option<int>,
   latestProcessedBlock: option<int>,
+  //The block up to which the block handlers of the chain have run
+  blockHandlersProcessedTo: int,
   numEventsProcessed: int,
   numBatchesFetched: int,
   lastBlockScannedHashes: ReorgDetection.LastBlockScannedHashes.t,
@@ -40,6 +42,7 @@ let make = (
   ~endBlock,
   ~dbFirstEventBlockNumber,
   ~latestProcessedBlock,
+  ~blockHandlersProcessedTo,
   ~logger,
   ~timestampCaughtUpToHeadOrEndblock,
   ~numEventsProcessed,
@@ -61,12 +64,49 @@ let make = (
   let staticContracts = Js.Dict.empty()
   let eventConfigs: array<FetchState.eventConfig> = []
 
+  // Keep only the bounds narrower than the chain ones,
+  // so events without custom ranges are queried as usual
+  let makeEventConfig = (
+    ~contractName,
+    ~eventId,
+    ~isWildcard,
+    ~eventStartBlock,
+    ~eventEndBlock,
+  ): FetchState.eventConfig => {
+    contractName,
+    eventId,
+    isWildcard,
+    startBlock: ?(eventStartBlock > chainConfig.startBlock ? Some(eventStartBlock) : None),
+    endBlock: ?(eventEndBlock != chainConfig.endBlock ? eventEndBlock : None),
+  }
+
   chainConfig.contracts->Array.forEach(contract => {
     let contractName = contract.name
+    let contractStartBlock = contract.startBlock->Option.getWithDefault(chainConfig.startBlock)
+    let contractEndBlock = switch contract.endBlock {
+    | Some(_) as endBlock => endBlock
+    | None => chainConfig.endBlock
+    }
 
     contract.events->Array.forEach(event => {
       let module(Event) = event
 
+      let configuredBlockRange =
+        contract.eventBlockRanges
+        ->Option.getWithDefault([])
+        ->Js.Array2.find((range: Config.eventBlockRange) => range.eventName === Event.name)
+      let eventStartBlock = Pervasives.max(
+        switch configuredBlockRange {
+        | Some({startBlock: Some(startBlock)}) => startBlock
+        | _ => contractStartBlock
+        },
+        chainConfig.startBlock,
+      )
+      let eventEndBlock = switch configuredBlockRange {
+      | Some({endBlock: Some(_) as endBlock}) => endBlock
+      | _ => contractEndBlock
+      }
+
       let {isWildcard, preRegisterDynamicContracts} =
         Event.handlerRegister->Types.HandlerTypes.Register.getEventOptions
       let hasContractRegister =
@@ -101,11 +141,36 @@ let make = (
       }
 
       if shouldBeIncluded {
-        eventConfigs->Array.push({
-          contractName,
-          eventId: Event.id,
-          isWildcard,
-        })
+        eventConfigs->Array.push(
+          makeEventConfig(
+            ~contractName,
+            ~eventId=Event.id,
+            ~isWildcard,
+            ~eventStartBlock,
+            ~eventEndBlock,
+          ),
+        )
+      }
+    })
+
+    contract
+    ->FunctionCalls.getFunctions
+    ->Array.forEach(function => {
+      let module(Function) = function
+      // Calls can't register dynamic contracts, so they aren't needed on preRegistration
+      let shouldBeIncluded =
+        !isPreRegisteringDynamicContracts &&
+        Function.handlerRegister->Types.OnCall.Register.getHandler->Option.isSome
+      if shouldBeIncluded {
+        eventConfigs->Array.push(
+          makeEventConfig(
+            ~contractName,
+            ~eventId=Function.selector,
+            ~isWildcard=false,
+            ~eventStartBlock=Pervasives.max(contractStartBlock, chainConfig.startBlock),
+            ~eventEndBlock=contractEndBlock,
+          ),
+        )
       }
     })
 
@@ -119,6 +184,10 @@ let make = (
     ~startBlock,
     ~endBlock,
     ~eventConfigs,
+    ~hasBlockHandlers=chainConfig.blockHandlers
+    ->BlockHandlers.getRegisteredBlockHandlers
+    ->Utils.Array.isEmpty
+    ->not,
   )
 
   {
@@ -135,6 +204,7 @@ let make = (
     fetchState,
     dbFirstEventBlockNumber,
     latestProcessedBlock,
+    blockHandlersProcessedTo,
     timestampCaughtUpToHeadOrEndblock,
     numEventsProcessed,
     numBatchesFetched,
@@ -159,6 +229,7 @@ let makeFromConfig = (chainConfig: Config.chainConfig, ~maxAddrInPartition, ~ena
     ~lastBlockScannedHashes,
     ~dbFirstEventBlockNumber=None,
     ~latestProcessedBlock=None,
+    ~blockHandlersProcessedTo=chainConfig.startBlock - 1,
     ~timestampCaughtUpToHeadOrEndblock=None,
     ~numEventsProcessed=0,
     ~numBatchesFetched=0,
@@ -193,6 +264,7 @@ let makeFromDbState = async (
     restartLogIndex: int,
     isPreRegisteringDynamicContracts: bool,
     processingFilters: option<array<processingFilter>>,
+    blockHandlersProcessedTo: int,
   ) = switch latestProcessedEvent {
   | Some(event) =>
     // Start from the same block but filter out any events already processed
@@ -215,8 +287,10 @@ let makeFromDbState = async (
       event.logIndex,
       event.isPreRegisteringDynamicContracts,
       Some(processingFilters),
+      //The sync state is saved with the block handler log index, once the block handlers of the block ran
+      event.logIndex === BlockHandlers.logIndex ? event.blockNumber : event.blockNumber - 1,
     )
-  | None => (chainConfig.startBlock, 0, preRegisterDynamicContracts, None)
+  | None => (chainConfig.startBlock, 0, preRegisterDynamicContracts, None, chainConfig.startBlock - 1)
   }
 
   let _ = await Promise.all([
@@ -307,6 +381,7 @@ let makeFromDbState = async (
     ~lastBlockScannedHashes,
     ~dbFirstEventBlockNumber=firstEventBlockNumber,
     ~latestProcessedBlock=latestProcessedBlockChainMetadata,
+    ~blockHandlersProcessedTo,
     ~timestampCaughtUpToHeadOrEndblock,
     ~numEventsProcessed=numEventsProcessed->Option.getWithDefault(0),
     ~numBatchesFetched=0,
@@ -405,8 +480,15 @@ let hasProcessedToEndblock = (self: t) => {
   }
 }
 
+//Whether the block handlers still need to run on some of the fetched blocks
+let hasPendingBlockHandlers = (self: t) =>
+  self.chainConfig.blockHandlers->BlockHandlers.hasRuns(
+    ~fromBlock=self.blockHandlersProcessedTo + 1,
+    ~toBlock=(self.fetchState->FetchState.getLatestFullyFetchedBlock).blockNumber,
+  )
+
 let hasNoMoreEventsToProcess = (self: t, ~hasArbQueueEvents) => {
-  !hasArbQueueEvents && self.fetchState->FetchState.queueSize === 0
+  !hasArbQueueEvents && self.fetchState->FetchState.queueSize === 0 && !(self->hasPendingBlockHandlers)
 }
 
 let getHeighestBlockBelowThreshold = (cf: t): int => {
@@ -466,6 +548,75 @@ let getLastKnownValidBlock = async (
   }
 }
 
+//The last block to keep, when reprocessing the events from the given block
+let getBlockBeforeReprocessing = async (chainFetcher: t, ~blockNumber) => {
+  switch await chainFetcher.sourceManager.activeSource.getBlockHashes(
+    ~blockNumbers=[blockNumber - 1],
+    ~logger=chainFetcher.logger,
+  ) {
+  | Ok([block]) => block
+  | Ok(_) =>
+    Js.Exn.raiseError(
+      "Unexpected case. Failed to fetch block data for the block before the reprocessed block",
+    )
+  | Error(exn) =>
+    exn->ErrorHandling.mkLogAndRaise(
+      ~msg="Failed to fetch block data for the block before the reprocessed block",
+    )
+  }
+}
+
+//Fetches the timestamps of the blocks the planned block handlers run on
+let getBlockHandlerRunsWithTimestamps = async (
+  chainFetcher: t,
+  ~chainRuns: BlockHandlers.chainRuns<BlockHandlers.plannedRun>,
+): BlockHandlers.chainRuns<BlockHandlers.run> => {
+  let blockNumbers = []
+  chainRuns.runs->Array.forEach(({blockNumber}) =>
+    switch blockNumbers->Utils.Array.last {
+    | Some(lastBlockNumber) if lastBlockNumber === blockNumber => ()
+    | _ => blockNumbers->Js.Array2.push(blockNumber)->ignore
+    }
+  )
+
+  let blockTimestamps = switch blockNumbers {
+  | [] => Js.Dict.empty()
+  | _ =>
+    switch await chainFetcher.sourceManager.activeSource.getBlockHashes(
+      ~blockNumbers,
+      ~logger=chainFetcher.logger,
+    ) {
+    | Ok(blocks) =>
+      blocks
+      ->Array.map(({blockNumber, blockTimestamp}) => (blockNumber->Int.toString, blockTimestamp))
+      ->Js.Dict.fromArray
+    | Error(exn) =>
+      exn->ErrorHandling.mkLogAndRaise(
+        ~logger=chainFetcher.logger,
+        ~msg="Failed to fetch the timestamps of the blocks to run the block handlers on",
+      )
+    }
+  }
+
+  {
+    processedTo: chainRuns.processedTo,
+    runs: chainRuns.runs->Array.map(({blockNumber, blockHandlerName, handler}): BlockHandlers.run => {
+      blockNumber,
+      blockHandlerName,
+      handler,
+      blockTimestamp: switch blockTimestamps->Utils.Dict.dangerouslyGetNonOption(
+        blockNumber->Int.toString,
+      ) {
+      | Some(blockTimestamp) => blockTimestamp
+      | None =>
+        Js.Exn.raiseError(
+          `Unexpected case. Missing the timestamp of block ${blockNumber->Int.toString} to run the block handlers on`,
+        )
+      },
+    }),
+  }
+}
+
 let isFetchingAtHead = (chainFetcher: t) => chainFetcher.fetchState.isFetchingAtHead
 
 let isActivelyIndexing = (chainFetcher: t) => chainFetcher.fetchState->FetchState.isActivelyIndexing
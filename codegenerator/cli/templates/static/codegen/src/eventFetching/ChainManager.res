@@ -118,7 +118,8 @@ let determineNextEvent = (
       (chain, {fetchState, heighestBlockBelowThreshold}),
     ) => {
       // If the fetch state has reached the end block we don't need to consider it
-      if fetchState->FetchState.isActivelyIndexing {
+      // Chains with only block handlers don't have events to order
+      if fetchState->FetchState.isActivelyIndexing && fetchState->FetchState.hasEventsToFetch {
         let earliestEvent = fetchState->FetchState.getEarliestEvent
         let current: multiChainEventComparitor = {chain, earliestEvent}
         switch accum.val {
@@ -347,10 +348,84 @@ let createBatchInternal = (
 
 type batchRes = {
   batch: array<Internal.eventItem>,
+  blockHandlerRuns: ChainMap.t<BlockHandlers.chainRuns<BlockHandlers.plannedRun>>,
   fetchStatesMap: ChainMap.t<fetchStateWithData>,
   arbitraryEventQueue: array<Internal.eventItem>,
 }
 
+/**
+Plans the block handler runs of each chain up to the block before its next unprocessed event,
+or up to its latest fetched block, when the batch includes all of its fetched events.
+Beyond the events of the batch, at most maxBatchSize runs are planned per chain.
+*/
+let planBlockHandlerRuns = (
+  self: t,
+  ~batch: array<Internal.eventItem>,
+  ~fetchStatesMap: ChainMap.t<fetchStateWithData>,
+  ~arbitraryEventQueue: array<Internal.eventItem>,
+  ~maxBatchSize,
+  ~onlyBelowReorgThreshold,
+) => {
+  let isInReorgThreshold = ref(false)
+  let blockHandlerRuns = self.chainFetchers->ChainMap.map(cf => {
+    let chain = cf.chainConfig.chain
+    let {fetchState, heighestBlockBelowThreshold} = fetchStatesMap->ChainMap.get(chain)
+    let fromBlock = cf.blockHandlersProcessedTo + 1
+
+    let nextUnprocessedBlock = Utils.Math.minOptInt(
+      switch fetchState->FetchState.getEarliestEvent {
+      | Item({item}) => Some(item.blockNumber)
+      | NoItem(_) => None
+      },
+      arbitraryEventQueue
+      ->Utils.Array.findReverseWithIndex(item => item.chain == chain)
+      ->Option.map(((item, _)) => item.blockNumber),
+    )
+    let toBlock = switch nextUnprocessedBlock {
+    | Some(blockNumber) => blockNumber - 1
+    | None => (fetchState->FetchState.getLatestFullyFetchedBlock).blockNumber
+    }
+    let toBlock = if onlyBelowReorgThreshold && toBlock > heighestBlockBelowThreshold {
+      if (
+        cf.chainConfig.blockHandlers->BlockHandlers.hasRuns(
+          ~fromBlock=Pervasives.max(fromBlock, heighestBlockBelowThreshold + 1),
+          ~toBlock,
+        )
+      ) {
+        isInReorgThreshold := true
+      }
+      heighestBlockBelowThreshold
+    } else {
+      toBlock
+    }
+
+    if (
+      cf->ChainFetcher.isPreRegisteringDynamicContracts ||
+      toBlock < fromBlock ||
+      cf.chainConfig.blockHandlers->BlockHandlers.getRegisteredBlockHandlers->Utils.Array.isEmpty
+    ) {
+      {BlockHandlers.runs: [], processedTo: cf.blockHandlersProcessedTo}
+    } else {
+      let runs =
+        cf.chainConfig.blockHandlers->BlockHandlers.planRuns(
+          ~fromBlock,
+          ~toBlock,
+          ~mustRunToBlock=switch batch->Utils.Array.findReverseWithIndex(item => item.chain == chain) {
+          | Some((item, _)) => item.blockNumber - 1
+          | None => fromBlock - 1
+          },
+          ~maxRuns=maxBatchSize,
+        )
+      if runs.processedTo > heighestBlockBelowThreshold && runs.runs->Utils.Array.isEmpty->not {
+        isInReorgThreshold := true
+      }
+      runs
+    }
+  })
+
+  {val: blockHandlerRuns, isInReorgThreshold: isInReorgThreshold.contents}
+}
+
 let createBatch = (self: t, ~maxBatchSize: int, ~onlyBelowReorgThreshold: bool) => {
   let refTime = Hrtime.makeTimer()
 
@@ -375,9 +450,23 @@ let createBatch = (self: t, ~maxBatchSize: int, ~onlyBelowReorgThreshold: bool)
     }
   })
 
+  let {val: blockHandlerRuns, isInReorgThreshold: isBlockHandlerRunInReorgThreshold} =
+    self->planBlockHandlerRuns(
+      ~batch,
+      ~fetchStatesMap,
+      ~arbitraryEventQueue,
+      ~maxBatchSize,
+      ~onlyBelowReorgThreshold,
+    )
+  let isInReorgThreshold = isInReorgThreshold || isBlockHandlerRunInReorgThreshold
+  let hasBlockHandlerRuns =
+    blockHandlerRuns
+    ->ChainMap.values
+    ->Array.some(({runs}) => runs->Utils.Array.isEmpty->not)
+
   let batchSize = batch->Array.length
 
-  let val = if batchSize > 0 {
+  let val = if batchSize > 0 || hasBlockHandlerRuns {
     let fetchedEventsBuffer =
       fetchStatesMap
       ->ChainMap.entries
@@ -407,7 +496,7 @@ let createBatch = (self: t, ~maxBatchSize: int, ~onlyBelowReorgThreshold: bool)
       Benchmark.addSummaryData(~group, ~label=`Batch Size`, ~value=batchSize->Belt.Int.toFloat)
     }
 
-    Some({batch, fetchStatesMap, arbitraryEventQueue})
+    Some({batch, blockHandlerRuns, fetchStatesMap, arbitraryEventQueue})
   } else {
     None
   }
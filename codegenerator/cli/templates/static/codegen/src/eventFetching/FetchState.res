@@ -17,6 +17,10 @@ type eventConfig = {
   contractName: string,
   eventId: string,
   isWildcard: bool,
+  // The block range where the event is indexed,
+  // when it differs from the chain one
+  startBlock?: int,
+  endBlock?: int,
 }
 
 type selection = {
@@ -87,6 +91,110 @@ let checkIsInSelection = (~selection, ~contractName, ~eventId, ~isWildcard) => {
     })
 }
 
+/*
+Events might be indexed only in a part of the chain blocks.
+Queries select only the events indexed at their fromBlock
+and stop at the block where the set of the indexed events changes.
+*/
+module BlockRange = {
+  let hasBlockRange = (ec: eventConfig) =>
+    ec.startBlock->Option.isSome || ec.endBlock->Option.isSome
+
+  let includes = (ec: eventConfig, ~blockNumber) =>
+    switch ec.startBlock {
+    | Some(startBlock) => startBlock <= blockNumber
+    | None => true
+    } &&
+    switch ec.endBlock {
+    | Some(endBlock) => blockNumber <= endBlock
+    | None => true
+    }
+
+  type segment = {
+    fromBlock: int,
+    toBlock: option<int>,
+    selection: selection,
+  }
+
+  // Sources cache their query config by the selection object,
+  // so every narrowed selection is created only once
+  let narrowedSelectionsCache: Utils.WeakMap.t<selection, dict<selection>> = Utils.WeakMap.make()
+
+  let getNarrowedSelection = (selection: selection, ~eventConfigs) =>
+    if eventConfigs->Array.length === selection.eventConfigs->Array.length {
+      selection
+    } else {
+      let narrowedSelections = switch narrowedSelectionsCache->Utils.WeakMap.get(selection) {
+      | Some(narrowedSelections) => narrowedSelections
+      | None => {
+          let narrowedSelections = Js.Dict.empty()
+          narrowedSelectionsCache->Utils.WeakMap.set(selection, narrowedSelections)->ignore
+          narrowedSelections
+        }
+      }
+      let key =
+        eventConfigs
+        ->Array.map(ec => `${ec.contractName}.${ec.eventId}`)
+        ->Js.Array2.joinWith(",")
+      switch narrowedSelections->Utils.Dict.dangerouslyGetNonOption(key) {
+      | Some(narrowedSelection) => narrowedSelection
+      | None => {
+          let narrowedSelection = {isWildcard: selection.isWildcard, eventConfigs}
+          narrowedSelections->Js.Dict.set(key, narrowedSelection)
+          narrowedSelection
+        }
+      }
+    }
+
+  /**
+  Returns the first block range starting from the block,
+  where at least one of the selection events is indexed.
+  None when the selection doesn't have events to index anymore.
+  */
+  let getSegment = (selection: selection, ~fromBlock) =>
+    if selection.eventConfigs->Array.some(hasBlockRange)->not {
+      Some({fromBlock, toBlock: None, selection})
+    } else {
+      // The blocks where an event starts or stops being indexed
+      let boundaries = []
+      selection.eventConfigs->Array.forEach(ec => {
+        switch ec.startBlock {
+        | Some(startBlock) => boundaries->Array.push(startBlock)
+        | None => ()
+        }
+        switch ec.endBlock {
+        | Some(endBlock) => boundaries->Array.push(endBlock + 1)
+        | None => ()
+        }
+      })
+      let getNextBoundary = blockNumber =>
+        boundaries->Array.reduce(None, (acc, boundary) =>
+          boundary > blockNumber &&
+            switch acc {
+            | Some(acc) => boundary < acc
+            | None => true
+            }
+            ? Some(boundary)
+            : acc
+        )
+
+      let rec loop = fromBlock => {
+        let eventConfigs = selection.eventConfigs->Array.keep(includes(_, ~blockNumber=fromBlock))
+        switch (eventConfigs, getNextBoundary(fromBlock)) {
+        | ([], None) => None
+        | ([], Some(nextBoundary)) => loop(nextBoundary)
+        | (_, nextBoundary) =>
+          Some({
+            fromBlock,
+            toBlock: nextBoundary->Option.map(b => b - 1),
+            selection: getNarrowedSelection(selection, ~eventConfigs),
+          })
+        }
+      }
+      loop(fromBlock)
+    }
+}
+
 /*
 Comapritor for two events from the same chain. No need for chain id or timestamp
 */
@@ -233,6 +341,19 @@ let addItemsToPartition = (
  * for a contract that emits events with dynamic contracts, it is possible that those dynamic contracts will need to be indexed from blocks way before
  * the current block height. This is a toleration check where if there are dynamic contracts within a batch, check how far are they from the currentblock height.
  * If it is less than 1 thousandth of a percent, then we deem that contract to be within the synced range, and therefore do not reset the synced status of the chain */
+let getPartitionFromBlock = (p: partition) =>
+  switch p.latestFetchedBlock.blockNumber {
+  | 0 => 0
+  | latestFetchedBlockNumber => latestFetchedBlockNumber + 1
+  }
+
+/*
+The partition doesn't have events to fetch after its latest fetched block,
+because the block ranges of all its events have ended
+*/
+let isFinishedPartition = (p: partition) =>
+  p.selection->BlockRange.getSegment(~fromBlock=p->getPartitionFromBlock)->Option.isNone
+
 let checkIsWithinSyncRange = (~latestFetchedBlock: blockNumberAndTimestamp, ~currentBlockHeight) =>
   (currentBlockHeight->Int.toFloat -. latestFetchedBlock.blockNumber->Int.toFloat) /.
     currentBlockHeight->Int.toFloat <= 0.001
@@ -247,10 +368,24 @@ let updateInternal = (
   ~firstEventBlockNumber=fetchState.firstEventBlockNumber,
   ~currentBlockHeight=?,
 ): t => {
-  let firstPartition = partitions->Js.Array2.unsafe_get(0)
-
   let queueSize = ref(0)
-  let latestFullyFetchedBlock = ref(firstPartition.latestFetchedBlock)
+  // Finished partitions are considered fetched up to the chain height
+  let fetchingPartitions = partitions->Array.keep(p => !(p->isFinishedPartition))
+  let latestFullyFetchedBlock = ref(
+    switch (fetchingPartitions[0], currentBlockHeight) {
+    | (Some(firstPartition), _) => firstPartition.latestFetchedBlock
+    // Chains with only block handlers don't have anything to fetch,
+    // so all the blocks up to the chain height are considered fetched
+    | (None, Some(currentBlockHeight)) => {
+        blockNumber: switch fetchState.endBlock {
+        | Some(endBlock) => Pervasives.min(currentBlockHeight, endBlock)
+        | None => currentBlockHeight
+        },
+        blockTimestamp: 0,
+      }
+    | (None, None) => fetchState.latestFullyFetchedBlock
+    },
+  )
 
   for idx in 0 to partitions->Array.length - 1 {
     let p = partitions->Js.Array2.unsafe_get(idx)
@@ -258,11 +393,13 @@ let updateInternal = (
     let partitionQueueSize = p.fetchedEventQueue->Array.length
 
     queueSize := queueSize.contents + partitionQueueSize
+  }
 
+  fetchingPartitions->Array.forEach(p => {
     if latestFullyFetchedBlock.contents.blockNumber > p.latestFetchedBlock.blockNumber {
       latestFullyFetchedBlock := p.latestFetchedBlock
     }
-  }
+  })
 
   if (
     Env.Benchmark.shouldSaveData && fetchState.partitions->Array.length !== partitions->Array.length
@@ -477,33 +614,51 @@ let setQueryResponse = (
     )
   })
 
-let makePartitionQuery = (p: partition, ~endBlock, ~mergeTarget) => {
-  let fromBlock = switch p.latestFetchedBlock.blockNumber {
-  | 0 => 0
-  | latestFetchedBlockNumber => latestFetchedBlockNumber + 1
+let makePartitionQuery = (p: partition, ~endBlock, ~mergeTarget, ~currentBlockHeight) => {
+  switch p.selection->BlockRange.getSegment(~fromBlock=p->getPartitionFromBlock) {
+  | None => None
+  // Skipped blocks without events to index shouldn't go beyond the head
+  | Some({fromBlock}) if fromBlock > currentBlockHeight => None
+  | Some({fromBlock, toBlock: segmentToBlock, selection}) =>
+    let endBlock = Utils.Math.minOptInt(endBlock, segmentToBlock)
+    switch (endBlock, mergeTarget) {
+    | (Some(endBlock), _) if fromBlock > endBlock => None
+    | (_, Some(mergeTarget))
+      if fromBlock <= mergeTarget.latestFetchedBlock.blockNumber &&
+        switch endBlock {
+        | Some(endBlock) => mergeTarget.latestFetchedBlock.blockNumber <= endBlock
+        | None => true
+        } =>
+      Some(
+        Merge({
+          toBlock: mergeTarget.latestFetchedBlock.blockNumber,
+          intoPartitionId: mergeTarget.id,
+        }),
+      )
+    | (Some(endBlock), _) => Some(EndBlock({toBlock: endBlock}))
+    | (None, _) => Some(Head)
+    }->Option.map(target => {
+      {
+        partitionId: p.id,
+        fromBlock,
+        target,
+        selection,
+        contractAddressMapping: p.contractAddressMapping,
+      }
+    })
   }
-  switch (endBlock, mergeTarget) {
-  | (Some(endBlock), _) if fromBlock > endBlock => None
-  | (_, Some(mergeTarget)) =>
-    Some(
-      Merge({
-        toBlock: mergeTarget.latestFetchedBlock.blockNumber,
-        intoPartitionId: mergeTarget.id,
-      }),
-    )
-  | (Some(endBlock), None) => Some(EndBlock({toBlock: endBlock}))
-  | (None, None) => Some(Head)
-  }->Option.map(target => {
-    {
-      partitionId: p.id,
-      fromBlock,
-      target,
-      selection: p.selection,
-      contractAddressMapping: p.contractAddressMapping,
-    }
-  })
 }
 
+/*
+The partition can't be queried until the new blocks,
+since its events are indexed only after the current height
+*/
+let isIdlePartition = (p: partition, ~currentBlockHeight) =>
+  switch p.selection->BlockRange.getSegment(~fromBlock=p->getPartitionFromBlock) {
+  | None => true
+  | Some({fromBlock}) => fromBlock > currentBlockHeight
+  }
+
 type nextQuery =
   | ReachedMaxConcurrency
   | WaitingForNewBlock
@@ -565,7 +720,8 @@ let getNextQuery = (
       let p = partitions->Js.Array2.unsafe_get(idx)
 
       let isFetching = checkIsFetchingPartition(p)
-      let isReachedTheHead = p.latestFetchedBlock.blockNumber >= currentBlockHeight
+      let isIdle = !isFetching && p->isIdlePartition(~currentBlockHeight)
+      let isReachedTheHead = p.latestFetchedBlock.blockNumber >= currentBlockHeight || isIdle
 
       if isFetching || !isReachedTheHead {
         // Even if there are some partitions waiting for the new block
@@ -576,7 +732,11 @@ let getNextQuery = (
         shouldWaitForNewBlock := false
       }
 
-      if p->isFullPartition(~maxAddrInPartition) {
+      if isIdle {
+        // Nothing to query for the partition until the new blocks.
+        // Also, it shouldn't hold the merging of the other partitions
+        ()
+      } else if p->isFullPartition(~maxAddrInPartition) {
         fullPartitions->Array.push(p)
       } else {
         mergingPartitions->Array.push(p)
@@ -633,7 +793,7 @@ let getNextQuery = (
             : !checkIsWithinSyncRange(~latestFetchedBlock=p.latestFetchedBlock, ~currentBlockHeight)
         )
       ) {
-        switch p->makePartitionQuery(~endBlock, ~mergeTarget) {
+        switch p->makePartitionQuery(~endBlock, ~mergeTarget, ~currentBlockHeight) {
         | Some(q) => queries->Array.push(q)
         | None => ()
         }
@@ -762,16 +922,24 @@ Gets the earliest queueItem from thgetNodeEarliestEventWithUpdatedQueue.
 Finds the earliest queue item across all partitions and then returns that
 queue item with an update fetch state.
 */
-let getEarliestEvent = ({partitions}: t) => {
-  let item = ref(partitions->Js.Array2.unsafe_get(0)->getEarliestEventInPartition)
-  for idx in 1 to partitions->Array.length - 1 {
+let getEarliestEvent = ({partitions, latestFullyFetchedBlock}: t) => {
+  let earliestItem = ref(None)
+  for idx in 0 to partitions->Array.length - 1 {
     let p = partitions->Js.Array2.unsafe_get(idx)
-    let pItem = p->getEarliestEventInPartition
-    if pItem->qItemLt(item.contents) {
-      item := pItem
+    // Finished partitions without items shouldn't hold the processing
+    // at their latest fetched block
+    if p.fetchedEventQueue->Utils.Array.isEmpty->not || !(p->isFinishedPartition) {
+      let pItem = p->getEarliestEventInPartition
+      switch earliestItem.contents {
+      | Some(item) if !(pItem->qItemLt(item)) => ()
+      | _ => earliestItem := Some(pItem)
+      }
     }
   }
-  item.contents
+  switch earliestItem.contents {
+  | Some(item) => item
+  | None => NoItem({latestFetchedBlock: latestFullyFetchedBlock})
+  }
 }
 
 /**
@@ -784,6 +952,7 @@ let make = (
   ~staticContracts: dict<array<Address.t>>,
   ~dynamicContracts: array<TablesStatic.DynamicContractRegistry.t>,
   ~maxAddrInPartition,
+  ~hasBlockHandlers=false,
 ): t => {
   let latestFetchedBlock = {
     blockTimestamp: 0,
@@ -891,9 +1060,9 @@ let make = (
     }
   }
 
-  if partitions->Array.length === 0 {
+  if partitions->Array.length === 0 && !hasBlockHandlers {
     Js.Exn.raiseError(
-      "Invalid configuration: Nothing to fetch. Make sure that you provided at least one contract address to index, or have events with Wildcard mode enabled.",
+      "Invalid configuration: Nothing to fetch. Make sure that you provided at least one contract address to index, have events with Wildcard mode enabled or block handlers.",
     )
   }
 
@@ -1058,12 +1227,31 @@ let rollbackPartition = (p: partition, ~firstChangeEvent: blockNumberAndLogIndex
 }
 
 let rollback = (fetchState: t, ~firstChangeEvent) => {
-  let partitions =
-    fetchState.partitions->Array.keepMap(p => p->rollbackPartition(~firstChangeEvent))
-
-  fetchState->updateInternal(~partitions)
+  switch fetchState.partitions {
+  // Without partitions, only the fetched block needs to be rolled back
+  | [] => {
+      ...fetchState,
+      latestFullyFetchedBlock: {
+        blockNumber: Pervasives.min(
+          fetchState.latestFullyFetchedBlock.blockNumber,
+          Pervasives.max(firstChangeEvent.blockNumber - 1, 0),
+        ),
+        blockTimestamp: 0,
+      },
+    }
+  | partitions =>
+    let partitions = partitions->Array.keepMap(p => p->rollbackPartition(~firstChangeEvent))
+    fetchState->updateInternal(~partitions)
+  }
 }
 
+//Chains with only block handlers or with ended event block ranges
+//don't have any events to fetch
+let hasEventsToFetch = ({partitions}: t) =>
+  partitions->Array.some(p =>
+    p.fetchedEventQueue->Utils.Array.isEmpty->not || !(p->isFinishedPartition)
+  )
+
 /**
 * Returns a boolean indicating whether the fetch state is actively indexing
 * used for comparing event queues in the chain manager
@@ -22,6 +22,19 @@ type blockRangeFetchResponse = {
   stats: blockRangeFetchStats,
 }
 
+/**
+The role of the source when a chain has multiple of them.
+Sources without a role are used as the primary one.
+*/
+type role =
+  // Used until the realtime source takes over
+  | Historical
+  // Takes over from the historical source close to the chain head.
+  // Both sources fall back to each other while a query takes longer than the stall timeout
+  | Realtime({switchBlocksBehindHead: int, stallTimeoutMillis: int})
+  // Takes over while a query to the primary source takes longer than the timeout
+  | Fallback({stallTimeoutMillis: int})
+
 type t = {
   name: string,
   chain: ChainMap.Chain.t,
@@ -33,6 +46,9 @@ type t = {
     ~logger: Pino.t,
   ) => promise<result<array<ReorgDetection.blockDataWithTimestamp>, exn>>,
   getHeightOrThrow: unit => promise<int>,
+  /* Resolves with the first height greater than the given one, received from a newHeads subscription. */
+  waitForNewHead?: (~currentBlockHeight: int) => promise<int>,
+  role?: role,
   fetchBlockRange: (
     ~fromBlock: int,
     ~toBlock: option<int>,
@@ -44,6 +60,117 @@ type t = {
   ) => promise<result<blockRangeFetchResponse, ErrorHandling.t>>,
 }
 
+let withRole = (source: t, role) => {...source, role}
+
+let raceWithFallback = (
+  primaryFn,
+  ~fallbackFn,
+  ~stallTimeoutMillis,
+  ~primaryName,
+  ~fallbackName,
+  ~logger,
+) => {
+  let fallbackPromise = ref(None)
+  let getFallbackPromise = (~reason) =>
+    switch fallbackPromise.contents {
+    | Some(promise) => promise
+    | None =>
+      logger->Logging.childWarn(`${reason} Querying the ${fallbackName} fallback instead.`)
+      let promise = fallbackFn()
+      fallbackPromise := Some(promise)
+      promise
+    }
+
+  let isPrimaryOk = ref(false)
+  let primaryPromise = primaryFn()->Promise.then(result =>
+    switch result {
+    | Ok(_) =>
+      isPrimaryOk := true
+      Promise.resolve(result)
+    | Error(_) => getFallbackPromise(~reason=`The ${primaryName} query failed.`)
+    }
+  )
+
+  Promise.race([
+    primaryPromise,
+    Time.resolvePromiseAfterDelay(~delayMilliseconds=stallTimeoutMillis)->Promise.then(() =>
+      isPrimaryOk.contents
+        ? primaryPromise
+        : getFallbackPromise(
+            ~reason=`The ${primaryName} query didn't respond in ${stallTimeoutMillis->Int.toString}ms.`,
+          )
+    ),
+  ])
+}
+
+let toResult = promise =>
+  promise->Promise.thenResolve(value => Ok(value))->Promise.catch(exn => Error(exn)->Promise.resolve)
+
+// Uses the fallback source when the primary one fails
+// or doesn't respond within the stall timeout
+let withFallback = (primary: t, ~fallback: t, ~stallTimeoutMillis) => {
+  let heightLogger = Logging.createChild(
+    ~params={
+      "chainId": primary.chain->ChainMap.Chain.toChainId,
+      "logType": "Height Query",
+    },
+  )
+  {
+    ...primary,
+    getBlockHashes: (~blockNumbers, ~logger) =>
+      raceWithFallback(
+        () => primary.getBlockHashes(~blockNumbers, ~logger),
+        ~fallbackFn=() => fallback.getBlockHashes(~blockNumbers, ~logger),
+        ~stallTimeoutMillis,
+        ~primaryName=primary.name,
+        ~fallbackName=fallback.name,
+        ~logger,
+      ),
+    getHeightOrThrow: () =>
+      raceWithFallback(
+        () => primary.getHeightOrThrow()->toResult,
+        ~fallbackFn=() => fallback.getHeightOrThrow()->toResult,
+        ~stallTimeoutMillis,
+        ~primaryName=primary.name,
+        ~fallbackName=fallback.name,
+        ~logger=heightLogger,
+      )->Promise.then(result =>
+        switch result {
+        | Ok(height) => Promise.resolve(height)
+        | Error(exn) => Promise.reject(exn)
+        }
+      ),
+    fetchBlockRange: (
+      ~fromBlock,
+      ~toBlock,
+      ~contractAddressMapping,
+      ~currentBlockHeight,
+      ~partitionId,
+      ~selection,
+      ~logger,
+    ) => {
+      let fetch = (source: t) => () =>
+        source.fetchBlockRange(
+          ~fromBlock,
+          ~toBlock,
+          ~contractAddressMapping,
+          ~currentBlockHeight,
+          ~partitionId,
+          ~selection,
+          ~logger,
+        )
+      raceWithFallback(
+        fetch(primary),
+        ~fallbackFn=fetch(fallback),
+        ~stallTimeoutMillis,
+        ~primaryName=primary.name,
+        ~fallbackName=fallback.name,
+        ~logger,
+      )
+    },
+  }
+}
+
 let getHeightWithRetry = async (~source, ~logger) => {
   //Amount the retry interval is multiplied between each retry
   let backOffMultiplicative = 2
@@ -69,8 +196,11 @@ let getHeightWithRetry = async (~source, ~logger) => {
   height.contents
 }
 
+let subscriptionFallbackPollingMultiplier = 30
+
 //Poll for a height greater or equal to the given blocknumber.
 //Used for waiting until there is a new block to index
+//Sources with a newHeads subscription are notified instead of polling
 let waitForNewBlock = async (~source, ~currentBlockHeight, ~logger) => {
   let logger = Logging.createChildFrom(
     ~logger,
@@ -84,8 +214,28 @@ let waitForNewBlock = async (~source, ~currentBlockHeight, ~logger) => {
   let pollHeight = ref(await getHeightWithRetry(~source, ~logger))
 
   while pollHeight.contents <= currentBlockHeight {
-    await Time.resolvePromiseAfterDelay(~delayMilliseconds=source.pollingInterval)
-    pollHeight := (await getHeightWithRetry(~source, ~logger))
+    pollHeight := switch source.waitForNewHead {
+    | Some(waitForNewHead) =>
+      // Still poll once in a while, in case the subscription silently dropped
+      let fallbackPollingTimeoutId = ref(None)
+      let fallbackPolling = Promise.make((resolve, _reject) => {
+        fallbackPollingTimeoutId :=
+          Some(
+            Js.Global.setTimeout(
+              () => resolve(),
+              source.pollingInterval * subscriptionFallbackPollingMultiplier,
+            ),
+          )
+      })->Promise.then(() => getHeightWithRetry(~source, ~logger))
+      let height = await Promise.race([waitForNewHead(~currentBlockHeight), fallbackPolling])
+      // Don't keep the timer of the fallback polling
+      // when the subscription resolved first
+      fallbackPollingTimeoutId.contents->Option.forEach(Js.Global.clearTimeout)
+      height
+    | None =>
+      await Time.resolvePromiseAfterDelay(~delayMilliseconds=source.pollingInterval)
+      await getHeightWithRetry(~source, ~logger)
+    }
   }
 
   pollHeight.contents
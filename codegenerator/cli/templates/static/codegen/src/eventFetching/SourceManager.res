@@ -9,27 +9,81 @@ type t = {
   logger: Pino.t,
   sources: array<Source.t>,
   maxPartitionConcurrency: int,
+  // The source used until the realtime source takes over
+  mainSource: Source.t,
+  realtimeSource: option<(Source.t, int)>,
   mutable activeSource: Source.t,
   mutable waitingForNewBlockStateId: option<int>,
   // Should take into consideration partitions fetching for previous states (before rollback)
   mutable fetchingPartitionsCount: int,
 }
 
-let make = (~sources, ~maxPartitionConcurrency, ~logger) => {
-  let activeSource = switch sources->Array.get(0) {
-  | Some(source) => source
-  | None => Js.Exn.raiseError("Invalid configuration, no data-sources provided")
+let make = (~sources: array<Source.t>, ~maxPartitionConcurrency, ~logger) => {
+  let mainSource = ref(None)
+  let realtimeSource = ref(None)
+  let fallbackSource = ref(None)
+  sources->Array.forEach(source =>
+    switch (source.role, mainSource.contents) {
+    | (Some(Realtime({switchBlocksBehindHead, stallTimeoutMillis})), _) =>
+      realtimeSource := Some((source, switchBlocksBehindHead, stallTimeoutMillis))
+    | (Some(Fallback({stallTimeoutMillis})), _) =>
+      fallbackSource := Some((source, stallTimeoutMillis))
+    | (None | Some(Historical), None) => mainSource := Some(source)
+    | (None | Some(Historical), Some(_)) => ()
+    }
+  )
+  let mainSource = switch (mainSource.contents, fallbackSource.contents) {
+  | (Some(source), Some((fallback, stallTimeoutMillis))) =>
+    source->Source.withFallback(~fallback, ~stallTimeoutMillis)
+  | (Some(source), None) => source
+  | (None, _) => Js.Exn.raiseError("Invalid configuration, no data-sources provided")
+  }
+  // The historical and realtime sources hand over to each other when one of them stalls
+  let (mainSource, realtimeSource) = switch realtimeSource.contents {
+  | Some((realtimeSource, switchBlocksBehindHead, stallTimeoutMillis)) => (
+      mainSource->Source.withFallback(~fallback=realtimeSource, ~stallTimeoutMillis),
+      Some((
+        realtimeSource->Source.withFallback(~fallback=mainSource, ~stallTimeoutMillis),
+        switchBlocksBehindHead,
+      )),
+    )
+  | None => (mainSource, None)
   }
   {
     logger,
     maxPartitionConcurrency,
     sources,
-    activeSource,
+    mainSource,
+    realtimeSource,
+    activeSource: mainSource,
     waitingForNewBlockStateId: None,
     fetchingPartitionsCount: 0,
   }
 }
 
+let getActiveSource = (sourceManager: t, ~fetchState: FetchState.t, ~currentBlockHeight) => {
+  switch sourceManager.realtimeSource {
+  | Some((realtimeSource, switchBlocksBehindHead))
+    if currentBlockHeight > 0 &&
+      currentBlockHeight - fetchState.latestFullyFetchedBlock.blockNumber <=
+        switchBlocksBehindHead => realtimeSource
+  | _ => sourceManager.mainSource
+  }
+}
+
+let updateActiveSource = (sourceManager: t, ~fetchState, ~currentBlockHeight) => {
+  let activeSource = sourceManager->getActiveSource(~fetchState, ~currentBlockHeight)
+  if activeSource !== sourceManager.activeSource {
+    sourceManager.logger->Logging.childInfo({
+      "msg": `Switching data-source from ${sourceManager.activeSource.name} to ${activeSource.name}`,
+      "currentBlockHeight": currentBlockHeight,
+      "latestFetchedBlock": fetchState.latestFullyFetchedBlock.blockNumber,
+    })
+    sourceManager.activeSource = activeSource
+  }
+  activeSource
+}
+
 let fetchNext = async (
   sourceManager: t,
   ~fetchState: FetchState.t,
@@ -40,7 +94,8 @@ let fetchNext = async (
   ~maxPerChainQueueSize,
   ~stateId,
 ) => {
-  let {logger, maxPartitionConcurrency, activeSource} = sourceManager
+  let {logger, maxPartitionConcurrency} = sourceManager
+  let activeSource = sourceManager->updateActiveSource(~fetchState, ~currentBlockHeight)
 
   switch fetchState->FetchState.getNextQuery(
     ~concurrencyLimit={
@@ -27,6 +27,16 @@ type logsQueryPageItem = {
 
 type logsQueryPage = hyperSyncPage<logsQueryPageItem>
 
+type callsQueryPageItem = {
+  transaction: HyperSyncClient.ResponseTypes.transaction,
+  block: Internal.eventBlock,
+}
+
+type callsQueryPage = {
+  items: array<callsQueryPageItem>,
+  nextBlock: int,
+}
+
 type missingParams = {
   queryName: string,
   missingParams: array<string>,
@@ -198,6 +208,95 @@ module LogsQuery = {
   }
 }
 
+module CallsQuery = {
+  let convertResponse = (
+    res: HyperSyncClient.queryResponse,
+    ~nonOptionalBlockFieldNames,
+    ~nonOptionalTransactionFieldNames,
+  ): queryResponse<callsQueryPage> => {
+    let blocks = Js.Dict.empty()
+    res.data.blocks
+    ->Option.getWithDefault([])
+    ->Array.forEach(block =>
+      switch block.number {
+      | Some(blockNumber) => blocks->Js.Dict.set(blockNumber->Int.toString, block)
+      | None => ()
+      }
+    )
+
+    let missingParams = []
+    let items = res.data.transactions
+    ->Option.getWithDefault([])
+    ->Array.keepMap(transaction => {
+      missingParams->LogsQuery.addMissingParams(
+        ["blockNumber", "input", "to", "transactionIndex"]->Array.concat(
+          nonOptionalTransactionFieldNames,
+        ),
+        transaction,
+        ~prefix="transaction",
+      )
+      switch transaction.blockNumber->Option.flatMap(blockNumber =>
+        blocks->Utils.Dict.dangerouslyGetNonOption(blockNumber->Int.toString)
+      ) {
+      | Some(block) =>
+        missingParams->LogsQuery.addMissingParams(
+          nonOptionalBlockFieldNames,
+          block,
+          ~prefix="block",
+        )
+        Some({transaction, block: block->Utils.magic})
+      | None =>
+        missingParams->Array.push("block")
+        None
+      }
+    })
+
+    if missingParams->Array.length > 0 {
+      Error(
+        UnexpectedMissingParams({
+          queryName: "queryCallsPage HyperSync",
+          missingParams,
+        }),
+      )
+    } else {
+      Ok({items, nextBlock: res.nextBlock})
+    }
+  }
+
+  let queryCallsPage = async (
+    ~client: HyperSyncClient.t,
+    ~fromBlock,
+    ~toBlock,
+    ~transactionSelections,
+    ~fieldSelection,
+    ~nonOptionalBlockFieldNames,
+    ~nonOptionalTransactionFieldNames,
+    ~logger,
+  ): queryResponse<callsQueryPage> => {
+    let query: HyperSyncClient.QueryTypes.query = {
+      fromBlock,
+      toBlockExclusive: toBlock + 1,
+      transactions: transactionSelections,
+      fieldSelection,
+    }
+
+    let executeQuery = async () => {
+      let res = await client.get(~query)
+      if res.nextBlock <= fromBlock {
+        // Might happen when /height response was from another instance of HyperSync
+        Js.Exn.raiseError(
+          "Received page response from another instance of HyperSync. Should work after a retry.",
+        )
+      }
+      res
+    }
+
+    let res = await executeQuery->Time.retryAsyncWithExponentialBackOff(~logger)
+
+    res->convertResponse(~nonOptionalBlockFieldNames, ~nonOptionalTransactionFieldNames)
+  }
+}
+
 module BlockData = {
   let makeRequestBody = (~fromBlock, ~toBlock): HyperSyncJsonApi.QueryTypes.postQueryBody => {
     fromBlock,
@@ -346,6 +445,7 @@ module BlockData = {
 }
 
 let queryLogsPage = LogsQuery.queryLogsPage
+let queryCallsPage = CallsQuery.queryCallsPage
 let queryBlockData = (~serverUrl, ~blockNumber, ~logger) =>
   BlockData.queryBlockData(
     ~serverUrl,
@@ -6,7 +6,7 @@ module Helpers = {
     ~backoffMsOnFailure=200,
     ~callDepth=0,
     ~maxCallDepth=15,
-    query: unit => promise<HyperSync.queryResponse<HyperSync.logsQueryPage>>,
+    query: unit => promise<HyperSync.queryResponse<'page>>,
     logger: Pino.t,
   ) =>
     switch await query() {
@@ -43,7 +43,11 @@ type selectionConfig = {
   getLogSelectionOrThrow: (
     ~contractAddressMapping: ContractAddressingMap.mapping,
   ) => array<LogSelection.t>,
+  getTransactionSelections: (
+    ~contractAddressMapping: ContractAddressingMap.mapping,
+  ) => array<HyperSyncClient.QueryTypes.transactionSelection>,
   fieldSelection: HyperSyncClient.QueryTypes.fieldSelection,
+  callsFieldSelection: HyperSyncClient.QueryTypes.fieldSelection,
   nonOptionalBlockFieldNames: array<string>,
   nonOptionalTransactionFieldNames: array<string>,
 }
@@ -56,8 +60,27 @@ let getSelectionConfig = (selection: FetchState.selection, ~contracts: array<Con
   let wildcardTopicSelections = []
 
   let contractTopicSelections = []
+  let contractCallSelectors = []
 
   contracts->Array.forEach(contract => {
+    switch contract->FunctionCalls.getSelectors(~selection) {
+    | [] => ()
+    | selectors =>
+      contractCallSelectors->Array.push((contract.name, selectors))
+      nonOptionalBlockFieldNames->Utils.Set.addMany(
+        Types.Block.schema->Utils.Schema.getNonOptionalFieldNames,
+      )
+      nonOptionalTransactionFieldNames->Utils.Set.addMany(
+        Types.Transaction.schema->Utils.Schema.getNonOptionalFieldNames,
+      )
+      capitalizedBlockFields->Utils.Set.addMany(
+        Types.Block.schema->Utils.Schema.getCapitalizedFieldNames,
+      )
+      capitalizedTransactionFields->Utils.Set.addMany(
+        Types.Transaction.schema->Utils.Schema.getCapitalizedFieldNames,
+      )
+    }
+
     let normalTopicSelections = []
 
     contract.events->Array.forEach(event => {
@@ -113,6 +136,35 @@ let getSelectionConfig = (selection: FetchState.selection, ~contracts: array<Con
     ->(Utils.magic: array<string> => array<HyperSyncClient.QueryTypes.transactionField>),
   }
 
+  // The calls are matched by the transaction input and need the block of the transaction
+  let callsFieldSelection: HyperSyncClient.QueryTypes.fieldSelection = {
+    block: capitalizedBlockFields
+    ->Utils.Set.toArray
+    ->Array.concat(["Number", "Timestamp", "Hash"])
+    ->Utils.Set.fromEntries
+    ->Utils.Set.toArray
+    ->(Utils.magic: array<string> => array<HyperSyncClient.QueryTypes.blockField>),
+    transaction: capitalizedTransactionFields
+    ->Utils.Set.toArray
+    ->Array.concat(["BlockNumber", "Input", "To", "TransactionIndex"])
+    ->Utils.Set.fromEntries
+    ->Utils.Set.toArray
+    ->(Utils.magic: array<string> => array<HyperSyncClient.QueryTypes.transactionField>),
+  }
+
+  let getTransactionSelections = (~contractAddressMapping) =>
+    contractCallSelectors->Array.keepMap(((
+      contractName,
+      selectors,
+    )): option<HyperSyncClient.QueryTypes.transactionSelection> =>
+      switch contractAddressMapping->ContractAddressingMap.getAddressesFromContractName(
+        ~contractName,
+      ) {
+      | [] => None
+      | addresses => Some({to_: addresses, sighash: selectors})
+      }
+    )
+
   let getNormalLogSelectionOrThrow = (~contractAddressMapping): array<LogSelection.t> => {
     contractTopicSelections->Belt.Array.keepMap((data): option<LogSelection.t> => {
       let contractName = data["contractName"]
@@ -137,7 +189,9 @@ let getSelectionConfig = (selection: FetchState.selection, ~contracts: array<Con
 
   {
     getLogSelectionOrThrow,
+    getTransactionSelections,
     fieldSelection,
+    callsFieldSelection,
     nonOptionalBlockFieldNames: nonOptionalBlockFieldNames->Utils.Set.toArray,
     nonOptionalTransactionFieldNames: nonOptionalTransactionFieldNames->Utils.Set.toArray,
   }
@@ -476,6 +530,82 @@ let make = (
         })
       }
 
+      //Calls are queried separately for the same block range
+      let callItems = []
+      switch selectionConfig.getTransactionSelections(~contractAddressMapping) {
+      | [] => ()
+      | transactionSelections =>
+        let callsFromBlock = ref(fromBlock)
+        while callsFromBlock.contents <= heighestBlockQueried {
+          let callsPage = await Helpers.queryLogsPageWithBackoff(() =>
+            HyperSync.queryCallsPage(
+              ~client,
+              ~fromBlock=callsFromBlock.contents,
+              ~toBlock=heighestBlockQueried,
+              ~transactionSelections,
+              ~fieldSelection=selectionConfig.callsFieldSelection,
+              ~nonOptionalBlockFieldNames=selectionConfig.nonOptionalBlockFieldNames,
+              ~nonOptionalTransactionFieldNames=selectionConfig.nonOptionalTransactionFieldNames,
+              ~logger,
+            )
+          , logger)
+
+          callsPage.items->Array.forEach(({transaction, block}) => {
+            //The fields are checked to be present when converting the response
+            let srcAddress = transaction.to->Option.getUnsafe->Address.unsafeFromString
+            let input = transaction.input->Option.getUnsafe
+            let maybeContract =
+              contractAddressMapping
+              ->ContractAddressingMap.getContractNameFromAddress(~contractAddress=srcAddress)
+              ->Option.flatMap(contractName =>
+                contracts->Js.Array2.find(contract => contract.name === contractName)
+              )
+            switch maybeContract->Option.flatMap(contract =>
+              contract
+              ->FunctionCalls.getFunctionBySelector(~selector=input->FunctionCalls.getSelector)
+              ->Option.map(function => (contract, function))
+            ) {
+            | Some((contract, function)) =>
+              let inputs = try function->FunctionCalls.decodeInputsOrThrow(
+                ~abi=contract.abi,
+                ~input,
+              ) catch {
+              | exn =>
+                exn->mkLogAndRaise(
+                  ~msg=`Failed to decode the inputs of a ${contract.name} call, please double-check your ABI.`,
+                )
+              }
+              callItems
+              ->Js.Array2.push(
+                function->FunctionCalls.makeEventItem(
+                  ~inputs,
+                  ~chain,
+                  ~srcAddress,
+                  ~transactionIndex=transaction.transactionIndex->Option.getUnsafe,
+                  ~block,
+                  ~transaction=transaction->(
+                    Utils.magic: HyperSyncClient.ResponseTypes.transaction => Internal.eventTransaction
+                  ),
+                ),
+              )
+              ->ignore
+            | None => () //Ignore calls of functions that aren't registered
+            }
+          })
+          callsFromBlock := callsPage.nextBlock
+        }
+      }
+      let parsedQueueItems = switch callItems {
+      | [] => parsedQueueItems
+      | _ =>
+        Utils.Array.mergeSorted(
+          (a: Internal.eventItem, b: Internal.eventItem) =>
+            (a.blockNumber, a.logIndex) <= (b.blockNumber, b.logIndex),
+          parsedQueueItems,
+          callItems,
+        )
+      }
+
       let parsingTimeElapsed =
         parsingTimeRef->Hrtime.timeSince->Hrtime.toMillis->Hrtime.intFromMillis
 
@@ -7,6 +7,12 @@ let getSelectionConfig = (selection: FetchState.selection, ~contracts: array<Con
   let includedTopicSelections = []
 
   contracts->Belt.Array.forEach(contract => {
+    if contract->FunctionCalls.getSelectors(~selection)->Utils.Array.isEmpty->not {
+      Js.Exn.raiseError(
+        `RPC data-source doesn't support function calls, which are configured for the ${contract.name} contract. Please use HyperSync for the network.`,
+      )
+    }
+
     contract.events->Belt.Array.forEach(event => {
       let module(Event) = event
       let {isWildcard, topicSelections} =
@@ -141,13 +147,87 @@ let makeThrowingGetEventTransaction = (~getTransactionFields) => {
 type options = {
   syncConfig: Config.syncConfig,
   urls: array<string>,
+  // WebSocket urls used for the newHeads subscription
+  wsUrls?: array<string>,
   chain: ChainMap.Chain.t,
   contracts: array<Config.contract>,
   eventRouter: EventRouter.t<module(Types.InternalEvent)>,
 }
 
-let make = ({syncConfig, urls, chain, contracts, eventRouter}: options): t => {
-  let provider = Ethers.JsonRpcProvider.make(~rpcUrls=urls, ~chainId=chain->ChainMap.Chain.toChainId, ~fallbackStallTimeout=syncConfig.fallbackStallTimeout)
+let wsReconnectDelayMillis = 1000
+
+// Subscribes to newHeads with the first WebSocket url
+// and moves on to the next one whenever the connection is closed
+let makeWaitForNewHead = (~wsUrls, ~chainId, ~logger) => {
+  let latestHead = ref(0)
+  let resolvers = []
+  let wsUrlIndex = ref(0)
+
+  let onBlock = blockNumber => {
+    if blockNumber > latestHead.contents {
+      latestHead := blockNumber
+      resolvers
+      ->Js.Array2.removeCountInPlace(~pos=0, ~count=resolvers->Array.length)
+      ->Array.forEach(resolve => resolve(blockNumber))
+    }
+  }
+
+  let rec subscribe = () => {
+    let wsProvider = Ethers.WebSocketProvider.make(
+      ~wsUrl=wsUrls->Js.Array2.unsafe_get(wsUrlIndex.contents),
+      ~chainId,
+    )
+    wsProvider->Ethers.WebSocketProvider.onBlock(onBlock)
+    let websocket = wsProvider->Ethers.WebSocketProvider.websocket
+    websocket->Ethers.WebSocketProvider.onError(() => {
+      logger->Logging.childWarn({
+        "msg": "The newHeads subscription connection failed",
+        "wsUrlIndex": wsUrlIndex.contents,
+      })
+    })
+    websocket->Ethers.WebSocketProvider.onClose(() => {
+      wsProvider->Ethers.WebSocketProvider.destroy
+      wsUrlIndex := mod(wsUrlIndex.contents + 1, wsUrls->Array.length)
+      logger->Logging.childWarn({
+        "msg": `The newHeads subscription connection was closed. Reconnecting in ${wsReconnectDelayMillis->Int.toString}ms...`,
+        "wsUrlIndex": wsUrlIndex.contents,
+      })
+      Js.Global.setTimeout(subscribe, wsReconnectDelayMillis)->ignore
+    })
+  }
+  subscribe()
+
+  (~currentBlockHeight) =>
+    if latestHead.contents > currentBlockHeight {
+      Promise.resolve(latestHead.contents)
+    } else {
+      Promise.make((resolve, _reject) => resolvers->Js.Array2.push(resolve)->ignore)
+    }
+}
+
+let isWsUrl = url => url->Js.String2.startsWith("ws://") || url->Js.String2.startsWith("wss://")
+
+let make = ({syncConfig, urls: configUrls, ?wsUrls, chain, contracts, eventRouter}: options): t => {
+  let chainId = chain->ChainMap.Chain.toChainId
+  // The urls resolved from env variables at runtime
+  // can only be classified after the interpolation
+  let (runtimeWsUrls, urls) = configUrls->Array.partition(isWsUrl)
+  let wsUrls = wsUrls->Option.getWithDefault([])->Array.concat(runtimeWsUrls)
+  let provider = if urls->Utils.Array.isEmpty && wsUrls->Utils.Array.isEmpty->not {
+    // Use the WebSocket connections for queries
+    // when there are no http urls provided
+    Ethers.WebSocketProvider.makeWithFallbacks(
+      ~wsUrls,
+      ~chainId,
+      ~fallbackStallTimeout=syncConfig.fallbackStallTimeout,
+    )
+  } else {
+    Ethers.JsonRpcProvider.make(
+      ~rpcUrls=urls,
+      ~chainId,
+      ~fallbackStallTimeout=syncConfig.fallbackStallTimeout,
+    )
+  }
 
   let name = "RPC"
 
@@ -391,6 +471,17 @@ let make = ({syncConfig, urls, chain, contracts, eventRouter}: options): t => {
     pollingInterval: 1000,
     getBlockHashes,
     getHeightOrThrow: () => provider->Ethers.JsonRpcProvider.getBlockNumber,
+    waitForNewHead: ?(
+      wsUrls->Utils.Array.isEmpty
+        ? None
+        : Some(
+            makeWaitForNewHead(
+              ~wsUrls,
+              ~chainId,
+              ~logger=Logging.createChild(~params={"chainId": chainId, "source": name}),
+            ),
+          )
+    ),
     fetchBlockRange,
   }
 }
@@ -1,7 +1,12 @@
 open Belt
 
 type chain = ChainMap.Chain.t
-type rollbackState = NoRollback | RollingBack(chain) | RollbackInMemStore(InMemoryStore.t)
+type rollbackState =
+  | NoRollback
+  | RollingBack(chain)
+  //Rolling back the chain to reprocess its events with changed handlers
+  | ReprocessingFrom({chain: chain, blockNumber: int})
+  | RollbackInMemStore(InMemoryStore.t)
 
 module WriteThrottlers = {
   type t = {
@@ -82,6 +87,10 @@ let make = (~config, ~chainManager, ~loadLayer) => {
 let getId = self => self.id
 let incrementId = self => {...self, id: self.id + 1}
 let setRollingBack = (self, chain) => {...self, rollbackState: RollingBack(chain)}
+let setReprocessingFrom = (self, ~chain, ~blockNumber) => {
+  ...self,
+  rollbackState: ReprocessingFrom({chain, blockNumber}),
+}
 let setChainManager = (self, chainManager) => {
   ...self,
   chainManager,
@@ -89,7 +98,7 @@ let setChainManager = (self, chainManager) => {
 
 let isRollingBack = state =>
   switch state.rollbackState {
-  | RollingBack(_) => true
+  | RollingBack(_) | ReprocessingFrom(_) => true
   | _ => false
   }
 
@@ -274,7 +283,11 @@ let updateLatestProcessedBlocks = (
     ...state.chainManager,
     chainFetchers: state.chainManager.chainFetchers->ChainMap.map(cf => {
       let {chainConfig: {chain}, fetchState} = cf
-      let {numEventsProcessed, latestProcessedBlock} = latestProcessedBlocks->ChainMap.get(chain)
+      let {
+        numEventsProcessed,
+        latestProcessedBlock,
+        blockHandlersProcessedTo,
+      } = latestProcessedBlocks->ChainMap.get(chain)
 
       let hasArbQueueEvents = state.chainManager->ChainManager.hasChainItemsOnArbQueue(~chain)
       let hasNoMoreEventsToProcess = cf->ChainFetcher.hasNoMoreEventsToProcess(~hasArbQueueEvents)
@@ -289,6 +302,7 @@ let updateLatestProcessedBlocks = (
         ...cf,
         latestProcessedBlock,
         numEventsProcessed,
+        blockHandlersProcessedTo,
       }
     }),
   }
@@ -467,17 +481,29 @@ let updateChainFetcher = (chainFetcherUpdate, ~state, ~chain) => {
 
 let actionReducer = (state: t, action: action) => {
   switch action {
-  | FinishWaitingForNewBlock({chain, currentBlockHeight}) => (
+  | FinishWaitingForNewBlock({chain, currentBlockHeight}) =>
+    let chainFetcher = state.chainManager.chainFetchers->ChainMap.get(chain)
+    //A chain without events to fetch only follows the chain height,
+    //so the new blocks are ready for its block handlers
+    let isFollowingHeight = !(chainFetcher.fetchState->FetchState.hasEventsToFetch)
+    let chainFetcher = chainFetcher->updateChainFetcherCurrentBlockHeight(~currentBlockHeight)
+    let chainFetcher = if isFollowingHeight {
+      {
+        ...chainFetcher,
+        fetchState: chainFetcher.fetchState->FetchState.updateInternal(~currentBlockHeight),
+      }
+    } else {
+      chainFetcher
+    }
+    (
       {
         ...state,
         chainManager: {
           ...state.chainManager,
-          chainFetchers: state.chainManager.chainFetchers->ChainMap.update(chain, chainFetcher => {
-            chainFetcher->updateChainFetcherCurrentBlockHeight(~currentBlockHeight)
-          }),
+          chainFetchers: state.chainManager.chainFetchers->ChainMap.set(chain, chainFetcher),
         },
       },
-      [NextQuery(Chain(chain))],
+      isFollowingHeight ? [NextQuery(Chain(chain)), ProcessEventBatch] : [NextQuery(Chain(chain))],
     )
   | PartitionQueryResponse({chain, response, query}) =>
     state->handlePartitionQueryResponse(~chain, ~response, ~query)
@@ -733,7 +759,7 @@ let actionReducer = (state: t, action: action) => {
 
 let invalidatedActionReducer = (state: t, action: action) =>
   switch (state, action) {
-  | ({rollbackState: RollingBack(_)}, EventBatchProcessed(_)) =>
+  | ({rollbackState: RollingBack(_) | ReprocessingFrom(_)}, EventBatchProcessed(_)) =>
     Logging.info("Finished processing batch before rollback, actioning rollback")
     ({...state, currentlyProcessingBatch: false}, [Rollback])
   | (_, ErrorExit(_)) => actionReducer(state, action)
@@ -760,6 +786,20 @@ let executeQuery = (q: FetchState.query, ~logger, ~source, ~currentBlockHeight,
   )
 }
 
+//Fetches the block timestamps of the planned block handler runs of every chain
+let getBlockHandlerRunsWithTimestamps = async (chainManager: ChainManager.t, ~blockHandlerRuns) =>
+  (
+    await chainManager.chainFetchers
+    ->ChainMap.entries
+    ->Array.map(async ((chain, cf)) => (
+      chain,
+      await cf->ChainFetcher.getBlockHandlerRunsWithTimestamps(
+        ~chainRuns=blockHandlerRuns->ChainMap.get(chain),
+      ),
+    ))
+    ->Promise.all
+  )->ChainMap.fromArrayUnsafe
+
 let checkAndFetchForChain = (
   //Used for dependency injection for tests
   ~waitForNewBlock,
@@ -859,7 +899,7 @@ let injectedTaskReducer = (
           false
         }
         let timeRef = Hrtime.makeTimer()
-        let _ = await Promise.all(Entities.allEntities->Belt.Array.map(entityMod => {
+        let _ = await Promise.all(Entities.allEntitiesWithHistory->Belt.Array.map(entityMod => {
           let module(Entity) = entityMod
           Db.sql->DbFunctions.EntityHistory.pruneStaleEntityHistory(
             ~entityName=Entity.name,
@@ -1000,7 +1040,10 @@ let injectedTaskReducer = (
       ) {
       | {isInReorgThreshold: true, val: None} if onlyBelowReorgThreshold =>
         dispatchAction(SetIsInReorgThreshold(true))
-      | {isInReorgThreshold, val: Some({batch, fetchStatesMap, arbitraryEventQueue})} =>
+      | {
+          isInReorgThreshold,
+          val: Some({batch, fetchStatesMap, arbitraryEventQueue, blockHandlerRuns}),
+        } =>
         dispatchAction(SetCurrentlyProcessing(true))
         dispatchAction(UpdateQueues(fetchStatesMap, arbitraryEventQueue))
         if (
@@ -1038,7 +1081,8 @@ let injectedTaskReducer = (
         let rollbackInMemStore = switch state.rollbackState {
         | RollbackInMemStore(inMemoryStore) => Some(inMemoryStore)
         | NoRollback
-        | RollingBack(
+        | RollingBack(_)
+        | ReprocessingFrom(
           _,
         ) /* This is an impossible case due to the surrounding if statement check */ =>
           None
@@ -1048,6 +1092,9 @@ let injectedTaskReducer = (
 
         switch await EventProcessing.processEventBatch(
           ~eventBatch=batch,
+          ~blockHandlerRuns=await state.chainManager->getBlockHandlerRunsWithTimestamps(
+            ~blockHandlerRuns,
+          ),
           ~inMemoryStore,
           ~isInReorgThreshold,
           ~checkContractIsRegistered,
@@ -1078,14 +1125,20 @@ let injectedTaskReducer = (
   | Rollback =>
     //If it isn't processing a batch currently continue with rollback otherwise wait for current batch to finish processing
     switch state {
-    | {currentlyProcessingBatch: false, rollbackState: RollingBack(reorgChain)} =>
+    | {
+        currentlyProcessingBatch: false,
+        rollbackState: RollingBack(reorgChain) | ReprocessingFrom({chain: reorgChain}),
+      } =>
       let chainFetcher = state.chainManager.chainFetchers->ChainMap.get(reorgChain)
 
       let {
         blockNumber: lastKnownValidBlockNumber,
         blockTimestamp: lastKnownValidBlockTimestamp,
-      }: ReorgDetection.blockDataWithTimestamp =
-        await chainFetcher->getLastKnownValidBlock
+      }: ReorgDetection.blockDataWithTimestamp = switch state.rollbackState {
+      | ReprocessingFrom({blockNumber}) =>
+        await chainFetcher->ChainFetcher.getBlockBeforeReprocessing(~blockNumber)
+      | _ => await chainFetcher->getLastKnownValidBlock
+      }
 
       chainFetcher.logger->Logging.childInfo({
         "msg": "Executing indexer rollback",
@@ -1130,6 +1183,10 @@ let injectedTaskReducer = (
 
           let rolledBackCf = {
             ...cf,
+            blockHandlersProcessedTo: Pervasives.min(
+              cf.blockHandlersProcessedTo,
+              firstChangeEvent.blockNumber - 1,
+            ),
             lastBlockScannedHashes: chain == reorgChain
               ? cf.lastBlockScannedHashes->ReorgDetection.LastBlockScannedHashes.rollbackToValidBlockNumber(
                   ~blockNumber=lastKnownValidBlockNumber,
@@ -1164,6 +1221,7 @@ let injectedTaskReducer = (
         ~blockNumber=lastKnownValidBlockNumber,
         ~logIndex=0,
         ~isUnorderedMultichainMode,
+        ~firstChangeEventPerChain=firstChangeEventIdentifierPerChain,
       )
 
       let chainManager = {
@@ -63,6 +63,7 @@ impl InitCombo {
             directory: Some(self.get_dir()),
             output_directory: DEFAULT_GENERATED_PATH.to_string(),
             config: DEFAULT_CONFIG_PATH.to_string(),
+            profile: None,
         }
     }
 }
@@ -58,6 +58,24 @@ module type S = {
       with type eventArgs = Internal.eventParams
       and type transaction = Internal.eventTransaction
       and type block = Internal.eventBlock
+
+    module OnCall: {
+      module Register: {
+        type t
+      }
+    }
+
+    module type Function = {
+      let selector: string
+      let name: string
+      let abiName: string
+      let contractName: string
+      let inputNames: array<string>
+      type inputs
+      type handler
+      let inputsSchema: RescriptSchema.S.schema<inputs>
+      let handlerRegister: OnCall.Register.t
+    }
   }
 
   module ContractAddressingMap: {
@@ -134,11 +152,18 @@ module type S = {
   }
 
   module Config: {
+    type blockHandler
+    type eventBlockRange
+
     type contract = {
       name: string,
       abi: Ethers.abi,
       addresses: array<Address.t>,
       events: array<module(Types.Event)>,
+      functions?: array<module(Types.Function)>,
+      startBlock?: int,
+      endBlock?: int,
+      eventBlockRanges?: array<eventBlockRange>,
     }
 
     type chainConfig = {
@@ -147,6 +172,7 @@ module type S = {
       confirmedBlockThreshold: int,
       chain: ChainMap.Chain.t,
       contracts: array<contract>,
+      blockHandlers: array<blockHandler>,
       sources: array<Source.t>,
     }
   }
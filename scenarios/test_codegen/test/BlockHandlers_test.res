@@ -0,0 +1,113 @@
+open RescriptMocha
+
+let mockBlockHandler = (~name, ~interval, ~startBlock, ~endBlock=?): Config.blockHandler => {
+  let handlerRegister = Types.OnBlock.Register.make(~name)
+  handlerRegister->Types.OnBlock.Register.setHandler(async _ => ())
+  {name, interval, startBlock, endBlock, handlerRegister}
+}
+
+let toBlockNumbersWithNames = (runs: array<BlockHandlers.plannedRun>) =>
+  runs->Js.Array2.map(({blockNumber, blockHandlerName}) => (blockNumber, blockHandlerName))
+
+describe("BlockHandlers", () => {
+  it("Gets the next run block respecting the start block, interval and end block", () => {
+    let blockHandler = mockBlockHandler(~name="Every3", ~interval=3, ~startBlock=10, ~endBlock=20)
+
+    Assert.deepEqual(blockHandler->BlockHandlers.getNextRunBlock(~fromBlock=0), Some(10))
+    Assert.deepEqual(blockHandler->BlockHandlers.getNextRunBlock(~fromBlock=11), Some(13))
+    Assert.deepEqual(blockHandler->BlockHandlers.getNextRunBlock(~fromBlock=19), Some(19))
+    Assert.deepEqual(blockHandler->BlockHandlers.getNextRunBlock(~fromBlock=20), None)
+  })
+
+  it("Plans the runs of all the block handlers in block order", () => {
+    let blockHandlers = [
+      mockBlockHandler(~name="Every2", ~interval=2, ~startBlock=0),
+      mockBlockHandler(~name="Every3", ~interval=3, ~startBlock=0),
+    ]
+
+    let chainRuns =
+      blockHandlers->BlockHandlers.planRuns(
+        ~fromBlock=1,
+        ~toBlock=6,
+        ~mustRunToBlock=0,
+        ~maxRuns=100,
+      )
+
+    Assert.deepEqual(
+      chainRuns.runs->toBlockNumbersWithNames,
+      [(2, "Every2"), (3, "Every3"), (4, "Every2"), (6, "Every2"), (6, "Every3")],
+    )
+    Assert.equal(chainRuns.processedTo, 6)
+  })
+
+  it("Processes the whole range when there are no runs in it", () => {
+    let blockHandlers = [mockBlockHandler(~name="Every10", ~interval=10, ~startBlock=0)]
+
+    let chainRuns =
+      blockHandlers->BlockHandlers.planRuns(
+        ~fromBlock=1,
+        ~toBlock=9,
+        ~mustRunToBlock=0,
+        ~maxRuns=100,
+      )
+
+    Assert.deepEqual(chainRuns.runs, [])
+    Assert.equal(chainRuns.processedTo, 9)
+  })
+
+  it("Cuts the range at maxRuns, but never before mustRunToBlock", () => {
+    let blockHandlers = [mockBlockHandler(~name="EveryBlock", ~interval=1, ~startBlock=0)]
+
+    let chainRuns =
+      blockHandlers->BlockHandlers.planRuns(
+        ~fromBlock=1,
+        ~toBlock=100,
+        ~mustRunToBlock=0,
+        ~maxRuns=2,
+      )
+    Assert.deepEqual(chainRuns.runs->toBlockNumbersWithNames, [(1, "EveryBlock"), (2, "EveryBlock")])
+    Assert.equal(chainRuns.processedTo, 2)
+
+    let chainRuns =
+      blockHandlers->BlockHandlers.planRuns(
+        ~fromBlock=1,
+        ~toBlock=100,
+        ~mustRunToBlock=4,
+        ~maxRuns=2,
+      )
+    Assert.equal(chainRuns.runs->Js.Array2.length, 4)
+    Assert.equal(chainRuns.processedTo, 4)
+  })
+
+  it("Skips the block handlers without a registered handler", () => {
+    let blockHandlers = [
+      mockBlockHandler(~name="Registered", ~interval=5, ~startBlock=0),
+      {
+        name: "NotRegistered",
+        interval: 1,
+        startBlock: 0,
+        endBlock: None,
+        handlerRegister: Types.OnBlock.Register.make(~name="NotRegistered"),
+      },
+    ]
+
+    Assert.equal(blockHandlers->BlockHandlers.hasRuns(~fromBlock=1, ~toBlock=4), false)
+    Assert.equal(blockHandlers->BlockHandlers.hasRuns(~fromBlock=1, ~toBlock=5), true)
+  })
+
+  it("Cuts off the runs after the given block", () => {
+    let chainRuns: BlockHandlers.chainRuns<BlockHandlers.run> = {
+      runs: [3, 5, 8]->Js.Array2.map((blockNumber): BlockHandlers.run => {
+        blockNumber,
+        blockHandlerName: "Test",
+        handler: async _ => (),
+        blockTimestamp: blockNumber * 12,
+      }),
+      processedTo: 10,
+    }
+
+    let cutOff = chainRuns->BlockHandlers.cutOff(~toBlock=6)
+    Assert.deepEqual(cutOff.runs->Js.Array2.map(run => run.blockNumber), [3, 5])
+    Assert.equal(cutOff.processedTo, 6)
+  })
+})
@@ -143,6 +143,7 @@ let populateChainQueuesWithRandomEvents = (~runTime=1000, ~maxBlockTime=15, ())
       dbFirstEventBlockNumber: None,
       latestProcessedBlock: None,
       numEventsProcessed: 0,
+      blockHandlersProcessedTo: -1,
       numBatchesFetched: 0,
       startBlock: 0,
       fetchState: fetchState.contents,
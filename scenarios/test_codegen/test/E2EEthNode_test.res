@@ -31,6 +31,7 @@ describe("E2E Integration Test", () => {
         endBlock: None,
         chain,
         contracts,
+        blockHandlers: [],
         sources: [
           RpcSource.make({
             chain,
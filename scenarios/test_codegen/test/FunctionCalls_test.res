@@ -0,0 +1,113 @@
+open RescriptMocha
+
+let recipient = "0x1111111111111111111111111111111111111111"->Address.unsafeFromString
+
+let abi = %raw(`[
+  {
+    type: "function",
+    name: "transfer",
+    inputs: [
+      { name: "recipient", type: "address" },
+      { name: "amount", type: "uint256" },
+    ],
+    outputs: [{ name: "", type: "bool" }],
+    stateMutability: "nonpayable",
+  },
+]`)
+
+// transfer(0x1111111111111111111111111111111111111111, 100)
+let input = "0xa9059cbb00000000000000000000000011111111111111111111111111111111111111110000000000000000000000000000000000000000000000000000000000000064"
+
+module MockTransferCall = {
+  let selector = "0xa9059cbb"
+  let name = "Transfer"
+  let abiName = "transfer"
+  let contractName = "Token"
+  let inputNames = ["recipient", "amount"]
+
+  type inputs = {recipient: Address.t, amount: bigint}
+  type handler = Internal.callHandler
+
+  let inputsSchema = S.object(s => {
+    recipient: s.field("recipient", Address.schema),
+    amount: s.field("amount", BigInt.schema),
+  })
+
+  let handlerRegister = Types.OnCall.Register.make(~selector, ~contractName, ~functionName=name)
+}
+
+let mockTransferCall: module(Types.Function) = module(MockTransferCall)
+
+let mockContract: Config.contract = {
+  name: MockTransferCall.contractName,
+  abi,
+  addresses: [recipient],
+  events: [],
+  functions: [mockTransferCall],
+}
+
+describe("FunctionCalls", () => {
+  Async.it("Decodes the call inputs and runs the registered call handler", async () => {
+    let calls = []
+    MockTransferCall.handlerRegister->Types.OnCall.Register.setHandler(async ({call}) =>
+      calls->Js.Array2.push(call)->ignore
+    )
+
+    let inputs = mockTransferCall->FunctionCalls.decodeInputsOrThrow(~abi, ~input)
+    let eventItem =
+      mockTransferCall->FunctionCalls.makeEventItem(
+        ~inputs,
+        ~chain=ChainMap.Chain.makeUnsafe(~chainId=1),
+        ~srcAddress=recipient,
+        ~transactionIndex=3,
+        ~block=%raw(`{number: 10, timestamp: 20, hash: "0x10"}`),
+        ~transaction=%raw(`{}`),
+      )
+
+    Assert.equal(eventItem.blockNumber, 10)
+    Assert.equal(eventItem.timestamp, 20)
+    Assert.equal(eventItem.logIndex, FunctionCalls.logIndexOffset + 3)
+
+    switch eventItem.handler {
+    | Some(handler) =>
+      await handler({
+        event: eventItem.event,
+        context: %raw(`{}`),
+        loaderReturn: %raw(`undefined`),
+      })
+    | None => Assert.fail("The call handler should be registered")
+    }
+
+    Assert.deepEqual(
+      calls->Js.Array2.map(call =>
+        call.inputs->(Utils.magic: Internal.callInputs => MockTransferCall.inputs)
+      ),
+      [{recipient, amount: BigInt.fromInt(100)}],
+    )
+  })
+
+  it("Selects the transactions to the contract addresses by the function selectors", () => {
+    let selection: FetchState.selection = {
+      isWildcard: false,
+      eventConfigs: [
+        {
+          contractName: MockTransferCall.contractName,
+          eventId: MockTransferCall.selector,
+          isWildcard: false,
+        },
+      ],
+    }
+    let contractAddressMapping = ContractAddressingMap.make()
+    contractAddressMapping->ContractAddressingMap.addAddress(
+      ~name=MockTransferCall.contractName,
+      ~address=recipient,
+    )
+
+    let selectionConfig = selection->HyperSyncSource.getSelectionConfig(~contracts=[mockContract])
+
+    Assert.deepEqual(
+      selectionConfig.getTransactionSelections(~contractAddressMapping),
+      [{to_: [recipient], sighash: [MockTransferCall.selector]}],
+    )
+  })
+})
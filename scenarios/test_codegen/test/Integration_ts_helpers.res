@@ -24,6 +24,7 @@ let getLocalChainConfig = (nftFactoryContractAddress): chainConfig => {
     endBlock: None,
     chain,
     contracts,
+    blockHandlers: [],
     sources: [
       RpcSource.make({
         chain,
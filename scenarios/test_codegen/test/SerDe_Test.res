@@ -180,4 +180,89 @@ describe("SerDe Test", () => {
     | _ => Assert.fail("Should have returned a row")
     }
   })
+
+  Async.it("Entity with each nullability combination of lists", async () => {
+    let entities: array<Entities.EntityWithNullableLists.t> = [
+      {
+        id: "1",
+        arrayOfOptStrings: [Some("string1"), None, Some("string2")],
+        optArrayOfStrings: Some(["string1", "string2"]),
+        optArrayOfOptBigInts: Some([None, Some(BigInt.fromInt(1))]),
+        matrixOfInts: [[1, 2, 3], [4]],
+        matrixOfOptInts: [[Some(1), None], [], [None]],
+        arrayOfOptArraysOfInts: [Some([1, 2]), None, Some([3])],
+        optMatrixOfOptBigInts: Some([Some([Some(BigInt.fromInt(1)), None]), None]),
+      },
+      {
+        id: "2",
+        arrayOfOptStrings: [None],
+        optArrayOfStrings: None,
+        optArrayOfOptBigInts: None,
+        matrixOfInts: [],
+        matrixOfOptInts: [[None, None]],
+        arrayOfOptArraysOfInts: [None],
+        optMatrixOfOptBigInts: None,
+      },
+    ]
+
+    let historyRows: array<EntityHistory.historyRow<_>> = entities->Js.Array2.map(entity => {
+      let historyRow: EntityHistory.historyRow<_> = {
+        current: {
+          chain_id: 1,
+          block_timestamp: 1,
+          block_number: 1,
+          log_index: 1,
+        },
+        previous: None,
+        entityData: Set(entity),
+      }
+      historyRow
+    })
+
+    //Fails if serialziation does not work
+    let set = DbFunctionsEntities.batchSet(~entityMod=module(Entities.EntityWithNullableLists))
+    //Fails if parsing does not work
+    let read = DbFunctionsEntities.batchRead(~entityMod=module(Entities.EntityWithNullableLists))
+
+    try await Entities.EntityWithNullableLists.entityHistory->EntityHistory.batchInsertRows(
+      ~sql=Db.sql,
+      ~rows=historyRows,
+      ~shouldCopyCurrentEntity=true,
+    ) catch {
+    | exn =>
+      Js.log2("setHistory exn", exn)
+      Assert.fail("Failed to set entity history in table")
+    }
+
+    try await Db.sql->set(entities) catch {
+    | exn =>
+      Js.log(exn)
+      Assert.fail("Failed to set entities in table")
+    }
+
+    switch await Db.sql->read(entities->Js.Array2.map(entity => entity.id)) {
+    | exception exn =>
+      Js.log(exn)
+      Assert.fail("Failed to read entities from table")
+    | readEntities =>
+      Assert.deepEqual(
+        readEntities->Js.Array2.sortInPlaceWith((a, b) => compare(a.id, b.id)),
+        entities,
+      )
+    }
+
+    let res =
+      await Db.sql->Postgres.unsafe(
+        `SELECT * FROM public."EntityWithNullableLists_history" ORDER BY id;`,
+      )
+
+    Assert.deepEqual(
+      res->Js.Array2.map(row => {
+        let parsed = row->S.parseJsonOrThrow(Entities.EntityWithNullableLists.entityHistory.schema)
+        parsed.entityData
+      }),
+      entities->Js.Array2.map(entity => EntityHistory.Set(entity)),
+      ~message="Postgres json serialization should be compatable with our schema",
+    )
+  })
 })
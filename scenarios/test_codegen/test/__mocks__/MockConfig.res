@@ -33,6 +33,7 @@ let mockChainConfig: Config.chainConfig = {
   endBlock: None,
   chain: chain1337,
   contracts,
+  blockHandlers: [],
   sources: [
     RpcSource.make({
       chain: chain1337,
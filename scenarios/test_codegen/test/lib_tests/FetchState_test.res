@@ -1633,6 +1633,149 @@ describe("FetchState unit tests for specific cases", () => {
     )
   })
 
+  it("Queries only the events within their block ranges", () => {
+    let startedEventConfig: FetchState.eventConfig = {
+      contractName: "Gravatar",
+      eventId: "0",
+      isWildcard: false,
+      startBlock: 100,
+    }
+    let endedEventConfig: FetchState.eventConfig = {
+      contractName: "Gravatar",
+      eventId: "1",
+      isWildcard: false,
+      endBlock: 50,
+    }
+    let fetchState = FetchState.make(
+      ~eventConfigs=[startedEventConfig, endedEventConfig],
+      ~staticContracts=Js.Dict.fromArray([("Gravatar", [mockAddress0])]),
+      ~dynamicContracts=[],
+      ~startBlock=0,
+      ~endBlock=None,
+      ~maxAddrInPartition=3,
+    )
+    let getNextQuery = (fs, ~currentBlockHeight) =>
+      fs->FetchState.getNextQuery(
+        ~concurrencyLimit=10,
+        ~currentBlockHeight,
+        ~maxQueueSize=10,
+        ~stateId=0,
+      )
+
+    let query = switch fetchState->getNextQuery(~currentBlockHeight=200) {
+    | Ready([query]) => query
+    | _ => Assert.fail("Failed to extract query")
+    }
+    Assert.deepEqual(
+      query,
+      {
+        partitionId: "0",
+        target: EndBlock({toBlock: 50}),
+        selection: {isWildcard: false, eventConfigs: [endedEventConfig]},
+        contractAddressMapping: ContractAddressingMap.fromArray([(mockAddress0, "Gravatar")]),
+        fromBlock: 0,
+      },
+      ~message=`Should query only the events indexed at the fromBlock
+      and stop at the block where the indexed events change`,
+    )
+    Assert.equal(
+      switch fetchState->getNextQuery(~currentBlockHeight=200) {
+      | Ready([repeatedQuery]) => repeatedQuery.selection === query.selection
+      | _ => false
+      },
+      true,
+      ~message="Should reuse the narrowed selection, since sources cache queries by it",
+    )
+
+    let fetchState =
+      fetchState
+      ->FetchState.setQueryResponse(
+        ~query,
+        ~latestFetchedBlock=getBlockData(~blockNumber=50),
+        ~newItems=[],
+        ~currentBlockHeight=80,
+      )
+      ->Result.getExn
+
+    Assert.deepEqual(
+      fetchState->getNextQuery(~currentBlockHeight=80),
+      WaitingForNewBlock,
+      ~message="Should wait for the block where the next event starts",
+    )
+    Assert.deepEqual(
+      fetchState->getNextQuery(~currentBlockHeight=200),
+      Ready([
+        {
+          partitionId: "0",
+          target: Head,
+          selection: {isWildcard: false, eventConfigs: [startedEventConfig]},
+          contractAddressMapping: ContractAddressingMap.fromArray([(mockAddress0, "Gravatar")]),
+          fromBlock: 100,
+        },
+      ]),
+      ~message="Should skip the blocks without events to index",
+    )
+  })
+
+  it("Doesn't fetch partitions with ended event block ranges", () => {
+    let fetchState = FetchState.make(
+      ~eventConfigs=[
+        {
+          contractName: "Gravatar",
+          eventId: "0",
+          isWildcard: false,
+          endBlock: 50,
+        },
+      ],
+      ~staticContracts=Js.Dict.fromArray([("Gravatar", [mockAddress0])]),
+      ~dynamicContracts=[],
+      ~startBlock=0,
+      ~endBlock=None,
+      ~maxAddrInPartition=3,
+    )
+    let fetchState =
+      fetchState
+      ->FetchState.setQueryResponse(
+        ~query={
+          partitionId: "0",
+          target: EndBlock({toBlock: 50}),
+          selection: fetchState.normalSelection,
+          contractAddressMapping: ContractAddressingMap.make(),
+          fromBlock: 0,
+        },
+        ~latestFetchedBlock=getBlockData(~blockNumber=50),
+        ~newItems=[mockEvent(~blockNumber=10)],
+        ~currentBlockHeight=200,
+      )
+      ->Result.getExn
+
+    Assert.deepEqual(
+      fetchState->FetchState.getNextQuery(
+        ~concurrencyLimit=10,
+        ~currentBlockHeight=200,
+        ~maxQueueSize=10,
+        ~stateId=0,
+      ),
+      WaitingForNewBlock,
+    )
+    Assert.deepEqual(
+      fetchState->FetchState.getLatestFullyFetchedBlock,
+      {blockNumber: 200, blockTimestamp: 0},
+      ~message="The finished partition shouldn't hold the fetched block",
+    )
+    Assert.equal(
+      fetchState->FetchState.hasEventsToFetch,
+      true,
+      ~message="Should process the fetched items first",
+    )
+
+    switch fetchState->FetchState.getEarliestEvent {
+    | Item({popItemOffQueue}) => popItemOffQueue()
+    | NoItem(_) => Assert.fail("Should have the fetched item")
+    }
+    Assert.equal(fetchState->FetchState.hasEventsToFetch, false)
+  })
+
   it("Shouldn't query full partitions at the head until all partitions entered sync range", () => {
     let currentBlockHeight = 1_000_000
     let syncRange = 1_000 // Should be 1/1000 of block height
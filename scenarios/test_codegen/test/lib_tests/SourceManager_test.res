@@ -739,4 +739,123 @@ describe("SourceManager fetchNext", () => {
 
     Assert.deepEqual(executeQueryMock.callIds, ["4"])
   })
+
+  Async.it("Switches to the realtime source within its threshold to the head", async () => {
+    let historicalSource = {...source, name: "HistoricalSource"}->Source.withRole(Historical)
+    let realtimeSource =
+      {...source, name: "RealtimeSource"}->Source.withRole(
+        Realtime({switchBlocksBehindHead: 5, stallTimeoutMillis: 1000}),
+      )
+
+    let sourceManager = SourceManager.make(
+      ~sources=[realtimeSource, historicalSource],
+      ~maxPartitionConcurrency=10,
+      ~logger=Logging.logger,
+    )
+
+    let querySourceNames = []
+    let executeQuery = async (_, ~source: Source.t) => {
+      querySourceNames->Js.Array2.push(source.name)->ignore
+    }
+
+    await sourceManager->SourceManager.fetchNext(
+      ~fetchState=mockFetchState([mockFullPartition(~partitionIndex=0, ~latestFetchedBlockNumber=4)]),
+      ~maxPerChainQueueSize=1000,
+      ~currentBlockHeight=20,
+      ~executeQuery,
+      ~waitForNewBlock=neverWaitForNewBlock,
+      ~onNewBlock=neverOnNewBlock,
+      ~stateId=0,
+    )
+
+    await sourceManager->SourceManager.fetchNext(
+      ~fetchState=mockFetchState([mockFullPartition(~partitionIndex=0, ~latestFetchedBlockNumber=15)]),
+      ~maxPerChainQueueSize=1000,
+      ~currentBlockHeight=20,
+      ~executeQuery,
+      ~waitForNewBlock=neverWaitForNewBlock,
+      ~onNewBlock=neverOnNewBlock,
+      ~stateId=0,
+    )
+
+    Assert.deepEqual(querySourceNames, ["HistoricalSource", "RealtimeSource"])
+  })
+
+  Async.it("Queries the fallback source when the primary one fails", async () => {
+    let fetchBlockRangeWith = (~response) => (
+      ~fromBlock as _,
+      ~toBlock as _,
+      ~contractAddressMapping as _,
+      ~currentBlockHeight as _,
+      ~partitionId as _,
+      ~selection as _,
+      ~logger as _,
+    ) => response->Promise.resolve
+
+    let primarySource = {
+      ...source,
+      name: "PrimarySource",
+      fetchBlockRange: fetchBlockRangeWith(
+        ~response=Error(ErrorHandling.make(Not_found, ~msg="Failed to fetch")),
+      ),
+    }
+    let fallbackSource = {
+      ...source,
+      name: "FallbackSource",
+      fetchBlockRange: fetchBlockRangeWith(~response=Ok("fallbackResponse"->Utils.magic)),
+    }->Source.withRole(Fallback({stallTimeoutMillis: 1000}))
+
+    let sourceManager = SourceManager.make(
+      ~sources=[primarySource, fallbackSource],
+      ~maxPartitionConcurrency=10,
+      ~logger=Logging.logger,
+    )
+
+    let responses = []
+    let executeQuery = async (query: FetchState.query, ~source: Source.t) => {
+      let response = await source.fetchBlockRange(
+        ~fromBlock=query.fromBlock,
+        ~toBlock=None,
+        ~contractAddressMapping=query.contractAddressMapping,
+        ~currentBlockHeight=20,
+        ~partitionId=query.partitionId,
+        ~selection=query.selection,
+        ~logger=Logging.logger,
+      )
+      responses->Js.Array2.push(response)->ignore
+    }
+
+    await sourceManager->SourceManager.fetchNext(
+      ~fetchState=mockFetchState([mockFullPartition(~partitionIndex=0, ~latestFetchedBlockNumber=4)]),
+      ~maxPerChainQueueSize=1000,
+      ~currentBlockHeight=20,
+      ~executeQuery,
+      ~waitForNewBlock=neverWaitForNewBlock,
+      ~onNewBlock=neverOnNewBlock,
+      ~stateId=0,
+    )
+
+    Assert.deepEqual(responses, [Ok("fallbackResponse"->Utils.magic)])
+  })
+
+  Async.it("Gets the height from the realtime source when the historical one stalls", async () => {
+    let historicalSource = {
+      ...source,
+      name: "HistoricalSource",
+      getHeightOrThrow: () => Promise.make((_resolve, _reject) => ()),
+    }->Source.withRole(Historical)
+    let realtimeSource = {
+      ...source,
+      name: "RealtimeSource",
+      getHeightOrThrow: () => Promise.resolve(20),
+    }->Source.withRole(Realtime({switchBlocksBehindHead: 5, stallTimeoutMillis: 10}))
+
+    let sourceManager = SourceManager.make(
+      ~sources=[historicalSource, realtimeSource],
+      ~maxPartitionConcurrency=10,
+      ~logger=Logging.logger,
+    )
+
+    Assert.equal(await sourceManager.mainSource.getHeightOrThrow(), 20)
+  })
 })
@@ -144,4 +144,47 @@ describe("Table functions postgres interop", () => {
 
     Assert.equal(batchSetFnString, expected)
   })
+
+  Async.it("Recreates the fulltext search column when its fields change", async () => {
+    let makeTable = fieldNames =>
+      mkTable(
+        "fulltext_test",
+        ~schemaName=Env.Db.publicSchema,
+        ~fields=[mkField("id", Text, ~isPrimaryKey), mkField("title", Text), mkField("body", Text)],
+        ~fulltextSearches=[mkFulltextSearch("fulltext_test_search", ~language="english", ~fieldNames)],
+      )
+    let createFulltextSearch = async table =>
+      switch table.fulltextSearches {
+      | [fulltextSearch] =>
+        let _ = await Db.sql->Postgres.unsafe(table->FulltextSearch.makeCreateQuery(fulltextSearch))
+      | _ => Assert.fail("Expected a single fulltext search")
+      }
+    let search = async text => {
+      let rows: array<{"id": string}> =
+        await Db.sql->Postgres.unsafe(
+          `SELECT id FROM "${Env.Db.publicSchema}"."fulltext_test_search"('${text}');`,
+        )
+      rows->Js.Array2.map(row => row["id"])
+    }
+
+    let _ = await Migrations.runDownMigrations(~shouldExit=false)
+    let titleTable = makeTable(["title"])
+    let _ = await Migrations.creatTableIfNotExists(Db.sql, titleTable)
+    await titleTable->createFulltextSearch
+    let _ =
+      await Db.sql->Postgres.unsafe(
+        `INSERT INTO "${Env.Db.publicSchema}"."fulltext_test" (id, title, body) VALUES ('1', 'hello', 'world');`,
+      )
+    Assert.deepEqual(await search("world"), [], ~message="Only the title should be searched")
+
+    await makeTable(["title", "body"])->createFulltextSearch
+    Assert.deepEqual(
+      await search("world"),
+      ["1"],
+      ~message="The column should be generated from the new fields",
+    )
+
+    await makeTable(["title", "body"])->createFulltextSearch
+    Assert.deepEqual(await search("hello"), ["1"], ~message="Rerunning should keep the column")
+  })
 })